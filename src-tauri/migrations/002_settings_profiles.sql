@@ -0,0 +1,11 @@
+-- Named settings profiles ("Work", "Home"). `settings` holds a JSON object of
+-- portable setting keys to values; at most one profile is active.
+CREATE TABLE IF NOT EXISTS settings_profiles (
+  name TEXT PRIMARY KEY,
+  settings TEXT NOT NULL,
+  is_active INTEGER NOT NULL DEFAULT 0,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (2);
@@ -0,0 +1,50 @@
+-- Session history for time-use insights. Timestamps are RFC 3339 UTC
+-- ("2026-03-02T09:00:00Z") so they sort and compare as text.
+
+-- Every BorderState phase change, plus "offline" when the app stops.
+CREATE TABLE IF NOT EXISTS history_phase_transitions (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  phase TEXT NOT NULL,
+  at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_history_phase_transitions_at
+  ON history_phase_transitions (at);
+
+-- Meetings that ended while Morph was running (copied from the event cache).
+CREATE TABLE IF NOT EXISTS history_meetings (
+  event_id TEXT PRIMARY KEY,
+  provider_id TEXT NOT NULL,
+  calendar_id TEXT,
+  title TEXT NOT NULL,
+  start_time TEXT NOT NULL,
+  end_time TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_history_meetings_start
+  ON history_meetings (start_time);
+
+-- Finished timer runs. `active_seconds` excludes time spent paused.
+CREATE TABLE IF NOT EXISTS history_timer_sessions (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  preset_id TEXT NOT NULL,
+  name TEXT NOT NULL,
+  duration_seconds INTEGER NOT NULL,
+  active_seconds INTEGER NOT NULL,
+  started_at TEXT NOT NULL,
+  ended_at TEXT NOT NULL,
+  outcome TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_history_timer_sessions_started
+  ON history_timer_sessions (started_at);
+
+-- Border pauses. `ended_at` is set when the border is resumed early.
+CREATE TABLE IF NOT EXISTS history_pauses (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  started_at TEXT NOT NULL,
+  planned_until TEXT,
+  ended_at TEXT,
+  reason TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_history_pauses_started
+  ON history_pauses (started_at);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (3);
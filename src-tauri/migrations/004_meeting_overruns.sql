@@ -0,0 +1,22 @@
+-- Recurring series for calendar events, so overruns can be grouped per
+-- series ("Weekly sync"). NULL for one-off events.
+ALTER TABLE calendar_events ADD COLUMN series_id TEXT;
+ALTER TABLE history_meetings ADD COLUMN series_id TEXT;
+
+-- How each meeting's overtime ended: "left" (the user confirmed from the
+-- tray), "yielded" (the next meeting took over) or "expired" (nobody
+-- confirmed). `overrun_seconds` is only known for "left".
+CREATE TABLE IF NOT EXISTS meeting_overruns (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  event_id TEXT NOT NULL,
+  series_id TEXT,
+  title TEXT NOT NULL,
+  scheduled_end TEXT NOT NULL,
+  ended_at TEXT NOT NULL,
+  outcome TEXT NOT NULL,
+  overrun_seconds INTEGER
+);
+CREATE INDEX IF NOT EXISTS idx_meeting_overruns_series
+  ON meeting_overruns (series_id, scheduled_end);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (4);
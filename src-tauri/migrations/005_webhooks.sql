@@ -0,0 +1,29 @@
+-- Outgoing webhook targets. `events` is a JSON array of event names
+-- ("phase-changed", "meeting-started", ...); `secret` signs each request.
+CREATE TABLE IF NOT EXISTS webhook_targets (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL,
+  url TEXT NOT NULL,
+  secret TEXT NOT NULL,
+  events TEXT NOT NULL DEFAULT '[]',
+  enabled INTEGER NOT NULL DEFAULT 1,
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- One row per delivery attempt, trimmed to the most recent per target.
+-- `status_code` is NULL when the request never got a response.
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  target_id INTEGER NOT NULL,
+  delivery_id TEXT NOT NULL,
+  event TEXT NOT NULL,
+  attempt INTEGER NOT NULL,
+  attempted_at TEXT NOT NULL,
+  status_code INTEGER,
+  error TEXT,
+  success INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_target
+  ON webhook_deliveries (target_id, id);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (5);
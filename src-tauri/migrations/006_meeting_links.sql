@@ -0,0 +1,5 @@
+-- Conferencing link for each cached event, used by the "Join" action on
+-- notifications. NULL when the provider reported none.
+ALTER TABLE calendar_events ADD COLUMN meeting_url TEXT;
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (6);
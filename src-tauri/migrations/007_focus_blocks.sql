@@ -0,0 +1,12 @@
+-- Blocked apps caught during focus sessions. `action` is what Morph did:
+-- "warned", "hidden" or "quit".
+CREATE TABLE IF NOT EXISTS history_focus_blocks (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  app TEXT NOT NULL,
+  action TEXT NOT NULL,
+  at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_history_focus_blocks_at
+  ON history_focus_blocks (at);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (7);
@@ -0,0 +1,15 @@
+-- The active border pause, so it survives a restart. At most one row.
+-- `kind` is how the end was chosen: "minutes", "next-event", "event-end",
+-- "tomorrow" or "quiet". Next-event and event-end pauses keep the event
+-- they follow so the end can move with it.
+CREATE TABLE IF NOT EXISTS border_pause (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  kind TEXT NOT NULL,
+  started_at TEXT NOT NULL,
+  until TEXT NOT NULL,
+  source TEXT NOT NULL,
+  event_id TEXT,
+  title TEXT
+);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (8);
@@ -0,0 +1,5 @@
+-- Where each cached event takes place, as the provider reports it. Travel
+-- buffers apply to events with a physical location. NULL when none.
+ALTER TABLE calendar_events ADD COLUMN location TEXT;
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (9);
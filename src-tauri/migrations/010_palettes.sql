@@ -0,0 +1,12 @@
+-- User-defined border palettes. `entries` is a JSON object of phase name
+-- to {hex, opacity, pulseSpeed}; built-in presets live in code. IDs are
+-- "custom-<slug>" so they never clash with a preset.
+CREATE TABLE IF NOT EXISTS palettes (
+  id TEXT PRIMARY KEY,
+  name TEXT NOT NULL,
+  entries TEXT NOT NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (10);
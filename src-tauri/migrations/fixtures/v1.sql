@@ -0,0 +1,66 @@
+-- morph.db as shipped at schema version 1 (migrations applied by
+-- tauri-plugin-sql), with representative user data.
+CREATE TABLE _sqlx_migrations (
+  version BIGINT PRIMARY KEY,
+  description TEXT NOT NULL,
+  installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  success BOOLEAN NOT NULL,
+  checksum BLOB NOT NULL,
+  execution_time BIGINT NOT NULL
+);
+INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+  VALUES (1, 'create initial schema', 1, x'00', 0);
+
+CREATE TABLE settings (
+  key TEXT PRIMARY KEY,
+  value TEXT NOT NULL,
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE calendar_events (
+  id TEXT PRIMARY KEY,
+  provider_id TEXT NOT NULL,
+  calendar_id TEXT,
+  title TEXT NOT NULL,
+  start_time TEXT NOT NULL,
+  end_time TEXT NOT NULL,
+  is_all_day INTEGER DEFAULT 0,
+  ignored INTEGER DEFAULT 0,
+  fetched_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE calendar_providers (
+  id TEXT PRIMARY KEY,
+  provider_type TEXT NOT NULL,
+  account_name TEXT NOT NULL,
+  connected_at TEXT NOT NULL DEFAULT (datetime('now')),
+  last_sync_at TEXT,
+  status TEXT NOT NULL DEFAULT 'connected'
+);
+
+CREATE TABLE timer (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  duration_seconds INTEGER NOT NULL,
+  started_at TEXT NOT NULL,
+  paused_at TEXT,
+  elapsed_before_pause INTEGER DEFAULT 0,
+  status TEXT NOT NULL DEFAULT 'running'
+);
+
+CREATE TABLE schema_version (
+  version INTEGER PRIMARY KEY,
+  applied_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+INSERT INTO schema_version (version) VALUES (1);
+
+INSERT INTO settings (key, value) VALUES ('border_thickness', 'thick');
+INSERT INTO settings (key, value) VALUES ('border_position', 'top');
+INSERT INTO settings (key, value) VALUES ('color_palette', 'ocean');
+INSERT INTO settings (key, value) VALUES ('poll_interval_seconds', '120');
+INSERT INTO settings (key, value) VALUES ('onboarding_complete', 'true');
+
+INSERT INTO calendar_providers (id, provider_type, account_name)
+  VALUES ('google', 'google', 'me@example.com');
+
+INSERT INTO calendar_events (id, provider_id, calendar_id, title, start_time, end_time)
+  VALUES ('evt-1', 'google', 'primary', 'Standup', '2026-03-02T09:00:00Z', '2026-03-02T09:15:00Z');
@@ -0,0 +1,174 @@
+-- morph.db at schema version 5, with representative user data.
+CREATE TABLE _sqlx_migrations (
+  version BIGINT PRIMARY KEY,
+  description TEXT NOT NULL,
+  installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  success BOOLEAN NOT NULL,
+  checksum BLOB NOT NULL,
+  execution_time BIGINT NOT NULL
+);
+INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+  VALUES (1, 'create initial schema', 1, x'00', 0),
+         (2, 'add settings profiles', 1, x'00', 0);
+
+CREATE TABLE settings (
+  key TEXT PRIMARY KEY,
+  value TEXT NOT NULL,
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE calendar_events (
+  id TEXT PRIMARY KEY,
+  provider_id TEXT NOT NULL,
+  calendar_id TEXT,
+  title TEXT NOT NULL,
+  start_time TEXT NOT NULL,
+  end_time TEXT NOT NULL,
+  is_all_day INTEGER DEFAULT 0,
+  ignored INTEGER DEFAULT 0,
+  fetched_at TEXT NOT NULL DEFAULT (datetime('now')),
+  series_id TEXT
+);
+
+CREATE TABLE calendar_providers (
+  id TEXT PRIMARY KEY,
+  provider_type TEXT NOT NULL,
+  account_name TEXT NOT NULL,
+  connected_at TEXT NOT NULL DEFAULT (datetime('now')),
+  last_sync_at TEXT,
+  status TEXT NOT NULL DEFAULT 'connected'
+);
+
+CREATE TABLE timer (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  duration_seconds INTEGER NOT NULL,
+  started_at TEXT NOT NULL,
+  paused_at TEXT,
+  elapsed_before_pause INTEGER DEFAULT 0,
+  status TEXT NOT NULL DEFAULT 'running'
+);
+
+CREATE TABLE schema_version (
+  version INTEGER PRIMARY KEY,
+  applied_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+INSERT INTO schema_version (version) VALUES (1), (2), (3), (4), (5);
+
+INSERT INTO settings (key, value) VALUES ('border_thickness', 'thick');
+INSERT INTO settings (key, value) VALUES ('border_position', 'top');
+INSERT INTO settings (key, value) VALUES ('color_palette', 'ocean');
+INSERT INTO settings (key, value) VALUES ('poll_interval_seconds', '120');
+INSERT INTO settings (key, value) VALUES ('onboarding_complete', 'true');
+
+INSERT INTO calendar_providers (id, provider_type, account_name)
+  VALUES ('google', 'google', 'me@example.com');
+
+INSERT INTO calendar_events (id, provider_id, calendar_id, title, start_time, end_time, series_id)
+  VALUES ('evt-1', 'google', 'primary', 'Standup', '2026-03-02T09:00:00Z', '2026-03-02T09:15:00Z', 'standup');
+
+CREATE TABLE settings_profiles (
+  name TEXT PRIMARY KEY,
+  settings TEXT NOT NULL,
+  is_active INTEGER NOT NULL DEFAULT 0,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+INSERT INTO settings_profiles (name, settings, is_active)
+  VALUES ('Work', '{"border_thickness":"thick","color_palette":"ocean"}', 1);
+
+-- Every BorderState phase change, plus "offline" when the app stops.
+CREATE TABLE history_phase_transitions (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  phase TEXT NOT NULL,
+  at TEXT NOT NULL
+);
+CREATE INDEX idx_history_phase_transitions_at
+  ON history_phase_transitions (at);
+
+-- Meetings that ended while Morph was running (copied from the event cache).
+CREATE TABLE history_meetings (
+  event_id TEXT PRIMARY KEY,
+  provider_id TEXT NOT NULL,
+  calendar_id TEXT,
+  title TEXT NOT NULL,
+  start_time TEXT NOT NULL,
+  end_time TEXT NOT NULL,
+  series_id TEXT
+);
+CREATE INDEX idx_history_meetings_start
+  ON history_meetings (start_time);
+
+-- Finished timer runs. `active_seconds` excludes time spent paused.
+CREATE TABLE history_timer_sessions (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  preset_id TEXT NOT NULL,
+  name TEXT NOT NULL,
+  duration_seconds INTEGER NOT NULL,
+  active_seconds INTEGER NOT NULL,
+  started_at TEXT NOT NULL,
+  ended_at TEXT NOT NULL,
+  outcome TEXT NOT NULL
+);
+CREATE INDEX idx_history_timer_sessions_started
+  ON history_timer_sessions (started_at);
+
+-- Border pauses. `ended_at` is set when the border is resumed early.
+CREATE TABLE history_pauses (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  started_at TEXT NOT NULL,
+  planned_until TEXT,
+  ended_at TEXT,
+  reason TEXT NOT NULL
+);
+CREATE INDEX idx_history_pauses_started
+  ON history_pauses (started_at);
+
+INSERT INTO history_phase_transitions (phase, at) VALUES ('free-deep', '2026-03-02T08:00:00Z');
+INSERT INTO history_phase_transitions (phase, at) VALUES ('in-session-early', '2026-03-02T09:00:00Z');
+INSERT INTO history_meetings (event_id, provider_id, calendar_id, title, start_time, end_time, series_id)
+  VALUES ('evt-0', 'google', 'primary', 'Planning', '2026-03-01T09:00:00Z', '2026-03-01T10:00:00Z', NULL);
+
+CREATE TABLE meeting_overruns (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  event_id TEXT NOT NULL,
+  series_id TEXT,
+  title TEXT NOT NULL,
+  scheduled_end TEXT NOT NULL,
+  ended_at TEXT NOT NULL,
+  outcome TEXT NOT NULL,
+  overrun_seconds INTEGER
+);
+CREATE INDEX idx_meeting_overruns_series
+  ON meeting_overruns (series_id, scheduled_end);
+
+INSERT INTO meeting_overruns (event_id, series_id, title, scheduled_end, ended_at, outcome, overrun_seconds)
+  VALUES ('evt-0', 'standup', 'Standup', '2026-03-01T09:15:00Z', '2026-03-01T09:22:00Z', 'left', 420);
+
+CREATE TABLE webhook_targets (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL,
+  url TEXT NOT NULL,
+  secret TEXT NOT NULL,
+  events TEXT NOT NULL DEFAULT '[]',
+  enabled INTEGER NOT NULL DEFAULT 1,
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE webhook_deliveries (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  target_id INTEGER NOT NULL,
+  delivery_id TEXT NOT NULL,
+  event TEXT NOT NULL,
+  attempt INTEGER NOT NULL,
+  attempted_at TEXT NOT NULL,
+  status_code INTEGER,
+  error TEXT,
+  success INTEGER NOT NULL
+);
+CREATE INDEX idx_webhook_deliveries_target
+  ON webhook_deliveries (target_id, id);
+
+INSERT INTO webhook_targets (name, url, secret, events)
+  VALUES ('Busy light', 'http://192.168.1.20/busy', 'c2VjcmV0', '["meeting-started","meeting-ended"]');
+INSERT INTO webhook_deliveries (target_id, delivery_id, event, attempt, attempted_at, status_code, success)
+  VALUES (1, 'd-1', 'meeting-started', 1, '2026-03-02T09:00:01Z', 200, 1);
@@ -0,0 +1,364 @@
+//! `morph-cli`: control a running Morph instance from scripts and shortcuts.
+//!
+//! Talks to the app over the local socket in `morph_lib::ipc`. Exits with 1
+//! if Morph is not running or the command fails, and 2 on a usage error.
+
+use chrono::{DateTime, Local, NaiveTime, Utc};
+use serde_json::Value;
+
+use morph_lib::ipc::{self, IpcRequest};
+use morph_lib::timer::TimerState;
+
+const USAGE: &str = "\
+Usage: morph-cli [--json] <command>
+
+Commands:
+  status                      Border phase, timer, pause and next event
+  timer start <duration> [--name <name>]
+  timer pause | resume | stop
+  pause <duration>            Pause the border, e.g. 30m or 1h
+  resume                      Resume the border
+  sync                        Fetch calendar events now
+  events [--today]            List today's events
+  settings get [<key>]        Print one setting, or all of them
+  settings set <key> <value>
+
+Durations: 25m, 1h30m, 90s, or a bare number of minutes.
+--json prints the raw response.";
+
+/// A parsed command line.
+#[derive(Debug, PartialEq)]
+struct Cli {
+    request: IpcRequest,
+    json: bool,
+}
+
+fn parse_args(args: &[String], now: DateTime<Local>) -> Result<Cli, String> {
+    let json = args.iter().any(|a| a == "--json");
+    let words: Vec<&str> = args
+        .iter()
+        .map(String::as_str)
+        .filter(|a| *a != "--json")
+        .collect();
+
+    let request = match words.as_slice() {
+        ["status"] => IpcRequest::Status,
+        ["timer", "start", duration, rest @ ..] => {
+            let name = match rest {
+                [] => None,
+                ["--name", name] => Some(name.to_string()),
+                _ => return Err("expected: timer start <duration> [--name <name>]".into()),
+            };
+            IpcRequest::TimerStart {
+                duration_seconds: ipc::parse_duration(duration)?,
+                name,
+            }
+        }
+        ["timer", "pause"] => IpcRequest::TimerPause,
+        ["timer", "resume"] => IpcRequest::TimerResume,
+        ["timer", "stop"] => IpcRequest::TimerStop,
+        ["pause", duration] => {
+            let seconds = ipc::parse_duration(duration)?;
+            IpcRequest::Pause {
+                minutes: seconds.div_ceil(60) as i32,
+            }
+        }
+        ["resume"] => IpcRequest::Resume,
+        ["sync"] => IpcRequest::Sync,
+        ["events"] | ["events", "--today"] => {
+            let midnight = now
+                .date_naive()
+                .and_time(NaiveTime::MIN)
+                .and_local_timezone(Local)
+                .earliest()
+                .unwrap_or(now);
+            IpcRequest::Events {
+                from: midnight.with_timezone(&Utc),
+                to: (midnight + chrono::Duration::days(1)).with_timezone(&Utc),
+            }
+        }
+        ["settings", "get"] => IpcRequest::SettingsGet { key: None },
+        ["settings", "get", key] => IpcRequest::SettingsGet {
+            key: Some(key.to_string()),
+        },
+        ["settings", "set", key, value] => IpcRequest::SettingsSet {
+            key: key.to_string(),
+            value: value.to_string(),
+        },
+        [] => return Err("missing command".into()),
+        _ => return Err(format!("unknown command: {}", words.join(" "))),
+    };
+    Ok(Cli { request, json })
+}
+
+fn local_time(value: &Value) -> Option<DateTime<Local>> {
+    let at = DateTime::parse_from_rfc3339(value.as_str()?).ok()?;
+    Some(at.with_timezone(&Local))
+}
+
+fn clock(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Human-readable `status` output.
+fn format_status(state: &Value, now: DateTime<Utc>) -> String {
+    let mut lines = Vec::new();
+    let phase = state["border"]["phase"].as_str().unwrap_or("unknown");
+    lines.push(format!("Border     {phase}"));
+
+    if let Some(until) = local_time(&state["pause"]["paused_until"]) {
+        lines.push(format!("Paused     until {}", until.format("%H:%M")));
+    }
+
+    if let Ok(timer) = serde_json::from_value::<TimerState>(state["timer"].clone()) {
+        if timer.is_active() {
+            lines.push(format!(
+                "Timer      {}, {} left",
+                timer.status,
+                clock(timer.remaining_seconds(now))
+            ));
+        }
+    }
+
+    let next = &state["nextEvent"];
+    match local_time(&next["startTime"]) {
+        Some(start) => {
+            let minutes = (start.with_timezone(&Utc) - now).num_minutes();
+            lines.push(format!(
+                "Next       {} {} (in {minutes} min)",
+                start.format("%H:%M"),
+                next["title"].as_str().unwrap_or("")
+            ));
+        }
+        None => lines.push("Next       no upcoming events".to_string()),
+    }
+
+    let calendars: Vec<String> = state["providers"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|p| {
+            let name = p["provider"].as_str().unwrap_or("?");
+            match p["status"]["accountName"].as_str() {
+                Some(account) => format!("{name} ({account})"),
+                None => name.to_string(),
+            }
+        })
+        .collect();
+    if calendars.is_empty() {
+        lines.push("Calendars  none connected".to_string());
+    } else {
+        lines.push(format!("Calendars  {}", calendars.join(", ")));
+    }
+    lines.join("\n")
+}
+
+/// Human-readable `events` output.
+fn format_events(events: &Value) -> String {
+    let lines: Vec<String> = events
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|e| {
+            let when = match (local_time(&e["startTime"]), local_time(&e["endTime"])) {
+                _ if e["isAllDay"].as_bool() == Some(true) => "all day    ".to_string(),
+                (Some(start), Some(end)) => {
+                    format!("{}-{}", start.format("%H:%M"), end.format("%H:%M"))
+                }
+                _ => "?          ".to_string(),
+            };
+            let ignored = if e["ignored"].as_bool() == Some(true) {
+                " (ignored)"
+            } else {
+                ""
+            };
+            format!("{when}  {}{ignored}", e["title"].as_str().unwrap_or(""))
+        })
+        .collect();
+    if lines.is_empty() {
+        "No events today".to_string()
+    } else {
+        lines.join("\n")
+    }
+}
+
+fn format_reply(request: &IpcRequest, reply: &Value) -> String {
+    match request {
+        IpcRequest::Status => format_status(reply, Utc::now()),
+        IpcRequest::Events { .. } => format_events(reply),
+        IpcRequest::TimerStart {
+            duration_seconds, ..
+        } => format!("Timer started ({})", clock(*duration_seconds as f64)),
+        IpcRequest::TimerPause => "Timer paused".to_string(),
+        IpcRequest::TimerResume => "Timer resumed".to_string(),
+        IpcRequest::TimerStop => "Timer stopped".to_string(),
+        IpcRequest::Pause { minutes } => format!("Border paused for {minutes} min"),
+        IpcRequest::Resume => "Border resumed".to_string(),
+        IpcRequest::Sync => "Calendars synced".to_string(),
+        IpcRequest::SettingsGet { key: Some(_) } => reply.as_str().unwrap_or("").to_string(),
+        IpcRequest::SettingsGet { key: None } => reply
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(k, v)| format!("{k} = {}", v.as_str().unwrap_or("")))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        IpcRequest::SettingsSet { key, value } => format!("{key} = {value}"),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{USAGE}");
+        return;
+    }
+    let cli = match parse_args(&args, Local::now()) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("morph-cli: {e}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    match ipc::send(&cli.request) {
+        Ok(reply) if cli.json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&reply).unwrap_or_default()
+            );
+        }
+        Ok(reply) => {
+            let text = format_reply(&cli.request, &reply);
+            if !text.is_empty() {
+                println!("{text}");
+            }
+        }
+        Err(e) => {
+            eprintln!("morph-cli: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    fn parse(line: &str) -> Result<Cli, String> {
+        parse_args(&args(line), Local::now())
+    }
+
+    #[test]
+    fn test_parses_commands() {
+        assert_eq!(parse("status").unwrap().request, IpcRequest::Status);
+        assert_eq!(
+            parse("timer start 25m").unwrap().request,
+            IpcRequest::TimerStart {
+                duration_seconds: 1500,
+                name: None
+            }
+        );
+        assert_eq!(
+            parse("timer start 1h --name Focus").unwrap().request,
+            IpcRequest::TimerStart {
+                duration_seconds: 3600,
+                name: Some("Focus".to_string())
+            }
+        );
+        assert_eq!(parse("timer stop").unwrap().request, IpcRequest::TimerStop);
+        assert_eq!(
+            parse("pause 30m").unwrap().request,
+            IpcRequest::Pause { minutes: 30 }
+        );
+        // Sub-minute pauses round up rather than resuming immediately.
+        assert_eq!(
+            parse("pause 90s").unwrap().request,
+            IpcRequest::Pause { minutes: 2 }
+        );
+        assert_eq!(
+            parse("settings set border_thickness thick")
+                .unwrap()
+                .request,
+            IpcRequest::SettingsSet {
+                key: "border_thickness".to_string(),
+                value: "thick".to_string()
+            }
+        );
+        assert_eq!(
+            parse("settings get").unwrap().request,
+            IpcRequest::SettingsGet { key: None }
+        );
+    }
+
+    #[test]
+    fn test_json_flag_anywhere() {
+        let cli = parse("--json status").unwrap();
+        assert!(cli.json);
+        assert_eq!(cli.request, IpcRequest::Status);
+        assert!(parse("sync --json").unwrap().json);
+        assert!(!parse("sync").unwrap().json);
+    }
+
+    #[test]
+    fn test_events_today_spans_one_local_day() {
+        let now = Local.with_ymd_and_hms(2026, 3, 2, 15, 30, 0).unwrap();
+        let cli = parse_args(&args("events --today"), now).unwrap();
+        let IpcRequest::Events { from, to } = cli.request else {
+            panic!("expected events request");
+        };
+        assert_eq!(
+            from.with_timezone(&Local).format("%F %T").to_string(),
+            "2026-03-02 00:00:00"
+        );
+        assert!(from <= now.with_timezone(&Utc) && now.with_timezone(&Utc) < to);
+    }
+
+    #[test]
+    fn test_rejects_bad_usage() {
+        assert!(parse("").is_err());
+        assert!(parse("timer start").is_err());
+        assert!(parse("timer start soon").is_err());
+        assert!(parse("pause").is_err());
+        assert!(parse("settings set key").is_err());
+        assert!(parse("launch").is_err());
+    }
+
+    #[test]
+    fn test_format_status() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 2, 9, 35, 0).unwrap();
+        let state = serde_json::json!({
+            "border": { "phase": "free-deep", "color": "#4A9B6E", "opacity": 0.25, "pulseSpeed": 0 },
+            "timer": {
+                "status": "running",
+                "durationSeconds": 1500,
+                "startedAt": "2026-03-02T09:30:00+00:00",
+                "pausedAt": null,
+                "elapsedBeforePause": 0.0
+            },
+            "pause": { "paused_until": null },
+            "nextEvent": {
+                "id": "evt-1",
+                "title": "Standup",
+                "startTime": "2026-03-02T10:00:00Z",
+                "endTime": "2026-03-02T10:15:00Z"
+            },
+            "providers": [
+                { "provider": "google", "status": { "connected": true, "accountName": "me@example.com" } }
+            ]
+        });
+
+        let text = format_status(&state, now);
+        assert!(text.contains("Border     free-deep"));
+        assert!(text.contains("Timer      running, 20:00 left"));
+        assert!(text.contains("Standup (in 25 min)"));
+        assert!(text.contains("Calendars  google (me@example.com)"));
+        assert!(!text.contains("Paused"));
+    }
+}
@@ -230,6 +230,10 @@ mod tests {
             calendar_id: None,
             provider_id: provider_id.to_string(),
             is_all_day: false,
+            series_id: None,
+            meeting_url: None,
+            location: None,
+            is_buffer: false,
         }
     }
 
@@ -289,6 +293,10 @@ mod tests {
             calendar_id: None,
             provider_id: "google-work".to_string(),
             is_all_day: false,
+            series_id: None,
+            meeting_url: None,
+            location: None,
+            is_buffer: false,
         };
         let shared_event_ms = CalendarEvent {
             id: "ms-shared".to_string(),
@@ -299,6 +307,10 @@ mod tests {
             calendar_id: None,
             provider_id: "ms-work".to_string(),
             is_all_day: false,
+            series_id: None,
+            meeting_url: None,
+            location: None,
+            is_buffer: false,
         };
 
         let mut agg = CalendarAggregator::new();
@@ -12,6 +12,7 @@ use objc2_foundation::NSDate;
 use std::sync::mpsc;
 
 use super::error::CalendarError;
+use super::links;
 use super::provider::CalendarProvider;
 use super::types::{CalendarEvent, ProviderType};
 
@@ -187,6 +188,9 @@ fn map_ek_event(event: &EKEvent, provider_id: &str) -> Option<CalendarEvent> {
     let title = unsafe { event.title().to_string() };
     let event_id = unsafe { event.calendarItemIdentifier().to_string() };
     let is_all_day = unsafe { event.isAllDay() };
+    // Occurrences of a recurring EventKit event share one calendar item
+    // identifier, which doubles as the series ID.
+    let series_id = unsafe { event.hasRecurrenceRules() }.then(|| event_id.clone());
 
     let start_ts = unsafe { event.startDate().timeIntervalSince1970() };
     let end_ts = unsafe { event.endDate().timeIntervalSince1970() };
@@ -200,6 +204,11 @@ fn map_ek_event(event: &EKEvent, provider_id: &str) -> Option<CalendarEvent> {
             .map(|cal| cal.calendarIdentifier().to_string())
     };
 
+    // EventKit has no conferencing field; links live in the location or notes.
+    let location = unsafe { event.location() }.map(|s| s.to_string());
+    let notes = unsafe { event.notes() }.map(|s| s.to_string());
+    let meeting_url = links::meeting_url(&[], &[location.as_deref(), notes.as_deref()]);
+
     Some(CalendarEvent {
         id: event_id,
         title,
@@ -209,6 +218,10 @@ fn map_ek_event(event: &EKEvent, provider_id: &str) -> Option<CalendarEvent> {
         calendar_id,
         provider_id: provider_id.to_string(),
         is_all_day,
+        series_id,
+        meeting_url,
+        location,
+        is_buffer: false,
     })
 }
 
@@ -249,6 +262,10 @@ mod tests {
             calendar_id: Some("personal-cal".to_string()),
             provider_id: "apple-calendar".to_string(),
             is_all_day: false,
+            series_id: None,
+            meeting_url: None,
+            location: None,
+            is_buffer: false,
         };
 
         let json = serde_json::to_string(&event).unwrap();
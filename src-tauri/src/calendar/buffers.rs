@@ -0,0 +1,447 @@
+//! Travel and prep buffers: time set aside before some events, like 15
+//! minutes before anything with a physical location, or 10 minutes before
+//! interviews. Each buffer is a virtual event, flagged `is_buffer`, that
+//! ends when its event starts. Buffers are added to the event list whenever
+//! it is emitted, so the overlay's phase engine and notifications warn ahead
+//! of the buffer instead of the event; the phase engine counts the buffer
+//! itself as lead-up time, not a meeting. Buffers are never cached: history,
+//! status sync, focus mode and the tray read the cache and only see real
+//! events, while the HTTP and MQTT event streams pass the flag on.
+
+use std::collections::HashSet;
+
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use super::links;
+use super::types::CalendarEvent;
+use crate::settings;
+
+pub const RULES_KEY: &str = "buffer_rules";
+
+/// `provider_id` of buffer events.
+pub const PROVIDER_ID: &str = "buffer";
+
+/// Buffer event IDs are this plus the ID of the event they precede.
+const ID_PREFIX: &str = "buffer:";
+
+const DEFAULT_LABEL: &str = "Prep";
+
+/// Longest buffer a rule may ask for.
+const MAX_MINUTES: u32 = 240;
+
+/// Locations that name an online meeting rather than a place.
+const ONLINE_LOCATIONS: &[&str] = &[
+    "google meet",
+    "microsoft teams",
+    "microsoft teams meeting",
+    "online",
+    "teams",
+    "virtual",
+    "webex",
+    "zoom",
+];
+
+/// Time to set aside before matching events. Every condition given must
+/// match; a rule needs at least one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BufferRule {
+    pub minutes: u32,
+    /// Names the buffer, as in "Travel: Offsite". Defaults to "Prep".
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Only events with a physical location.
+    #[serde(default)]
+    pub in_person: bool,
+    /// Case-insensitive text the title must contain.
+    #[serde(default)]
+    pub title_contains: Option<String>,
+    #[serde(default)]
+    pub calendar_id: Option<String>,
+    /// A single event, or every occurrence of a recurring series.
+    #[serde(default)]
+    pub event_id: Option<String>,
+}
+
+impl BufferRule {
+    fn label(&self) -> &str {
+        self.label
+            .as_deref()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .unwrap_or(DEFAULT_LABEL)
+    }
+
+    fn matches(&self, event: &CalendarEvent) -> bool {
+        (!self.in_person || has_physical_location(event))
+            && self.title_contains.as_ref().is_none_or(|text| {
+                event
+                    .title
+                    .to_lowercase()
+                    .contains(&text.trim().to_lowercase())
+            })
+            && self
+                .calendar_id
+                .as_ref()
+                .is_none_or(|id| event.calendar_id.as_ref() == Some(id))
+            && self
+                .event_id
+                .as_ref()
+                .is_none_or(|id| event.id == *id || event.series_id.as_ref() == Some(id))
+    }
+}
+
+/// Parse and validate the `buffer_rules` setting.
+pub fn parse_rules(json: &str) -> Result<Vec<BufferRule>, String> {
+    let rules: Vec<BufferRule> =
+        serde_json::from_str(json).map_err(|e| format!("invalid buffer rules: {e}"))?;
+    for rule in &rules {
+        if rule.minutes == 0 || rule.minutes > MAX_MINUTES {
+            return Err(format!(
+                "buffer '{}': minutes must be between 1 and {MAX_MINUTES}",
+                rule.label()
+            ));
+        }
+        let blank = |value: &Option<String>| value.as_ref().is_some_and(|v| v.trim().is_empty());
+        if blank(&rule.title_contains) || blank(&rule.calendar_id) || blank(&rule.event_id) {
+            return Err(format!(
+                "buffer '{}': conditions must not be empty",
+                rule.label()
+            ));
+        }
+        if !rule.in_person
+            && rule.title_contains.is_none()
+            && rule.calendar_id.is_none()
+            && rule.event_id.is_none()
+        {
+            return Err(format!(
+                "buffer '{}': needs inPerson, titleContains, calendarId or eventId",
+                rule.label()
+            ));
+        }
+    }
+    Ok(rules)
+}
+
+/// Whether an event takes place somewhere you'd travel to: it has a
+/// location that isn't a meeting link or the name of a meeting service.
+pub fn has_physical_location(event: &CalendarEvent) -> bool {
+    event.location.as_deref().is_some_and(|location| {
+        let location = location.trim();
+        !location.is_empty()
+            && !location.contains("://")
+            && links::find_in_text(location).is_none()
+            && !ONLINE_LOCATIONS.contains(&location.to_lowercase().as_str())
+    })
+}
+
+/// The ID of the event a buffer precedes, if `event` is a buffer.
+pub fn buffered_event_id(event: &CalendarEvent) -> Option<&str> {
+    event
+        .is_buffer
+        .then(|| event.id.strip_prefix(ID_PREFIX))
+        .flatten()
+}
+
+/// `events` plus a buffer before each event that matches a rule, sorted
+/// by start. The longest matching rule wins. A buffer starts no earlier
+/// than the end of the meeting before it, and is skipped if that leaves
+/// no time.
+pub fn with_buffers(
+    events: &[CalendarEvent],
+    rules: &[BufferRule],
+    ignored_calendar_ids: &[String],
+) -> Vec<CalendarEvent> {
+    let counts = |e: &CalendarEvent| {
+        !e.ignored
+            && !e.is_all_day
+            && !e.is_buffer
+            && !e
+                .calendar_id
+                .as_ref()
+                .is_some_and(|id| ignored_calendar_ids.contains(id))
+    };
+    let meetings: Vec<&CalendarEvent> = events.iter().filter(|e| counts(e)).collect();
+
+    let mut all = events.to_vec();
+    for event in &meetings {
+        // `rev` so the first of equally long rules wins.
+        let Some(rule) = rules
+            .iter()
+            .rev()
+            .filter(|r| r.matches(event))
+            .max_by_key(|r| r.minutes)
+        else {
+            continue;
+        };
+        let mut start = event.start_time - Duration::minutes(i64::from(rule.minutes));
+        for other in &meetings {
+            if other.start_time < event.start_time && other.end_time > start {
+                start = other.end_time.min(event.start_time);
+            }
+        }
+        if start >= event.start_time {
+            continue;
+        }
+        all.push(CalendarEvent {
+            id: format!("{ID_PREFIX}{}", event.id),
+            title: format!("{}: {}", rule.label(), event.title),
+            start_time: start,
+            end_time: event.start_time,
+            ignored: false,
+            calendar_id: event.calendar_id.clone(),
+            provider_id: PROVIDER_ID.to_string(),
+            is_all_day: false,
+            series_id: None,
+            meeting_url: None,
+            location: event.location.clone(),
+            is_buffer: true,
+        });
+    }
+    all.sort_by_key(|e| e.start_time);
+    all
+}
+
+/// IDs of the events that have a buffer in `events`.
+pub fn buffered_ids(events: &[CalendarEvent]) -> HashSet<&str> {
+    events.iter().filter_map(buffered_event_id).collect()
+}
+
+// --- Runtime ---
+
+/// Emit `calendar-events-update` with buffers from the current settings.
+pub async fn emit_events(app: &AppHandle, events: &[CalendarEvent]) -> Result<(), String> {
+    let settings = settings::current_settings(app).await;
+    let events = with_buffers(
+        events,
+        &settings.buffer_rules,
+        &settings.ignored_calendar_ids,
+    );
+    app.emit("calendar-events-update", &events)
+        .map_err(|e| e.to_string())
+}
+
+/// Re-emit the cached events after the rules change.
+pub async fn refresh(app: &AppHandle) {
+    let events = match settings::app_pool(app).await {
+        Ok(pool) => super::poller::load_cached_events(&pool).await,
+        Err(e) => Err(e),
+    };
+    let result = match events {
+        Ok(events) => emit_events(app, &events).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        eprintln!("[buffers] Failed to refresh events: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use chrono::{DateTime, TimeZone, Utc};
+
+    fn at(h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, h, m, 0).unwrap()
+    }
+
+    fn event(id: &str, title: &str, start: DateTime<Utc>, minutes: i64) -> CalendarEvent {
+        CalendarEvent {
+            title: title.to_string(),
+            ..test_support::event(id, start, start + Duration::minutes(minutes))
+        }
+    }
+
+    fn rule(minutes: u32, label: &str) -> BufferRule {
+        BufferRule {
+            minutes,
+            label: Some(label.to_string()),
+            in_person: false,
+            title_contains: None,
+            calendar_id: None,
+            event_id: None,
+        }
+    }
+
+    fn buffers(events: &[CalendarEvent], rules: &[BufferRule]) -> Vec<(String, String, u32)> {
+        with_buffers(events, rules, &["personal".to_string()])
+            .into_iter()
+            .filter(|e| e.is_buffer)
+            .map(|e| {
+                let start = e.start_time.format("%H%M").to_string();
+                (
+                    e.title,
+                    start,
+                    (e.end_time - e.start_time).num_minutes() as u32,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_has_physical_location() {
+        let cases = [
+            (Some("Building 4, Room 210"), true),
+            (Some("  "), false),
+            (Some("Microsoft Teams Meeting"), false),
+            (Some("Zoom"), false),
+            (Some("https://zoom.us/j/123"), false),
+            (Some("https://maps.example.com/hq"), false),
+            (None, false),
+        ];
+        for (location, expected) in cases {
+            let mut e = event("a", "Sync", at(10, 0), 30);
+            e.location = location.map(str::to_string);
+            assert_eq!(has_physical_location(&e), expected, "{location:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        let rules = parse_rules(
+            r#"[{"minutes": 15, "label": "Travel", "inPerson": true},
+                {"minutes": 10, "titleContains": "Interview"}]"#,
+        )
+        .unwrap();
+        assert_eq!(rules.len(), 2);
+        assert!(rules[0].in_person);
+        assert_eq!(rules[1].label(), "Prep");
+
+        for json in [
+            r#"[{"minutes": 0, "inPerson": true}]"#,
+            r#"[{"minutes": 500, "inPerson": true}]"#,
+            r#"[{"minutes": 10}]"#,
+            r#"[{"minutes": 10, "titleContains": " "}]"#,
+            r#"{"minutes": 10}"#,
+        ] {
+            assert!(parse_rules(json).is_err(), "{json}");
+        }
+    }
+
+    #[test]
+    fn test_with_buffers_matches_rules() {
+        let mut offsite = event("offsite", "Offsite planning", at(14, 0), 60);
+        offsite.location = Some("HQ, 5th floor".to_string());
+        let mut standup = event("standup_20260302", "Standup", at(9, 0), 15);
+        standup.series_id = Some("standup".to_string());
+        let mut personal = event("personal", "Interview prep call", at(16, 0), 30);
+        personal.calendar_id = Some("personal".to_string());
+        let events = vec![
+            offsite,
+            event("interview", "Interview: Sam", at(11, 0), 45),
+            standup,
+            personal,
+        ];
+        let rules = vec![
+            BufferRule {
+                in_person: true,
+                ..rule(15, "Travel")
+            },
+            BufferRule {
+                title_contains: Some("interview".to_string()),
+                ..rule(10, " ")
+            },
+            BufferRule {
+                event_id: Some("standup".to_string()),
+                ..rule(5, "Coffee")
+            },
+        ];
+
+        assert_eq!(
+            buffers(&events, &rules),
+            [
+                ("Coffee: Standup".to_string(), "0855".to_string(), 5),
+                ("Prep: Interview: Sam".to_string(), "1050".to_string(), 10),
+                (
+                    "Travel: Offsite planning".to_string(),
+                    "1345".to_string(),
+                    15
+                ),
+            ]
+        );
+        let all = with_buffers(&events, &rules, &[]);
+        assert!(all.windows(2).all(|w| w[0].start_time <= w[1].start_time));
+        let ids = buffered_ids(&all);
+        assert!(ids.contains("interview") && ids.contains("personal"));
+
+        // The overlay tells buffers from meetings by the flag.
+        let json = serde_json::to_value(&all).unwrap();
+        let flagged: Vec<_> = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|e| e["isBuffer"] == true)
+            .map(|e| e["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            flagged,
+            [
+                "buffer:standup_20260302",
+                "buffer:interview",
+                "buffer:offsite",
+                "buffer:personal"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_longest_rule_wins() {
+        let mut offsite = event("offsite", "Interview onsite", at(14, 0), 60);
+        offsite.location = Some("HQ".to_string());
+        let rules = vec![
+            BufferRule {
+                title_contains: Some("Interview".to_string()),
+                ..rule(10, "Prep")
+            },
+            BufferRule {
+                in_person: true,
+                ..rule(20, "Travel")
+            },
+        ];
+        assert_eq!(
+            buffers(&[offsite], &rules),
+            [(
+                "Travel: Interview onsite".to_string(),
+                "1340".to_string(),
+                20
+            )]
+        );
+    }
+
+    #[test]
+    fn test_buffer_starts_after_previous_meeting() {
+        let rules = vec![BufferRule {
+            title_contains: Some("Review".to_string()),
+            ..rule(15, "Prep")
+        }];
+        let ends_inside = vec![
+            event("sync", "Sync", at(9, 0), 50),
+            event("review", "Review", at(10, 0), 30),
+        ];
+        assert_eq!(
+            buffers(&ends_inside, &rules),
+            [("Prep: Review".to_string(), "0950".to_string(), 10)]
+        );
+
+        // Back to back: no time left for the buffer.
+        let back_to_back = vec![
+            event("sync", "Sync", at(9, 0), 60),
+            event("review", "Review", at(10, 0), 30),
+        ];
+        assert!(buffers(&back_to_back, &rules).is_empty());
+
+        // Ignored events and calendars don't take buffer time.
+        let mut ignored = event("sync", "Sync", at(9, 0), 60);
+        ignored.ignored = true;
+        let mut personal = event("gym", "Gym", at(9, 30), 30);
+        personal.calendar_id = Some("personal".to_string());
+        let events = vec![ignored, personal, event("review", "Review", at(10, 0), 30)];
+        assert_eq!(
+            buffers(&events, &rules),
+            [("Prep: Review".to_string(), "0945".to_string(), 15)]
+        );
+    }
+}
@@ -7,6 +7,7 @@ use sha2::{Digest, Sha256};
 use url::Url;
 
 use super::error::CalendarError;
+use super::links;
 use super::provider::CalendarProvider;
 use super::types::{CalendarEvent, ProviderType};
 
@@ -92,6 +93,42 @@ struct GoogleEvent {
     start: Option<EventDateTime>,
     end: Option<EventDateTime>,
     status: Option<String>,
+    /// Set on instances of a recurring event; the ID of the recurring event.
+    #[serde(rename = "recurringEventId")]
+    recurring_event_id: Option<String>,
+    /// Google Meet link, if the event has one.
+    #[serde(rename = "hangoutLink")]
+    hangout_link: Option<String>,
+    /// Meet or add-on conferencing (Zoom, Teams, ...) attached to the event.
+    #[serde(rename = "conferenceData")]
+    conference_data: Option<ConferenceData>,
+    location: Option<String>,
+    /// HTML or plain text.
+    description: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ConferenceData {
+    #[serde(rename = "entryPoints", default)]
+    entry_points: Vec<EntryPoint>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct EntryPoint {
+    /// "video", "phone", "sip" or "more".
+    #[serde(rename = "entryPointType")]
+    entry_point_type: Option<String>,
+    uri: Option<String>,
+}
+
+impl ConferenceData {
+    /// The video entry point's URL.
+    fn video_uri(&self) -> Option<&str> {
+        self.entry_points
+            .iter()
+            .find(|e| e.entry_point_type.as_deref() == Some("video"))
+            .and_then(|e| e.uri.as_deref())
+    }
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -561,6 +598,16 @@ fn map_google_event(
 
     let start = parse_event_datetime(&event.start)?;
     let end = parse_event_datetime(&event.end)?;
+    let meeting_url = links::meeting_url(
+        &[
+            event.hangout_link.as_deref(),
+            event
+                .conference_data
+                .as_ref()
+                .and_then(ConferenceData::video_uri),
+        ],
+        &[event.location.as_deref(), event.description.as_deref()],
+    );
 
     Some(CalendarEvent {
         id: event.id.unwrap_or_default(),
@@ -571,6 +618,10 @@ fn map_google_event(
         calendar_id: Some(calendar_id.to_string()),
         provider_id: provider_id.to_string(),
         is_all_day: is_all_day(&event.start),
+        series_id: event.recurring_event_id,
+        meeting_url,
+        location: event.location,
+        is_buffer: false,
     })
 }
 
@@ -824,6 +875,11 @@ mod tests {
                 date: None,
             }),
             status: Some("confirmed".to_string()),
+            recurring_event_id: None,
+            hangout_link: None,
+            conference_data: None,
+            location: None,
+            description: None,
         }
     }
 
@@ -845,6 +901,11 @@ mod tests {
                 date: Some(end_date.to_string()),
             }),
             status: Some("confirmed".to_string()),
+            recurring_event_id: None,
+            hangout_link: None,
+            conference_data: None,
+            location: None,
+            description: None,
         }
     }
 
@@ -875,6 +936,77 @@ mod tests {
         assert_eq!(ce.calendar_id, Some("primary".to_string()));
     }
 
+    #[test]
+    fn maps_recurring_instance_to_series() {
+        let mut ge = make_google_event(
+            "weekly_20260220T100000Z",
+            "Weekly sync",
+            "2026-02-20T10:00:00Z",
+            "2026-02-20T10:30:00Z",
+        );
+        ge.recurring_event_id = Some("weekly".to_string());
+
+        let ce = map_google_event(ge, "google-test@gmail.com", "primary").unwrap();
+        assert_eq!(ce.series_id.as_deref(), Some("weekly"));
+    }
+
+    #[test]
+    fn maps_meet_link() {
+        let mut ge = make_google_event(
+            "evt-meet",
+            "Design review",
+            "2026-02-20T10:00:00Z",
+            "2026-02-20T10:30:00Z",
+        );
+        ge.hangout_link = Some("https://meet.google.com/abc-defg-hij".to_string());
+
+        let ce = map_google_event(ge, "google-test@gmail.com", "primary").unwrap();
+        assert_eq!(
+            ce.meeting_url.as_deref(),
+            Some("https://meet.google.com/abc-defg-hij")
+        );
+    }
+
+    #[test]
+    fn maps_conference_data_and_description_links() {
+        let ge: GoogleEvent = serde_json::from_str(
+            r#"{
+                "id": "evt-zoom",
+                "summary": "Customer call",
+                "start": { "dateTime": "2026-02-20T10:00:00Z" },
+                "end": { "dateTime": "2026-02-20T10:30:00Z" },
+                "conferenceData": {
+                    "entryPoints": [
+                        { "entryPointType": "phone", "uri": "tel:+1-555-0100" },
+                        { "entryPointType": "video", "uri": "https://acme.zoom.us/j/123" }
+                    ]
+                },
+                "description": "Backup: https://meet.jit.si/backup"
+            }"#,
+        )
+        .unwrap();
+        let ce = map_google_event(ge, "google-test@gmail.com", "primary").unwrap();
+        assert_eq!(
+            ce.meeting_url.as_deref(),
+            Some("https://acme.zoom.us/j/123")
+        );
+
+        let mut ge = make_google_event(
+            "evt-webex",
+            "Vendor sync",
+            "2026-02-20T10:00:00Z",
+            "2026-02-20T10:30:00Z",
+        );
+        ge.location = Some("Room 4B".to_string());
+        ge.description =
+            Some("<p>Join <a href=\"https://acme.webex.com/meet/pat\">here</a></p>".to_string());
+        let ce = map_google_event(ge, "google-test@gmail.com", "primary").unwrap();
+        assert_eq!(
+            ce.meeting_url.as_deref(),
+            Some("https://acme.webex.com/meet/pat")
+        );
+    }
+
     #[test]
     fn maps_all_day_event() {
         let ge = make_all_day_google_event("evt-2", "Holiday", "2026-02-20", "2026-02-21");
@@ -907,6 +1039,11 @@ mod tests {
                 date: None,
             }),
             status: Some("cancelled".to_string()),
+            recurring_event_id: None,
+            hangout_link: None,
+            conference_data: None,
+            location: None,
+            description: None,
         };
 
         assert!(map_google_event(ge, "google-test@gmail.com", "primary").is_none());
@@ -926,6 +1063,11 @@ mod tests {
                 date: None,
             }),
             status: Some("confirmed".to_string()),
+            recurring_event_id: None,
+            hangout_link: None,
+            conference_data: None,
+            location: None,
+            description: None,
         };
 
         let ce = map_google_event(ge, "google-test@gmail.com", "primary").unwrap();
@@ -943,6 +1085,11 @@ mod tests {
                 date: None,
             }),
             status: Some("confirmed".to_string()),
+            recurring_event_id: None,
+            hangout_link: None,
+            conference_data: None,
+            location: None,
+            description: None,
         };
 
         assert!(map_google_event(ge, "google-test@gmail.com", "primary").is_none());
@@ -0,0 +1,152 @@
+//! Conferencing links for events. Providers report some directly (Google
+//! `hangoutLink` and `conferenceData`, Graph `onlineMeeting.joinUrl`); the
+//! rest are pasted into the location or description, so those are searched
+//! for Zoom, Meet, Teams, Webex and Jitsi links.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// Join links by service. A URL runs until whitespace, a quote or an
+/// angle bracket, so links inside HTML attributes stop at the attribute.
+static PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    [
+        // Zoom: https://zoom.us/j/123, https://acme.zoom.us/my/room, zoomgov.com
+        r#"https://(?:[\w-]+\.)*zoom(?:gov)?\.(?:us|com)/(?:j|my|w|s|wc/join)/[^\s<>"']+"#,
+        r"https://meet\.google\.com/[a-z]{3}-[a-z]{4}-[a-z]{3}",
+        r#"https://teams\.(?:microsoft|live)\.com/(?:l/meetup-join|meet)/[^\s<>"']+"#,
+        // Webex: https://acme.webex.com/meet/pat, https://acme.webex.com/acme/j.php?MTID=...
+        r#"https://[\w-]+\.webex\.com/[^\s<>"']+"#,
+        r#"https://meet\.jit\.si/[^\s<>"']+"#,
+    ]
+    .iter()
+    .map(|pattern| Regex::new(&format!("(?i){pattern}")).expect("meeting link pattern is valid"))
+    .collect()
+});
+
+/// Characters that end a sentence rather than a URL: "join at https://zoom.us/j/1."
+const TRAILING: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '}'];
+
+/// The first conferencing link in free text, by position.
+pub fn find_in_text(text: &str) -> Option<String> {
+    let found = PATTERNS
+        .iter()
+        .filter_map(|pattern| pattern.find(text))
+        .min_by_key(|m| m.start())?;
+    let url = found.as_str().trim_end_matches(TRAILING);
+    // Descriptions are often HTML.
+    Some(url.replace("&amp;", "&"))
+}
+
+/// The link to join an event: the first link the provider reported, else
+/// the first one found in `text` (location, then description).
+pub fn meeting_url(provided: &[Option<&str>], text: &[Option<&str>]) -> Option<String> {
+    provided
+        .iter()
+        .flatten()
+        .map(|url| url.trim())
+        .find(|url| url.starts_with("https://") || url.starts_with("http://"))
+        .map(str::to_string)
+        .or_else(|| text.iter().flatten().find_map(|text| find_in_text(text)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_in_text() {
+        let cases: &[(&str, Option<&str>)] = &[
+            ("https://zoom.us/j/123456789", Some("https://zoom.us/j/123456789")),
+            (
+                "Join: https://acme.zoom.us/j/987?pwd=AbC123.",
+                Some("https://acme.zoom.us/j/987?pwd=AbC123"),
+            ),
+            (
+                "Room: https://acme.zoom.us/my/pat",
+                Some("https://acme.zoom.us/my/pat"),
+            ),
+            (
+                "https://agency.zoomgov.com/j/1611234567",
+                Some("https://agency.zoomgov.com/j/1611234567"),
+            ),
+            (
+                "Video: meet.google.com/abc-defg-hij or https://meet.google.com/abc-defg-hij",
+                Some("https://meet.google.com/abc-defg-hij"),
+            ),
+            (
+                "<a href=\"https://teams.microsoft.com/l/meetup-join/19%3ameeting_X%40thread.v2/0?context=%7b%7d\">Join</a>",
+                Some("https://teams.microsoft.com/l/meetup-join/19%3ameeting_X%40thread.v2/0?context=%7b%7d"),
+            ),
+            (
+                "(https://acme.webex.com/meet/pat)",
+                Some("https://acme.webex.com/meet/pat"),
+            ),
+            (
+                "https://acme.webex.com/acme/j.php?MTID=m1&amp;from=cal",
+                Some("https://acme.webex.com/acme/j.php?MTID=m1&from=cal"),
+            ),
+            (
+                "Call: https://meet.jit.si/WeeklySync, dial-in below",
+                Some("https://meet.jit.si/WeeklySync"),
+            ),
+            ("HTTPS://ZOOM.US/J/42", Some("HTTPS://ZOOM.US/J/42")),
+            // The earliest link wins, whatever the service.
+            (
+                "Jitsi https://meet.jit.si/a, fallback https://zoom.us/j/1",
+                Some("https://meet.jit.si/a"),
+            ),
+            // Not join links.
+            ("https://zoom.us/pricing", None),
+            ("http://zoom.us/j/123", None),
+            ("Conference room 4B", None),
+            ("https://example.com/meet/abc", None),
+            ("", None),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(find_in_text(text).as_deref(), *expected, "text: {text:?}");
+        }
+    }
+
+    #[test]
+    fn test_meeting_url_prefers_provider_link() {
+        /// (provider links, text, expected)
+        type Case<'a> = (
+            &'a [Option<&'a str>],
+            &'a [Option<&'a str>],
+            Option<&'a str>,
+        );
+        let zoom = Some("Zoom: https://zoom.us/j/1");
+        let cases: &[Case] = &[
+            (
+                &[Some("https://meet.google.com/abc-defg-hij")],
+                &[zoom],
+                Some("https://meet.google.com/abc-defg-hij"),
+            ),
+            (
+                &[None, Some("https://teams.microsoft.com/l/meetup-join/x")],
+                &[zoom],
+                Some("https://teams.microsoft.com/l/meetup-join/x"),
+            ),
+            // Blank or non-web provider values fall through to the text.
+            (
+                &[Some(""), Some("tel:+1-555-0100")],
+                &[zoom],
+                Some("https://zoom.us/j/1"),
+            ),
+            (
+                &[None],
+                &[Some("Room 4B"), Some("https://meet.jit.si/x")],
+                Some("https://meet.jit.si/x"),
+            ),
+            (&[None], &[None, Some("Agenda attached")], None),
+        ];
+        for (provided, text, expected) in cases {
+            assert_eq!(
+                meeting_url(provided, text).as_deref(),
+                *expected,
+                "provided: {provided:?}, text: {text:?}"
+            );
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
 use rand::Rng;
 use reqwest::Client;
 use serde::Deserialize;
@@ -8,6 +9,7 @@ use sha2::{Digest, Sha256};
 use url::Url;
 
 use super::error::CalendarError;
+use super::links;
 use super::provider::CalendarProvider;
 use super::types::{CalendarEvent, ProviderType};
 
@@ -15,14 +17,102 @@ const MS_CLIENT_ID: &str = "PLACEHOLDER_AZURE_CLIENT_ID";
 const AUTH_URL: &str = "https://login.microsoftonline.com/common/oauth2/v2.0/authorize";
 const TOKEN_URL: &str = "https://login.microsoftonline.com/common/oauth2/v2.0/token";
 const CALENDAR_VIEW_URL: &str = "https://graph.microsoft.com/v1.0/me/calendarView";
+const WORKING_HOURS_URL: &str = "https://graph.microsoft.com/v1.0/me/mailboxSettings/workingHours";
 const REDIRECT_PORT_START: u16 = 19857;
 const REDIRECT_PORT_END: u16 = 19867;
 const SCOPES: &str = "Calendars.Read offline_access";
 const KEYRING_SERVICE: &str = "com.morph.microsoft-oauth";
+/// Scopes for the separate Teams status grant; kept out of `SCOPES` so
+/// existing calendar tokens keep refreshing without new consent.
+const PRESENCE_SCOPES: &str = "Presence.ReadWrite offline_access";
+pub const PRESENCE_KEYRING_SERVICE: &str = "com.morph.microsoft-presence";
+/// Scopes for reading Outlook working hours, asked for on first import for
+/// the same reason.
+const MAILBOX_SCOPES: &str = "MailboxSettings.Read offline_access";
+pub const MAILBOX_KEYRING_SERVICE: &str = "com.morph.microsoft-mailbox";
+
+/// Windows time zone IDs, as Outlook usually reports them, and the IANA zone
+/// each stands for (from CLDR's `windowsZones.xml`).
+const WINDOWS_ZONES: &[(&str, &str)] = &[
+    ("Dateline Standard Time", "Etc/GMT+12"),
+    ("UTC-11", "Etc/GMT+11"),
+    ("Hawaiian Standard Time", "Pacific/Honolulu"),
+    ("Alaskan Standard Time", "America/Anchorage"),
+    ("Pacific Standard Time (Mexico)", "America/Tijuana"),
+    ("Pacific Standard Time", "America/Los_Angeles"),
+    ("US Mountain Standard Time", "America/Phoenix"),
+    ("Mountain Standard Time (Mexico)", "America/Mazatlan"),
+    ("Mountain Standard Time", "America/Denver"),
+    ("Central America Standard Time", "America/Guatemala"),
+    ("Central Standard Time", "America/Chicago"),
+    ("Central Standard Time (Mexico)", "America/Mexico_City"),
+    ("Canada Central Standard Time", "America/Regina"),
+    ("SA Pacific Standard Time", "America/Bogota"),
+    ("Eastern Standard Time", "America/New_York"),
+    ("Eastern Standard Time (Mexico)", "America/Cancun"),
+    ("US Eastern Standard Time", "America/Indiana/Indianapolis"),
+    ("Venezuela Standard Time", "America/Caracas"),
+    ("Atlantic Standard Time", "America/Halifax"),
+    ("SA Western Standard Time", "America/La_Paz"),
+    ("Pacific SA Standard Time", "America/Santiago"),
+    ("Newfoundland Standard Time", "America/St_Johns"),
+    ("E. South America Standard Time", "America/Sao_Paulo"),
+    ("Argentina Standard Time", "America/Argentina/Buenos_Aires"),
+    ("SA Eastern Standard Time", "America/Cayenne"),
+    ("Greenland Standard Time", "America/Nuuk"),
+    ("UTC-02", "Etc/GMT+2"),
+    ("Azores Standard Time", "Atlantic/Azores"),
+    ("Cape Verde Standard Time", "Atlantic/Cape_Verde"),
+    ("UTC", "Etc/UTC"),
+    ("GMT Standard Time", "Europe/London"),
+    ("Greenwich Standard Time", "Atlantic/Reykjavik"),
+    ("W. Europe Standard Time", "Europe/Berlin"),
+    ("Central Europe Standard Time", "Europe/Budapest"),
+    ("Romance Standard Time", "Europe/Paris"),
+    ("Central European Standard Time", "Europe/Warsaw"),
+    ("W. Central Africa Standard Time", "Africa/Lagos"),
+    ("GTB Standard Time", "Europe/Bucharest"),
+    ("Middle East Standard Time", "Asia/Beirut"),
+    ("Egypt Standard Time", "Africa/Cairo"),
+    ("South Africa Standard Time", "Africa/Johannesburg"),
+    ("FLE Standard Time", "Europe/Kyiv"),
+    ("Israel Standard Time", "Asia/Jerusalem"),
+    ("E. Europe Standard Time", "Europe/Chisinau"),
+    ("Arabic Standard Time", "Asia/Baghdad"),
+    ("Arab Standard Time", "Asia/Riyadh"),
+    ("Russian Standard Time", "Europe/Moscow"),
+    ("E. Africa Standard Time", "Africa/Nairobi"),
+    ("Turkey Standard Time", "Europe/Istanbul"),
+    ("Iran Standard Time", "Asia/Tehran"),
+    ("Arabian Standard Time", "Asia/Dubai"),
+    ("Afghanistan Standard Time", "Asia/Kabul"),
+    ("Pakistan Standard Time", "Asia/Karachi"),
+    ("India Standard Time", "Asia/Kolkata"),
+    ("Sri Lanka Standard Time", "Asia/Colombo"),
+    ("Nepal Standard Time", "Asia/Kathmandu"),
+    ("Bangladesh Standard Time", "Asia/Dhaka"),
+    ("SE Asia Standard Time", "Asia/Bangkok"),
+    ("China Standard Time", "Asia/Shanghai"),
+    ("Singapore Standard Time", "Asia/Singapore"),
+    ("Taipei Standard Time", "Asia/Taipei"),
+    ("W. Australia Standard Time", "Australia/Perth"),
+    ("Tokyo Standard Time", "Asia/Tokyo"),
+    ("Korea Standard Time", "Asia/Seoul"),
+    ("Cen. Australia Standard Time", "Australia/Adelaide"),
+    ("AUS Central Standard Time", "Australia/Darwin"),
+    ("E. Australia Standard Time", "Australia/Brisbane"),
+    ("AUS Eastern Standard Time", "Australia/Sydney"),
+    ("Tasmania Standard Time", "Australia/Hobart"),
+    ("New Zealand Standard Time", "Pacific/Auckland"),
+    ("Fiji Standard Time", "Pacific/Fiji"),
+    ("Tonga Standard Time", "Pacific/Tongatapu"),
+];
 
 /// Microsoft Graph Calendar provider using OAuth2 PKCE.
 pub struct MicrosoftCalendarProvider {
     client_id: String,
+    scopes: &'static str,
+    keyring_service: &'static str,
     account_email: Option<String>,
     access_token: Option<String>,
     refresh_token: Option<String>,
@@ -45,6 +135,30 @@ struct MsEvent {
     start: Option<MsDateTimeZone>,
     end: Option<MsDateTimeZone>,
     is_all_day: Option<bool>,
+    /// Set on occurrences of a recurring event; the ID of the series master.
+    series_master_id: Option<String>,
+    /// Set for Teams (and other online) meetings.
+    online_meeting: Option<MsOnlineMeeting>,
+    location: Option<MsLocation>,
+    /// Requested as plain text (see the `Prefer` header on calendar view).
+    body: Option<MsItemBody>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MsOnlineMeeting {
+    join_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MsLocation {
+    display_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MsItemBody {
+    content: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -55,6 +169,39 @@ struct MsDateTimeZone {
     time_zone: Option<String>,
 }
 
+/// Working hours from Outlook mailbox settings. Times are in `time_zone`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MsWorkingHours {
+    /// "monday", "tuesday", ...
+    pub days_of_week: Vec<String>,
+    /// "08:00:00.0000000"
+    pub start_time: String,
+    pub end_time: String,
+    pub time_zone: MsTimeZone,
+}
+
+/// A Graph `timeZoneBase`: a Windows or IANA zone name, or the name of a
+/// custom zone.
+#[derive(Debug, Deserialize)]
+pub struct MsTimeZone {
+    pub name: String,
+}
+
+impl MsTimeZone {
+    /// The zone, if it is a known Windows or IANA name. Custom zones aren't
+    /// supported.
+    pub fn tz(&self) -> Option<Tz> {
+        let name = self.name.trim();
+        WINDOWS_ZONES
+            .iter()
+            .find(|(windows, _)| windows.eq_ignore_ascii_case(name))
+            .map_or(name, |(_, iana)| iana)
+            .parse()
+            .ok()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct TokenResponse {
     access_token: String,
@@ -81,6 +228,8 @@ impl MicrosoftCalendarProvider {
     pub fn new() -> Self {
         Self {
             client_id: MS_CLIENT_ID.to_string(),
+            scopes: SCOPES,
+            keyring_service: KEYRING_SERVICE,
             account_email: None,
             access_token: None,
             refresh_token: None,
@@ -89,9 +238,29 @@ impl MicrosoftCalendarProvider {
         }
     }
 
+    /// A Graph client for Teams presence. Uses the same OAuth flow with the
+    /// presence scope and keeps its tokens apart from the calendar grant.
+    pub fn for_presence() -> Self {
+        Self {
+            scopes: PRESENCE_SCOPES,
+            keyring_service: PRESENCE_KEYRING_SERVICE,
+            ..Self::new()
+        }
+    }
+
+    /// A Graph client for mailbox settings (working hours), with its own
+    /// grant like [`Self::for_presence`].
+    pub fn for_mailbox() -> Self {
+        Self {
+            scopes: MAILBOX_SCOPES,
+            keyring_service: MAILBOX_KEYRING_SERVICE,
+            ..Self::new()
+        }
+    }
+
     /// Try to load tokens from the system keyring on startup.
     pub fn load_stored_tokens(&mut self) -> Result<(), CalendarError> {
-        let entry = keyring::Entry::new(KEYRING_SERVICE, "refresh_token").map_err(|e| {
+        let entry = keyring::Entry::new(self.keyring_service, "refresh_token").map_err(|e| {
             CalendarError::ProviderError {
                 provider: "microsoft".into(),
                 message: format!("keyring error: {e}"),
@@ -102,7 +271,8 @@ impl MicrosoftCalendarProvider {
             Ok(token) => {
                 self.refresh_token = Some(token);
                 // Also try to load email
-                if let Ok(email_entry) = keyring::Entry::new(KEYRING_SERVICE, "account_email") {
+                if let Ok(email_entry) = keyring::Entry::new(self.keyring_service, "account_email")
+                {
                     if let Ok(email) = email_entry.get_password() {
                         self.account_email = Some(email);
                     }
@@ -120,12 +290,13 @@ impl MicrosoftCalendarProvider {
     /// Store tokens in the system keyring.
     fn store_tokens(&self) -> Result<(), CalendarError> {
         if let Some(ref rt) = self.refresh_token {
-            let entry = keyring::Entry::new(KEYRING_SERVICE, "refresh_token").map_err(|e| {
-                CalendarError::ProviderError {
-                    provider: "microsoft".into(),
-                    message: format!("keyring error: {e}"),
-                }
-            })?;
+            let entry =
+                keyring::Entry::new(self.keyring_service, "refresh_token").map_err(|e| {
+                    CalendarError::ProviderError {
+                        provider: "microsoft".into(),
+                        message: format!("keyring error: {e}"),
+                    }
+                })?;
             entry
                 .set_password(rt)
                 .map_err(|e| CalendarError::ProviderError {
@@ -135,12 +306,13 @@ impl MicrosoftCalendarProvider {
         }
 
         if let Some(ref email) = self.account_email {
-            let entry = keyring::Entry::new(KEYRING_SERVICE, "account_email").map_err(|e| {
-                CalendarError::ProviderError {
-                    provider: "microsoft".into(),
-                    message: format!("keyring error: {e}"),
-                }
-            })?;
+            let entry =
+                keyring::Entry::new(self.keyring_service, "account_email").map_err(|e| {
+                    CalendarError::ProviderError {
+                        provider: "microsoft".into(),
+                        message: format!("keyring error: {e}"),
+                    }
+                })?;
             entry
                 .set_password(email)
                 .map_err(|e| CalendarError::ProviderError {
@@ -173,13 +345,14 @@ impl MicrosoftCalendarProvider {
         redirect_uri: &str,
         code_challenge: &str,
         state: &str,
+        scopes: &str,
     ) -> String {
         let mut url = Url::parse(AUTH_URL).expect("valid auth URL constant");
         url.query_pairs_mut()
             .append_pair("client_id", client_id)
             .append_pair("response_type", "code")
             .append_pair("redirect_uri", redirect_uri)
-            .append_pair("scope", SCOPES)
+            .append_pair("scope", scopes)
             .append_pair("code_challenge_method", "S256")
             .append_pair("code_challenge", code_challenge)
             .append_pair("state", state)
@@ -198,8 +371,13 @@ impl MicrosoftCalendarProvider {
         let (server, port) = Self::start_redirect_server()?;
         let redirect_uri = format!("http://localhost:{port}");
 
-        let auth_url =
-            Self::build_auth_url(&self.client_id, &redirect_uri, &code_challenge, &state);
+        let auth_url = Self::build_auth_url(
+            &self.client_id,
+            &redirect_uri,
+            &code_challenge,
+            &state,
+            self.scopes,
+        );
 
         // Open browser
         open::that(&auth_url).map_err(|e| {
@@ -305,7 +483,7 @@ impl MicrosoftCalendarProvider {
             ("code", code),
             ("redirect_uri", redirect_uri),
             ("code_verifier", code_verifier),
-            ("scope", SCOPES),
+            ("scope", self.scopes),
         ];
 
         let resp = self
@@ -367,13 +545,48 @@ impl MicrosoftCalendarProvider {
     }
 
     /// Check if the access token is expired or about to expire (within 60s).
-    #[allow(dead_code)] // Used in tests and will be called by the poller
     fn is_token_expired(&self) -> bool {
         match self.token_expiry {
             Some(expiry) => Utc::now() + Duration::seconds(60) >= expiry,
             None => true,
         }
     }
+
+    /// Return a usable Graph access token, refreshing it first if needed.
+    pub async fn access_token(&mut self) -> Result<String, CalendarError> {
+        if self.access_token.is_none() || self.is_token_expired() {
+            self.refresh_token().await?;
+        }
+        self.access_token
+            .clone()
+            .ok_or(CalendarError::NotAuthenticated)
+    }
+
+    /// Fetch the working hours set in Outlook. Needs a [`Self::for_mailbox`]
+    /// client.
+    pub async fn fetch_working_hours(&mut self) -> Result<MsWorkingHours, CalendarError> {
+        let access_token = self.access_token().await?;
+
+        let resp = self
+            .http_client
+            .get(WORKING_HOURS_URL)
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| CalendarError::NetworkError(format!("MS Graph request failed: {e}")))?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(CalendarError::FetchFailed(format!(
+                "MS Graph API error {status}: {body}"
+            )));
+        }
+
+        resp.json().await.map_err(|e| {
+            CalendarError::DeserializationError(format!("failed to parse MS Graph response: {e}"))
+        })
+    }
 }
 
 #[async_trait]
@@ -405,10 +618,15 @@ impl CalendarProvider for MicrosoftCalendarProvider {
             .http_client
             .get(CALENDAR_VIEW_URL)
             .bearer_auth(access_token)
+            .header("Prefer", "outlook.body-content-type=\"text\"")
             .query(&[
                 ("startDateTime", from.to_rfc3339()),
                 ("endDateTime", to.to_rfc3339()),
-                ("$select", "id,subject,start,end,isAllDay".to_string()),
+                (
+                    "$select",
+                    "id,subject,start,end,isAllDay,seriesMasterId,onlineMeeting,location,body"
+                        .to_string(),
+                ),
                 ("$orderby", "start/dateTime".to_string()),
                 ("$top", "250".to_string()),
             ])
@@ -448,7 +666,7 @@ impl CalendarProvider for MicrosoftCalendarProvider {
             ("client_id", self.client_id.as_str()),
             ("grant_type", "refresh_token"),
             ("refresh_token", refresh_token.as_str()),
-            ("scope", SCOPES),
+            ("scope", self.scopes),
         ];
 
         let resp = self
@@ -525,6 +743,20 @@ fn convert_ms_event(ms_event: MsEvent, provider_id: &str) -> Option<CalendarEven
     let end_time = parse_ms_datetime(&end_str)?;
 
     let is_all_day = ms_event.is_all_day.unwrap_or(false);
+    let series_id = ms_event.series_master_id;
+    let meeting_url = links::meeting_url(
+        &[ms_event
+            .online_meeting
+            .as_ref()
+            .and_then(|m| m.join_url.as_deref())],
+        &[
+            ms_event
+                .location
+                .as_ref()
+                .and_then(|l| l.display_name.as_deref()),
+            ms_event.body.as_ref().and_then(|b| b.content.as_deref()),
+        ],
+    );
 
     Some(CalendarEvent {
         id,
@@ -535,6 +767,10 @@ fn convert_ms_event(ms_event: MsEvent, provider_id: &str) -> Option<CalendarEven
         calendar_id: None,
         provider_id: provider_id.to_string(),
         is_all_day,
+        series_id,
+        meeting_url,
+        location: ms_event.location.and_then(|l| l.display_name),
+        is_buffer: false,
     })
 }
 
@@ -600,6 +836,7 @@ mod tests {
             "http://localhost:19857",
             "test-challenge",
             "test-state",
+            SCOPES,
         );
 
         let url = Url::parse(&url_str).expect("valid URL");
@@ -678,6 +915,10 @@ mod tests {
                 time_zone: Some("UTC".to_string()),
             }),
             is_all_day: Some(false),
+            series_master_id: None,
+            online_meeting: None,
+            location: None,
+            body: None,
         };
 
         let event = convert_ms_event(ms_event, "ms-user@outlook.com").unwrap();
@@ -694,6 +935,67 @@ mod tests {
         assert!(!event.is_all_day);
         assert!(!event.ignored);
         assert_eq!(event.provider_id, "ms-user@outlook.com");
+        assert_eq!(event.series_id, None);
+    }
+
+    #[test]
+    fn test_convert_ms_event_occurrence_keeps_series() {
+        let ms_event: MsEvent = serde_json::from_str(
+            r#"{
+                "id": "occurrence-1",
+                "subject": "Weekly sync",
+                "start": { "dateTime": "2026-02-20T10:00:00.0000000", "timeZone": "UTC" },
+                "end": { "dateTime": "2026-02-20T10:30:00.0000000", "timeZone": "UTC" },
+                "isAllDay": false,
+                "seriesMasterId": "master-1"
+            }"#,
+        )
+        .unwrap();
+
+        let event = convert_ms_event(ms_event, "ms-user@outlook.com").unwrap();
+        assert_eq!(event.series_id.as_deref(), Some("master-1"));
+    }
+
+    #[test]
+    fn test_convert_ms_event_online_meeting_link() {
+        let ms_event: MsEvent = serde_json::from_str(
+            r#"{
+                "id": "teams-1",
+                "subject": "Planning",
+                "start": { "dateTime": "2026-02-20T10:00:00.0000000", "timeZone": "UTC" },
+                "end": { "dateTime": "2026-02-20T10:30:00.0000000", "timeZone": "UTC" },
+                "onlineMeeting": { "joinUrl": "https://teams.microsoft.com/l/meetup-join/abc" }
+            }"#,
+        )
+        .unwrap();
+
+        let event = convert_ms_event(ms_event, "ms-user@outlook.com").unwrap();
+        assert_eq!(
+            event.meeting_url.as_deref(),
+            Some("https://teams.microsoft.com/l/meetup-join/abc")
+        );
+    }
+
+    #[test]
+    fn test_convert_ms_event_link_in_location_or_body() {
+        let ms_event: MsEvent = serde_json::from_str(
+            r#"{
+                "id": "zoom-1",
+                "subject": "Customer call",
+                "start": { "dateTime": "2026-02-20T10:00:00.0000000", "timeZone": "UTC" },
+                "end": { "dateTime": "2026-02-20T10:30:00.0000000", "timeZone": "UTC" },
+                "onlineMeeting": null,
+                "location": { "displayName": "Zoom" },
+                "body": { "contentType": "text", "content": "Join Zoom Meeting\r\nhttps://acme.zoom.us/j/555?pwd=x\r\n" }
+            }"#,
+        )
+        .unwrap();
+
+        let event = convert_ms_event(ms_event, "ms-user@outlook.com").unwrap();
+        assert_eq!(
+            event.meeting_url.as_deref(),
+            Some("https://acme.zoom.us/j/555?pwd=x")
+        );
     }
 
     #[test]
@@ -710,6 +1012,10 @@ mod tests {
                 time_zone: Some("UTC".to_string()),
             }),
             is_all_day: Some(true),
+            series_master_id: None,
+            online_meeting: None,
+            location: None,
+            body: None,
         };
 
         let event = convert_ms_event(ms_event, "ms-user@outlook.com").unwrap();
@@ -731,6 +1037,10 @@ mod tests {
                 time_zone: Some("UTC".to_string()),
             }),
             is_all_day: None,
+            series_master_id: None,
+            online_meeting: None,
+            location: None,
+            body: None,
         };
 
         let event = convert_ms_event(ms_event, "ms-user@outlook.com").unwrap();
@@ -749,6 +1059,10 @@ mod tests {
                 time_zone: Some("UTC".to_string()),
             }),
             is_all_day: None,
+            series_master_id: None,
+            online_meeting: None,
+            location: None,
+            body: None,
         };
 
         assert!(convert_ms_event(ms_event, "provider").is_none());
@@ -765,6 +1079,10 @@ mod tests {
             }),
             end: None,
             is_all_day: None,
+            series_master_id: None,
+            online_meeting: None,
+            location: None,
+            body: None,
         };
 
         assert!(convert_ms_event(ms_event, "provider").is_none());
@@ -815,6 +1133,41 @@ mod tests {
         assert!(provider.is_token_expired());
     }
 
+    #[test]
+    fn test_presence_provider_keeps_its_own_grant() {
+        let provider = MicrosoftCalendarProvider::for_presence();
+        assert_eq!(provider.scopes, "Presence.ReadWrite offline_access");
+        assert_eq!(provider.keyring_service, "com.morph.microsoft-presence");
+        assert_eq!(MicrosoftCalendarProvider::new().scopes, SCOPES);
+
+        let mailbox = MicrosoftCalendarProvider::for_mailbox();
+        assert_eq!(mailbox.scopes, "MailboxSettings.Read offline_access");
+        assert_eq!(mailbox.keyring_service, "com.morph.microsoft-mailbox");
+    }
+
+    #[test]
+    fn test_time_zone_names() {
+        let zone = |name: &str| {
+            MsTimeZone {
+                name: name.to_string(),
+            }
+            .tz()
+        };
+        assert_eq!(
+            zone("Pacific Standard Time"),
+            Some(chrono_tz::America::Los_Angeles)
+        );
+        assert_eq!(
+            zone("w. europe standard time"),
+            Some(chrono_tz::Europe::Berlin)
+        );
+        assert_eq!(zone("Europe/Paris"), Some(chrono_tz::Europe::Paris));
+        assert_eq!(zone("Customized Time Zone"), None);
+        for (windows, _) in WINDOWS_ZONES {
+            assert!(zone(windows).is_some(), "{windows}");
+        }
+    }
+
     #[test]
     fn test_provider_type_is_microsoft() {
         let provider = MicrosoftCalendarProvider::new();
@@ -1,8 +1,10 @@
 pub mod aggregator;
 #[cfg(target_os = "macos")]
 pub mod apple;
+pub mod buffers;
 pub mod error;
 pub mod google;
+pub mod links;
 pub mod microsoft;
 pub mod poller;
 pub mod provider;
@@ -65,7 +65,7 @@ impl CalendarPoller {
                             last_fingerprints
                                 .insert(event.id.clone(), EventFingerprint::from(event));
                         }
-                        let _ = app.emit("calendar-events-update", &cached);
+                        let _ = super::buffers::emit_events(&app, &cached).await;
                     }
                 }
                 Err(e) => {
@@ -113,8 +113,13 @@ impl CalendarPoller {
                     .collect();
 
                 if new_fingerprints != last_fingerprints {
-                    // Events changed — emit update and cache
-                    let _ = app.emit("calendar-events-update", &events);
+                    // Events changed — emit update (with buffers) and cache.
+                    // Meetings that ended drop out of the fetch, so keep them
+                    // in history first.
+                    let _ = super::buffers::emit_events(&app, &events).await;
+                    if let Err(e) = crate::history::record_ended_meetings(&pool, Utc::now()).await {
+                        eprintln!("[poller] Failed to record ended meetings: {e}");
+                    }
                     if let Err(e) = cache_events(&pool, &events).await {
                         eprintln!("[poller] Failed to cache events: {e}");
                     }
@@ -125,25 +130,11 @@ impl CalendarPoller {
     }
 }
 
-/// Read the poll interval from the settings table. Falls back to 60 seconds.
+/// Read the poll interval from the settings table. Falls back to the
+/// schema default if the stored value is missing or invalid.
 async fn read_poll_interval(app: &AppHandle) -> Duration {
-    let pool = match get_sqlite_pool(app).await {
-        Ok(p) => p,
-        Err(_) => return Duration::from_secs(60),
-    };
-
-    let row = sqlx::query("SELECT value FROM settings WHERE key = 'poll_interval_seconds'")
-        .fetch_optional(&pool)
-        .await;
-
-    match row {
-        Ok(Some(r)) => {
-            let val: String = r.get("value");
-            let secs: u64 = val.parse().unwrap_or(60);
-            Duration::from_secs(secs)
-        }
-        _ => Duration::from_secs(60),
-    }
+    let settings = crate::settings::current_settings(app).await;
+    Duration::from_secs(u64::from(settings.poll_interval_seconds))
 }
 
 /// Get the SQLite pool from tauri-plugin-sql's managed DbInstances.
@@ -161,9 +152,10 @@ async fn get_sqlite_pool(app: &AppHandle) -> Result<SqlitePool, String> {
 }
 
 /// Load cached calendar events from SQLite.
-async fn load_cached_events(pool: &SqlitePool) -> Result<Vec<CalendarEvent>, String> {
+pub(crate) async fn load_cached_events(pool: &SqlitePool) -> Result<Vec<CalendarEvent>, String> {
     let rows = sqlx::query(
-        "SELECT id, provider_id, calendar_id, title, start_time, end_time, is_all_day, ignored
+        "SELECT id, provider_id, calendar_id, title, start_time, end_time, is_all_day, ignored,
+                series_id, meeting_url, location
          FROM calendar_events
          ORDER BY start_time",
     )
@@ -190,6 +182,10 @@ async fn load_cached_events(pool: &SqlitePool) -> Result<Vec<CalendarEvent>, Str
                 end_time,
                 is_all_day: is_all_day_int != 0,
                 ignored: ignored_int != 0,
+                series_id: row.get("series_id"),
+                meeting_url: row.get("meeting_url"),
+                location: row.get("location"),
+                is_buffer: false,
             })
         })
         .collect();
@@ -209,8 +205,8 @@ async fn cache_events(pool: &SqlitePool, events: &[CalendarEvent]) -> Result<(),
 
     for event in events {
         sqlx::query(
-            "INSERT INTO calendar_events (id, provider_id, calendar_id, title, start_time, end_time, is_all_day, ignored, fetched_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, datetime('now'))",
+            "INSERT INTO calendar_events (id, provider_id, calendar_id, title, start_time, end_time, is_all_day, ignored, series_id, meeting_url, location, fetched_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, datetime('now'))",
         )
         .bind(&event.id)
         .bind(&event.provider_id)
@@ -220,6 +216,9 @@ async fn cache_events(pool: &SqlitePool, events: &[CalendarEvent]) -> Result<(),
         .bind(event.end_time.to_rfc3339())
         .bind(event.is_all_day as i32)
         .bind(event.ignored as i32)
+        .bind(&event.series_id)
+        .bind(&event.meeting_url)
+        .bind(&event.location)
         .execute(&mut *tx)
         .await
         .map_err(|e| e.to_string())?;
@@ -253,6 +252,10 @@ mod tests {
             calendar_id: None,
             provider_id: provider_id.to_string(),
             is_all_day: false,
+            series_id: None,
+            meeting_url: None,
+            location: None,
+            is_buffer: false,
         }
     }
 
@@ -346,7 +349,10 @@ mod tests {
                 end_time TEXT NOT NULL,
                 is_all_day INTEGER DEFAULT 0,
                 ignored INTEGER DEFAULT 0,
-                fetched_at TEXT NOT NULL DEFAULT (datetime('now'))
+                fetched_at TEXT NOT NULL DEFAULT (datetime('now')),
+                series_id TEXT,
+                meeting_url TEXT,
+                location TEXT
             )",
         )
         .execute(&pool)
@@ -364,6 +370,10 @@ mod tests {
                 calendar_id: Some("cal-work".to_string()),
                 provider_id: "google".to_string(),
                 is_all_day: true,
+                series_id: Some("series-1".to_string()),
+                meeting_url: Some("https://meet.google.com/abc-defg-hij".to_string()),
+                location: Some("Room 4B".to_string()),
+                is_buffer: false,
             },
         ];
 
@@ -381,6 +391,12 @@ mod tests {
         assert!(loaded[0].is_all_day);
         assert!(loaded[0].ignored);
         assert_eq!(loaded[0].calendar_id, Some("cal-work".to_string()));
+        assert_eq!(loaded[0].series_id, Some("series-1".to_string()));
+        assert_eq!(
+            loaded[0].meeting_url.as_deref(),
+            Some("https://meet.google.com/abc-defg-hij")
+        );
+        assert_eq!(loaded[0].location.as_deref(), Some("Room 4B"));
 
         assert_eq!(loaded[1].id, "evt-1");
         assert_eq!(loaded[1].title, "Stand-up");
@@ -404,7 +420,10 @@ mod tests {
                 end_time TEXT NOT NULL,
                 is_all_day INTEGER DEFAULT 0,
                 ignored INTEGER DEFAULT 0,
-                fetched_at TEXT NOT NULL DEFAULT (datetime('now'))
+                fetched_at TEXT NOT NULL DEFAULT (datetime('now')),
+                series_id TEXT,
+                meeting_url TEXT,
+                location TEXT
             )",
         )
         .execute(&pool)
@@ -444,7 +463,10 @@ mod tests {
                 end_time TEXT NOT NULL,
                 is_all_day INTEGER DEFAULT 0,
                 ignored INTEGER DEFAULT 0,
-                fetched_at TEXT NOT NULL DEFAULT (datetime('now'))
+                fetched_at TEXT NOT NULL DEFAULT (datetime('now')),
+                series_id TEXT,
+                meeting_url TEXT,
+                location TEXT
             )",
         )
         .execute(&pool)
@@ -13,6 +13,34 @@ pub struct CalendarEvent {
     pub calendar_id: Option<String>,
     pub provider_id: String,
     pub is_all_day: bool,
+    /// Identifies the recurring series this occurrence belongs to, if any.
+    #[serde(default)]
+    pub series_id: Option<String>,
+    /// Conferencing link (Meet, Teams, ...) reported by the provider.
+    #[serde(default)]
+    pub meeting_url: Option<String>,
+    /// Where the event takes place, as entered: a room, an address, or
+    /// sometimes just "Zoom".
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Travel or prep time before another event, added by
+    /// [`super::buffers`] rather than fetched from a calendar.
+    #[serde(default)]
+    pub is_buffer: bool,
+}
+
+impl CalendarEvent {
+    /// Whether the event counts as a meeting: timed, not ignored, and not on
+    /// one of `ignored_calendar_ids`. Every backend feature that reacts to
+    /// meetings uses this, matching the overlay.
+    pub fn counts_as_meeting(&self, ignored_calendar_ids: &[String]) -> bool {
+        !self.ignored
+            && !self.is_all_day
+            && !self
+                .calendar_id
+                .as_ref()
+                .is_some_and(|id| ignored_calendar_ids.contains(id))
+    }
 }
 
 /// The type of calendar provider.
@@ -40,6 +68,10 @@ mod tests {
             calendar_id: Some("cal-work".to_string()),
             provider_id: "google-user@gmail.com".to_string(),
             is_all_day: false,
+            series_id: None,
+            meeting_url: None,
+            location: None,
+            is_buffer: false,
         };
 
         let json = serde_json::to_string(&event).unwrap();
@@ -62,6 +94,10 @@ mod tests {
             calendar_id: None,
             provider_id: "apple-personal".to_string(),
             is_all_day: false,
+            series_id: None,
+            meeting_url: None,
+            location: None,
+            is_buffer: false,
         };
 
         let json = serde_json::to_string(&event).unwrap();
@@ -85,4 +121,28 @@ mod tests {
         let json = serde_json::to_string(&ProviderType::Apple).unwrap();
         assert_eq!(json, "\"apple\"");
     }
+
+    #[test]
+    fn counts_only_timed_unignored_meetings() {
+        let start = Utc.with_ymd_and_hms(2026, 2, 19, 10, 0, 0).unwrap();
+        let meeting = crate::test_support::event("1", start, start + chrono::Duration::hours(1));
+        let ignored_calendars = ["personal".to_string()];
+        assert!(meeting.counts_as_meeting(&ignored_calendars));
+
+        let ignored = CalendarEvent {
+            ignored: true,
+            ..meeting.clone()
+        };
+        let all_day = CalendarEvent {
+            is_all_day: true,
+            ..meeting.clone()
+        };
+        let personal = CalendarEvent {
+            calendar_id: Some("personal".to_string()),
+            ..meeting
+        };
+        for event in [ignored, all_day, personal] {
+            assert!(!event.counts_as_meeting(&ignored_calendars));
+        }
+    }
 }
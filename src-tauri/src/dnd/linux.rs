@@ -0,0 +1,117 @@
+//! GNOME Do Not Disturb: the `show-banners` key in
+//! `org.gnome.desktop.notifications`, read and written with `gsettings` so
+//! the change goes through dconf over D-Bus like the Settings app's toggle.
+
+use std::process::Command;
+
+use super::{DndBackend, DndError};
+
+const SCHEMA: &str = "org.gnome.desktop.notifications";
+const KEY: &str = "show-banners";
+
+pub struct GnomeBanners {
+    program: String,
+}
+
+impl Default for GnomeBanners {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GnomeBanners {
+    pub fn new() -> Self {
+        Self::with_program("gsettings")
+    }
+
+    /// Use another `gsettings` executable (for tests).
+    pub fn with_program(program: &str) -> Self {
+        Self {
+            program: program.to_string(),
+        }
+    }
+
+    fn run(&self, args: &[&str]) -> Result<String, DndError> {
+        let output = Command::new(&self.program)
+            .args(args)
+            .output()
+            .map_err(|e| DndError::Unavailable(format!("{}: {e}", self.program)))?;
+        if !output.status.success() {
+            return Err(DndError::Failed(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+impl DndBackend for GnomeBanners {
+    fn name(&self) -> &'static str {
+        "GNOME"
+    }
+
+    /// Do Not Disturb is on when banners are hidden.
+    fn is_enabled(&self) -> Result<bool, DndError> {
+        match self.run(&["get", SCHEMA, KEY])?.as_str() {
+            "true" => Ok(false),
+            "false" => Ok(true),
+            other => Err(DndError::Failed(format!("unexpected {KEY} value: {other}"))),
+        }
+    }
+
+    fn set_enabled(&self, enabled: bool) -> Result<(), DndError> {
+        let banners = if enabled { "false" } else { "true" };
+        self.run(&["set", SCHEMA, KEY, banners]).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+
+    /// A stand-in `gsettings` that keeps the key in a file next to it.
+    fn fake_gsettings(name: &str, initial: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("morph-dnd-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("value"), initial).unwrap();
+        let script = dir.join("gsettings");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\n\
+             dir=$(dirname \"$0\")\n\
+             [ \"$2 $3\" = \"org.gnome.desktop.notifications show-banners\" ] || exit 1\n\
+             case \"$1\" in\n\
+               get) cat \"$dir/value\" ;;\n\
+               set) printf '%s\\n' \"$4\" > \"$dir/value\" ;;\n\
+               *) exit 1 ;;\n\
+             esac\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        script
+    }
+
+    #[test]
+    fn test_switches_show_banners() {
+        let script = fake_gsettings("switch", "true\n");
+        let gnome = GnomeBanners::with_program(script.to_str().unwrap());
+
+        assert!(!gnome.is_enabled().unwrap());
+        gnome.set_enabled(true).unwrap();
+        assert!(gnome.is_enabled().unwrap());
+        let stored = std::fs::read_to_string(script.with_file_name("value")).unwrap();
+        assert_eq!(stored.trim(), "false");
+
+        gnome.set_enabled(false).unwrap();
+        assert!(!gnome.is_enabled().unwrap());
+        std::fs::remove_dir_all(script.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_reports_missing_gsettings() {
+        let gnome = GnomeBanners::with_program("/nonexistent/gsettings");
+        assert!(matches!(gnome.is_enabled(), Err(DndError::Unavailable(_))));
+    }
+}
@@ -0,0 +1,95 @@
+//! macOS Focus.
+//!
+//! macOS has no public API to switch Focus, so Morph runs two Shortcuts the
+//! user creates once with the "Set Focus" action: "Morph Focus On" and
+//! "Morph Focus Off". The current state comes from the Focus assertions file,
+//! which lists the active Focus modes; when it can't be read (it needs Full
+//! Disk Access on recent releases) Focus is assumed off.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use super::{DndBackend, DndError};
+
+const SHORTCUT_ON: &str = "Morph Focus On";
+const SHORTCUT_OFF: &str = "Morph Focus Off";
+
+pub struct FocusShortcuts {
+    assertions: Option<PathBuf>,
+}
+
+impl Default for FocusShortcuts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FocusShortcuts {
+    pub fn new() -> Self {
+        Self {
+            assertions: std::env::var_os("HOME")
+                .map(|home| PathBuf::from(home).join("Library/DoNotDisturb/DB/Assertions.json")),
+        }
+    }
+}
+
+/// Whether an assertions file lists any active Focus.
+fn focus_active(assertions: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(assertions)
+        .ok()
+        .and_then(|v| {
+            v["data"].as_array().map(|data| {
+                data.iter().any(|d| {
+                    d["storeAssertionRecords"]
+                        .as_array()
+                        .is_some_and(|records| !records.is_empty())
+                })
+            })
+        })
+        .unwrap_or(false)
+}
+
+impl DndBackend for FocusShortcuts {
+    fn name(&self) -> &'static str {
+        "macOS Focus"
+    }
+
+    fn is_enabled(&self) -> Result<bool, DndError> {
+        Ok(self
+            .assertions
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .is_some_and(|text| focus_active(&text)))
+    }
+
+    fn set_enabled(&self, enabled: bool) -> Result<(), DndError> {
+        let shortcut = if enabled { SHORTCUT_ON } else { SHORTCUT_OFF };
+        let output = Command::new("shortcuts")
+            .args(["run", shortcut])
+            .output()
+            .map_err(|e| DndError::Unavailable(format!("shortcuts: {e}")))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(DndError::Failed(format!(
+                "shortcut \"{shortcut}\": {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_focus_active() {
+        assert!(focus_active(
+            r#"{"data":[{"storeAssertionRecords":[{"assertionDetails":{"assertionDetailsModeIdentifier":"com.apple.donotdisturb.mode.default"}}]}]}"#
+        ));
+        assert!(!focus_active(r#"{"data":[{"storeAssertionRecords":[]}]}"#));
+        assert!(!focus_active(r#"{"data":[{}]}"#));
+        assert!(!focus_active("not json"));
+    }
+}
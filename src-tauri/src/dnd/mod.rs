@@ -0,0 +1,306 @@
+//! Do Not Disturb sync.
+//!
+//! While in a meeting (`in-session-*` phases) or while a focus timer runs,
+//! the OS notification focus mode is turned on; afterwards it is put back
+//! the way the user had it. If Do Not Disturb was already on, Morph leaves
+//! it alone.
+//!
+//! Each platform has a [`DndBackend`]:
+//! - Linux: GNOME's `org.gnome.desktop.notifications show-banners`
+//! - macOS: Focus, through user-created Shortcuts
+//! - Windows: Focus Assist
+
+#[cfg(target_os = "linux")]
+pub mod linux;
+#[cfg(target_os = "macos")]
+pub mod macos;
+#[cfg(target_os = "windows")]
+pub mod windows;
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{command, AppHandle, Listener, Manager};
+use thiserror::Error;
+
+use crate::border_state::BorderState;
+use crate::settings;
+use crate::timer::{TimerService, TimerState};
+
+pub const ENABLED_KEY: &str = "dnd_sync";
+
+#[derive(Debug, Error)]
+pub enum DndError {
+    #[error("Do Not Disturb is not available: {0}")]
+    Unavailable(String),
+
+    #[error("failed to change Do Not Disturb: {0}")]
+    Failed(String),
+}
+
+/// Reads and switches the OS Do Not Disturb mode.
+pub trait DndBackend: Send {
+    fn name(&self) -> &'static str;
+
+    fn is_enabled(&self) -> Result<bool, DndError>;
+
+    fn set_enabled(&self, enabled: bool) -> Result<(), DndError>;
+}
+
+/// The backend for this platform, if there is one.
+#[cfg(target_os = "linux")]
+pub fn platform_backend() -> Option<Box<dyn DndBackend>> {
+    Some(Box::new(linux::GnomeBanners::new()))
+}
+
+#[cfg(target_os = "macos")]
+pub fn platform_backend() -> Option<Box<dyn DndBackend>> {
+    Some(Box::new(macos::FocusShortcuts::new()))
+}
+
+#[cfg(target_os = "windows")]
+pub fn platform_backend() -> Option<Box<dyn DndBackend>> {
+    Some(Box::new(windows::FocusAssist))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn platform_backend() -> Option<Box<dyn DndBackend>> {
+    None
+}
+
+/// Whether Do Not Disturb should be on for this phase and timer. Break
+/// timers don't count.
+pub fn wanted(phase: &str, timer: &TimerState) -> bool {
+    phase.starts_with("in-session") || timer.is_focusing()
+}
+
+/// Switches Do Not Disturb on and off and remembers what to restore.
+pub struct DndSync {
+    backend: Option<Box<dyn DndBackend>>,
+    /// While Morph holds Do Not Disturb: whether it was on before.
+    restore: Option<bool>,
+}
+
+impl Default for DndSync {
+    fn default() -> Self {
+        Self::new(platform_backend())
+    }
+}
+
+impl DndSync {
+    pub fn new(backend: Option<Box<dyn DndBackend>>) -> Self {
+        Self {
+            backend,
+            restore: None,
+        }
+    }
+
+    /// Whether Morph currently holds Do Not Disturb.
+    pub fn active(&self) -> bool {
+        self.restore.is_some()
+    }
+
+    /// Turn Do Not Disturb on when `want` starts, and restore the previous
+    /// state when it stops. A failed switch is retried on the next update.
+    pub fn update(&mut self, want: bool) -> Result<(), DndError> {
+        let Some(backend) = &self.backend else {
+            return Ok(());
+        };
+        match (want, self.restore) {
+            (true, None) => {
+                let before = backend.is_enabled()?;
+                if !before {
+                    backend.set_enabled(true)?;
+                }
+                self.restore = Some(before);
+            }
+            (false, Some(before)) => {
+                if !before {
+                    backend.set_enabled(false)?;
+                }
+                self.restore = None;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+// --- Runtime ---
+
+fn update(app: &AppHandle, want: bool) {
+    let managed = app.state::<Mutex<DndSync>>();
+    let Ok(mut dnd) = managed.lock() else {
+        return;
+    };
+    if let Err(e) = dnd.update(want) {
+        eprintln!("[dnd] {e}");
+    }
+}
+
+/// Switch Do Not Disturb to match the current phase, timer and setting.
+pub async fn sync(app: &AppHandle) {
+    let enabled = settings::current_settings(app).await.dnd_sync;
+    let phase = app
+        .state::<Mutex<BorderState>>()
+        .lock()
+        .map(|b| b.phase.clone())
+        .unwrap_or_default();
+    let timer = app
+        .state::<Mutex<TimerService>>()
+        .lock()
+        .map(|t| t.state().clone())
+        .unwrap_or_default();
+    let want = enabled && wanted(&phase, &timer);
+
+    // Backends run commands or system calls; keep them off the async workers.
+    let app = app.clone();
+    let _ = tauri::async_runtime::spawn_blocking(move || update(&app, want)).await;
+}
+
+/// Put Do Not Disturb back before the app exits.
+pub fn shutdown(app: &AppHandle) {
+    update(app, false);
+}
+
+/// Follow phase and timer changes.
+pub fn start(app: &tauri::App) {
+    for name in ["border-state-update", "timer-state-update"] {
+        let handle = app.handle().clone();
+        app.listen(name, move |_| {
+            let handle = handle.clone();
+            tauri::async_runtime::spawn(async move {
+                sync(&handle).await;
+            });
+        });
+    }
+}
+
+// --- Tauri commands ---
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DndStatus {
+    /// The platform backend, or `null` where Do Not Disturb can't be synced.
+    pub backend: Option<&'static str>,
+    /// Whether Morph has Do Not Disturb on right now.
+    pub active: bool,
+}
+
+#[command]
+pub fn get_dnd_status(app: AppHandle) -> Result<DndStatus, String> {
+    let managed = app.state::<Mutex<DndSync>>();
+    let dnd = managed.lock().map_err(|e| e.to_string())?;
+    Ok(DndStatus {
+        backend: dnd.backend.as_ref().map(|b| b.name()),
+        active: dnd.active(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// Records switches and fails when asked to.
+    #[derive(Clone, Default)]
+    struct MockDnd {
+        enabled: Arc<Mutex<bool>>,
+        calls: Arc<Mutex<Vec<bool>>>,
+        fail: Arc<Mutex<bool>>,
+    }
+
+    impl DndBackend for MockDnd {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        fn is_enabled(&self) -> Result<bool, DndError> {
+            Ok(*self.enabled.lock().unwrap())
+        }
+
+        fn set_enabled(&self, enabled: bool) -> Result<(), DndError> {
+            if *self.fail.lock().unwrap() {
+                return Err(DndError::Failed("denied".to_string()));
+            }
+            self.calls.lock().unwrap().push(enabled);
+            *self.enabled.lock().unwrap() = enabled;
+            Ok(())
+        }
+    }
+
+    fn sync_with(mock: &MockDnd) -> DndSync {
+        DndSync::new(Some(Box::new(mock.clone())))
+    }
+
+    #[test]
+    fn test_wanted_in_session_or_timer_running() {
+        let idle = TimerState::default();
+        let running = TimerState::running(1500, chrono::Utc::now());
+        assert!(wanted("in-session-early", &idle));
+        assert!(wanted("in-session-late", &idle));
+        assert!(!wanted("warning-imminent", &idle));
+        assert!(!wanted("free-deep", &idle));
+        assert!(wanted("free-deep", &running));
+
+        let on_break = TimerState {
+            is_break: true,
+            ..running
+        };
+        assert!(!wanted("free-deep", &on_break));
+        assert!(wanted("in-session-mid", &on_break));
+    }
+
+    #[test]
+    fn test_turns_on_and_restores_off() {
+        let mock = MockDnd::default();
+        let mut dnd = sync_with(&mock);
+
+        dnd.update(true).unwrap();
+        dnd.update(true).unwrap();
+        assert!(dnd.active());
+        assert!(*mock.enabled.lock().unwrap());
+
+        dnd.update(false).unwrap();
+        dnd.update(false).unwrap();
+        assert!(!dnd.active());
+        assert!(!*mock.enabled.lock().unwrap());
+        assert_eq!(*mock.calls.lock().unwrap(), vec![true, false]);
+    }
+
+    #[test]
+    fn test_leaves_user_dnd_alone() {
+        let mock = MockDnd::default();
+        *mock.enabled.lock().unwrap() = true;
+        let mut dnd = sync_with(&mock);
+
+        dnd.update(true).unwrap();
+        assert!(dnd.active());
+        dnd.update(false).unwrap();
+
+        assert!(mock.calls.lock().unwrap().is_empty());
+        assert!(*mock.enabled.lock().unwrap());
+    }
+
+    #[test]
+    fn test_failed_switch_is_retried() {
+        let mock = MockDnd::default();
+        *mock.fail.lock().unwrap() = true;
+        let mut dnd = sync_with(&mock);
+
+        assert!(dnd.update(true).is_err());
+        assert!(!dnd.active());
+
+        *mock.fail.lock().unwrap() = false;
+        dnd.update(true).unwrap();
+        assert!(dnd.active());
+        assert_eq!(*mock.calls.lock().unwrap(), vec![true]);
+    }
+
+    #[test]
+    fn test_without_backend_does_nothing() {
+        let mut dnd = DndSync::new(None);
+        dnd.update(true).unwrap();
+        assert!(!dnd.active());
+    }
+}
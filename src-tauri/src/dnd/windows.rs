@@ -0,0 +1,95 @@
+//! Windows Focus Assist ("Do not disturb" on Windows 11).
+//!
+//! There is no public API for it; like the Settings app, Morph reads and
+//! writes the quiet-hours profile through its WNF state in ntdll. "On" is
+//! the priority-only profile.
+
+use std::ffi::c_void;
+use std::ptr;
+
+use super::{DndBackend, DndError};
+
+/// `WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED`
+const QUIET_HOURS_STATE: u64 = 0x0D83_063E_A3BF_1C75;
+
+const PROFILE_OFF: u32 = 0;
+const PROFILE_PRIORITY_ONLY: u32 = 1;
+
+#[link(name = "ntdll")]
+extern "system" {
+    fn NtQueryWnfStateData(
+        state_name: *const u64,
+        type_id: *const c_void,
+        explicit_scope: *const c_void,
+        change_stamp: *mut u32,
+        buffer: *mut c_void,
+        buffer_size: *mut u32,
+    ) -> i32;
+
+    fn NtUpdateWnfStateData(
+        state_name: *const u64,
+        buffer: *const c_void,
+        length: u32,
+        type_id: *const c_void,
+        explicit_scope: *const c_void,
+        matching_change_stamp: u32,
+        check_stamp: u32,
+    ) -> i32;
+}
+
+pub struct FocusAssist;
+
+impl DndBackend for FocusAssist {
+    fn name(&self) -> &'static str {
+        "Focus Assist"
+    }
+
+    fn is_enabled(&self) -> Result<bool, DndError> {
+        let mut profile: u32 = 0;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let mut stamp = 0;
+        // SAFETY: every pointer refers to a live local of the advertised size.
+        let status = unsafe {
+            NtQueryWnfStateData(
+                &QUIET_HOURS_STATE,
+                ptr::null(),
+                ptr::null(),
+                &mut stamp,
+                &mut profile as *mut u32 as *mut c_void,
+                &mut size,
+            )
+        };
+        if status < 0 {
+            return Err(DndError::Unavailable(format!(
+                "NtQueryWnfStateData: {status:#x}"
+            )));
+        }
+        Ok(profile != PROFILE_OFF)
+    }
+
+    fn set_enabled(&self, enabled: bool) -> Result<(), DndError> {
+        let profile = if enabled {
+            PROFILE_PRIORITY_ONLY
+        } else {
+            PROFILE_OFF
+        };
+        // SAFETY: the buffer is a live u32 and the length matches it.
+        let status = unsafe {
+            NtUpdateWnfStateData(
+                &QUIET_HOURS_STATE,
+                &profile as *const u32 as *const c_void,
+                std::mem::size_of::<u32>() as u32,
+                ptr::null(),
+                ptr::null(),
+                0,
+                0,
+            )
+        };
+        if status < 0 {
+            return Err(DndError::Failed(format!(
+                "NtUpdateWnfStateData: {status:#x}"
+            )));
+        }
+        Ok(())
+    }
+}
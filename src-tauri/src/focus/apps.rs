@@ -0,0 +1,402 @@
+//! Finding blocked apps among running processes, and hiding or quitting them.
+//!
+//! Processes are listed from `/proc` on Linux, `ps` on macOS and `tasklist`
+//! on Windows. The parsers take plain text or a directory so they can be
+//! tested anywhere.
+
+use std::process::Command;
+
+/// A running process, as far as matching needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    /// Short process name (`comm` on Linux, the image name on Windows).
+    pub name: String,
+    /// Executable path, when known.
+    pub path: Option<String>,
+}
+
+/// A running process that matched the blocklist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockedApp {
+    pub pid: u32,
+    /// The blocklist entry, as the user wrote it.
+    pub app: String,
+}
+
+/// Compare names case-insensitively and without `.exe` / `.app`.
+fn normalize(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    for suffix in [".exe", ".app"] {
+        if let Some(stem) = name.strip_suffix(suffix) {
+            return stem.to_string();
+        }
+    }
+    name
+}
+
+/// Names a process may be listed under: its name, the executable's file
+/// name, and on macOS the `.app` bundle it lives in.
+fn candidates(process: &ProcessInfo) -> Vec<String> {
+    let mut names = vec![normalize(&process.name)];
+    if let Some(path) = &process.path {
+        let mut parts = path.split(['/', '\\']).filter(|p| !p.is_empty());
+        names.extend(
+            parts
+                .clone()
+                .filter(|p| p.to_lowercase().ends_with(".app"))
+                .map(normalize),
+        );
+        if let Some(file) = parts.next_back() {
+            names.push(normalize(file));
+        }
+    }
+    names
+}
+
+/// App names to block.
+#[derive(Debug, Clone, Default)]
+pub struct Blocklist {
+    /// (normalized name, name as entered)
+    entries: Vec<(String, String)>,
+}
+
+impl Blocklist {
+    pub fn new(apps: &[String]) -> Self {
+        let entries = apps
+            .iter()
+            .filter(|app| !app.trim().is_empty())
+            .map(|app| (normalize(app), app.trim().to_string()))
+            .collect();
+        Self { entries }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The entry `process` matches, if any.
+    pub fn matches(&self, process: &ProcessInfo) -> Option<&str> {
+        let names = candidates(process);
+        self.entries
+            .iter()
+            .find(|(normalized, _)| names.contains(normalized))
+            .map(|(_, app)| app.as_str())
+    }
+
+    /// Blocked processes among `processes`, never including `own_pid`.
+    pub fn find(&self, processes: &[ProcessInfo], own_pid: u32) -> Vec<BlockedApp> {
+        processes
+            .iter()
+            .filter(|p| p.pid != own_pid)
+            .filter_map(|p| {
+                Some(BlockedApp {
+                    pid: p.pid,
+                    app: self.matches(p)?.to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+// --- Process listing ---
+
+/// Read processes from a `/proc`-style directory: `<pid>/comm` for the name
+/// and the first `<pid>/cmdline` argument for the path. Processes that exit
+/// while being read are skipped.
+#[cfg(any(target_os = "linux", test))]
+pub fn read_proc(root: &std::path::Path) -> Result<Vec<ProcessInfo>, String> {
+    let entries = std::fs::read_dir(root).map_err(|e| format!("{}: {e}", root.display()))?;
+    let mut processes = Vec::new();
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse().ok()) else {
+            continue;
+        };
+        let Ok(comm) = std::fs::read_to_string(entry.path().join("comm")) else {
+            continue;
+        };
+        let path = std::fs::read(entry.path().join("cmdline"))
+            .ok()
+            .and_then(|cmdline| {
+                let first = cmdline.split(|b| *b == 0).next()?;
+                (!first.is_empty()).then(|| String::from_utf8_lossy(first).into_owned())
+            });
+        processes.push(ProcessInfo {
+            pid,
+            name: comm.trim_end().to_string(),
+            path,
+        });
+    }
+    Ok(processes)
+}
+
+/// Parse `ps -axo pid=,comm=`, where `comm` is the executable path.
+#[cfg(any(target_os = "macos", test))]
+pub fn parse_ps(output: &str) -> Vec<ProcessInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (pid, path) = line.trim().split_once(char::is_whitespace)?;
+            let path = path.trim();
+            Some(ProcessInfo {
+                pid: pid.parse().ok()?,
+                name: path.rsplit('/').next().unwrap_or(path).to_string(),
+                path: Some(path.to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Parse `tasklist /fo csv /nh`: `"chrome.exe","1234","Console","1","98,304 K"`.
+#[cfg(any(target_os = "windows", test))]
+pub fn parse_tasklist(output: &str) -> Vec<ProcessInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.trim().trim_matches('"').split("\",\"");
+            let name = fields.next()?;
+            let pid = fields.next()?.parse().ok()?;
+            Some(ProcessInfo {
+                pid,
+                name: name.to_string(),
+                path: None,
+            })
+        })
+        .collect()
+}
+
+/// A command that doesn't flash a console window on Windows.
+fn command(program: &str) -> Command {
+    #[allow(unused_mut)]
+    let mut command = Command::new(program);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command
+}
+
+/// Run a command and return its stdout, or stderr as the error.
+pub(crate) fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = command(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("{program}: {e}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Every running process.
+pub fn running() -> Result<Vec<ProcessInfo>, String> {
+    #[cfg(target_os = "linux")]
+    let processes = read_proc(std::path::Path::new("/proc"));
+    #[cfg(target_os = "macos")]
+    let processes = run("ps", &["-axo", "pid=,comm="]).map(|out| parse_ps(&out));
+    #[cfg(target_os = "windows")]
+    let processes = run("tasklist", &["/fo", "csv", "/nh"]).map(|out| parse_tasklist(&out));
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    let processes = Err("listing processes is not supported on this platform".to_string());
+    processes
+}
+
+// --- Actions ---
+
+/// Ask a process to quit. It may still show a "save changes?" prompt.
+pub fn quit(pid: u32) -> Result<(), String> {
+    let pid = pid.to_string();
+    #[cfg(target_os = "windows")]
+    let result = run("taskkill", &["/pid", &pid]);
+    #[cfg(not(target_os = "windows"))]
+    let result = run("kill", &["-TERM", &pid]);
+    result.map(|_| ())
+}
+
+/// Hide or minimize a process's windows.
+pub fn hide(pid: u32) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let result = run(
+        "osascript",
+        &[
+            "-e",
+            &format!(
+                "tell application \"System Events\" to set visible of \
+                 (first process whose unix id is {pid}) to false"
+            ),
+        ],
+    )
+    .map(|_| ());
+    // X11 only; Wayland compositors don't let other clients minimize windows.
+    #[cfg(target_os = "linux")]
+    let result = run(
+        "xdotool",
+        &["search", "--pid", &pid.to_string(), "windowminimize", "%@"],
+    )
+    .map(|_| ());
+    #[cfg(target_os = "windows")]
+    let result = win32::minimize_windows(pid);
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    let result = Err(format!(
+        "hiding apps is not supported on this platform (pid {pid})"
+    ));
+    result
+}
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    type Hwnd = isize;
+
+    const SW_MINIMIZE: i32 = 6;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn EnumWindows(callback: extern "system" fn(Hwnd, isize) -> i32, lparam: isize) -> i32;
+        fn GetWindowThreadProcessId(hwnd: Hwnd, pid: *mut u32) -> u32;
+        fn IsWindowVisible(hwnd: Hwnd) -> i32;
+        fn ShowWindow(hwnd: Hwnd, cmd: i32) -> i32;
+    }
+
+    extern "system" fn minimize_if_owned(hwnd: Hwnd, target: isize) -> i32 {
+        let mut pid = 0u32;
+        // SAFETY: `hwnd` comes from EnumWindows and `pid` is a valid out pointer.
+        unsafe {
+            GetWindowThreadProcessId(hwnd, &mut pid);
+            if pid as isize == target && IsWindowVisible(hwnd) != 0 {
+                ShowWindow(hwnd, SW_MINIMIZE);
+            }
+        }
+        1
+    }
+
+    /// Minimize every visible top-level window owned by `pid`.
+    pub fn minimize_windows(pid: u32) -> Result<(), String> {
+        // SAFETY: the callback only reads the window handles it is given.
+        if unsafe { EnumWindows(minimize_if_owned, pid as isize) } == 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, name: &str, path: Option<&str>) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            name: name.to_string(),
+            path: path.map(str::to_string),
+        }
+    }
+
+    fn blocklist(apps: &[&str]) -> Blocklist {
+        Blocklist::new(&apps.iter().map(|a| a.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_matches_name_path_and_bundle() {
+        let list = blocklist(&["Slack", "steam.exe", "Discord.app", "  "]);
+        assert_eq!(list.matches(&process(1, "slack", None)), Some("Slack"));
+        assert_eq!(
+            list.matches(&process(2, "Steam.exe", None)),
+            Some("steam.exe")
+        );
+        // `comm` is cut to 15 bytes on Linux; the path still has the full name.
+        assert_eq!(
+            list.matches(&process(
+                3,
+                "Discord Helper",
+                Some("/Applications/Discord.app/Contents/Frameworks/Discord Helper")
+            )),
+            Some("Discord.app")
+        );
+        assert_eq!(
+            list.matches(&process(
+                4,
+                "steamwebhelper",
+                Some("C:\\Games\\Steam\\steam.exe")
+            )),
+            Some("steam.exe")
+        );
+        assert_eq!(list.matches(&process(5, "slackware-tool", None)), None);
+        assert_eq!(list.matches(&process(6, "", Some("/usr/bin/code"))), None);
+    }
+
+    #[test]
+    fn test_find_skips_own_process() {
+        let list = blocklist(&["morph", "firefox"]);
+        let processes = [
+            process(10, "morph", None),
+            process(11, "firefox", Some("/usr/lib/firefox/firefox")),
+            process(12, "bash", None),
+        ];
+        assert_eq!(
+            list.find(&processes, 10),
+            vec![BlockedApp {
+                pid: 11,
+                app: "firefox".to_string(),
+            }]
+        );
+        assert!(Blocklist::new(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_read_proc() {
+        let root = std::env::temp_dir().join(format!("morph-proc-{}", std::process::id()));
+        for (pid, comm, cmdline) in [
+            (
+                "101",
+                "signal-desktop\n",
+                &b"/opt/Signal/signal-desktop\0--no-sandbox\0"[..],
+            ),
+            ("102", "kworker/0:1\n", &b""[..]),
+        ] {
+            std::fs::create_dir_all(root.join(pid)).unwrap();
+            std::fs::write(root.join(pid).join("comm"), comm).unwrap();
+            std::fs::write(root.join(pid).join("cmdline"), cmdline).unwrap();
+        }
+        std::fs::create_dir_all(root.join("self")).unwrap();
+        // Exited between listing and reading.
+        std::fs::create_dir_all(root.join("103")).unwrap();
+
+        let mut processes = read_proc(&root).unwrap();
+        processes.sort_by_key(|p| p.pid);
+        assert_eq!(
+            processes,
+            vec![
+                process(101, "signal-desktop", Some("/opt/Signal/signal-desktop")),
+                process(102, "kworker/0:1", None),
+            ]
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_parse_ps() {
+        let output = "    1 /sbin/launchd\n  412 /Applications/Slack.app/Contents/MacOS/Slack\n\n";
+        let processes = parse_ps(output);
+        assert_eq!(processes.len(), 2);
+        assert_eq!(processes[1].pid, 412);
+        assert_eq!(processes[1].name, "Slack");
+        assert_eq!(blocklist(&["slack"]).matches(&processes[1]), Some("slack"));
+    }
+
+    #[test]
+    fn test_parse_tasklist() {
+        let output = "\"System Idle Process\",\"0\",\"Services\",\"0\",\"8 K\"\r\n\
+                      \"chrome.exe\",\"1234\",\"Console\",\"1\",\"98,304 K\"\r\n\
+                      INFO: No tasks are running\r\n";
+        assert_eq!(
+            parse_tasklist(output),
+            vec![
+                process(0, "System Idle Process", None),
+                process(1234, "chrome.exe", None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,317 @@
+//! Site blocking through a Morph-managed section of the hosts file.
+//!
+//! Blocked domains (and their `www.` variants) are pointed at `0.0.0.0` and
+//! `::` between two marker lines. Everything outside the markers is left
+//! byte-for-byte as it was, so removing the section restores the file.
+
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+const BEGIN_MARKER: &str = "# BEGIN Morph focus mode";
+const END_MARKER: &str = "# END Morph focus mode";
+
+/// Addresses blocked domains resolve to.
+const SINKS: [&str; 2] = ["0.0.0.0", "::"];
+
+#[derive(Debug, Error)]
+pub enum HostsError {
+    #[error("no permission to change {0}")]
+    PermissionDenied(String),
+
+    #[error("failed to update {path}: {message}")]
+    Io { path: String, message: String },
+}
+
+impl HostsError {
+    fn from_io(path: &Path, e: std::io::Error) -> Self {
+        let path = path.display().to_string();
+        match e.kind() {
+            std::io::ErrorKind::PermissionDenied => HostsError::PermissionDenied(path),
+            _ => HostsError::Io {
+                path,
+                message: e.to_string(),
+            },
+        }
+    }
+}
+
+/// The host name in a blocklist entry, which may be a bare domain or a URL
+/// ("https://www.reddit.com/r/rust"). `None` if it isn't a domain name.
+pub fn normalize_domain(entry: &str) -> Option<String> {
+    let entry = entry.trim().to_ascii_lowercase();
+    let rest = entry.split_once("://").map_or(entry.as_str(), |(_, r)| r);
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+    let host = host.split(':').next()?.trim_end_matches('.');
+
+    let valid_label = |label: &str| {
+        (1..=63).contains(&label.len())
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    };
+    (host.len() <= 253 && host.contains('.') && host.split('.').all(valid_label))
+        .then(|| host.to_string())
+}
+
+/// Each domain followed by its `www.` variant, without duplicates.
+fn expand(domains: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for domain in domains {
+        let www = (!domain.starts_with("www.")).then(|| format!("www.{domain}"));
+        for name in std::iter::once(domain.clone()).chain(www) {
+            if !out.contains(&name) {
+                out.push(name);
+            }
+        }
+    }
+    out
+}
+
+fn is_entry(line: &str) -> bool {
+    SINKS.iter().any(|sink| {
+        line.strip_prefix(sink)
+            .is_some_and(|rest| rest.starts_with([' ', '\t']))
+    })
+}
+
+/// `contents` without Morph's section. A section missing its end marker
+/// (say, after a crash mid-write) ends at the first line Morph didn't write.
+pub fn strip_section(contents: &str) -> String {
+    let mut out = String::with_capacity(contents.len());
+    let mut inside = false;
+    for line in contents.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed == BEGIN_MARKER {
+            inside = true;
+            continue;
+        }
+        if trimmed == END_MARKER {
+            inside = false;
+            continue;
+        }
+        if inside && is_entry(trimmed) {
+            continue;
+        }
+        inside = false;
+        out.push_str(line);
+    }
+    out
+}
+
+/// `contents` with Morph's section (re)written at the end to block
+/// `domains`. With no domains this is [`strip_section`].
+pub fn with_section(contents: &str, domains: &[String]) -> String {
+    let mut out = strip_section(contents);
+    if domains.is_empty() {
+        return out;
+    }
+    let newline = if contents.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push_str(newline);
+    }
+    out.push_str(BEGIN_MARKER);
+    out.push_str(newline);
+    for name in expand(domains) {
+        for sink in SINKS {
+            out.push_str(&format!("{sink} {name}{newline}"));
+        }
+    }
+    out.push_str(END_MARKER);
+    out.push_str(newline);
+    out
+}
+
+/// A hosts file Morph may rewrite.
+#[derive(Debug, Clone)]
+pub struct HostsFile {
+    path: PathBuf,
+}
+
+impl HostsFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// The system hosts file.
+    pub fn system() -> Self {
+        #[cfg(target_os = "windows")]
+        let path = std::env::var_os("SystemRoot")
+            .map_or_else(|| PathBuf::from("C:\\Windows"), PathBuf::from)
+            .join("System32\\drivers\\etc\\hosts");
+        #[cfg(not(target_os = "windows"))]
+        let path = PathBuf::from("/etc/hosts");
+        Self::new(path)
+    }
+
+    /// Block `domains`, replacing whatever Morph blocked before.
+    pub fn apply(&self, domains: &[String]) -> Result<(), HostsError> {
+        self.rewrite(|contents| with_section(contents, domains))
+    }
+
+    /// Remove Morph's section, if there is one.
+    pub fn restore(&self) -> Result<(), HostsError> {
+        self.rewrite(strip_section)
+    }
+
+    /// Write the file only when its contents change. The new contents go to
+    /// a temporary file next to it, which is then renamed over it, so a
+    /// crash mid-write can't leave the hosts file truncated.
+    fn rewrite(&self, update: impl FnOnce(&str) -> String) -> Result<(), HostsError> {
+        let err = |e| HostsError::from_io(&self.path, e);
+        // A symlinked hosts file is replaced where it points, keeping the link.
+        let path = std::fs::canonicalize(&self.path).map_err(err)?;
+        let contents = std::fs::read_to_string(&path).map_err(err)?;
+        let updated = update(&contents);
+        if updated == contents {
+            return Ok(());
+        }
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let temp = path.with_file_name(format!(".{name}.morph-tmp"));
+        let result = replace(&path, &temp, &updated);
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temp);
+        }
+        result.map_err(err)
+    }
+}
+
+/// Write `contents` to `temp` with `path`'s permissions (and owner, on
+/// Unix), then rename it over `path`.
+fn replace(path: &Path, temp: &Path, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let metadata = std::fs::metadata(path)?;
+    // Left behind by an earlier crash.
+    let _ = std::fs::remove_file(temp);
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(temp)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    drop(file);
+    std::fs::set_permissions(temp, metadata.permissions())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        std::os::unix::fs::chown(temp, Some(metadata.uid()), Some(metadata.gid()))?;
+    }
+    std::fs::rename(temp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOSTS: &str = "127.0.0.1\tlocalhost\n::1\tlocalhost\n0.0.0.0 ads.example.com\n";
+
+    fn domains(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_normalize_domain() {
+        assert_eq!(normalize_domain("Reddit.com"), Some("reddit.com".into()));
+        assert_eq!(
+            normalize_domain("https://www.reddit.com:443/r/rust?x=1"),
+            Some("www.reddit.com".into())
+        );
+        assert_eq!(
+            normalize_domain(" news.ycombinator.com. "),
+            Some("news.ycombinator.com".into())
+        );
+        assert_eq!(normalize_domain("localhost"), None);
+        assert_eq!(normalize_domain("bad_name.com"), None);
+        assert_eq!(normalize_domain("-x.com"), None);
+        assert_eq!(normalize_domain(""), None);
+    }
+
+    #[test]
+    fn test_section_blocks_domain_and_www() {
+        let updated = with_section(HOSTS, &domains(&["reddit.com", "www.youtube.com"]));
+        assert!(updated.starts_with(HOSTS));
+        let section = &updated[HOSTS.len()..];
+        assert_eq!(
+            section,
+            "# BEGIN Morph focus mode\n\
+             0.0.0.0 reddit.com\n\
+             :: reddit.com\n\
+             0.0.0.0 www.reddit.com\n\
+             :: www.reddit.com\n\
+             0.0.0.0 www.youtube.com\n\
+             :: www.youtube.com\n\
+             # END Morph focus mode\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_restores_original() {
+        let updated = with_section(HOSTS, &domains(&["reddit.com"]));
+        assert_eq!(strip_section(&updated), HOSTS);
+        // Re-applying replaces the old section rather than adding another.
+        let again = with_section(&updated, &domains(&["x.com"]));
+        assert_eq!(again.matches("# BEGIN").count(), 1);
+        assert!(!again.contains("reddit.com"));
+        assert_eq!(with_section(&updated, &[]), HOSTS);
+    }
+
+    #[test]
+    fn test_keeps_crlf_and_missing_final_newline() {
+        let windows = "127.0.0.1 localhost\r\n# comment";
+        let updated = with_section(windows, &domains(&["x.com"]));
+        assert!(updated.starts_with("127.0.0.1 localhost\r\n# comment\r\n# BEGIN"));
+        assert!(updated.ends_with("# END Morph focus mode\r\n"));
+        assert_eq!(
+            strip_section(&updated),
+            "127.0.0.1 localhost\r\n# comment\r\n"
+        );
+    }
+
+    #[test]
+    fn test_strips_unterminated_section() {
+        let broken =
+            format!("{HOSTS}# BEGIN Morph focus mode\n0.0.0.0 x.com\n:: x.com\n10.0.0.2 nas\n");
+        assert_eq!(strip_section(&broken), format!("{HOSTS}10.0.0.2 nas\n"));
+    }
+
+    #[test]
+    fn test_hosts_file_apply_and_restore() {
+        let path = std::env::temp_dir().join(format!("morph-hosts-{}", std::process::id()));
+        std::fs::write(&path, HOSTS).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        }
+        let file = HostsFile::new(&path);
+
+        file.apply(&domains(&["reddit.com"])).unwrap();
+        let blocked = std::fs::read_to_string(&path).unwrap();
+        assert!(blocked.contains("0.0.0.0 www.reddit.com\n"));
+        let name = path.file_name().unwrap().to_string_lossy();
+        assert!(!path.with_file_name(format!(".{name}.morph-tmp")).exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o644);
+        }
+
+        file.restore().unwrap();
+        file.restore().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), HOSTS);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_missing_hosts_file_is_an_error() {
+        let file = HostsFile::new("/nonexistent/hosts");
+        assert!(matches!(file.restore(), Err(HostsError::Io { .. })));
+    }
+}
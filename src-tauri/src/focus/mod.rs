@@ -0,0 +1,557 @@
+//! Focus mode: block distracting apps and sites during focus sessions.
+//!
+//! A session runs while a focus timer is running, or during a calendar event
+//! whose title contains one of `focus_calendar_keywords`. During a session:
+//! - running apps from `focus_blocked_apps` are warned about, hidden or quit
+//!   (`focus_app_action`), and each one is recorded in history;
+//! - with `focus_block_sites` on, domains from `focus_blocked_sites` resolve
+//!   to nowhere through a Morph-managed section of the hosts file.
+//!
+//! The hosts section is removed when the session ends, when the user stops
+//! blocking from the tray, when Morph exits, and at startup in case it didn't
+//! exit cleanly. Changing the hosts file needs write access to it; failures
+//! are reported by `get_focus_status`. Browsers may keep already-resolved
+//! sites reachable until their DNS cache expires.
+
+pub mod apps;
+pub mod hosts;
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::{command, AppHandle, Listener, Manager};
+
+use crate::calendar::types::CalendarEvent;
+use crate::history;
+use crate::notifications::{self, Notice};
+use crate::settings::{self, FocusAppAction, Settings};
+use crate::timer::{TimerService, TimerState};
+use crate::tray;
+
+use apps::{BlockedApp, Blocklist};
+use hosts::HostsFile;
+
+pub const ENABLED_KEY: &str = "focus_mode";
+pub const BLOCKED_APPS_KEY: &str = "focus_blocked_apps";
+pub const APP_ACTION_KEY: &str = "focus_app_action";
+pub const BLOCKED_SITES_KEY: &str = "focus_blocked_sites";
+pub const BLOCK_SITES_KEY: &str = "focus_block_sites";
+pub const CALENDAR_KEYWORDS_KEY: &str = "focus_calendar_keywords";
+
+/// Settings that change what is blocked or when.
+pub const KEYS: &[&str] = &[
+    ENABLED_KEY,
+    BLOCKED_APPS_KEY,
+    APP_ACTION_KEY,
+    BLOCKED_SITES_KEY,
+    BLOCK_SITES_KEY,
+    CALENDAR_KEYWORDS_KEY,
+];
+
+/// How often running apps are checked during a session.
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Focus mode inputs derived from settings.
+#[derive(Debug, Clone)]
+pub struct FocusConfig {
+    pub enabled: bool,
+    pub blocklist: Blocklist,
+    pub action: FocusAppAction,
+    /// Normalized domains to block; empty when site blocking is off.
+    pub sites: Vec<String>,
+    /// Lowercased title keywords that make a calendar event a focus block.
+    pub calendar_keywords: Vec<String>,
+    pub ignored_calendar_ids: Vec<String>,
+}
+
+impl FocusConfig {
+    pub fn from_settings(settings: &Settings) -> Self {
+        let sites = if settings.focus_block_sites {
+            let mut sites: Vec<String> = settings
+                .focus_blocked_sites
+                .iter()
+                .filter_map(|s| hosts::normalize_domain(s))
+                .collect();
+            sites.sort();
+            sites.dedup();
+            sites
+        } else {
+            Vec::new()
+        };
+        Self {
+            enabled: settings.focus_mode,
+            blocklist: Blocklist::new(&settings.focus_blocked_apps),
+            action: settings.focus_app_action,
+            sites,
+            calendar_keywords: settings
+                .focus_calendar_keywords
+                .iter()
+                .map(|k| k.trim().to_lowercase())
+                .filter(|k| !k.is_empty())
+                .collect(),
+            ignored_calendar_ids: settings.ignored_calendar_ids.clone(),
+        }
+    }
+
+    /// The focus block in progress at `now`, if any. All-day events, like
+    /// other non-meetings, never count.
+    pub fn focus_block<'a>(
+        &self,
+        events: &'a [CalendarEvent],
+        now: DateTime<Utc>,
+    ) -> Option<&'a CalendarEvent> {
+        events.iter().find(|e| {
+            e.counts_as_meeting(&self.ignored_calendar_ids)
+                && e.start_time <= now
+                && now < e.end_time
+                && self
+                    .calendar_keywords
+                    .iter()
+                    .any(|k| e.title.to_lowercase().contains(k))
+        })
+    }
+
+    /// Whether a focus session should be running. Break timers don't start
+    /// one: breaks are when blocked apps and sites are fair game.
+    pub fn wants_session(
+        &self,
+        timer: &TimerState,
+        events: &[CalendarEvent],
+        now: DateTime<Utc>,
+    ) -> bool {
+        self.enabled && (timer.is_focusing() || self.focus_block(events, now).is_some())
+    }
+}
+
+/// Session state: whether blocking is on, what has been handled, and what
+/// the hosts file currently blocks.
+#[derive(Debug, Default)]
+pub struct FocusGuard {
+    session: bool,
+    /// Set by the emergency override; lasts until the session ends.
+    overridden: bool,
+    /// Processes already acted on this session, so each launch counts once.
+    handled: HashSet<u32>,
+    /// Domains in the hosts file's Morph section.
+    blocked_sites: Vec<String>,
+    hosts_error: Option<String>,
+}
+
+impl FocusGuard {
+    /// Start or end the session. Returns whether blocking turned on or off.
+    pub fn update(&mut self, wanted: bool) -> bool {
+        let was = self.enforcing();
+        if wanted != self.session {
+            self.session = wanted;
+            self.overridden = false;
+            self.handled.clear();
+        }
+        was != self.enforcing()
+    }
+
+    /// Whether apps and sites are being blocked.
+    pub fn enforcing(&self) -> bool {
+        self.session && !self.overridden
+    }
+
+    /// Stop blocking until the current session ends. Returns whether
+    /// anything was being blocked.
+    pub fn override_session(&mut self) -> bool {
+        let was = self.enforcing();
+        self.overridden = true;
+        was
+    }
+
+    /// `blocked` minus processes already handled this session. Handled
+    /// processes that have exited are forgotten, so a relaunch counts again.
+    pub fn take_new(&mut self, running: &[u32], blocked: Vec<BlockedApp>) -> Vec<BlockedApp> {
+        self.handled.retain(|pid| running.contains(pid));
+        blocked
+            .into_iter()
+            .filter(|b| self.handled.insert(b.pid))
+            .collect()
+    }
+
+    /// Make the hosts file block exactly `sites`. A failed write is retried
+    /// on the next call, and logged once until the error changes.
+    pub fn sync_hosts(&mut self, file: &HostsFile, sites: &[String]) {
+        if self.blocked_sites == sites && self.hosts_error.is_none() {
+            return;
+        }
+        let result = if sites.is_empty() {
+            file.restore()
+        } else {
+            file.apply(sites)
+        };
+        match result {
+            Ok(()) => {
+                self.blocked_sites = sites.to_vec();
+                self.hosts_error = None;
+            }
+            Err(e) => {
+                let message = e.to_string();
+                if self.hosts_error.as_ref() != Some(&message) {
+                    eprintln!("[focus] {message}");
+                }
+                self.hosts_error = Some(message);
+            }
+        }
+    }
+}
+
+// --- Runtime ---
+
+/// Act on a blocked app and return what was done, for history.
+fn act(app: &AppHandle, action: FocusAppAction, blocked: &BlockedApp) -> &'static str {
+    let name = &blocked.app;
+    let (outcome, body) = match action {
+        FocusAppAction::Warn => ("warned", format!("{name} is on your focus blocklist")),
+        FocusAppAction::Hide => match apps::hide(blocked.pid) {
+            Ok(()) => (
+                "hidden",
+                format!("{name} was hidden until your focus session ends"),
+            ),
+            Err(e) => {
+                eprintln!("[focus] Failed to hide {name}: {e}");
+                ("warned", format!("{name} is on your focus blocklist"))
+            }
+        },
+        FocusAppAction::Quit => match apps::quit(blocked.pid) {
+            Ok(()) => (
+                "quit",
+                format!("{name} was closed during your focus session"),
+            ),
+            Err(e) => {
+                eprintln!("[focus] Failed to quit {name}: {e}");
+                ("warned", format!("{name} is on your focus blocklist"))
+            }
+        },
+    };
+    notifications::show(
+        app,
+        Notice {
+            title: "Focus mode".to_string(),
+            body,
+            join_url: None,
+        },
+    );
+    outcome
+}
+
+/// Apply one check: start or end the session, sync the hosts file and
+/// handle newly launched blocked apps. Runs commands and file writes, so it
+/// belongs on a blocking thread.
+fn enforce(app: &AppHandle, config: &FocusConfig, wanted: bool) {
+    let (changed, blocked) = {
+        let managed = app.state::<Mutex<FocusGuard>>();
+        let Ok(mut guard) = managed.lock() else {
+            return;
+        };
+        let changed = guard.update(wanted);
+        let enforcing = guard.enforcing();
+        let sites = if enforcing {
+            config.sites.as_slice()
+        } else {
+            &[]
+        };
+        guard.sync_hosts(&HostsFile::system(), sites);
+
+        let blocked = match (enforcing && !config.blocklist.is_empty()).then(apps::running) {
+            Some(Ok(processes)) => {
+                let pids: Vec<u32> = processes.iter().map(|p| p.pid).collect();
+                let found = config.blocklist.find(&processes, std::process::id());
+                guard.take_new(&pids, found)
+            }
+            Some(Err(e)) => {
+                eprintln!("[focus] Failed to list processes: {e}");
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+        (changed, blocked)
+    };
+
+    for app_blocked in blocked {
+        let outcome = act(app, config.action, &app_blocked);
+        history::record_focus_block(app, app_blocked.app, outcome);
+    }
+    if changed {
+        if let Err(e) = tray::refresh_tray_menu(app) {
+            eprintln!("[focus] Failed to update tray menu: {e}");
+        }
+    }
+}
+
+/// Check the timer, calendar and running apps against the settings.
+pub async fn check(app: &AppHandle) {
+    let config = FocusConfig::from_settings(&settings::current_settings(app).await);
+    let timer = app
+        .state::<Mutex<TimerService>>()
+        .lock()
+        .map(|t| t.state().clone())
+        .unwrap_or_default();
+    let events = if config.enabled && !config.calendar_keywords.is_empty() {
+        match settings::app_pool(app).await {
+            Ok(pool) => crate::calendar::poller::load_cached_events(&pool)
+                .await
+                .unwrap_or_else(|e| {
+                    eprintln!("[focus] Failed to load events: {e}");
+                    Vec::new()
+                }),
+            Err(_) => Vec::new(),
+        }
+    } else {
+        Vec::new()
+    };
+    let wanted = config.wants_session(&timer, &events, Utc::now());
+
+    let app = app.clone();
+    let _ = tauri::async_runtime::spawn_blocking(move || enforce(&app, &config, wanted)).await;
+}
+
+/// Whether apps and sites are being blocked right now.
+pub fn enforcing(app: &AppHandle) -> bool {
+    app.try_state::<Mutex<FocusGuard>>()
+        .and_then(|guard| guard.lock().ok().map(|g| g.enforcing()))
+        .unwrap_or(false)
+}
+
+/// Emergency override: stop blocking until the current session ends.
+pub fn override_session(app: &AppHandle) {
+    {
+        let managed = app.state::<Mutex<FocusGuard>>();
+        let Ok(mut guard) = managed.lock() else {
+            return;
+        };
+        if !guard.override_session() {
+            return;
+        }
+        guard.sync_hosts(&HostsFile::system(), &[]);
+    }
+    eprintln!("[focus] Blocking overridden until the session ends");
+    if let Err(e) = tray::refresh_tray_menu(app) {
+        eprintln!("[focus] Failed to update tray menu: {e}");
+    }
+}
+
+/// Remove blocked sites from the hosts file before the app exits.
+pub fn shutdown(app: &AppHandle) {
+    let managed = app.state::<Mutex<FocusGuard>>();
+    let Ok(mut guard) = managed.lock() else {
+        return;
+    };
+    guard.update(false);
+    guard.sync_hosts(&HostsFile::system(), &[]);
+}
+
+/// Clean up after an unclean exit, then check periodically and whenever the
+/// timer changes.
+pub fn start(app: &tauri::App) {
+    let handle = app.handle().clone();
+    tauri::async_runtime::spawn(async move {
+        let file = HostsFile::system();
+        let _ = tauri::async_runtime::spawn_blocking(move || {
+            if let Err(e) = file.restore() {
+                eprintln!("[focus] Failed to clean up the hosts file: {e}");
+            }
+        })
+        .await;
+
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            check(&handle).await;
+        }
+    });
+
+    let handle = app.handle().clone();
+    app.listen("timer-state-update", move |_| {
+        let handle = handle.clone();
+        tauri::async_runtime::spawn(async move {
+            check(&handle).await;
+        });
+    });
+}
+
+// --- Tauri commands ---
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusStatus {
+    /// Whether a focus session is running (blocking or overridden).
+    pub session: bool,
+    pub enforcing: bool,
+    pub overridden: bool,
+    /// Domains currently blocked in the hosts file.
+    pub blocked_sites: Vec<String>,
+    /// Why the hosts file couldn't be changed, if it couldn't.
+    pub hosts_error: Option<String>,
+}
+
+#[command]
+pub fn get_focus_status(app: AppHandle) -> Result<FocusStatus, String> {
+    let managed = app.state::<Mutex<FocusGuard>>();
+    let guard = managed.lock().map_err(|e| e.to_string())?;
+    Ok(FocusStatus {
+        session: guard.session,
+        enforcing: guard.enforcing(),
+        overridden: guard.overridden,
+        blocked_sites: guard.blocked_sites.clone(),
+        hosts_error: guard.hosts_error.clone(),
+    })
+}
+
+/// Stop blocking until the current focus session ends.
+#[command]
+pub fn override_focus(app: AppHandle) {
+    override_session(&app);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, hour, minute, 0).unwrap()
+    }
+
+    fn event(title: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> CalendarEvent {
+        CalendarEvent {
+            title: title.to_string(),
+            ..test_support::event(title, start, end)
+        }
+    }
+
+    fn config() -> FocusConfig {
+        FocusConfig::from_settings(&Settings {
+            focus_mode: true,
+            focus_blocked_sites: vec![
+                "reddit.com".to_string(),
+                "https://Reddit.com/r/rust".to_string(),
+                "not a domain".to_string(),
+            ],
+            focus_block_sites: true,
+            focus_calendar_keywords: vec!["Focus".to_string(), " ".to_string()],
+            ..Settings::default()
+        })
+    }
+
+    fn blocked(pid: u32) -> BlockedApp {
+        BlockedApp {
+            pid,
+            app: "Slack".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_config_from_settings() {
+        let config = config();
+        assert_eq!(config.sites, vec!["reddit.com".to_string()]);
+        assert_eq!(config.calendar_keywords, vec!["focus".to_string()]);
+        assert_eq!(config.action, FocusAppAction::Warn);
+
+        let off = FocusConfig::from_settings(&Settings {
+            focus_blocked_sites: vec!["reddit.com".to_string()],
+            ..Settings::default()
+        });
+        assert!(!off.enabled);
+        assert!(off.sites.is_empty());
+    }
+
+    #[test]
+    fn test_session_from_timer_or_focus_block() {
+        let config = config();
+        let idle = TimerState::default();
+        let running = TimerState::running(1500, at(9, 0));
+        let events = [
+            event("Focus time", at(13, 0), at(15, 0)),
+            event("Standup", at(9, 0), at(9, 15)),
+        ];
+
+        assert!(config.wants_session(&running, &[], at(9, 5)));
+        let on_break = TimerState {
+            is_break: true,
+            ..running.clone()
+        };
+        assert!(!config.wants_session(&on_break, &[], at(9, 5)));
+        assert!(!config.wants_session(&idle, &events, at(9, 5)));
+        assert!(config.wants_session(&idle, &events, at(14, 0)));
+        assert!(!config.wants_session(&idle, &events, at(15, 0)));
+
+        let all_day = CalendarEvent {
+            is_all_day: true,
+            ..event("Focus week", at(0, 0), at(23, 59))
+        };
+        assert!(!config.wants_session(&idle, &[all_day], at(10, 0)));
+
+        let disabled = FocusConfig {
+            enabled: false,
+            ..config
+        };
+        assert!(!disabled.wants_session(&running, &events, at(14, 0)));
+    }
+
+    #[test]
+    fn test_override_lasts_until_session_ends() {
+        let mut guard = FocusGuard::default();
+        assert!(guard.update(true));
+        assert!(guard.enforcing());
+
+        assert!(guard.override_session());
+        assert!(!guard.enforcing());
+        assert!(!guard.update(true));
+        assert!(!guard.enforcing());
+
+        assert!(!guard.update(false));
+        assert!(!guard.override_session());
+        assert!(guard.update(true));
+        assert!(guard.enforcing());
+    }
+
+    #[test]
+    fn test_each_launch_is_handled_once() {
+        let mut guard = FocusGuard::default();
+        guard.update(true);
+
+        assert_eq!(guard.take_new(&[1, 2], vec![blocked(2)]), vec![blocked(2)]);
+        assert!(guard.take_new(&[1, 2], vec![blocked(2)]).is_empty());
+        // Quit and relaunched under a new pid.
+        assert_eq!(guard.take_new(&[1, 3], vec![blocked(3)]), vec![blocked(3)]);
+        // A new session starts fresh.
+        guard.update(false);
+        guard.update(true);
+        assert_eq!(guard.take_new(&[1, 3], vec![blocked(3)]), vec![blocked(3)]);
+    }
+
+    #[test]
+    fn test_sync_hosts_applies_and_restores() {
+        let path = std::env::temp_dir().join(format!("morph-focus-hosts-{}", std::process::id()));
+        std::fs::write(&path, "127.0.0.1 localhost\n").unwrap();
+        let file = HostsFile::new(&path);
+        let mut guard = FocusGuard::default();
+
+        guard.sync_hosts(&file, &["reddit.com".to_string()]);
+        assert_eq!(guard.blocked_sites, vec!["reddit.com".to_string()]);
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("0.0.0.0 reddit.com"));
+
+        guard.sync_hosts(&file, &[]);
+        assert!(guard.blocked_sites.is_empty());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "127.0.0.1 localhost\n"
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        // A missing file is reported and retried.
+        guard.sync_hosts(&file, &["reddit.com".to_string()]);
+        assert!(guard.hosts_error.is_some());
+        assert!(guard.blocked_sites.is_empty());
+    }
+}
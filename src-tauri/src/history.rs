@@ -0,0 +1,957 @@
+//! Session history and time-use insights.
+//!
+//! Records phase transitions reported through `emit_border_state`, meetings
+//! that ended (copied from the event cache), finished timer sessions and
+//! border pauses into the `history_*` tables, and aggregates them into daily
+//! insights. Rows older than the `history_retention_days` setting are purged
+//! periodically. Apps blocked by focus mode are recorded too.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, SecondsFormat, TimeZone, Utc};
+use serde::Serialize;
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+use tauri::{command, AppHandle};
+
+use crate::settings;
+use crate::working_hours::OFF_HOURS_PHASE;
+
+/// Setting key for how many days of history to keep.
+pub const RETENTION_KEY: &str = "history_retention_days";
+
+/// Pseudo-phase recorded when the app stops or starts, so time while Morph
+/// was not running is not attributed to the last real phase.
+pub const OFFLINE_PHASE: &str = "offline";
+
+const DEEP_WORK_PHASE: &str = "free-deep";
+const OVERTIME_PHASE: &str = "overtime";
+
+/// How often rows older than the retention period are purged.
+const PURGE_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Longest range `get_history_insights` will aggregate.
+const MAX_INSIGHT_DAYS: u32 = 366;
+
+/// Format a timestamp the way history rows store it.
+pub(crate) fn timestamp(t: DateTime<Utc>) -> String {
+    t.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Parse a timestamp written by [`timestamp`] (or any RFC 3339 string).
+pub(crate) fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// A finished timer run, produced by the timer service.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimerSessionRecord {
+    pub preset_id: String,
+    pub name: String,
+    pub duration_seconds: u32,
+    pub active_seconds: u32,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    /// "completed" or "stopped".
+    pub outcome: &'static str,
+}
+
+/// Aggregates for one local calendar day.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyInsights {
+    pub date: NaiveDate,
+    pub deep_work_minutes: f64,
+    pub context_switches: u32,
+    pub meetings: u32,
+    pub average_gap_minutes: Option<f64>,
+    pub overtime_minutes: f64,
+    pub timer_sessions: u32,
+    pub timer_minutes: f64,
+    pub pause_minutes: f64,
+    pub blocked_apps: u32,
+}
+
+/// Aggregates over a range of days, with per-day breakdown.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryInsights {
+    pub days: Vec<DailyInsights>,
+    pub deep_work_minutes: f64,
+    pub context_switches: u32,
+    pub meetings: u32,
+    /// Mean gap between consecutive meetings on the same day.
+    pub average_gap_minutes: Option<f64>,
+    pub overtime_minutes: f64,
+    pub timer_minutes: f64,
+    pub pause_minutes: f64,
+    /// Blocked apps caught by focus mode.
+    pub blocked_apps: u32,
+}
+
+/// Whether a phase counts as being in a meeting. `None` for the offline
+/// marker and time outside working hours, which neither start nor end a
+/// context.
+fn is_meeting_phase(phase: &str) -> Option<bool> {
+    match phase {
+        OFFLINE_PHASE | OFF_HOURS_PHASE => None,
+        OVERTIME_PHASE => Some(true),
+        p => Some(p.starts_with("in-session")),
+    }
+}
+
+/// Split `[start, end)` into minutes per local day.
+fn minutes_by_day(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    offset: FixedOffset,
+) -> Vec<(NaiveDate, f64)> {
+    let mut out = Vec::new();
+    let mut cursor = start;
+    while cursor < end {
+        let local = cursor.with_timezone(&offset);
+        let next_midnight = local
+            .date_naive()
+            .succ_opt()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .and_then(|d| offset.from_local_datetime(&d).single())
+            .map(|d| d.with_timezone(&Utc))
+            .unwrap_or(end);
+        let chunk_end = next_midnight.min(end);
+        out.push((
+            local.date_naive(),
+            (chunk_end - cursor).num_seconds() as f64 / 60.0,
+        ));
+        cursor = chunk_end;
+    }
+    out
+}
+
+/// A `[start, end)` time span.
+type Span = (DateTime<Utc>, DateTime<Utc>);
+
+/// Raw history rows for a range, loaded from the database.
+#[derive(Debug, Default)]
+struct HistoryRows {
+    /// The last transition before the range start, if any.
+    prior_phase: Option<String>,
+    transitions: Vec<(String, DateTime<Utc>)>,
+    meetings: Vec<Span>,
+    timer_sessions: Vec<(DateTime<Utc>, u32)>,
+    pauses: Vec<(DateTime<Utc>, Option<DateTime<Utc>>)>,
+    focus_blocks: Vec<DateTime<Utc>>,
+}
+
+/// Aggregate history rows over `[from, to)` into per-day insights.
+fn aggregate(
+    rows: &HistoryRows,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    offset: FixedOffset,
+) -> HistoryInsights {
+    let mut days: BTreeMap<NaiveDate, DailyInsights> = BTreeMap::new();
+    let mut date = from.with_timezone(&offset).date_naive();
+    let last = (to - chrono::Duration::seconds(1))
+        .with_timezone(&offset)
+        .date_naive();
+    while date <= last {
+        days.insert(
+            date,
+            DailyInsights {
+                date,
+                ..Default::default()
+            },
+        );
+        date = match date.succ_opt() {
+            Some(d) => d,
+            None => break,
+        };
+    }
+    let local_date = |t: DateTime<Utc>| t.with_timezone(&offset).date_naive();
+
+    // Phase segments: each phase lasts until the next transition (or `to`).
+    let mut current = rows.prior_phase.clone().map(|p| (p, from));
+    let mut previous_context = rows.prior_phase.as_deref().and_then(is_meeting_phase);
+    let mut close_segment = |phase: &str, start: DateTime<Utc>, end: DateTime<Utc>| {
+        let field = match phase {
+            DEEP_WORK_PHASE => |d: &mut DailyInsights, m: f64| d.deep_work_minutes += m,
+            OVERTIME_PHASE => |d: &mut DailyInsights, m: f64| d.overtime_minutes += m,
+            _ => return,
+        };
+        for (date, minutes) in minutes_by_day(start.max(from), end.min(to), offset) {
+            if let Some(day) = days.get_mut(&date) {
+                field(day, minutes);
+            }
+        }
+    };
+    let mut switches: Vec<DateTime<Utc>> = Vec::new();
+    for (phase, at) in &rows.transitions {
+        if let Some((prev, start)) = current.take() {
+            close_segment(&prev, start, *at);
+        }
+        let context = is_meeting_phase(phase);
+        if let (Some(before), Some(after)) = (previous_context, context) {
+            if before != after {
+                switches.push(*at);
+            }
+        }
+        previous_context = context;
+        current = Some((phase.clone(), *at));
+    }
+    if let Some((phase, start)) = current {
+        close_segment(&phase, start, to);
+    }
+    for at in switches {
+        if let Some(day) = days.get_mut(&local_date(at)) {
+            day.context_switches += 1;
+        }
+    }
+
+    // Meetings and the gaps between them, per day.
+    let mut meetings = rows.meetings.clone();
+    meetings.sort();
+    let mut all_gaps = Vec::new();
+    let mut by_day: BTreeMap<NaiveDate, Vec<Span>> = BTreeMap::new();
+    for (start, end) in meetings {
+        by_day
+            .entry(local_date(start))
+            .or_default()
+            .push((start, end));
+    }
+    for (date, meetings) in by_day {
+        let Some(day) = days.get_mut(&date) else {
+            continue;
+        };
+        day.meetings = meetings.len() as u32;
+        let mut gaps = Vec::new();
+        let mut latest_end = meetings[0].1;
+        for (start, end) in meetings.iter().skip(1) {
+            gaps.push(((*start - latest_end).num_seconds().max(0)) as f64 / 60.0);
+            latest_end = latest_end.max(*end);
+        }
+        if !gaps.is_empty() {
+            day.average_gap_minutes = Some(gaps.iter().sum::<f64>() / gaps.len() as f64);
+        }
+        all_gaps.extend(gaps);
+    }
+
+    for (started_at, active_seconds) in &rows.timer_sessions {
+        if let Some(day) = days.get_mut(&local_date(*started_at)) {
+            day.timer_sessions += 1;
+            day.timer_minutes += *active_seconds as f64 / 60.0;
+        }
+    }
+
+    for (started_at, ended_at) in &rows.pauses {
+        let end = ended_at.unwrap_or(to).min(to);
+        for (date, minutes) in minutes_by_day((*started_at).max(from), end, offset) {
+            if let Some(day) = days.get_mut(&date) {
+                day.pause_minutes += minutes;
+            }
+        }
+    }
+
+    for at in &rows.focus_blocks {
+        if let Some(day) = days.get_mut(&local_date(*at)) {
+            day.blocked_apps += 1;
+        }
+    }
+
+    let days: Vec<DailyInsights> = days.into_values().collect();
+    HistoryInsights {
+        deep_work_minutes: days.iter().map(|d| d.deep_work_minutes).sum(),
+        context_switches: days.iter().map(|d| d.context_switches).sum(),
+        meetings: days.iter().map(|d| d.meetings).sum(),
+        average_gap_minutes: (!all_gaps.is_empty())
+            .then(|| all_gaps.iter().sum::<f64>() / all_gaps.len() as f64),
+        overtime_minutes: days.iter().map(|d| d.overtime_minutes).sum(),
+        timer_minutes: days.iter().map(|d| d.timer_minutes).sum(),
+        pause_minutes: days.iter().map(|d| d.pause_minutes).sum(),
+        blocked_apps: days.iter().map(|d| d.blocked_apps).sum(),
+        days,
+    }
+}
+
+// --- Pool-based inner functions (testable without Tauri runtime) ---
+
+async fn insert_phase(pool: &SqlitePool, phase: &str, at: DateTime<Utc>) -> Result<(), String> {
+    sqlx::query("INSERT INTO history_phase_transitions (phase, at) VALUES (?1, ?2)")
+        .bind(phase)
+        .bind(timestamp(at))
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+async fn insert_timer_session(
+    pool: &SqlitePool,
+    record: &TimerSessionRecord,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO history_timer_sessions
+           (preset_id, name, duration_seconds, active_seconds, started_at, ended_at, outcome)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )
+    .bind(&record.preset_id)
+    .bind(&record.name)
+    .bind(record.duration_seconds)
+    .bind(record.active_seconds)
+    .bind(timestamp(record.started_at))
+    .bind(timestamp(record.ended_at))
+    .bind(record.outcome)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+async fn insert_focus_block(
+    pool: &SqlitePool,
+    app: &str,
+    action: &str,
+    at: DateTime<Utc>,
+) -> Result<(), String> {
+    sqlx::query("INSERT INTO history_focus_blocks (app, action, at) VALUES (?1, ?2, ?3)")
+        .bind(app)
+        .bind(action)
+        .bind(timestamp(at))
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Close any open pause at `at`.
+async fn end_pause(pool: &SqlitePool, at: DateTime<Utc>) -> Result<(), String> {
+    // A pause that already ran out ends at its planned time, not now.
+    sqlx::query(
+        "UPDATE history_pauses
+         SET ended_at = CASE WHEN planned_until IS NOT NULL AND planned_until < ?1
+                             THEN planned_until ELSE ?1 END
+         WHERE ended_at IS NULL",
+    )
+    .bind(timestamp(at))
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Record a new pause, closing any previous one.
+async fn start_pause(
+    pool: &SqlitePool,
+    at: DateTime<Utc>,
+    planned_until: Option<DateTime<Utc>>,
+    reason: &str,
+) -> Result<(), String> {
+    end_pause(pool, at).await?;
+    sqlx::query(
+        "INSERT INTO history_pauses (started_at, planned_until, reason) VALUES (?1, ?2, ?3)",
+    )
+    .bind(timestamp(at))
+    .bind(planned_until.map(timestamp))
+    .bind(reason)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Copy cached meetings that have ended by `now` into history. Returns the
+/// number of newly recorded meetings.
+///
+/// The poller only fetches events that have not ended yet, so this must run
+/// before it replaces the cache.
+pub(crate) async fn record_ended_meetings(
+    pool: &SqlitePool,
+    now: DateTime<Utc>,
+) -> Result<u64, String> {
+    let rows = sqlx::query(
+        "SELECT id, provider_id, calendar_id, title, start_time, end_time, series_id
+         FROM calendar_events WHERE ignored = 0 AND is_all_day = 0",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut recorded = 0;
+    for row in rows {
+        let start: String = row.get("start_time");
+        let end: String = row.get("end_time");
+        let (Some(start), Some(end)) = (parse_timestamp(&start), parse_timestamp(&end)) else {
+            continue;
+        };
+        if end > now {
+            continue;
+        }
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO history_meetings
+               (event_id, provider_id, calendar_id, title, start_time, end_time, series_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )
+        .bind(row.get::<String, _>("id"))
+        .bind(row.get::<String, _>("provider_id"))
+        .bind(row.get::<Option<String>, _>("calendar_id"))
+        .bind(row.get::<String, _>("title"))
+        .bind(timestamp(start))
+        .bind(timestamp(end))
+        .bind(row.get::<Option<String>, _>("series_id"))
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        recorded += result.rows_affected();
+    }
+    Ok(recorded)
+}
+
+/// Delete history older than `cutoff`. Returns the number of rows removed.
+async fn purge_inner(pool: &SqlitePool, cutoff: DateTime<Utc>) -> Result<u64, String> {
+    let cutoff = timestamp(cutoff);
+    let mut removed = 0;
+    for sql in [
+        "DELETE FROM history_phase_transitions WHERE at < ?1",
+        "DELETE FROM history_meetings WHERE end_time < ?1",
+        "DELETE FROM history_timer_sessions WHERE ended_at < ?1",
+        "DELETE FROM history_pauses WHERE COALESCE(ended_at, planned_until, started_at) < ?1",
+        "DELETE FROM meeting_overruns WHERE ended_at < ?1",
+        "DELETE FROM history_focus_blocks WHERE at < ?1",
+    ] {
+        removed += sqlx::query(sql)
+            .bind(&cutoff)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .rows_affected();
+    }
+    Ok(removed)
+}
+
+async fn load_rows(
+    pool: &SqlitePool,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<HistoryRows, String> {
+    let (from_s, to_s) = (timestamp(from), timestamp(to));
+    let err = |e: sqlx::Error| e.to_string();
+
+    let prior_phase = sqlx::query_scalar::<_, String>(
+        "SELECT phase FROM history_phase_transitions WHERE at < ?1 ORDER BY at DESC, id DESC LIMIT 1",
+    )
+    .bind(&from_s)
+    .fetch_optional(pool)
+    .await
+    .map_err(err)?;
+
+    let transitions = sqlx::query(
+        "SELECT phase, at FROM history_phase_transitions
+         WHERE at >= ?1 AND at < ?2 ORDER BY at, id",
+    )
+    .bind(&from_s)
+    .bind(&to_s)
+    .fetch_all(pool)
+    .await
+    .map_err(err)?
+    .iter()
+    .filter_map(|r| Some((r.get("phase"), parse_timestamp(&r.get::<String, _>("at"))?)))
+    .collect();
+
+    let meetings = sqlx::query(
+        "SELECT start_time, end_time FROM history_meetings
+         WHERE start_time >= ?1 AND start_time < ?2",
+    )
+    .bind(&from_s)
+    .bind(&to_s)
+    .fetch_all(pool)
+    .await
+    .map_err(err)?
+    .iter()
+    .filter_map(|r| {
+        Some((
+            parse_timestamp(&r.get::<String, _>("start_time"))?,
+            parse_timestamp(&r.get::<String, _>("end_time"))?,
+        ))
+    })
+    .collect();
+
+    let timer_sessions = sqlx::query(
+        "SELECT started_at, active_seconds FROM history_timer_sessions
+         WHERE started_at >= ?1 AND started_at < ?2",
+    )
+    .bind(&from_s)
+    .bind(&to_s)
+    .fetch_all(pool)
+    .await
+    .map_err(err)?
+    .iter()
+    .filter_map(|r| {
+        Some((
+            parse_timestamp(&r.get::<String, _>("started_at"))?,
+            r.get::<i64, _>("active_seconds").max(0) as u32,
+        ))
+    })
+    .collect();
+
+    let pauses = sqlx::query(
+        "SELECT started_at, COALESCE(ended_at, planned_until) AS ended_at FROM history_pauses
+         WHERE started_at < ?2 AND (COALESCE(ended_at, planned_until) IS NULL
+                                    OR COALESCE(ended_at, planned_until) > ?1)",
+    )
+    .bind(&from_s)
+    .bind(&to_s)
+    .fetch_all(pool)
+    .await
+    .map_err(err)?
+    .iter()
+    .filter_map(|r| {
+        let ended_at = r
+            .get::<Option<String>, _>("ended_at")
+            .and_then(|s| parse_timestamp(&s));
+        Some((
+            parse_timestamp(&r.get::<String, _>("started_at"))?,
+            ended_at,
+        ))
+    })
+    .collect();
+
+    let focus_blocks = sqlx::query_scalar::<_, String>(
+        "SELECT at FROM history_focus_blocks WHERE at >= ?1 AND at < ?2",
+    )
+    .bind(&from_s)
+    .bind(&to_s)
+    .fetch_all(pool)
+    .await
+    .map_err(err)?
+    .iter()
+    .filter_map(|at| parse_timestamp(at))
+    .collect();
+
+    Ok(HistoryRows {
+        prior_phase,
+        transitions,
+        meetings,
+        timer_sessions,
+        pauses,
+        focus_blocks,
+    })
+}
+
+async fn insights_inner(
+    pool: &SqlitePool,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    offset: FixedOffset,
+) -> Result<HistoryInsights, String> {
+    let rows = load_rows(pool, from, to).await?;
+    Ok(aggregate(&rows, from, to, offset))
+}
+
+/// Start of the local day `days - 1` days before `now`.
+fn range_start(now: DateTime<Utc>, days: u32, offset: FixedOffset) -> DateTime<Utc> {
+    let today = now.with_timezone(&offset).date_naive();
+    let first = today - chrono::Duration::days(i64::from(days.max(1)) - 1);
+    first
+        .and_hms_opt(0, 0, 0)
+        .and_then(|d| offset.from_local_datetime(&d).single())
+        .map_or(now, |d| d.with_timezone(&Utc))
+}
+
+// --- Runtime ---
+
+/// Run a history write in the background, logging failures.
+macro_rules! spawn_write {
+    ($app:expr, |$pool:ident| $body:expr) => {{
+        let app = $app.clone();
+        tauri::async_runtime::spawn(async move {
+            let $pool = match settings::app_pool(&app).await {
+                Ok(pool) => pool,
+                Err(_) => return,
+            };
+            if let Err(e) = $body.await {
+                eprintln!("[history] Write failed: {e}");
+            }
+        });
+    }};
+}
+
+/// Record a phase change at the current time.
+pub fn record_phase(app: &AppHandle, phase: String) {
+    let at = Utc::now();
+    spawn_write!(app, |pool| insert_phase(&pool, &phase, at));
+}
+
+/// Record finished timer sessions.
+pub fn record_timer_sessions(app: &AppHandle, sessions: Vec<TimerSessionRecord>) {
+    for session in sessions {
+        spawn_write!(app, |pool| insert_timer_session(&pool, &session));
+    }
+}
+
+/// Record a border pause planned to last `until`, or a resume (`None`).
+pub fn record_pause(app: &AppHandle, until: Option<DateTime<Utc>>, reason: String) {
+    let at = Utc::now();
+    match until {
+        Some(until) => spawn_write!(app, |pool| start_pause(&pool, at, Some(until), &reason)),
+        None => spawn_write!(app, |pool| end_pause(&pool, at)),
+    }
+}
+
+/// Record a blocked app caught by focus mode, and what was done about it.
+pub fn record_focus_block(app: &AppHandle, blocked_app: String, action: &'static str) {
+    let at = Utc::now();
+    spawn_write!(app, |pool| insert_focus_block(
+        &pool,
+        &blocked_app,
+        action,
+        at
+    ));
+}
+
+/// Mark the app as offline. Blocks briefly so the write lands before exit.
+pub fn record_shutdown(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::block_on(async move {
+        if let Ok(pool) = settings::app_pool(&app).await {
+            let _ = end_pause(&pool, Utc::now()).await;
+            let _ = insert_phase(&pool, OFFLINE_PHASE, Utc::now()).await;
+        }
+    });
+}
+
+async fn purge_expired(app: &AppHandle, pool: &SqlitePool) {
+    let days = settings::current_settings(app).await.history_retention_days;
+    let cutoff = Utc::now() - chrono::Duration::days(i64::from(days));
+    match purge_inner(pool, cutoff).await {
+        Ok(0) => {}
+        Ok(n) => eprintln!("[history] Purged {n} rows older than {days} days"),
+        Err(e) => eprintln!("[history] Purge failed: {e}"),
+    }
+}
+
+/// Start background recording: marks the start of this run, then purges
+/// expired rows periodically.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let Ok(pool) = settings::app_pool(&app).await else {
+            return;
+        };
+        // Anything recorded before this point ended when the app last stopped.
+        if let Err(e) = insert_phase(&pool, OFFLINE_PHASE, Utc::now()).await {
+            eprintln!("[history] Failed to mark startup: {e}");
+        }
+
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            purge_expired(&app, &pool).await;
+        }
+    });
+}
+
+// --- Tauri commands ---
+
+/// Aggregate the last `days` local days (including today) of history.
+#[command]
+pub async fn get_history_insights(app: AppHandle, days: u32) -> Result<HistoryInsights, String> {
+    if days == 0 || days > MAX_INSIGHT_DAYS {
+        return Err(format!("days must be between 1 and {MAX_INSIGHT_DAYS}"));
+    }
+    let pool = settings::app_pool(&app).await?;
+    let now = Utc::now();
+    let offset = *chrono::Local::now().offset();
+    insights_inner(&pool, range_start(now, days, offset), now, offset).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn test_pool() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory pool");
+        crate::migrations::migrate(&pool).await.unwrap();
+        pool
+    }
+
+    fn utc() -> FixedOffset {
+        FixedOffset::east_opt(0).unwrap()
+    }
+
+    /// 2026-03-02 at the given UTC time.
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, hour, minute, 0).unwrap()
+    }
+
+    fn day_start() -> DateTime<Utc> {
+        at(0, 0)
+    }
+
+    async fn phases(pool: &SqlitePool, entries: &[(&str, DateTime<Utc>)]) {
+        for (phase, t) in entries {
+            insert_phase(pool, phase, *t).await.unwrap();
+        }
+    }
+
+    async fn meeting(pool: &SqlitePool, id: &str, start: DateTime<Utc>, end: DateTime<Utc>) {
+        sqlx::query(
+            "INSERT INTO history_meetings (event_id, provider_id, title, start_time, end_time)
+             VALUES (?1, 'google', 'Meeting', ?2, ?3)",
+        )
+        .bind(id)
+        .bind(timestamp(start))
+        .bind(timestamp(end))
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_deep_work_and_overtime_minutes() {
+        let pool = test_pool().await;
+        phases(
+            &pool,
+            &[
+                ("free-deep", at(8, 0)),
+                ("warning-far", at(9, 30)),
+                ("in-session-early", at(10, 0)),
+                ("overtime", at(11, 0)),
+                ("free-deep", at(11, 5)),
+                ("offline", at(12, 0)),
+            ],
+        )
+        .await;
+
+        let insights = insights_inner(&pool, day_start(), at(18, 0), utc())
+            .await
+            .unwrap();
+        assert_eq!(insights.days.len(), 1);
+        assert_eq!(insights.deep_work_minutes, 90.0 + 55.0);
+        assert_eq!(insights.overtime_minutes, 5.0);
+    }
+
+    #[tokio::test]
+    async fn test_open_phase_runs_until_range_end() {
+        let pool = test_pool().await;
+        phases(&pool, &[("free-deep", at(16, 0))]).await;
+
+        let insights = insights_inner(&pool, day_start(), at(17, 30), utc())
+            .await
+            .unwrap();
+        assert_eq!(insights.deep_work_minutes, 90.0);
+    }
+
+    #[tokio::test]
+    async fn test_phase_started_before_range_is_clipped() {
+        let pool = test_pool().await;
+        // Deep work from 22:00 the previous day until 01:00.
+        phases(
+            &pool,
+            &[
+                ("free-deep", at(0, 0) - chrono::Duration::hours(2)),
+                ("in-session-early", at(1, 0)),
+            ],
+        )
+        .await;
+
+        let insights = insights_inner(&pool, day_start(), at(12, 0), utc())
+            .await
+            .unwrap();
+        assert_eq!(insights.deep_work_minutes, 60.0);
+    }
+
+    #[tokio::test]
+    async fn test_context_switches_count_meeting_boundaries() {
+        let pool = test_pool().await;
+        phases(
+            &pool,
+            &[
+                ("free-deep", at(8, 0)),
+                ("warning-near", at(8, 55)),     // still not in a meeting
+                ("in-session-early", at(9, 0)),  // switch 1
+                ("in-session-mid", at(9, 20)),   // same context
+                ("overtime", at(10, 0)),         // same context
+                ("gap-short", at(10, 5)),        // switch 2
+                ("offline", at(12, 0)),          // resets
+                ("in-session-early", at(13, 0)), // first after offline: not a switch
+                ("free-moderate", at(14, 0)),    // switch 3
+            ],
+        )
+        .await;
+
+        let insights = insights_inner(&pool, day_start(), at(18, 0), utc())
+            .await
+            .unwrap();
+        assert_eq!(insights.context_switches, 3);
+    }
+
+    #[tokio::test]
+    async fn test_off_hours_are_not_free_time() {
+        let pool = test_pool().await;
+        phases(
+            &pool,
+            &[
+                ("off-hours", at(0, 0)),
+                ("free-deep", at(9, 0)), // start of the day: not a switch
+                ("in-session-early", at(16, 0)), // switch
+                ("off-hours", at(17, 0)), // meeting to evening: not a switch
+                ("in-session-early", at(19, 0)), // evening call: not a switch
+                ("off-hours", at(20, 0)),
+            ],
+        )
+        .await;
+
+        let insights = insights_inner(&pool, day_start(), at(23, 0), utc())
+            .await
+            .unwrap();
+        assert_eq!(insights.deep_work_minutes, 7.0 * 60.0);
+        assert_eq!(insights.context_switches, 1);
+    }
+
+    #[tokio::test]
+    async fn test_average_gap_between_meetings() {
+        let pool = test_pool().await;
+        meeting(&pool, "a", at(9, 0), at(9, 30)).await;
+        meeting(&pool, "b", at(10, 0), at(11, 0)).await; // 30 min gap
+        meeting(&pool, "c", at(11, 0), at(11, 30)).await; // back to back: 0
+        meeting(&pool, "d", at(11, 15), at(12, 0)).await; // overlap: 0
+
+        let insights = insights_inner(&pool, day_start(), at(18, 0), utc())
+            .await
+            .unwrap();
+        assert_eq!(insights.meetings, 4);
+        assert_eq!(insights.average_gap_minutes, Some(10.0));
+    }
+
+    #[tokio::test]
+    async fn test_single_meeting_has_no_gap() {
+        let pool = test_pool().await;
+        meeting(&pool, "a", at(9, 0), at(9, 30)).await;
+
+        let insights = insights_inner(&pool, day_start(), at(18, 0), utc())
+            .await
+            .unwrap();
+        assert_eq!(insights.average_gap_minutes, None);
+    }
+
+    #[tokio::test]
+    async fn test_days_bucketed_by_local_offset() {
+        let pool = test_pool().await;
+        // 23:00-01:00 UTC is 00:00-02:00 on the next day at UTC+1.
+        phases(
+            &pool,
+            &[
+                ("free-deep", at(23, 0) - chrono::Duration::days(1)),
+                ("offline", at(1, 0)),
+            ],
+        )
+        .await;
+        let plus_one = FixedOffset::east_opt(3600).unwrap();
+        let from = range_start(at(12, 0), 2, plus_one);
+
+        let insights = insights_inner(&pool, from, at(12, 0), plus_one)
+            .await
+            .unwrap();
+        assert_eq!(insights.days.len(), 2);
+        assert_eq!(insights.days[0].deep_work_minutes, 0.0);
+        assert_eq!(insights.days[1].deep_work_minutes, 120.0);
+    }
+
+    #[tokio::test]
+    async fn test_timer_sessions_and_pauses() {
+        let pool = test_pool().await;
+        insert_timer_session(
+            &pool,
+            &TimerSessionRecord {
+                preset_id: "pomodoro".into(),
+                name: "Pomodoro".into(),
+                duration_seconds: 1500,
+                active_seconds: 1500,
+                started_at: at(9, 0),
+                ended_at: at(9, 25),
+                outcome: "completed",
+            },
+        )
+        .await
+        .unwrap();
+        start_pause(&pool, at(12, 0), Some(at(12, 30)), "manual")
+            .await
+            .unwrap();
+        end_pause(&pool, at(12, 10)).await.unwrap();
+        // Expired without an explicit resume: counts until its planned end.
+        start_pause(&pool, at(14, 0), Some(at(14, 15)), "manual")
+            .await
+            .unwrap();
+
+        let insights = insights_inner(&pool, day_start(), at(18, 0), utc())
+            .await
+            .unwrap();
+        assert_eq!(insights.days[0].timer_sessions, 1);
+        assert_eq!(insights.timer_minutes, 25.0);
+        assert_eq!(insights.pause_minutes, 10.0 + 15.0);
+    }
+
+    #[tokio::test]
+    async fn test_counts_blocked_apps_per_day() {
+        let pool = test_pool().await;
+        for (app, at) in [
+            ("Slack", at(9, 30)),
+            ("Slack", at(10, 0)),
+            ("Steam", at(23, 30)),
+        ] {
+            insert_focus_block(&pool, app, "warned", at).await.unwrap();
+        }
+
+        let plus_one = FixedOffset::east_opt(3600).unwrap();
+        let insights = insights_inner(&pool, day_start(), at(23, 59), plus_one)
+            .await
+            .unwrap();
+        assert_eq!(insights.blocked_apps, 3);
+        assert_eq!(insights.days.last().unwrap().blocked_apps, 1);
+
+        assert_eq!(purge_inner(&pool, at(12, 0)).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_records_only_ended_meetings_once() {
+        let pool = test_pool().await;
+        for (id, start, end, ignored) in [
+            ("done", at(9, 0), at(10, 0), 0),
+            ("later", at(15, 0), at(16, 0), 0),
+            ("skipped", at(8, 0), at(8, 30), 1),
+        ] {
+            sqlx::query(
+                "INSERT INTO calendar_events (id, provider_id, title, start_time, end_time, ignored)
+                 VALUES (?1, 'google', 'M', ?2, ?3, ?4)",
+            )
+            .bind(id)
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339())
+            .bind(ignored)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(record_ended_meetings(&pool, at(12, 0)).await.unwrap(), 1);
+        assert_eq!(record_ended_meetings(&pool, at(12, 5)).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_purge_removes_rows_older_than_cutoff() {
+        let pool = test_pool().await;
+        phases(&pool, &[("free-deep", at(8, 0)), ("offline", at(20, 0))]).await;
+        meeting(&pool, "old", at(7, 0), at(7, 30)).await;
+
+        let removed = purge_inner(&pool, at(12, 0)).await.unwrap();
+        assert_eq!(removed, 2);
+        let left: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM history_phase_transitions")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(left, 1);
+    }
+}
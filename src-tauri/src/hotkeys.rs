@@ -0,0 +1,435 @@
+//! Global keyboard shortcuts.
+//!
+//! Each action has its own `hotkey_*` setting holding an accelerator such as
+//! `CommandOrControl+Alt+Shift+T`; an empty value leaves the action unbound.
+//! Nothing is registered unless `hotkeys_enabled` is on. Two actions can't
+//! share a shortcut (`set_setting` rejects it), and a shortcut another app
+//! already holds is reported by `get_hotkey_status`.
+//!
+//! The actions run the same code as the `start-timer`, `pause-border` and
+//! `force-sync` listeners in `lib.rs`.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{command, AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::pause;
+use crate::settings::{self, Settings};
+use crate::timer::{self, TimerService};
+use crate::window_manager;
+
+pub const ENABLED_KEY: &str = "hotkeys_enabled";
+pub const START_TIMER_KEY: &str = "hotkey_start_timer";
+pub const TOGGLE_TIMER_KEY: &str = "hotkey_toggle_timer";
+pub const PAUSE_BORDER_KEY: &str = "hotkey_pause_border";
+pub const HIDE_BORDER_KEY: &str = "hotkey_hide_border";
+pub const SYNC_KEY: &str = "hotkey_sync";
+
+/// Settings that change which shortcuts are registered.
+pub const KEYS: &[&str] = &[
+    ENABLED_KEY,
+    START_TIMER_KEY,
+    TOGGLE_TIMER_KEY,
+    PAUSE_BORDER_KEY,
+    HIDE_BORDER_KEY,
+    SYNC_KEY,
+];
+
+/// How long the "pause border" shortcut pauses for.
+const PAUSE_MINUTES: u32 = 15;
+
+/// Something a shortcut can do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HotkeyAction {
+    /// Start the last preset or sequence again.
+    StartTimer,
+    /// Pause a running timer, or resume a paused one.
+    ToggleTimer,
+    /// Pause the border for 15 minutes.
+    PauseBorder,
+    /// Hide the border windows, or show them again.
+    HideBorder,
+    /// Fetch calendars now.
+    Sync,
+}
+
+impl HotkeyAction {
+    pub const ALL: [HotkeyAction; 5] = [
+        HotkeyAction::StartTimer,
+        HotkeyAction::ToggleTimer,
+        HotkeyAction::PauseBorder,
+        HotkeyAction::HideBorder,
+        HotkeyAction::Sync,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            HotkeyAction::StartTimer => START_TIMER_KEY,
+            HotkeyAction::ToggleTimer => TOGGLE_TIMER_KEY,
+            HotkeyAction::PauseBorder => PAUSE_BORDER_KEY,
+            HotkeyAction::HideBorder => HIDE_BORDER_KEY,
+            HotkeyAction::Sync => SYNC_KEY,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HotkeyAction::StartTimer => "Start last timer",
+            HotkeyAction::ToggleTimer => "Pause/resume timer",
+            HotkeyAction::PauseBorder => "Pause border for 15 minutes",
+            HotkeyAction::HideBorder => "Hide border",
+            HotkeyAction::Sync => "Sync calendars",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.key() == key)
+    }
+
+    /// The accelerator stored for this action.
+    fn binding(self, settings: &Settings) -> &str {
+        match self {
+            HotkeyAction::StartTimer => &settings.hotkey_start_timer,
+            HotkeyAction::ToggleTimer => &settings.hotkey_toggle_timer,
+            HotkeyAction::PauseBorder => &settings.hotkey_pause_border,
+            HotkeyAction::HideBorder => &settings.hotkey_hide_border,
+            HotkeyAction::Sync => &settings.hotkey_sync,
+        }
+    }
+}
+
+/// A parsed accelerator. `CommandOrControl` is resolved for this platform,
+/// so two spellings of the same keys compare equal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hotkey {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    /// Command on macOS, the Windows key elsewhere.
+    pub meta: bool,
+    /// Canonical key name: "T", "5", "F5", "Space", ...
+    pub key: String,
+}
+
+const NAMED_KEYS: &[&str] = &[
+    "Space",
+    "Tab",
+    "Enter",
+    "Escape",
+    "Backspace",
+    "Delete",
+    "Insert",
+    "Home",
+    "End",
+    "PageUp",
+    "PageDown",
+    "Up",
+    "Down",
+    "Left",
+    "Right",
+];
+
+/// The canonical name for a key token, if it is one we accept.
+fn key_name(token: &str) -> Option<String> {
+    let upper = token.to_ascii_uppercase();
+    let mut chars = upper.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return c.is_ascii_alphanumeric().then(|| c.to_string());
+    }
+    if let Some(n) = upper.strip_prefix('F').and_then(|n| n.parse::<u8>().ok()) {
+        return (1..=24).contains(&n).then(|| format!("F{n}"));
+    }
+    let alias = match upper.as_str() {
+        "ESC" => "ESCAPE",
+        "RETURN" => "ENTER",
+        "ARROWUP" => "UP",
+        "ARROWDOWN" => "DOWN",
+        "ARROWLEFT" => "LEFT",
+        "ARROWRIGHT" => "RIGHT",
+        other => other,
+    };
+    NAMED_KEYS
+        .iter()
+        .find(|k| k.eq_ignore_ascii_case(alias))
+        .map(|k| k.to_string())
+}
+
+impl FromStr for Hotkey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens: Vec<&str> = s.split('+').map(str::trim).collect();
+        let (key, modifiers) = tokens.split_last().ok_or("empty shortcut")?;
+        let mut hotkey = Hotkey {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            meta: false,
+            key: key_name(key).ok_or_else(|| format!("unknown key '{key}'"))?,
+        };
+        for modifier in modifiers {
+            let flag = match modifier.to_ascii_lowercase().as_str() {
+                "commandorcontrol" | "cmdorctrl" | "commandorctrl" | "cmdorcontrol" => {
+                    if cfg!(target_os = "macos") {
+                        &mut hotkey.meta
+                    } else {
+                        &mut hotkey.ctrl
+                    }
+                }
+                "control" | "ctrl" => &mut hotkey.ctrl,
+                "alt" | "option" => &mut hotkey.alt,
+                "shift" => &mut hotkey.shift,
+                "command" | "cmd" | "super" | "meta" => &mut hotkey.meta,
+                _ => return Err(format!("unknown modifier '{modifier}'")),
+            };
+            if *flag {
+                return Err(format!("'{modifier}' is repeated"));
+            }
+            *flag = true;
+        }
+        // Without Control, Alt or Command the shortcut would swallow typing.
+        if !(hotkey.ctrl || hotkey.alt || hotkey.meta) {
+            return Err("needs Control, Alt or Command".to_string());
+        }
+        Ok(hotkey)
+    }
+}
+
+impl fmt::Display for Hotkey {
+    /// Spelled the way the global shortcut plugin parses it.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (on, name) in [
+            (self.ctrl, "Control"),
+            (self.alt, "Alt"),
+            (self.shift, "Shift"),
+            (self.meta, "Super"),
+        ] {
+            if on {
+                write!(f, "{name}+")?;
+            }
+        }
+        f.write_str(&self.key)
+    }
+}
+
+/// Check a stored value: empty (unbound) or a valid shortcut.
+pub fn validate(value: &str) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Ok(());
+    }
+    value.parse::<Hotkey>().map(|_| ())
+}
+
+/// The bound actions in `settings`. Invalid values are skipped.
+pub fn bindings(settings: &Settings) -> Vec<(HotkeyAction, Hotkey)> {
+    HotkeyAction::ALL
+        .into_iter()
+        .filter_map(|action| Some((action, action.binding(settings).parse().ok()?)))
+        .collect()
+}
+
+/// Check that giving `key` the shortcut `value` doesn't take one another
+/// action already uses.
+pub fn check_conflict(settings: &Settings, key: &str, value: &str) -> Result<(), String> {
+    let (Some(action), Ok(hotkey)) = (HotkeyAction::from_key(key), value.parse::<Hotkey>()) else {
+        return Ok(());
+    };
+    match bindings(settings)
+        .into_iter()
+        .find(|(other, bound)| *other != action && *bound == hotkey)
+    {
+        Some((other, _)) => Err(format!(
+            "{hotkey} is already the shortcut for \"{}\"",
+            other.label()
+        )),
+        None => Ok(()),
+    }
+}
+
+// --- Runtime ---
+
+/// One action's registration result.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyStatus {
+    pub action: HotkeyAction,
+    pub shortcut: String,
+    /// Why the shortcut couldn't be registered, e.g. another app holds it.
+    pub error: Option<String>,
+}
+
+/// Registered shortcuts.
+#[derive(Debug, Default)]
+pub struct Hotkeys {
+    statuses: Vec<HotkeyStatus>,
+}
+
+fn toggle_timer(app: &AppHandle) -> Result<(), String> {
+    let status = app
+        .state::<Mutex<TimerService>>()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .state()
+        .status
+        .clone();
+    match status.as_str() {
+        "running" => timer::pause(app),
+        "paused" => timer::resume(app),
+        _ => Ok(()),
+    }
+}
+
+/// Hide the border, or show it again unless screen sharing still hides it.
+fn toggle_border(app: &AppHandle) -> Result<(), String> {
+    window_manager::update_visibility(app, |v| v.hotkey = !v.hotkey)
+}
+
+fn run(app: &AppHandle, action: HotkeyAction) {
+    let result = match action {
+        HotkeyAction::StartTimer => timer::start_last(app),
+        HotkeyAction::ToggleTimer => toggle_timer(app),
+        HotkeyAction::PauseBorder => pause::pause_for(app, PAUSE_MINUTES, "hotkey").map(|_| ()),
+        HotkeyAction::HideBorder => toggle_border(app),
+        HotkeyAction::Sync => {
+            let handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = crate::force_sync(handle).await {
+                    eprintln!("[hotkeys] Sync failed: {e}");
+                }
+            });
+            Ok(())
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("[hotkeys] {} failed: {e}", action.label());
+    }
+}
+
+/// Register the shortcuts from settings, replacing any registered before.
+pub async fn apply(app: &AppHandle) {
+    let settings = settings::current_settings(app).await;
+    let shortcuts = app.global_shortcut();
+    if let Err(e) = shortcuts.unregister_all() {
+        eprintln!("[hotkeys] Failed to unregister shortcuts: {e}");
+    }
+
+    let mut statuses = Vec::new();
+    if settings.hotkeys_enabled {
+        for (action, hotkey) in bindings(&settings) {
+            let shortcut = hotkey.to_string();
+            let registered = shortcuts.on_shortcut(shortcut.as_str(), move |app, _, event| {
+                if event.state == ShortcutState::Pressed {
+                    run(app, action);
+                }
+            });
+            let error = registered.err().map(|e| {
+                eprintln!("[hotkeys] Failed to register {shortcut}: {e}");
+                e.to_string()
+            });
+            statuses.push(HotkeyStatus {
+                action,
+                shortcut,
+                error,
+            });
+        }
+    }
+
+    if let Ok(mut hotkeys) = app.state::<Mutex<Hotkeys>>().lock() {
+        hotkeys.statuses = statuses;
+    }
+}
+
+/// Register shortcuts once settings are available.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        apply(&app).await;
+    });
+}
+
+// --- Tauri commands ---
+
+/// Registered shortcuts, with an error for any that couldn't be registered.
+#[command]
+pub fn get_hotkey_status(app: AppHandle) -> Result<Vec<HotkeyStatus>, String> {
+    let managed = app.state::<Mutex<Hotkeys>>();
+    let hotkeys = managed.lock().map_err(|e| e.to_string())?;
+    Ok(hotkeys.statuses.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<Hotkey, String> {
+        s.parse()
+    }
+
+    #[test]
+    fn test_parses_and_formats_canonically() {
+        let hotkey = parse("ctrl + shift + alt + t").unwrap();
+        assert_eq!(hotkey.to_string(), "Control+Alt+Shift+T");
+        assert_eq!(parse("Super+Esc").unwrap().to_string(), "Super+Escape");
+        assert_eq!(parse("Option+f5").unwrap().to_string(), "Alt+F5");
+        assert_eq!(parse("Ctrl+ArrowUp").unwrap().key, "Up");
+        assert_eq!(parse("Ctrl+Alt+pagedown").unwrap().key, "PageDown");
+        assert_eq!(parse("Ctrl+7").unwrap().key, "7");
+    }
+
+    #[test]
+    fn test_command_or_control_resolves_per_platform() {
+        let hotkey = parse("CmdOrCtrl+Shift+B").unwrap();
+        if cfg!(target_os = "macos") {
+            assert_eq!(hotkey, parse("Command+Shift+B").unwrap());
+        } else {
+            assert_eq!(hotkey, parse("Control+Shift+B").unwrap());
+        }
+    }
+
+    #[test]
+    fn test_rejects_invalid_shortcuts() {
+        for bad in [
+            "T",
+            "Shift+T",
+            "Ctrl+",
+            "Ctrl+Ctrl+T",
+            "Hyper+T",
+            "Ctrl+F25",
+            "Ctrl+Shift",
+        ] {
+            assert!(parse(bad).is_err(), "{bad} should be rejected");
+        }
+        assert!(validate("").is_ok());
+        assert!(validate("Ctrl+Alt+T").is_ok());
+        assert!(validate("Alt").is_err());
+    }
+
+    #[test]
+    fn test_detects_conflicts_between_actions() {
+        let settings = Settings {
+            hotkey_start_timer: "Control+Alt+T".to_string(),
+            hotkey_sync: "".to_string(),
+            ..Settings::default()
+        };
+        let err = check_conflict(&settings, SYNC_KEY, "ctrl+alt+t").unwrap_err();
+        assert!(err.contains("Start last timer"), "{err}");
+
+        // Rebinding an action to its own shortcut, or unbinding, is fine.
+        assert!(check_conflict(&settings, START_TIMER_KEY, "Alt+Control+T").is_ok());
+        assert!(check_conflict(&settings, SYNC_KEY, "").is_ok());
+        assert!(check_conflict(&settings, SYNC_KEY, "Ctrl+Alt+Y").is_ok());
+    }
+
+    #[test]
+    fn test_default_bindings_are_distinct() {
+        let bound = bindings(&Settings::default());
+        assert_eq!(bound.len(), HotkeyAction::ALL.len());
+        for (i, (_, a)) in bound.iter().enumerate() {
+            assert!(bound[i + 1..].iter().all(|(_, b)| a != b));
+        }
+    }
+}
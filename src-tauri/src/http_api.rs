@@ -0,0 +1,716 @@
+//! Opt-in localhost HTTP/JSON API for scripts and status bars (tmux, polybar,
+//! Stream Deck, shell prompts).
+//!
+//! The server listens on `127.0.0.1:<http_api_port>` while `http_api_enabled`
+//! is on. Every request must carry the API token, either as
+//! `Authorization: Bearer <token>` or as a `?token=` query parameter (for
+//! clients such as `EventSource` that cannot set headers). The token lives in
+//! the OS keyring; the settings window reads it with `get_http_api_token`.
+//!
+//! | Method | Path                 | Body                                   |
+//! |--------|----------------------|----------------------------------------|
+//! | GET    | `/v1/state`          |                                        |
+//! | GET    | `/v1/events/next`    |                                        |
+//! | GET    | `/v1/providers`      |                                        |
+//! | GET    | `/v1/stream`         | Server-Sent Events of state changes    |
+//! | POST   | `/v1/timer/start`    | `{presetId}`, `{sequenceId}` or `{durationSeconds, name?}` |
+//! | POST   | `/v1/timer/pause`    |                                        |
+//! | POST   | `/v1/timer/resume`   |                                        |
+//! | POST   | `/v1/timer/stop`     |                                        |
+//! | POST   | `/v1/border/pause`   | `{minutes}`                            |
+//! | POST   | `/v1/border/resume`  |                                        |
+//! | POST   | `/v1/sync`           |                                        |
+//!
+//! Actions return the state snapshot after they ran. Errors are returned as
+//! `{"error": "..."}` with a 4xx/5xx status.
+
+use std::io::{Read, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Listener, Manager};
+use thiserror::Error;
+use tiny_http::{Method, Request};
+use url::Url;
+
+use crate::border_state::BorderState;
+use crate::calendar::types::CalendarEvent;
+use crate::pause::{self, PauseState};
+use crate::settings;
+use crate::timer::{self, TimerState};
+use crate::ProviderStatusPayload;
+
+/// Setting key that turns the server on.
+pub const ENABLED_KEY: &str = "http_api_enabled";
+
+/// Setting key for the localhost port.
+pub const PORT_KEY: &str = "http_api_port";
+
+const KEYRING_SERVICE: &str = "com.morph.http-api";
+const KEYRING_TOKEN: &str = "token";
+
+/// Tauri events forwarded to `/v1/stream` subscribers, under the same names.
+const STREAM_EVENTS: &[&str] = &[
+    "border-state-update",
+    "timer-state-update",
+    "border-paused",
+    "border-resumed",
+    "overtime-state-update",
+    "provider-status-update",
+    "calendar-events-update",
+];
+
+/// Open `/v1/stream` connections allowed at once.
+const MAX_STREAMS: usize = 8;
+
+/// Frames buffered per stream before a slow client starts missing events.
+const STREAM_BUFFER: usize = 64;
+
+/// A comment line is sent this often on idle streams so dead clients are
+/// noticed and proxies keep the connection open.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Request bodies larger than this are rejected.
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+/// Errors returned to API clients.
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("missing or invalid API token")]
+    Unauthorized,
+
+    #[error("not found")]
+    NotFound,
+
+    #[error("{0}")]
+    BadRequest(String),
+
+    #[error("too many open streams")]
+    TooManyStreams,
+
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl ApiError {
+    fn status(&self) -> u16 {
+        match self {
+            ApiError::Unauthorized => 401,
+            ApiError::NotFound => 404,
+            ApiError::BadRequest(_) => 400,
+            ApiError::TooManyStreams => 503,
+            ApiError::Internal(_) => 500,
+        }
+    }
+}
+
+/// An API endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route {
+    State,
+    NextEvent,
+    Providers,
+    Stream,
+    StartTimer,
+    PauseTimer,
+    ResumeTimer,
+    StopTimer,
+    PauseBorder,
+    ResumeBorder,
+    Sync,
+}
+
+/// Match a request method and path (without query string) to a route.
+pub fn route(method: &Method, path: &str) -> Option<Route> {
+    let path = path.trim_end_matches('/');
+    let route = match (method, path) {
+        (Method::Get, "/v1/state") => Route::State,
+        (Method::Get, "/v1/events/next") => Route::NextEvent,
+        (Method::Get, "/v1/providers") => Route::Providers,
+        (Method::Get, "/v1/stream") => Route::Stream,
+        (Method::Post, "/v1/timer/start") => Route::StartTimer,
+        (Method::Post, "/v1/timer/pause") => Route::PauseTimer,
+        (Method::Post, "/v1/timer/resume") => Route::ResumeTimer,
+        (Method::Post, "/v1/timer/stop") => Route::StopTimer,
+        (Method::Post, "/v1/border/pause") => Route::PauseBorder,
+        (Method::Post, "/v1/border/resume") => Route::ResumeBorder,
+        (Method::Post, "/v1/sync") => Route::Sync,
+        _ => return None,
+    };
+    Some(route)
+}
+
+/// Body of `POST /v1/timer/start`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum StartTimerRequest {
+    #[serde(rename_all = "camelCase")]
+    Preset { preset_id: String },
+    #[serde(rename_all = "camelCase")]
+    Sequence { sequence_id: String },
+    #[serde(rename_all = "camelCase")]
+    Duration {
+        duration_seconds: u32,
+        #[serde(default)]
+        name: Option<String>,
+    },
+}
+
+/// Body of `POST /v1/border/pause`.
+#[derive(Debug, Clone, Deserialize)]
+struct PauseBorderRequest {
+    minutes: i32,
+}
+
+/// Everything a status bar needs in one response.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StateSnapshot {
+    border: BorderState,
+    timer: TimerState,
+    pause: PauseState,
+    next_event: Option<CalendarEvent>,
+    providers: Vec<ProviderStatusPayload>,
+}
+
+/// The first upcoming meeting the overlay would count: not ignored, not
+/// all-day, and not on an ignored calendar.
+pub fn next_event(
+    events: &[CalendarEvent],
+    now: DateTime<Utc>,
+    ignored_calendar_ids: &[String],
+) -> Option<CalendarEvent> {
+    events
+        .iter()
+        .filter(|e| e.start_time > now && !e.ignored && !e.is_all_day)
+        .filter(|e| {
+            !e.calendar_id
+                .as_ref()
+                .is_some_and(|id| ignored_calendar_ids.contains(id))
+        })
+        .min_by_key(|e| e.start_time)
+        .cloned()
+}
+
+/// Whether the request carries `expected`, from the `Authorization` header
+/// or the `token` query parameter.
+pub fn authorized(authorization: Option<&str>, query_token: Option<&str>, expected: &str) -> bool {
+    let presented = authorization
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .map(str::trim)
+        .or(query_token);
+    presented.is_some_and(|t| tokens_match(t, expected))
+}
+
+/// Compare tokens without short-circuiting on the first differing byte.
+fn tokens_match(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+/// Format one Server-Sent Events frame. `data` is a single line of JSON.
+pub fn sse_frame(event: &str, data: &str) -> String {
+    format!("event: {event}\ndata: {data}\n\n")
+}
+
+/// Write frames from `rx` to a stream until the client disconnects or the
+/// server stops (the sender side is dropped).
+fn pump_stream(rx: &Receiver<String>, out: &mut impl Write, keepalive: Duration) {
+    loop {
+        let frame = match rx.recv_timeout(keepalive) {
+            Ok(frame) => frame,
+            Err(RecvTimeoutError::Timeout) => ": keepalive\n\n".to_string(),
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+        if out
+            .write_all(frame.as_bytes())
+            .and_then(|_| out.flush())
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+// --- Token ---
+
+fn keyring_entry() -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_TOKEN)
+        .map_err(|e| format!("keyring entry error: {e}"))
+}
+
+fn generate_token() -> String {
+    let bytes: Vec<u8> = (0..32).map(|_| rand::rng().random::<u8>()).collect();
+    URL_SAFE_NO_PAD.encode(&bytes)
+}
+
+/// Store a fresh token in the keyring and return it.
+fn store_new_token() -> Result<String, String> {
+    let token = generate_token();
+    keyring_entry()?
+        .set_password(&token)
+        .map_err(|e| format!("keyring set error: {e}"))?;
+    Ok(token)
+}
+
+/// The API token, created on first use.
+fn token(app: &AppHandle) -> Result<String, String> {
+    let api = app.state::<HttpApi>();
+    let mut cached = api.token.lock().map_err(|e| e.to_string())?;
+    if let Some(token) = cached.as_ref() {
+        return Ok(token.clone());
+    }
+    let token = match keyring_entry()?.get_password() {
+        Ok(token) => token,
+        Err(keyring::Error::NoEntry) => store_new_token()?,
+        Err(e) => return Err(format!("keyring get error: {e}")),
+    };
+    *cached = Some(token.clone());
+    Ok(token)
+}
+
+// --- Runtime ---
+
+struct RunningServer {
+    server: Arc<tiny_http::Server>,
+    port: u16,
+}
+
+/// Managed state for the HTTP API.
+#[derive(Default)]
+pub struct HttpApi {
+    server: Mutex<Option<RunningServer>>,
+    token: Mutex<Option<String>>,
+    subscribers: Arc<Mutex<Vec<SyncSender<String>>>>,
+}
+
+impl HttpApi {
+    /// Register a new stream subscriber.
+    fn subscribe(&self) -> Result<Receiver<String>, ApiError> {
+        let mut subscribers = self
+            .subscribers
+            .lock()
+            .map_err(|e| ApiError::Internal(e.to_string()))?;
+        if subscribers.len() >= MAX_STREAMS {
+            return Err(ApiError::TooManyStreams);
+        }
+        let (tx, rx) = mpsc::sync_channel(STREAM_BUFFER);
+        subscribers.push(tx);
+        Ok(rx)
+    }
+}
+
+/// Send a frame to every subscriber, dropping those that disconnected. A
+/// subscriber whose buffer is full misses the frame.
+fn broadcast(subscribers: &Mutex<Vec<SyncSender<String>>>, frame: &str) {
+    if let Ok(mut subscribers) = subscribers.lock() {
+        subscribers.retain(|tx| {
+            !matches!(
+                tx.try_send(frame.to_string()),
+                Err(TrySendError::Disconnected(_))
+            )
+        });
+    }
+}
+
+fn snapshot(app: &AppHandle) -> Result<StateSnapshot, ApiError> {
+    let internal = ApiError::Internal;
+    let border = app
+        .state::<Mutex<BorderState>>()
+        .lock()
+        .map_err(|e| internal(e.to_string()))?
+        .clone();
+    let pause = app
+        .state::<Mutex<PauseState>>()
+        .lock()
+        .map_err(|e| internal(e.to_string()))?
+        .clone();
+    let timer = timer::get_timer_state(app.clone()).map_err(internal)?;
+    let (next_event, providers) = tauri::async_runtime::block_on(async {
+        let pool = settings::app_pool(app).await?;
+        let events = crate::calendar::poller::load_cached_events(&pool).await?;
+        let ignored = settings::current_settings(app).await.ignored_calendar_ids;
+        let providers = crate::get_provider_statuses(app.clone()).await?;
+        Ok::<_, String>((next_event(&events, Utc::now(), &ignored), providers))
+    })
+    .map_err(internal)?;
+    Ok(StateSnapshot {
+        border,
+        timer,
+        pause,
+        next_event,
+        providers,
+    })
+}
+
+fn parse_body<T: serde::de::DeserializeOwned>(body: &str) -> Result<T, ApiError> {
+    serde_json::from_str(body).map_err(|e| ApiError::BadRequest(format!("invalid body: {e}")))
+}
+
+/// Run a non-streaming route and return its JSON response. Actions reuse the
+/// same functions as the Tauri commands and tray.
+pub(crate) fn dispatch(
+    app: &AppHandle,
+    route: Route,
+    body: &str,
+) -> Result<serde_json::Value, ApiError> {
+    let failed = ApiError::BadRequest;
+    match route {
+        Route::State | Route::Stream => {}
+        Route::NextEvent => {
+            let snapshot = snapshot(app)?;
+            return serde_json::to_value(snapshot.next_event)
+                .map_err(|e| ApiError::Internal(e.to_string()));
+        }
+        Route::Providers => {
+            let snapshot = snapshot(app)?;
+            return serde_json::to_value(snapshot.providers)
+                .map_err(|e| ApiError::Internal(e.to_string()));
+        }
+        Route::StartTimer => match parse_body(body)? {
+            StartTimerRequest::Preset { preset_id } => {
+                timer::start_preset_by_id(app, &preset_id).map_err(failed)?
+            }
+            StartTimerRequest::Sequence { sequence_id } => {
+                timer::start_sequence_by_id(app, &sequence_id).map_err(failed)?
+            }
+            StartTimerRequest::Duration {
+                duration_seconds,
+                name,
+            } => timer::start(app, timer::TimerPreset::custom(name, duration_seconds))
+                .map_err(failed)?,
+        },
+        Route::PauseTimer => timer::pause(app).map_err(failed)?,
+        Route::ResumeTimer => timer::resume(app).map_err(failed)?,
+        Route::StopTimer => timer::stop(app).map_err(failed)?,
+        Route::PauseBorder => {
+            let request: PauseBorderRequest = parse_body(body)?;
+            let minutes = u32::try_from(request.minutes)
+                .ok()
+                .filter(|m| *m > 0)
+                .ok_or_else(|| ApiError::BadRequest("minutes must be positive".to_string()))?;
+            pause::pause_for(app, minutes, "api").map_err(failed)?;
+        }
+        Route::ResumeBorder => pause::resume(app, "api").map_err(failed)?,
+        Route::Sync => tauri::async_runtime::block_on(crate::force_sync(app.clone()))
+            .map_err(ApiError::Internal)?,
+    }
+    serde_json::to_value(snapshot(app)?).map_err(|e| ApiError::Internal(e.to_string()))
+}
+
+/// What to send back for an accepted request.
+enum Reply {
+    Json(serde_json::Value),
+    Stream(Receiver<String>),
+}
+
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv(name))
+        .map(|h| h.value.as_str())
+}
+
+fn prepare(app: &AppHandle, request: &mut Request) -> Result<Reply, ApiError> {
+    let url = Url::parse(&format!("http://localhost{}", request.url()))
+        .map_err(|_| ApiError::NotFound)?;
+    let query_token = url
+        .query_pairs()
+        .find(|(k, _)| k == "token")
+        .map(|(_, v)| v.into_owned());
+    let expected = token(app).map_err(ApiError::Internal)?;
+    if !authorized(
+        header(request, "Authorization"),
+        query_token.as_deref(),
+        &expected,
+    ) {
+        return Err(ApiError::Unauthorized);
+    }
+
+    let route = route(request.method(), url.path()).ok_or(ApiError::NotFound)?;
+    if route == Route::Stream {
+        return app.state::<HttpApi>().subscribe().map(Reply::Stream);
+    }
+
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_string(&mut body)
+        .map_err(|e| ApiError::BadRequest(format!("unreadable body: {e}")))?;
+    dispatch(app, route, &body).map(Reply::Json)
+}
+
+fn respond_json(request: Request, status: u16, body: &serde_json::Value) {
+    let response = tiny_http::Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(
+            "Content-Type: application/json"
+                .parse::<tiny_http::Header>()
+                .expect("valid header"),
+        );
+    if let Err(e) = request.respond(response) {
+        eprintln!("[http_api] Failed to send response: {e}");
+    }
+}
+
+/// Answer an SSE request. tiny_http's chunked encoder buffers output, so the
+/// stream is written directly and delimited by closing the connection.
+fn respond_stream(request: Request, rx: Receiver<String>) {
+    let mut out = request.into_writer();
+    let head = "HTTP/1.1 200 OK\r\n\
+                Content-Type: text/event-stream\r\n\
+                Cache-Control: no-cache\r\n\
+                Connection: close\r\n\r\n";
+    if out
+        .write_all(head.as_bytes())
+        .and_then(|_| out.flush())
+        .is_ok()
+    {
+        pump_stream(&rx, &mut out, KEEPALIVE_INTERVAL);
+    }
+}
+
+fn handle_request(app: &AppHandle, mut request: Request) {
+    match prepare(app, &mut request) {
+        Ok(Reply::Json(body)) => respond_json(request, 200, &body),
+        Ok(Reply::Stream(rx)) => respond_stream(request, rx),
+        Err(e) => respond_json(
+            request,
+            e.status(),
+            &serde_json::json!({ "error": e.to_string() }),
+        ),
+    }
+}
+
+fn serve(app: AppHandle, server: Arc<tiny_http::Server>) {
+    for request in server.incoming_requests() {
+        let app = app.clone();
+        std::thread::spawn(move || handle_request(&app, request));
+    }
+}
+
+/// Start, stop or move the server to match the current settings.
+pub async fn apply(app: &AppHandle) {
+    let saved = settings::current_settings(app).await;
+    let wanted = saved.http_api_enabled.then_some(saved.http_api_port);
+
+    let api = app.state::<HttpApi>();
+    let Ok(mut running) = api.server.lock() else {
+        return;
+    };
+    if running.as_ref().map(|r| r.port) == wanted {
+        return;
+    }
+    if let Some(old) = running.take() {
+        old.server.unblock();
+        if let Ok(mut subscribers) = api.subscribers.lock() {
+            subscribers.clear();
+        }
+        eprintln!("[http_api] Stopped server on port {}", old.port);
+    }
+    let Some(port) = wanted else {
+        return;
+    };
+
+    if let Err(e) = token(app) {
+        eprintln!("[http_api] API token unavailable: {e}");
+        return;
+    }
+    match tiny_http::Server::http(("127.0.0.1", port)) {
+        Ok(server) => {
+            let server = Arc::new(server);
+            let handle = app.clone();
+            let thread_server = server.clone();
+            std::thread::spawn(move || serve(handle, thread_server));
+            *running = Some(RunningServer { server, port });
+            eprintln!("[http_api] Listening on 127.0.0.1:{port}");
+        }
+        Err(e) => eprintln!("[http_api] Failed to listen on port {port}: {e}"),
+    }
+}
+
+/// Forward state changes to stream subscribers and start the server if it
+/// is enabled.
+pub fn start(app: &tauri::App) {
+    let subscribers = app.state::<HttpApi>().subscribers.clone();
+    for &name in STREAM_EVENTS {
+        let subscribers = subscribers.clone();
+        app.listen(name, move |event| {
+            broadcast(&subscribers, &sse_frame(name, event.payload()));
+        });
+    }
+
+    let handle = app.handle().clone();
+    tauri::async_runtime::spawn(async move {
+        apply(&handle).await;
+    });
+}
+
+// --- Tauri commands ---
+
+/// Return the API token, creating one on first use.
+#[command]
+pub fn get_http_api_token(app: AppHandle) -> Result<String, String> {
+    token(&app)
+}
+
+/// Replace the API token. Clients using the old token stop working.
+#[command]
+pub fn regenerate_http_api_token(app: AppHandle) -> Result<String, String> {
+    let token = store_new_token()?;
+    let api = app.state::<HttpApi>();
+    *api.token.lock().map_err(|e| e.to_string())? = Some(token.clone());
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use chrono::TimeZone;
+
+    fn at(h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, h, m, 0).unwrap()
+    }
+
+    fn event(id: &str, start: DateTime<Utc>, calendar_id: Option<&str>) -> CalendarEvent {
+        CalendarEvent {
+            title: id.to_string(),
+            calendar_id: calendar_id.map(str::to_string),
+            ..test_support::event(id, start, start + chrono::Duration::minutes(30))
+        }
+    }
+
+    #[test]
+    fn test_routes() {
+        assert_eq!(route(&Method::Get, "/v1/state"), Some(Route::State));
+        assert_eq!(route(&Method::Get, "/v1/state/"), Some(Route::State));
+        assert_eq!(route(&Method::Get, "/v1/stream"), Some(Route::Stream));
+        assert_eq!(
+            route(&Method::Post, "/v1/timer/start"),
+            Some(Route::StartTimer)
+        );
+        assert_eq!(
+            route(&Method::Post, "/v1/border/pause"),
+            Some(Route::PauseBorder)
+        );
+        assert_eq!(route(&Method::Post, "/v1/sync"), Some(Route::Sync));
+        // Actions are POST-only so a stray GET cannot change state.
+        assert_eq!(route(&Method::Get, "/v1/timer/stop"), None);
+        assert_eq!(route(&Method::Post, "/v1/state"), None);
+        assert_eq!(route(&Method::Get, "/v2/state"), None);
+    }
+
+    #[test]
+    fn test_authorization() {
+        assert!(authorized(Some("Bearer secret"), None, "secret"));
+        assert!(authorized(None, Some("secret"), "secret"));
+        assert!(!authorized(Some("Bearer wrong"), None, "secret"));
+        assert!(!authorized(Some("secret"), None, "secret"));
+        assert!(!authorized(None, Some("secre"), "secret"));
+        assert!(!authorized(None, None, "secret"));
+    }
+
+    #[test]
+    fn test_start_timer_request_shapes() {
+        let preset: StartTimerRequest = serde_json::from_str(r#"{"presetId":"pomodoro"}"#).unwrap();
+        assert_eq!(
+            preset,
+            StartTimerRequest::Preset {
+                preset_id: "pomodoro".to_string()
+            }
+        );
+
+        let sequence: StartTimerRequest =
+            serde_json::from_str(r#"{"sequenceId":"pomodoro-cycle"}"#).unwrap();
+        assert_eq!(
+            sequence,
+            StartTimerRequest::Sequence {
+                sequence_id: "pomodoro-cycle".to_string()
+            }
+        );
+
+        let custom: StartTimerRequest =
+            serde_json::from_str(r#"{"durationSeconds":1500,"name":"Focus"}"#).unwrap();
+        assert_eq!(
+            custom,
+            StartTimerRequest::Duration {
+                duration_seconds: 1500,
+                name: Some("Focus".to_string())
+            }
+        );
+
+        assert!(serde_json::from_str::<StartTimerRequest>(r#"{"minutes":25}"#).is_err());
+    }
+
+    #[test]
+    fn test_next_event_skips_past_ignored_and_all_day() {
+        let mut ignored = event("ignored", at(9, 30), None);
+        ignored.ignored = true;
+        let mut all_day = event("all-day", at(9, 0), None);
+        all_day.is_all_day = true;
+        let events = vec![
+            event("past", at(8, 0), None),
+            ignored,
+            all_day,
+            event("hidden-calendar", at(9, 45), Some("holidays")),
+            event("later", at(11, 0), None),
+            event("next", at(10, 0), Some("work")),
+        ];
+
+        let next = next_event(&events, at(9, 0), &["holidays".to_string()]).unwrap();
+        assert_eq!(next.id, "next");
+        assert!(next_event(&events, at(12, 0), &[]).is_none());
+    }
+
+    #[test]
+    fn test_stream_writes_frames_and_keepalives_until_closed() {
+        let subscribers = Mutex::new(Vec::new());
+        let (tx, rx) = mpsc::sync_channel(STREAM_BUFFER);
+        subscribers.lock().unwrap().push(tx);
+
+        broadcast(
+            &subscribers,
+            &sse_frame("timer-state-update", r#"{"status":"running"}"#),
+        );
+        let writer = std::thread::spawn(move || {
+            let mut out = Vec::new();
+            pump_stream(&rx, &mut out, Duration::from_millis(20));
+            String::from_utf8(out).unwrap()
+        });
+        std::thread::sleep(Duration::from_millis(60));
+        // Stopping the server drops the senders, which ends the stream.
+        subscribers.lock().unwrap().clear();
+
+        let out = writer.join().unwrap();
+        assert!(out.starts_with("event: timer-state-update\ndata: {\"status\":\"running\"}\n\n"));
+        assert!(out.contains(": keepalive\n\n"));
+    }
+
+    #[test]
+    fn test_broadcast_drops_disconnected_subscribers() {
+        let subscribers = Mutex::new(Vec::new());
+        let (tx, rx) = mpsc::sync_channel(1);
+        subscribers.lock().unwrap().push(tx);
+        let (gone_tx, _) = mpsc::sync_channel(1);
+        subscribers.lock().unwrap().push(gone_tx);
+
+        broadcast(&subscribers, "a");
+        // Full buffer: the frame is skipped but the subscriber is kept.
+        broadcast(&subscribers, "b");
+
+        assert_eq!(subscribers.lock().unwrap().len(), 1);
+        assert_eq!(rx.try_recv().unwrap(), "a");
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -0,0 +1,466 @@
+//! Local socket (Unix) / named pipe (Windows) used by `morph-cli` to control
+//! a running instance.
+//!
+//! Each connection carries one request and one response, both single lines
+//! of JSON. Requests are [`IpcRequest`]; responses are [`IpcResponse`]. Only
+//! the current user can reach the socket: it lives in `$XDG_RUNTIME_DIR`, or
+//! else in a `morph-$USER` directory under the temp dir that Morph creates
+//! with mode 0700 and refuses to use if another user owns it.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+use crate::calendar::aggregator::CalendarAggregator;
+use crate::http_api::{self, Route};
+use crate::settings;
+
+/// Requests longer than this are rejected.
+const MAX_REQUEST_BYTES: u64 = 64 * 1024;
+
+#[cfg(windows)]
+pub const PIPE_NAME: &str = r"\\.\pipe\morph";
+
+/// Where the running app listens.
+#[cfg(unix)]
+pub fn socket_path() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => PathBuf::from(dir).join("morph.sock"),
+        None => {
+            let user = std::env::var("USER").unwrap_or_default();
+            std::env::temp_dir()
+                .join(format!("morph-{user}"))
+                .join("morph.sock")
+        }
+    }
+}
+
+/// Make sure `dir` exists, is a real directory and only its owner can enter
+/// it. The name under a shared temp dir can be guessed, so a directory
+/// someone else created first fails here: only the owner may chmod it.
+#[cfg(unix)]
+fn ensure_private_dir(dir: &std::path::Path) -> std::io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    match std::fs::DirBuilder::new().mode(0o700).create(dir) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+        Err(e) => return Err(e),
+    }
+    if !std::fs::symlink_metadata(dir)?.is_dir() {
+        return Err(std::io::Error::other(format!(
+            "{} is not a directory",
+            dir.display()
+        )));
+    }
+    std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))
+}
+
+/// Where the running app listens.
+#[cfg(windows)]
+pub fn socket_path() -> PathBuf {
+    PathBuf::from(PIPE_NAME)
+}
+
+/// A command sent by `morph-cli`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "kebab-case")]
+pub enum IpcRequest {
+    /// Border, timer, pause, next event and provider state.
+    Status,
+    TimerStart {
+        duration_seconds: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
+    TimerPause,
+    TimerResume,
+    TimerStop,
+    /// Pause the border.
+    Pause {
+        minutes: i32,
+    },
+    /// Resume a paused border.
+    Resume,
+    Sync,
+    /// Events from all connected calendars between `from` and `to`.
+    Events {
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    },
+    /// One stored setting, or all of them when `key` is absent.
+    SettingsGet {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        key: Option<String>,
+    },
+    SettingsSet {
+        key: String,
+        value: String,
+    },
+}
+
+/// The reply to an [`IpcRequest`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IpcResponse {
+    Ok(Value),
+    Error(String),
+}
+
+impl From<Result<Value, String>> for IpcResponse {
+    fn from(result: Result<Value, String>) -> Self {
+        match result {
+            Ok(value) => IpcResponse::Ok(value),
+            Err(e) => IpcResponse::Error(e),
+        }
+    }
+}
+
+/// Parse a duration such as `25m`, `1h30m`, `90s` or a bare number of
+/// minutes into seconds.
+pub fn parse_duration(input: &str) -> Result<u32, String> {
+    let input = input.trim();
+    if let Ok(minutes) = input.parse::<u32>() {
+        return minutes
+            .checked_mul(60)
+            .filter(|s| *s > 0)
+            .ok_or_else(|| format!("invalid duration: {input}"));
+    }
+
+    let mut total: u32 = 0;
+    let mut digits = String::new();
+    for c in input.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let unit = match c {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(format!("invalid duration: {input}")),
+        };
+        let amount: u32 = digits
+            .parse()
+            .map_err(|_| format!("invalid duration: {input}"))?;
+        total = amount
+            .checked_mul(unit)
+            .and_then(|s| total.checked_add(s))
+            .ok_or_else(|| format!("duration too long: {input}"))?;
+        digits.clear();
+    }
+    if !digits.is_empty() || total == 0 {
+        return Err(format!("invalid duration: {input}"));
+    }
+    Ok(total)
+}
+
+// --- Server ---
+
+/// Run a route through the HTTP API's dispatcher, which blocks on async
+/// work and so must stay off the async runtime's worker threads.
+async fn api(app: &AppHandle, route: Route, body: Value) -> Result<Value, String> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        http_api::dispatch(&app, route, &body.to_string()).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+async fn handle(app: &AppHandle, request: IpcRequest) -> Result<Value, String> {
+    match request {
+        IpcRequest::Status => api(app, Route::State, Value::Null).await,
+        IpcRequest::TimerStart {
+            duration_seconds,
+            name,
+        } => {
+            let body = serde_json::json!({ "durationSeconds": duration_seconds, "name": name });
+            api(app, Route::StartTimer, body).await
+        }
+        IpcRequest::TimerPause => api(app, Route::PauseTimer, Value::Null).await,
+        IpcRequest::TimerResume => api(app, Route::ResumeTimer, Value::Null).await,
+        IpcRequest::TimerStop => api(app, Route::StopTimer, Value::Null).await,
+        IpcRequest::Pause { minutes } => {
+            let body = serde_json::json!({ "minutes": minutes });
+            api(app, Route::PauseBorder, body).await
+        }
+        IpcRequest::Resume => api(app, Route::ResumeBorder, Value::Null).await,
+        IpcRequest::Sync => api(app, Route::Sync, Value::Null).await,
+        IpcRequest::Events { from, to } => {
+            let aggregator = app.state::<std::sync::Arc<tokio::sync::Mutex<CalendarAggregator>>>();
+            let result = aggregator.lock().await.fetch_events(from, to).await;
+            for (provider_id, err) in &result.errors {
+                eprintln!("[ipc] Provider {provider_id} error: {err}");
+            }
+            serde_json::to_value(result.events).map_err(|e| e.to_string())
+        }
+        // Effective values, as the app uses them: schedule overrides included.
+        IpcRequest::SettingsGet { key: Some(key) } => {
+            if settings::setting_spec(&key).is_none() {
+                return Err(format!("unknown setting: {key}"));
+            }
+            let pairs = settings::effective_pairs(app).await?;
+            Ok(pairs
+                .into_iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v)
+                .into())
+        }
+        IpcRequest::SettingsGet { key: None } => {
+            let pairs = settings::effective_pairs(app).await?;
+            Ok(pairs
+                .into_iter()
+                .map(|(k, v)| (k, Value::String(v)))
+                .collect::<serde_json::Map<_, _>>()
+                .into())
+        }
+        IpcRequest::SettingsSet { key, value } => {
+            let pool = settings::app_pool(app).await?;
+            settings::set_setting_inner(&pool, &key, &value).await?;
+            settings::emit_setting_changed(app, &key, &value);
+            Ok(Value::Null)
+        }
+    }
+}
+
+/// Read one request from `stream`, run it, and write the response.
+async fn serve_connection<S>(app: &AppHandle, stream: S)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut line = String::new();
+    let response = match BufReader::new(reader)
+        .take(MAX_REQUEST_BYTES)
+        .read_line(&mut line)
+        .await
+    {
+        Ok(_) => match serde_json::from_str::<IpcRequest>(&line) {
+            Ok(request) => handle(app, request).await.into(),
+            Err(e) => IpcResponse::Error(format!("invalid request: {e}")),
+        },
+        Err(e) => IpcResponse::Error(format!("unreadable request: {e}")),
+    };
+
+    let mut out = serde_json::to_string(&response).unwrap_or_default();
+    out.push('\n');
+    if let Err(e) = writer.write_all(out.as_bytes()).await {
+        eprintln!("[ipc] Failed to send response: {e}");
+    }
+    let _ = writer.shutdown().await;
+}
+
+#[cfg(unix)]
+async fn listen(app: AppHandle) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = socket_path();
+    if std::env::var_os("XDG_RUNTIME_DIR").is_none() {
+        if let Some(dir) = path.parent() {
+            ensure_private_dir(dir)?;
+        }
+    }
+    if tokio::net::UnixStream::connect(&path).await.is_ok() {
+        eprintln!("[ipc] Another instance is listening on {}", path.display());
+        return Ok(());
+    }
+    // Left behind by a previous run that did not shut down cleanly.
+    let _ = std::fs::remove_file(&path);
+    let listener = tokio::net::UnixListener::bind(&path)?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    eprintln!("[ipc] Listening on {}", path.display());
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move { serve_connection(&app, stream).await });
+    }
+}
+
+#[cfg(windows)]
+async fn listen(app: AppHandle) -> std::io::Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .reject_remote_clients(true)
+        .create(PIPE_NAME)?;
+    eprintln!("[ipc] Listening on {PIPE_NAME}");
+
+    loop {
+        server.connect().await?;
+        let client = server;
+        server = ServerOptions::new()
+            .reject_remote_clients(true)
+            .create(PIPE_NAME)?;
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move { serve_connection(&app, client).await });
+    }
+}
+
+/// Listen for `morph-cli` connections in the background.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = listen(app).await {
+            eprintln!("[ipc] Socket server stopped: {e}");
+        }
+    });
+}
+
+/// Remove the socket file on exit so the CLI reports "not running" rather
+/// than a refused connection.
+pub fn shutdown() {
+    #[cfg(unix)]
+    {
+        let _ = std::fs::remove_file(socket_path());
+    }
+}
+
+// --- Client ---
+
+/// Errors seen by `morph-cli`.
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("Morph is not running (nothing is listening on {0})")]
+    NotRunning(String),
+
+    #[error("connection to Morph failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("unexpected reply from Morph: {0}")]
+    BadReply(String),
+
+    #[error("{0}")]
+    Failed(String),
+}
+
+/// Send one request to the running instance and wait for its reply.
+pub fn send(request: &IpcRequest) -> Result<Value, ClientError> {
+    send_to(&socket_path(), request)
+}
+
+fn send_to(path: &std::path::Path, request: &IpcRequest) -> Result<Value, ClientError> {
+    use std::io::{BufRead, Write};
+
+    #[cfg(unix)]
+    let stream = std::os::unix::net::UnixStream::connect(path);
+    #[cfg(windows)]
+    let stream = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path);
+    let mut stream = stream.map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound | std::io::ErrorKind::ConnectionRefused => {
+            ClientError::NotRunning(path.display().to_string())
+        }
+        _ => ClientError::Io(e),
+    })?;
+
+    let mut line =
+        serde_json::to_string(request).map_err(|e| ClientError::Failed(e.to_string()))?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+    stream.flush()?;
+
+    let mut reply = String::new();
+    std::io::BufReader::new(stream).read_line(&mut reply)?;
+    match serde_json::from_str::<IpcResponse>(&reply) {
+        Ok(IpcResponse::Ok(value)) => Ok(value),
+        Ok(IpcResponse::Error(e)) => Err(ClientError::Failed(e)),
+        Err(e) => Err(ClientError::BadReply(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("25"), Ok(25 * 60));
+        assert_eq!(parse_duration("25m"), Ok(25 * 60));
+        assert_eq!(parse_duration("90s"), Ok(90));
+        assert_eq!(parse_duration("1h"), Ok(3600));
+        assert_eq!(parse_duration("1h30m"), Ok(5400));
+        assert_eq!(parse_duration(" 2h5m10s "), Ok(7510));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("0").is_err());
+        assert!(parse_duration("0m").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("25x").is_err());
+        assert!(parse_duration("10m5").is_err());
+        assert!(parse_duration("99999999h").is_err());
+    }
+
+    #[test]
+    fn test_request_wire_format() {
+        let request = IpcRequest::TimerStart {
+            duration_seconds: 1500,
+            name: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"command":"timer-start","duration_seconds":1500}"#
+        );
+        let parsed: IpcRequest = serde_json::from_str(r#"{"command":"settings-get"}"#).unwrap();
+        assert_eq!(parsed, IpcRequest::SettingsGet { key: None });
+        assert!(serde_json::from_str::<IpcRequest>(r#"{"command":"reboot"}"#).is_err());
+    }
+
+    #[test]
+    fn test_response_wire_format() {
+        let ok: IpcResponse = Ok(serde_json::json!({ "phase": "free-deep" })).into();
+        assert_eq!(
+            serde_json::to_string(&ok).unwrap(),
+            r#"{"ok":{"phase":"free-deep"}}"#
+        );
+        let err: IpcResponse = Err("unknown setting: nope".to_string()).into();
+        assert_eq!(
+            serde_json::from_str::<IpcResponse>(&serde_json::to_string(&err).unwrap()).unwrap(),
+            IpcResponse::Error("unknown setting: nope".to_string())
+        );
+    }
+
+    #[test]
+    fn test_send_reports_not_running() {
+        let path = std::env::temp_dir().join(format!("morph-ipc-test-{}", std::process::id()));
+
+        let err = send_to(&path, &IpcRequest::Status).unwrap_err();
+        assert!(matches!(err, ClientError::NotRunning(_)), "{err}");
+        assert!(err.to_string().contains("Morph is not running"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ensure_private_dir() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let base = std::env::temp_dir().join(format!("morph-ipc-dir-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        std::fs::create_dir(&base).unwrap();
+        let mode = |p: &std::path::Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+
+        let created = base.join("new");
+        ensure_private_dir(&created).unwrap();
+        assert_eq!(mode(&created), 0o700);
+
+        // An existing directory of ours is tightened.
+        let loose = base.join("loose");
+        std::fs::create_dir(&loose).unwrap();
+        std::fs::set_permissions(&loose, std::fs::Permissions::from_mode(0o777)).unwrap();
+        ensure_private_dir(&loose).unwrap();
+        assert_eq!(mode(&loose), 0o700);
+
+        // A symlink planted in its place is refused.
+        let link = base.join("link");
+        std::os::unix::fs::symlink(&loose, &link).unwrap();
+        assert!(ensure_private_dir(&link).is_err());
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -0,0 +1,215 @@
+//! Joining meetings from the desktop. `join_next_meeting` opens the
+//! conferencing link of the meeting about to start (or already running);
+//! with `auto_join_meetings` on, links also open by themselves at start
+//! time. Links come from [`crate::calendar::links`] at sync time.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tauri::{command, AppHandle, Manager};
+
+use crate::calendar::types::CalendarEvent;
+use crate::settings;
+
+pub const AUTO_JOIN_KEY: &str = "auto_join_meetings";
+
+/// How often the event cache is checked for meetings starting.
+const TICK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// A start missed by more than this (sleep, a late poll) doesn't auto-open.
+const GRACE_SECONDS: i64 = 60;
+
+/// A meeting starting within this many minutes is "next" even while
+/// another one is still running.
+const LEAD_MINUTES: i64 = 5;
+
+/// Whether an event is a meeting with a link to join.
+fn joinable(event: &CalendarEvent, ignored_calendar_ids: &[String]) -> bool {
+    event.meeting_url.is_some() && event.counts_as_meeting(ignored_calendar_ids)
+}
+
+/// The meeting `join_next_meeting` opens: one starting within
+/// [`LEAD_MINUTES`], else the most recently started one still running, else
+/// the next one within a day.
+pub fn next_meeting<'a>(
+    events: &'a [CalendarEvent],
+    now: DateTime<Utc>,
+    ignored_calendar_ids: &[String],
+) -> Option<&'a CalendarEvent> {
+    let joinable: Vec<&CalendarEvent> = events
+        .iter()
+        .filter(|e| joinable(e, ignored_calendar_ids) && e.end_time > now)
+        .collect();
+    let upcoming = joinable
+        .iter()
+        .filter(|e| e.start_time > now && (e.start_time - now).num_hours() < 24)
+        .min_by_key(|e| e.start_time);
+    let running = joinable
+        .iter()
+        .filter(|e| e.start_time <= now)
+        .max_by_key(|e| e.start_time);
+    let soon = upcoming.filter(|e| (e.start_time - now).num_minutes() < LEAD_MINUTES);
+    soon.or(running).or(upcoming).copied()
+}
+
+/// Remembers which meetings were already opened automatically.
+#[derive(Debug, Default)]
+pub struct AutoJoin {
+    /// Event ID → the start it was opened for.
+    opened: HashMap<String, DateTime<Utc>>,
+}
+
+impl AutoJoin {
+    /// Links of meetings that started since the last tick.
+    pub fn tick(
+        &mut self,
+        events: &[CalendarEvent],
+        now: DateTime<Utc>,
+        ignored_calendar_ids: &[String],
+    ) -> Vec<String> {
+        let grace = chrono::Duration::seconds(GRACE_SECONDS);
+        self.opened.retain(|_, start| *start + grace > now);
+
+        events
+            .iter()
+            .filter(|e| joinable(e, ignored_calendar_ids))
+            .filter(|e| e.start_time <= now && now - e.start_time < grace)
+            .filter(|e| self.opened.insert(e.id.clone(), e.start_time) != Some(e.start_time))
+            .filter_map(|e| e.meeting_url.clone())
+            .collect()
+    }
+}
+
+// --- Runtime ---
+
+fn open_link(url: &str) -> Result<(), String> {
+    open::that(url).map_err(|e| format!("failed to open {url}: {e}"))
+}
+
+async fn tick(app: &AppHandle) {
+    let settings = settings::current_settings(app).await;
+    if !settings.auto_join_meetings {
+        return;
+    }
+    let Ok(pool) = settings::app_pool(app).await else {
+        return;
+    };
+    let events = match crate::calendar::poller::load_cached_events(&pool).await {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("[join] Failed to load events: {e}");
+            return;
+        }
+    };
+    let urls = {
+        let managed = app.state::<Mutex<AutoJoin>>();
+        let Ok(mut auto_join) = managed.lock() else {
+            return;
+        };
+        auto_join.tick(&events, Utc::now(), &settings.ignored_calendar_ids)
+    };
+    for url in urls {
+        eprintln!("[join] Opening {url}");
+        if let Err(e) = open_link(&url) {
+            eprintln!("[join] {e}");
+        }
+    }
+}
+
+/// Open meeting links at start time in the background, if enabled.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(TICK_INTERVAL);
+        loop {
+            interval.tick().await;
+            tick(&app).await;
+        }
+    });
+}
+
+// --- Tauri commands ---
+
+/// Open the next meeting's link. Returns the meeting's title.
+#[command]
+pub async fn join_next_meeting(app: AppHandle) -> Result<String, String> {
+    let settings = settings::current_settings(&app).await;
+    let pool = settings::app_pool(&app).await?;
+    let events = crate::calendar::poller::load_cached_events(&pool).await?;
+    let meeting = next_meeting(&events, Utc::now(), &settings.ignored_calendar_ids)
+        .ok_or_else(|| "no upcoming meeting with a link".to_string())?;
+    let url = meeting.meeting_url.as_deref().unwrap_or_default();
+    open_link(url)?;
+    Ok(meeting.title.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use chrono::TimeZone;
+
+    fn at(h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, h, m, 0).unwrap()
+    }
+
+    fn event(id: &str, start: DateTime<Utc>, minutes: i64) -> CalendarEvent {
+        CalendarEvent {
+            meeting_url: Some(format!("https://meet.example.com/{id}")),
+            ..test_support::event(id, start, start + chrono::Duration::minutes(minutes))
+        }
+    }
+
+    #[test]
+    fn test_next_meeting() {
+        let mut no_link = event("no-link", at(9, 50), 30);
+        no_link.meeting_url = None;
+        let mut personal = event("personal", at(9, 55), 30);
+        personal.calendar_id = Some("personal".to_string());
+        let events = vec![
+            event("standup", at(9, 0), 60),
+            no_link,
+            personal,
+            event("review", at(10, 0), 30),
+            event("tomorrow", at(9, 0) + chrono::Duration::days(1), 30),
+        ];
+        let ignored = vec!["personal".to_string()];
+
+        let cases = [
+            // Running, with the next one more than five minutes off.
+            (at(9, 30), Some("standup")),
+            // The next one is about to start.
+            (at(9, 56), Some("review")),
+            (at(10, 15), Some("review")),
+            // Nothing running: the next one, even tomorrow.
+            (at(10, 45), Some("tomorrow")),
+            (at(8, 0), Some("standup")),
+        ];
+        for (now, expected) in cases {
+            let id = next_meeting(&events, now, &ignored).map(|e| e.id.as_str());
+            assert_eq!(id, expected, "at {now}");
+        }
+        assert!(next_meeting(&events, at(9, 30) + chrono::Duration::days(2), &ignored).is_none());
+    }
+
+    #[test]
+    fn test_auto_join_opens_each_start_once() {
+        let events = vec![event("standup", at(9, 0), 30)];
+        let mut auto_join = AutoJoin::default();
+
+        assert!(auto_join.tick(&events, at(8, 59), &[]).is_empty());
+        let urls = auto_join.tick(&events, at(9, 0), &[]);
+        assert_eq!(urls, vec!["https://meet.example.com/standup".to_string()]);
+        assert!(auto_join
+            .tick(&events, at(9, 0) + chrono::Duration::seconds(15), &[])
+            .is_empty());
+        // Joined late (after sleep, say): too late to open.
+        let mut late = AutoJoin::default();
+        assert!(late.tick(&events, at(9, 2), &[]).is_empty());
+
+        // Moved to later: opens again at the new time.
+        let moved = vec![event("standup", at(9, 30), 30)];
+        assert_eq!(auto_join.tick(&moved, at(9, 30), &[]).len(), 1);
+    }
+}
@@ -1,13 +1,35 @@
 pub mod border_state;
 pub mod calendar;
+pub mod dnd;
+pub mod focus;
+pub mod history;
+pub mod hotkeys;
+pub mod http_api;
+pub mod ipc;
+pub mod join;
+pub mod lights;
+pub mod migrations;
+pub mod mqtt;
+pub mod notifications;
+pub mod overtime;
+pub mod palettes;
+pub mod pause;
+pub mod profiles;
+pub mod schedule;
+pub mod screen_share;
 pub mod settings;
+pub mod status_sync;
+#[cfg(test)]
+mod test_support;
+pub mod timer;
 pub mod tray;
+pub mod webhooks;
 pub mod window_manager;
+pub mod working_hours;
 
 use sqlx::Row;
 use std::sync::{Arc, Mutex};
 use tauri::{Emitter, Listener, Manager, RunEvent};
-use tauri_plugin_sql::{Migration, MigrationKind};
 
 use calendar::aggregator::CalendarAggregator;
 #[cfg(target_os = "macos")]
@@ -18,67 +40,36 @@ use calendar::poller::CalendarPoller;
 use calendar::provider::CalendarProvider;
 
 use border_state::BorderState;
+use timer::TimerService;
 
 #[cfg(target_os = "macos")]
 use window_manager::macos::MacOSOverlayManager;
 #[cfg(target_os = "macos")]
 use window_manager::OverlayManager;
 
-/// Default border thickness in logical pixels (maps to "medium").
-#[cfg(target_os = "macos")]
-const DEFAULT_THICKNESS: f64 = 16.0;
-
-/// Convert a thickness setting name to logical pixels.
-#[cfg(target_os = "macos")]
-fn thickness_to_px(name: &str) -> f64 {
-    match name {
-        "thin" => 8.0,
-        "medium" => 16.0,
-        "thick" => 28.0,
-        _ => DEFAULT_THICKNESS,
-    }
-}
-
-/// Tracks when the border overlay is paused until.
-#[derive(Debug, Clone, Default, serde::Serialize)]
-pub struct PauseState {
-    /// If `Some`, the border is paused until this time. If `None`, the border is active.
-    pub paused_until: Option<chrono::DateTime<chrono::Utc>>,
-}
-
-/// Timer state emitted to the overlay as `timer-state-update`.
-/// Matches the TypeScript `TimerState` interface exactly.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct TimerState {
-    pub status: String,
-    pub duration_seconds: u32,
-    pub started_at: Option<String>,
-    pub paused_at: Option<String>,
-    pub elapsed_before_pause: f64,
-}
-
-impl Default for TimerState {
-    fn default() -> Self {
-        Self {
-            status: "idle".to_string(),
-            duration_seconds: 0,
-            started_at: None,
-            paused_at: None,
-            elapsed_before_pause: 0.0,
-        }
-    }
-}
-
-/// Manually emit a border state update. Useful for testing and debugging.
-/// Updates the shared state so the tick emitter picks up the change.
+/// Report the border state computed by the overlay. Updates the shared state,
+/// records phase changes in history and sends them to webhooks, and re-emits
+/// it to other listeners.
 #[tauri::command]
 fn emit_border_state(app: tauri::AppHandle, state: BorderState) -> Result<(), String> {
-    // Update the shared state so the tick emitter will continue emitting this state
     let managed = app.state::<Mutex<BorderState>>();
-    {
+    let previous_phase = {
         let mut current = managed.lock().map_err(|e| e.to_string())?;
+        let previous = (current.phase != state.phase).then(|| current.phase.clone());
         *current = state.clone();
+        previous
+    };
+    if let Some(previous_phase) = previous_phase {
+        history::record_phase(&app, state.phase.clone());
+        webhooks::notify(
+            &app,
+            webhooks::WebhookEvent::PhaseChanged,
+            serde_json::json!({
+                "phase": state.phase,
+                "previousPhase": previous_phase,
+                "color": state.color,
+            }),
+        );
     }
     app.emit("border-state-update", &state)
         .map_err(|e| e.to_string())
@@ -165,6 +156,7 @@ async fn disconnect_provider(app: tauri::AppHandle, provider: String) -> Result<
                 agg.remove_providers_by_type(calendar::types::ProviderType::Microsoft);
             }
             clear_keyring_entries("com.morph.microsoft-oauth");
+            clear_keyring_entries(calendar::microsoft::MAILBOX_KEYRING_SERVICE);
             remove_provider_from_db(&app, "microsoft").await;
         }
         #[cfg(target_os = "macos")]
@@ -259,33 +251,7 @@ async fn force_sync(app: tauri::AppHandle) -> Result<(), String> {
         }
     }
 
-    app.emit("calendar-events-update", &result.events)
-        .map_err(|e| e.to_string())
-}
-
-/// Pause the border overlay for a given number of minutes.
-/// - `duration_minutes > 0`: pause for that many minutes
-/// - `duration_minutes == 0`: resume immediately (unpause)
-#[tauri::command]
-fn pause_border(app: tauri::AppHandle, duration_minutes: i32) -> Result<(), String> {
-    let managed = app.state::<Mutex<PauseState>>();
-    let mut state = managed.lock().map_err(|e| e.to_string())?;
-
-    if duration_minutes <= 0 {
-        // Resume: clear the pause
-        state.paused_until = None;
-    } else {
-        state.paused_until =
-            Some(chrono::Utc::now() + chrono::Duration::minutes(duration_minutes as i64));
-    }
-
-    // Emit the minutes value so the overlay can compute expiry locally.
-    // A value of 0 means "resume".
-    app.emit(
-        "border-paused",
-        serde_json::json!({ "minutes": duration_minutes }),
-    )
-    .map_err(|e| e.to_string())
+    calendar::buffers::emit_events(&app, &result.events).await
 }
 
 /// Return the list of connected monitors/displays.
@@ -433,44 +399,135 @@ async fn restore_providers(
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let migrations = vec![Migration {
-        version: 1,
-        description: "create initial schema",
-        sql: include_str!("../migrations/001_initial_schema.sql"),
-        kind: MigrationKind::Up,
-    }];
-
     let aggregator = Arc::new(tokio::sync::Mutex::new(CalendarAggregator::new()));
 
     tauri::Builder::default()
         .manage(Mutex::new(BorderState::default()))
-        .manage(Mutex::new(PauseState::default()))
-        .manage(Mutex::new(TimerState::default()))
+        .manage(Mutex::new(pause::PauseState::default()))
+        .manage(Mutex::new(TimerService::default()))
+        .manage(Mutex::new(profiles::ProfileList::default()))
+        .manage(Mutex::new(schedule::ScheduleState::default()))
+        .manage(Mutex::new(overtime::OvertimeTracker::default()))
+        .manage(Mutex::new(notifications::NoticeTracker::default()))
+        .manage(Mutex::new(join::AutoJoin::default()))
+        .manage(Mutex::new(dnd::DndSync::default()))
+        .manage(Mutex::new(focus::FocusGuard::default()))
+        .manage(Mutex::new(screen_share::ShareGuard::default()))
+        .manage(Mutex::new(hotkeys::Hotkeys::default()))
+        .manage(Mutex::new(window_manager::BorderVisibility::default()))
+        .manage(http_api::HttpApi::default())
+        .manage(status_sync::StatusSync::default())
+        .manage(lights::Lights::default())
+        .manage(mqtt::Mqtt::default())
         .manage(aggregator.clone())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
             None,
         ))
-        .plugin(
-            tauri_plugin_sql::Builder::default()
-                .add_migrations("sqlite:morph.db", migrations)
-                .build(),
-        )
+        .plugin(tauri_plugin_sql::Builder::default().build())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .invoke_handler(tauri::generate_handler![
             settings::get_setting,
             settings::set_setting,
             settings::get_all_settings,
+            settings::get_effective_settings,
             emit_border_state,
             connect_provider,
             disconnect_provider,
             get_provider_statuses,
             get_calendar_list,
             force_sync,
-            pause_border,
+            pause::pause_border,
+            pause::pause_border_until,
+            pause::get_pause_state,
             get_available_monitors,
+            timer::get_timer_state,
+            timer::get_timer_catalog,
+            timer::start_timer_preset,
+            timer::start_timer_sequence,
+            profiles::export_settings,
+            profiles::preview_settings_import,
+            profiles::import_settings,
+            profiles::list_profiles,
+            profiles::save_profile,
+            profiles::apply_profile,
+            profiles::delete_profile,
+            palettes::list_palettes,
+            palettes::get_active_palette,
+            palettes::check_palette,
+            palettes::save_palette,
+            palettes::delete_palette,
+            palettes::export_palette,
+            palettes::import_palette,
+            schedule::get_active_schedule_rule,
+            history::get_history_insights,
+            overtime::get_overtime_state,
+            overtime::end_overtime,
+            overtime::get_overrun_report,
+            notifications::send_test_notification,
+            join::join_next_meeting,
+            working_hours::import_working_hours,
+            dnd::get_dnd_status,
+            focus::get_focus_status,
+            focus::override_focus,
+            screen_share::get_screen_share_state,
+            screen_share::detect_screen_share,
+            hotkeys::get_hotkey_status,
+            http_api::get_http_api_token,
+            http_api::regenerate_http_api_token,
+            webhooks::list_webhooks,
+            webhooks::save_webhook,
+            webhooks::delete_webhook,
+            webhooks::get_webhook_deliveries,
+            webhooks::test_webhook,
+            status_sync::get_status_sync_state,
+            status_sync::connect_slack_status,
+            status_sync::connect_teams_status,
+            status_sync::disconnect_status_service,
+            lights::pair_hue_bridge,
+            lights::unpair_hue_bridge,
+            lights::list_hue_groups,
+            mqtt::get_mqtt_status,
+            mqtt::set_mqtt_credentials,
+            mqtt::clear_mqtt_credentials,
         ])
         .setup(|app| {
+            // Bring the schema up to date and seed defaults before anything
+            // reads the database.
+            let handle = app.handle().clone();
+            let migrated = tauri::async_runtime::block_on(async move {
+                let pool = match settings::app_pool(&handle).await {
+                    Ok(pool) => pool,
+                    Err(e) => {
+                        eprintln!("[startup] Database unavailable: {e}");
+                        return Ok(());
+                    }
+                };
+                let report = migrations::migrate(&pool).await?;
+                if let Some(backup) = report.backup {
+                    eprintln!("[startup] Backed up database to {backup}");
+                }
+                if let Err(e) = settings::seed_defaults_from_pool(&pool).await {
+                    eprintln!("[startup] Failed to seed defaults: {e}");
+                }
+                Ok::<(), migrations::MigrationError>(())
+            });
+            // Running on a partly migrated schema could damage user data, so
+            // stop here and say why.
+            if let Err(e) = migrated {
+                eprintln!("[startup] Database migration failed: {e}");
+                notifications::show_startup_error(&format!(
+                    "Morph couldn't update its database and has quit to keep your data safe: {e}"
+                ));
+                return Err(e.into());
+            }
+
+            // One overlay manager for the border windows, shared with the
+            // hotkey and screen-share hiding
+            #[cfg(any(target_os = "macos", target_os = "windows"))]
+            app.manage(Mutex::new(window_manager::PlatformOverlayManager::default()));
+
             // Border overlay windows are declared in tauri.conf.json.
             // Show them first so the webview loads and JS initializes,
             // then apply macOS NSWindow config after a delay.
@@ -493,39 +550,17 @@ pub fn run() {
                     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
                     // Read saved thickness and selected display from settings
-                    let (thickness, selected_display) = {
-                        let db = handle.state::<tauri_plugin_sql::DbInstances>();
-                        let instances = db.0.read().await;
-                        if let Some(tauri_plugin_sql::DbPool::Sqlite(pool)) =
-                            instances.get("sqlite:morph.db")
-                        {
-                            let t = sqlx::query_scalar::<_, String>(
-                                "SELECT value FROM settings WHERE key = 'border_thickness'",
-                            )
-                            .fetch_optional(pool)
-                            .await
-                            .ok()
-                            .flatten()
-                            .map(|v| thickness_to_px(&v))
-                            .unwrap_or(DEFAULT_THICKNESS);
-                            let d = sqlx::query_scalar::<_, String>(
-                                "SELECT value FROM settings WHERE key = 'selected_display'",
-                            )
-                            .fetch_optional(pool)
-                            .await
-                            .ok()
-                            .flatten()
-                            .unwrap_or_else(|| "primary".to_string());
-                            (t, d)
-                        } else {
-                            (DEFAULT_THICKNESS, "primary".to_string())
-                        }
-                    };
+                    let saved = settings::current_settings(&handle).await;
+                    let thickness = saved.border_thickness.px();
+                    let selected_display = saved.selected_display;
 
                     // NSWindow ops must run on the main thread
                     let handle2 = handle.clone();
                     let _ = handle.run_on_main_thread(move || {
-                        let mut overlay_mgr = MacOSOverlayManager::new();
+                        let managed = handle2.state::<Mutex<MacOSOverlayManager>>();
+                        let Ok(mut overlay_mgr) = managed.lock() else {
+                            return;
+                        };
                         if let Err(e) = overlay_mgr.create_overlay_windows(&handle2) {
                             eprintln!("Failed to configure macOS overlay: {e}");
                         } else {
@@ -542,6 +577,59 @@ pub fn run() {
                 eprintln!("Failed to set up system tray: {e}");
             }
 
+            // Load custom timer presets/sequences and saved profiles so the
+            // tray menu lists them
+            let timer_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                timer::reload_catalog(&timer_handle).await;
+                profiles::refresh(&timer_handle).await;
+            });
+
+            // Restore a pause from before the last exit and resume pauses as they run out
+            pause::start(app);
+
+            // Apply time-of-day / day-of-week setting overrides
+            schedule::start(app.handle().clone());
+
+            // Purge old history in the background
+            history::start(app.handle().clone());
+
+            // Follow meetings that run past their end
+            overtime::start(app.handle().clone());
+
+            // Backup notifications at meeting thresholds and timer completion
+            notifications::start(app.handle().clone());
+
+            // Open meeting links at start time, if enabled
+            join::start(app.handle().clone());
+
+            // Turn on OS Do Not Disturb during meetings and focus timers, if enabled
+            dnd::start(app);
+
+            // Block distracting apps and sites during focus sessions, if enabled
+            focus::start(app);
+
+            // Hide the border while the screen is shared or presented, if enabled
+            screen_share::start(app.handle().clone());
+
+            // Register global keyboard shortcuts, if enabled
+            hotkeys::start(app.handle().clone());
+
+            // Serve the localhost API for scripts and status bars, if enabled
+            http_api::start(app);
+
+            // Accept commands from morph-cli
+            ipc::start(app.handle().clone());
+
+            // Mirror meetings and focus sessions to Slack/Teams status
+            status_sync::start(app);
+
+            // Mirror the border onto Hue or LIFX lights, if enabled
+            lights::start(app);
+
+            // Publish state to an MQTT broker for home automation, if enabled
+            mqtt::start(app);
+
             // Restore previously connected calendar providers from the database.
             // Spawned before the poller so providers are available by the first poll cycle.
             let agg = app.state::<Arc<tokio::sync::Mutex<CalendarAggregator>>>();
@@ -558,23 +646,9 @@ pub fn run() {
             // The Settings UI currently uses emit() rather than invoke().
             setup_event_listeners(app);
 
-            // Seed default settings (INSERT OR IGNORE) then check onboarding state.
+            // Check onboarding state (defaults were seeded after migrating).
             let onboarding_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                // Seed defaults so new settings (like onboarding_complete) exist
-                // even if the migration SQL didn't include them.
-                {
-                    let db = onboarding_handle.state::<tauri_plugin_sql::DbInstances>();
-                    let instances = db.0.read().await;
-                    if let Some(tauri_plugin_sql::DbPool::Sqlite(pool)) =
-                        instances.get("sqlite:morph.db")
-                    {
-                        if let Err(e) = settings::seed_defaults_from_pool(pool).await {
-                            eprintln!("[startup] Failed to seed defaults: {e}");
-                        }
-                    }
-                }
-
                 let show_onboarding = {
                     let db = onboarding_handle.state::<tauri_plugin_sql::DbInstances>();
                     let instances = db.0.read().await;
@@ -632,12 +706,17 @@ pub fn run() {
         })
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
-        .run(|_app, event| {
+        .run(|app, event| match event {
             // Prevent the app from exiting when all windows are hidden/closed.
             // The system tray keeps the app alive.
-            if let RunEvent::ExitRequested { api, .. } = event {
-                api.prevent_exit();
+            RunEvent::ExitRequested { api, .. } => api.prevent_exit(),
+            RunEvent::Exit => {
+                history::record_shutdown(app);
+                dnd::shutdown(app);
+                focus::shutdown(app);
+                ipc::shutdown();
             }
+            _ => {}
         });
 }
 
@@ -650,7 +729,7 @@ struct ProviderPayload {
 /// Event payload emitted back to the frontend as `provider-status-update`.
 #[derive(Debug, Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
-struct ProviderStatusPayload {
+pub(crate) struct ProviderStatusPayload {
     provider: String,
     status: ProviderStatusInner,
 }
@@ -671,6 +750,35 @@ struct PausePayload {
     minutes: i32,
 }
 
+/// Event payload for start-timer events: a bare number of seconds, or a
+/// named duration from the tray popover.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(untagged)]
+enum StartTimerPayload {
+    Seconds(u32),
+    Named { name: String, duration_seconds: u32 },
+}
+
+impl StartTimerPayload {
+    fn into_preset(self) -> timer::TimerPreset {
+        match self {
+            StartTimerPayload::Seconds(secs) => timer::TimerPreset::custom(None, secs),
+            StartTimerPayload::Named {
+                name,
+                duration_seconds,
+            } => timer::TimerPreset::custom(Some(name), duration_seconds),
+        }
+    }
+}
+
+/// Event payload for `settings-changed`.
+#[derive(Debug, Clone, serde::Deserialize)]
+struct SettingChangedPayload {
+    key: String,
+    #[allow(dead_code)]
+    value: String,
+}
+
 /// Register event listeners that bridge frontend `emit()` calls to command logic.
 fn setup_event_listeners(app: &tauri::App) {
     // connect-provider
@@ -736,78 +844,113 @@ fn setup_event_listeners(app: &tauri::App) {
     let handle = app.handle().clone();
     app.listen("pause-border", move |event| {
         if let Ok(payload) = serde_json::from_str::<PausePayload>(event.payload()) {
-            if let Err(e) = pause_border(handle.clone(), payload.minutes) {
+            if let Err(e) = pause::pause_border(handle.clone(), payload.minutes) {
                 eprintln!("[event] pause-border error: {e}");
             }
         }
     });
 
-    // start-timer: tray menu emits "start-timer" with seconds as payload.
-    // Convert to a TimerState and emit "timer-state-update" for the overlay.
+    // start-timer: settings UI emits seconds, the tray popover emits
+    // `{ name, duration_seconds }`. Either way the timer service owns it.
     let handle = app.handle().clone();
     app.listen("start-timer", move |event| {
-        if let Ok(seconds) = event.payload().parse::<u32>() {
-            let now = chrono::Utc::now().to_rfc3339();
-            let new_state = TimerState {
-                status: "running".to_string(),
-                duration_seconds: seconds,
-                started_at: Some(now),
-                paused_at: None,
-                elapsed_before_pause: 0.0,
-            };
-
-            // Store in managed state
-            let managed = handle.state::<Mutex<TimerState>>();
-            if let Ok(mut state) = managed.lock() {
-                *state = new_state.clone();
+        if let Ok(payload) = serde_json::from_str::<StartTimerPayload>(event.payload()) {
+            if let Err(e) = timer::start(&handle, payload.into_preset()) {
+                eprintln!("[event] start-timer error: {e}");
             }
-
-            // Notify overlay
-            let _ = handle.emit("timer-state-update", &new_state);
         }
     });
 
     // stop-timer: frontend or settings UI can stop the active timer
     let handle = app.handle().clone();
     app.listen("stop-timer", move |_event| {
-        let new_state = TimerState::default();
-
-        let managed = handle.state::<Mutex<TimerState>>();
-        if let Ok(mut state) = managed.lock() {
-            *state = new_state.clone();
+        if let Err(e) = timer::stop(&handle) {
+            eprintln!("[event] stop-timer error: {e}");
         }
-
-        let _ = handle.emit("timer-state-update", &new_state);
     });
 
     // pause-timer: pause the active timer
     let handle = app.handle().clone();
     app.listen("pause-timer", move |_event| {
-        if let Ok(mut state) = handle.state::<Mutex<TimerState>>().lock() {
-            if state.status == "running" {
-                state.status = "paused".to_string();
-                state.paused_at = Some(chrono::Utc::now().to_rfc3339());
-                let _ = handle.emit("timer-state-update", &*state);
-            }
+        if let Err(e) = timer::pause(&handle) {
+            eprintln!("[event] pause-timer error: {e}");
         }
     });
 
     // resume-timer: resume a paused timer
     let handle = app.handle().clone();
     app.listen("resume-timer", move |_event| {
-        if let Ok(mut state) = handle.state::<Mutex<TimerState>>().lock() {
-            if state.status == "paused" {
-                if let Some(paused_at_str) = &state.paused_at {
-                    if let Ok(paused_at) = chrono::DateTime::parse_from_rfc3339(paused_at_str) {
-                        let pause_elapsed =
-                            (chrono::Utc::now() - paused_at.to_utc()).num_seconds() as f64;
-                        state.elapsed_before_pause += pause_elapsed;
-                    }
-                }
-                state.status = "running".to_string();
-                state.started_at = Some(chrono::Utc::now().to_rfc3339());
-                state.paused_at = None;
-                let _ = handle.emit("timer-state-update", &*state);
+        if let Err(e) = timer::resume(&handle) {
+            eprintln!("[event] resume-timer error: {e}");
+        }
+    });
+
+    // settings-changed: reload the timer catalog when custom presets or
+    // sequences are edited so the tray menu lists them, re-evaluate the
+    // schedule when its rules change, re-emit events when buffer rules
+    // change, send the overlay the newly chosen palette, and restart the
+    // HTTP API when it is toggled or moved.
+    let handle = app.handle().clone();
+    app.listen("settings-changed", move |event| {
+        if let Ok(payload) = serde_json::from_str::<SettingChangedPayload>(event.payload()) {
+            if payload.key == timer::CUSTOM_PRESETS_KEY || payload.key == timer::SEQUENCES_KEY {
+                let h = handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    timer::reload_catalog(&h).await;
+                });
+            } else if payload.key == schedule::RULES_KEY {
+                let h = handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    schedule::reevaluate(&h).await;
+                });
+            } else if payload.key == calendar::buffers::RULES_KEY {
+                let h = handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    calendar::buffers::refresh(&h).await;
+                });
+            } else if payload.key == palettes::ACTIVE_KEY {
+                let h = handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    palettes::emit_active(&h).await;
+                });
+            } else if payload.key == http_api::ENABLED_KEY || payload.key == http_api::PORT_KEY {
+                let h = handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    http_api::apply(&h).await;
+                });
+            } else if status_sync::KEYS.contains(&payload.key.as_str()) {
+                let h = handle.clone();
+                let key = payload.key.clone();
+                tauri::async_runtime::spawn(async move {
+                    status_sync::setting_changed(&h, &key).await;
+                });
+            } else if lights::KEYS.contains(&payload.key.as_str()) {
+                lights::refresh(&handle);
+            } else if payload.key == dnd::ENABLED_KEY {
+                let h = handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    dnd::sync(&h).await;
+                });
+            } else if focus::KEYS.contains(&payload.key.as_str()) {
+                let h = handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    focus::check(&h).await;
+                });
+            } else if payload.key == tray::COUNTDOWN_TITLE_KEY {
+                let h = handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    tray::reload(&h).await;
+                });
+            } else if hotkeys::KEYS.contains(&payload.key.as_str()) {
+                let h = handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    hotkeys::apply(&h).await;
+                });
+            } else if mqtt::KEYS.contains(&payload.key.as_str()) {
+                let h = handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    mqtt::apply(&h, false).await;
+                });
             }
         }
     });
@@ -817,50 +960,22 @@ fn setup_event_listeners(app: &tauri::App) {
     {
         let handle = app.handle().clone();
         app.listen("settings-changed", move |event| {
-            #[derive(serde::Deserialize)]
-            struct SettingChanged {
-                key: String,
-                #[allow(dead_code)]
-                value: String,
-            }
-            if let Ok(payload) = serde_json::from_str::<SettingChanged>(event.payload()) {
+            if let Ok(payload) = serde_json::from_str::<SettingChangedPayload>(event.payload()) {
                 if payload.key == "border_thickness" || payload.key == "selected_display" {
                     // Re-read both values from DB and reposition
                     let h = handle.clone();
                     tauri::async_runtime::spawn(async move {
-                        let (thickness, selected_display) = {
-                            let db = h.state::<tauri_plugin_sql::DbInstances>();
-                            let instances = db.0.read().await;
-                            if let Some(tauri_plugin_sql::DbPool::Sqlite(pool)) =
-                                instances.get("sqlite:morph.db")
-                            {
-                                let t = sqlx::query_scalar::<_, String>(
-                                    "SELECT value FROM settings WHERE key = 'border_thickness'",
-                                )
-                                .fetch_optional(pool)
-                                .await
-                                .ok()
-                                .flatten()
-                                .map(|v| thickness_to_px(&v))
-                                .unwrap_or(DEFAULT_THICKNESS);
-                                let d = sqlx::query_scalar::<_, String>(
-                                    "SELECT value FROM settings WHERE key = 'selected_display'",
-                                )
-                                .fetch_optional(pool)
-                                .await
-                                .ok()
-                                .flatten()
-                                .unwrap_or_else(|| "primary".to_string());
-                                (t, d)
-                            } else {
-                                (DEFAULT_THICKNESS, "primary".to_string())
-                            }
-                        };
+                        let saved = settings::current_settings(&h).await;
+                        let thickness = saved.border_thickness.px();
+                        let selected_display = saved.selected_display;
 
                         let h2 = h.clone();
                         let _ = h.run_on_main_thread(move || {
-                            let mut mgr = MacOSOverlayManager::new();
-                            if mgr.create_overlay_windows(&h2).is_ok() {
+                            let managed = h2.state::<Mutex<MacOSOverlayManager>>();
+                            let Ok(mut mgr) = managed.lock() else {
+                                return;
+                            };
+                            if mgr.top.is_some() || mgr.create_overlay_windows(&h2).is_ok() {
                                 mgr.set_target_monitor(&selected_display);
                                 mgr.set_thickness(thickness);
                             }
@@ -877,52 +992,19 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_timer_state_default_is_idle() {
-        let state = TimerState::default();
-        assert_eq!(state.status, "idle");
-        assert_eq!(state.duration_seconds, 0);
-        assert!(state.started_at.is_none());
-        assert!(state.paused_at.is_none());
-        assert_eq!(state.elapsed_before_pause, 0.0);
+    fn test_start_timer_payload_accepts_seconds() {
+        let payload: StartTimerPayload = serde_json::from_str("1500").unwrap();
+        let preset = payload.into_preset();
+        assert_eq!(preset.duration_seconds, 1500);
+        assert_eq!(preset.name, "Timer");
     }
 
     #[test]
-    fn test_timer_pause_sets_status() {
-        let mut state = TimerState::default();
-        state.status = "running".to_string();
-        state.started_at = Some(chrono::Utc::now().to_rfc3339());
-        // Simulate pause
-        state.status = "paused".to_string();
-        state.paused_at = Some(chrono::Utc::now().to_rfc3339());
-        assert_eq!(state.status, "paused");
-        assert!(state.paused_at.is_some());
-    }
-
-    #[test]
-    fn test_timer_resume_clears_paused_at() {
-        let mut state = TimerState::default();
-        state.status = "paused".to_string();
-        state.paused_at = Some(chrono::Utc::now().to_rfc3339());
-        // Simulate resume
-        state.status = "running".to_string();
-        state.started_at = Some(chrono::Utc::now().to_rfc3339());
-        state.paused_at = None;
-        assert_eq!(state.status, "running");
-        assert!(state.paused_at.is_none());
-    }
-
-    #[test]
-    fn test_timer_pause_preserves_duration() {
-        let mut state = TimerState {
-            status: "running".to_string(),
-            duration_seconds: 1500,
-            started_at: Some(chrono::Utc::now().to_rfc3339()),
-            paused_at: None,
-            elapsed_before_pause: 0.0,
-        };
-        // Simulate pause
-        state.status = "paused".to_string();
-        state.paused_at = Some(chrono::Utc::now().to_rfc3339());
-        assert_eq!(state.duration_seconds, 1500);
+    fn test_start_timer_payload_accepts_named_duration() {
+        let payload: StartTimerPayload =
+            serde_json::from_str(r#"{"name":"Focus","duration_seconds":3000}"#).unwrap();
+        let preset = payload.into_preset();
+        assert_eq!(preset.duration_seconds, 3000);
+        assert_eq!(preset.name, "Focus");
     }
 }
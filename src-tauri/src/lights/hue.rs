@@ -0,0 +1,346 @@
+//! Philips Hue over the bridge's local REST API (v1).
+//!
+//! Pairing posts to `/api` after the user presses the bridge's link button;
+//! the returned application key is kept in the system keyring. Updates go to
+//! a light group's `action` so a whole room changes at once.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{HueGroupInfo, LightBackend, LightError, LightState};
+
+const KEYRING_SERVICE: &str = "com.morph.hue";
+const KEYRING_KEY: &str = "application_key";
+
+/// Hue error type for "link button not pressed".
+const LINK_BUTTON_ERROR: u32 = 101;
+
+/// The bridge handles about one group command per second.
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A `lselect` alert breathes for 15 seconds.
+const ALERT_LENGTH: Duration = Duration::from_secs(15);
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The stored application key, if the bridge has been paired.
+pub fn stored_key() -> Option<String> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_KEY)
+        .ok()?
+        .get_password()
+        .ok()
+}
+
+pub fn store_key(key: &str) -> Result<(), LightError> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_KEY)
+        .and_then(|entry| entry.set_password(key))
+        .map_err(|e| LightError::Request(format!("keyring store error: {e}")))
+}
+
+pub fn delete_key() {
+    if let Ok(entry) = keyring::Entry::new(KEYRING_SERVICE, KEYRING_KEY) {
+        let _ = entry.delete_credential();
+    }
+}
+
+/// One entry of the array the bridge answers most requests with.
+#[derive(Debug, Deserialize)]
+struct HueResult {
+    success: Option<Value>,
+    error: Option<HueErrorBody>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HueErrorBody {
+    #[serde(rename = "type")]
+    kind: u32,
+    description: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct HueGroup {
+    name: String,
+}
+
+/// A Hue bridge at a base URL such as `http://192.168.1.20`.
+pub struct HueBridge {
+    client: reqwest::Client,
+    base: String,
+    key: Option<String>,
+}
+
+impl HueBridge {
+    pub fn new(base: &str) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            base: base.trim_end_matches('/').to_string(),
+            key: None,
+        }
+    }
+
+    pub fn with_key(mut self, key: String) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    fn key(&self) -> Result<&str, LightError> {
+        self.key.as_deref().ok_or(LightError::NotPaired)
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<Value, LightError> {
+        let resp = request
+            .send()
+            .await
+            .map_err(|e| LightError::Request(e.to_string()))?;
+        if !resp.status().is_success() {
+            return Err(LightError::Bridge(format!("HTTP {}", resp.status())));
+        }
+        resp.json()
+            .await
+            .map_err(|e| LightError::Bridge(format!("unexpected response: {e}")))
+    }
+
+    /// Turn a `[{"success": ..} | {"error": ..}]` reply into the first
+    /// success, or the first error.
+    fn first_success(reply: Value) -> Result<Value, LightError> {
+        let results: Vec<HueResult> = serde_json::from_value(reply)
+            .map_err(|e| LightError::Bridge(format!("unexpected response: {e}")))?;
+        let mut success = None;
+        for result in results {
+            if let Some(error) = result.error {
+                return Err(match error.kind {
+                    LINK_BUTTON_ERROR => LightError::LinkButtonNotPressed,
+                    _ => LightError::Bridge(error.description),
+                });
+            }
+            success = success.or(result.success);
+        }
+        success.ok_or_else(|| LightError::Bridge("empty response".to_string()))
+    }
+
+    /// Register Morph with the bridge and return the new application key.
+    pub async fn pair(&self) -> Result<String, LightError> {
+        let reply = self
+            .send(
+                self.client
+                    .post(format!("{}/api", self.base))
+                    .json(&json!({ "devicetype": "morph#desktop" })),
+            )
+            .await?;
+        Self::first_success(reply)?["username"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| LightError::Bridge("no application key in response".to_string()))
+    }
+
+    /// List rooms and zones, sorted by name. Group "0" (all lights) is
+    /// always offered first.
+    pub async fn groups(&self) -> Result<Vec<HueGroupInfo>, LightError> {
+        let reply = self
+            .send(
+                self.client
+                    .get(format!("{}/api/{}/groups", self.base, self.key()?)),
+            )
+            .await?;
+        if reply.is_array() {
+            // Errors (e.g. an unknown key) come back as an array.
+            Self::first_success(reply)?;
+            return Ok(Vec::new());
+        }
+        let groups: BTreeMap<String, HueGroup> = serde_json::from_value(reply)
+            .map_err(|e| LightError::Bridge(format!("unexpected response: {e}")))?;
+        let mut list: Vec<HueGroupInfo> = groups
+            .into_iter()
+            .map(|(id, group)| HueGroupInfo {
+                id,
+                name: group.name,
+            })
+            .collect();
+        list.sort_by(|a, b| a.name.cmp(&b.name));
+        list.insert(
+            0,
+            HueGroupInfo {
+                id: "0".to_string(),
+                name: "All lights".to_string(),
+            },
+        );
+        Ok(list)
+    }
+
+    async fn set_group(&self, group: &str, action: Value) -> Result<(), LightError> {
+        let reply = self
+            .send(
+                self.client
+                    .put(format!(
+                        "{}/api/{}/groups/{group}/action",
+                        self.base,
+                        self.key()?
+                    ))
+                    .json(&action),
+            )
+            .await?;
+        Self::first_success(reply).map(|_| ())
+    }
+}
+
+/// The body for a group `action`. Transitions last as long as the gap
+/// between updates so color changes stay smooth.
+pub fn group_action(state: &LightState) -> Value {
+    // transitiontime is in 100 ms steps.
+    let transition = MIN_INTERVAL.as_millis() / 100;
+    if !state.on {
+        return json!({ "on": false, "transitiontime": transition });
+    }
+    json!({
+        "on": true,
+        "hue": (state.hue / 360.0 * 65535.0).round() as u16,
+        "sat": (state.saturation * 254.0).round() as u8,
+        "bri": (state.brightness * 254.0).round().clamp(1.0, 254.0) as u8,
+        "transitiontime": transition,
+        "alert": if state.pulse_ms > 0 { "lselect" } else { "none" },
+    })
+}
+
+/// Drives one Hue light group.
+pub struct HueLights {
+    bridge: HueBridge,
+    group: String,
+}
+
+impl HueLights {
+    pub fn new(bridge: HueBridge, group: &str) -> Self {
+        Self {
+            bridge,
+            group: group.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl LightBackend for HueLights {
+    fn name(&self) -> &'static str {
+        "Hue"
+    }
+
+    fn min_interval(&self) -> Duration {
+        MIN_INTERVAL
+    }
+
+    fn pulse_refresh(&self) -> Option<Duration> {
+        Some(ALERT_LENGTH)
+    }
+
+    async fn apply(&self, state: &LightState) -> Result<(), LightError> {
+        self.bridge
+            .set_group(&self.group, group_action(state))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::stand_in;
+
+    #[tokio::test]
+    async fn test_pairing_waits_for_link_button() {
+        let (base, received) = stand_in(vec![
+            (
+                200,
+                r#"[{"error":{"type":101,"address":"","description":"link button not pressed"}}]"#,
+            ),
+            (200, r#"[{"success":{"username":"k3y"}}]"#),
+        ]);
+        let bridge = HueBridge::new(&format!("{base}/"));
+
+        assert!(matches!(
+            bridge.pair().await,
+            Err(LightError::LinkButtonNotPressed)
+        ));
+        assert_eq!(bridge.pair().await.unwrap(), "k3y");
+
+        let received = received.lock().unwrap();
+        assert_eq!(received[1].method, "POST");
+        assert_eq!(received[1].path, "/api");
+        assert_eq!(received[1].json()["devicetype"], "morph#desktop");
+    }
+
+    #[tokio::test]
+    async fn test_lists_groups_by_name() {
+        let (base, received) = stand_in(vec![(
+            200,
+            r#"{"1":{"name":"Office","lights":["1","2"]},"4":{"name":"Den","lights":["3"]}}"#,
+        )]);
+        let groups = HueBridge::new(&base)
+            .with_key("k3y".to_string())
+            .groups()
+            .await
+            .unwrap();
+        let names: Vec<(&str, &str)> = groups
+            .iter()
+            .map(|g| (g.id.as_str(), g.name.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![("0", "All lights"), ("4", "Den"), ("1", "Office")]
+        );
+        assert_eq!(received.lock().unwrap()[0].path, "/api/k3y/groups");
+    }
+
+    #[tokio::test]
+    async fn test_sets_group_action() {
+        let (base, received) = stand_in(vec![
+            (200, r#"[{"success":{"/groups/1/action/on":true}}]"#),
+            (
+                200,
+                r#"[{"error":{"type":1,"address":"/","description":"unauthorized user"}}]"#,
+            ),
+        ]);
+        let lights = HueLights::new(HueBridge::new(&base).with_key("k3y".to_string()), "1");
+        let state = LightState {
+            on: true,
+            hue: 270.0,
+            saturation: 0.5,
+            brightness: 0.8,
+            pulse_ms: 2500,
+        };
+
+        lights.apply(&state).await.unwrap();
+        let err = lights.apply(&LightState::OFF).await.unwrap_err();
+        assert!(err.to_string().contains("unauthorized user"));
+
+        let received = received.lock().unwrap();
+        assert_eq!(received[0].method, "PUT");
+        assert_eq!(received[0].path, "/api/k3y/groups/1/action");
+        let body = received[0].json();
+        assert_eq!(body["on"], true);
+        assert_eq!(body["hue"], 49151);
+        assert_eq!(body["sat"], 127);
+        assert_eq!(body["bri"], 203);
+        assert_eq!(body["alert"], "lselect");
+        assert_eq!(
+            received[1].json(),
+            json!({ "on": false, "transitiontime": 10 })
+        );
+    }
+
+    #[test]
+    fn test_dimmest_light_stays_on() {
+        let action = group_action(&LightState {
+            on: true,
+            hue: 0.0,
+            saturation: 0.0,
+            brightness: 0.0,
+            pulse_ms: 0,
+        });
+        assert_eq!(action["bri"], 1);
+        assert_eq!(action["alert"], "none");
+    }
+}
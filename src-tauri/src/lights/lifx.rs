@@ -0,0 +1,285 @@
+//! LIFX bulbs over the LAN protocol (UDP port 56700).
+//!
+//! Messages always carry the `tagged` flag and no target, so every bulb
+//! that receives them follows: all of them at the broadcast address, or one
+//! when the address setting names a single bulb's IP.
+//! Pulsing phases use a sine waveform that dims the bulb and brings it back
+//! once per cycle.
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::net::UdpSocket;
+
+use super::{LightBackend, LightError, LightState};
+
+const DEFAULT_PORT: u16 = 56700;
+
+const HEADER_LEN: usize = 36;
+const PROTOCOL: u16 = 1024;
+const ADDRESSABLE: u16 = 1 << 12;
+const TAGGED: u16 = 1 << 13;
+
+const SET_COLOR: u16 = 102;
+const SET_WAVEFORM: u16 = 103;
+const SET_POWER: u16 = 117;
+
+const WAVEFORM_SINE: u8 = 1;
+
+/// Color temperature sent with every color; only matters when saturation is
+/// low.
+const KELVIN: u16 = 3500;
+
+/// Bulbs handle about 20 messages a second; one update sends up to three.
+const MIN_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How far a pulse dims the bulb, as a fraction of its brightness.
+const PULSE_DEPTH: f64 = 0.5;
+
+/// Pulse cycles requested at once; the next update replaces the waveform.
+const PULSE_CYCLES: f32 = 100_000.0;
+
+/// Source ID for our messages; bulbs echo it in replies we never read.
+const SOURCE: u32 = 0x4d4f_5250;
+
+/// A LIFX HSBK color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hsbk {
+    pub hue: u16,
+    pub saturation: u16,
+    pub brightness: u16,
+    pub kelvin: u16,
+}
+
+impl Hsbk {
+    fn from_state(state: &LightState) -> Self {
+        let scale = |v: f64| (v.clamp(0.0, 1.0) * 65535.0).round() as u16;
+        Self {
+            hue: scale(state.hue / 360.0),
+            saturation: scale(state.saturation),
+            brightness: scale(state.brightness),
+            kelvin: KELVIN,
+        }
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        for v in [self.hue, self.saturation, self.brightness, self.kelvin] {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+}
+
+/// Frame a message with the 36-byte LIFX header, addressed to all bulbs.
+fn packet(msg_type: u16, sequence: u8, payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(HEADER_LEN + payload.len());
+    // Frame
+    out.extend_from_slice(&((HEADER_LEN + payload.len()) as u16).to_le_bytes());
+    out.extend_from_slice(&(PROTOCOL | ADDRESSABLE | TAGGED).to_le_bytes());
+    out.extend_from_slice(&SOURCE.to_le_bytes());
+    // Frame address: any target, no ack or response wanted.
+    out.extend_from_slice(&[0; 8]);
+    out.extend_from_slice(&[0; 6]);
+    out.push(0);
+    out.push(sequence);
+    // Protocol header
+    out.extend_from_slice(&[0; 8]);
+    out.extend_from_slice(&msg_type.to_le_bytes());
+    out.extend_from_slice(&[0; 2]);
+    out.extend_from_slice(payload);
+    out
+}
+
+fn set_power(on: bool, duration: Duration, sequence: u8) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(6);
+    payload.extend_from_slice(&(if on { u16::MAX } else { 0 }).to_le_bytes());
+    payload.extend_from_slice(&(duration.as_millis() as u32).to_le_bytes());
+    packet(SET_POWER, sequence, &payload)
+}
+
+fn set_color(color: Hsbk, duration: Duration, sequence: u8) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(13);
+    payload.push(0);
+    color.write(&mut payload);
+    payload.extend_from_slice(&(duration.as_millis() as u32).to_le_bytes());
+    packet(SET_COLOR, sequence, &payload)
+}
+
+/// A transient sine waveform towards `color` and back, `period_ms` per cycle.
+fn set_waveform(color: Hsbk, period_ms: u32, sequence: u8) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(21);
+    payload.push(0);
+    payload.push(1); // transient: return to the original color
+    color.write(&mut payload);
+    payload.extend_from_slice(&period_ms.to_le_bytes());
+    payload.extend_from_slice(&PULSE_CYCLES.to_le_bytes());
+    payload.extend_from_slice(&0i16.to_le_bytes()); // skew ratio
+    payload.push(WAVEFORM_SINE);
+    packet(SET_WAVEFORM, sequence, &payload)
+}
+
+/// The messages that bring the bulbs to `state`.
+pub fn messages(state: &LightState, sequence: u8) -> Vec<Vec<u8>> {
+    if !state.on {
+        return vec![set_power(false, MIN_INTERVAL, sequence)];
+    }
+    let color = Hsbk::from_state(state);
+    let mut out = vec![
+        set_power(true, Duration::ZERO, sequence),
+        set_color(color, MIN_INTERVAL, sequence.wrapping_add(1)),
+    ];
+    if state.pulse_ms > 0 {
+        let dimmed = Hsbk {
+            brightness: (color.brightness as f64 * (1.0 - PULSE_DEPTH)) as u16,
+            ..color
+        };
+        out.push(set_waveform(
+            dimmed,
+            state.pulse_ms,
+            sequence.wrapping_add(2),
+        ));
+    }
+    out
+}
+
+/// Resolve `host` or `host:port`, defaulting to the LIFX port.
+pub async fn parse_address(address: &str) -> Result<SocketAddr, LightError> {
+    let address = address.trim();
+    let with_port = if address.contains(':') {
+        address.to_string()
+    } else {
+        format!("{address}:{DEFAULT_PORT}")
+    };
+    tokio::net::lookup_host(with_port)
+        .await
+        .ok()
+        .and_then(|mut addrs| addrs.find(SocketAddr::is_ipv4))
+        .ok_or_else(|| LightError::InvalidAddress(address.to_string()))
+}
+
+/// Drives LIFX bulbs at one address, usually the broadcast address.
+pub struct LifxLan {
+    target: SocketAddr,
+    sequence: std::sync::atomic::AtomicU8,
+}
+
+impl LifxLan {
+    pub async fn new(address: &str) -> Result<Self, LightError> {
+        Ok(Self {
+            target: parse_address(address).await?,
+            sequence: Default::default(),
+        })
+    }
+}
+
+#[async_trait]
+impl LightBackend for LifxLan {
+    fn name(&self) -> &'static str {
+        "LIFX"
+    }
+
+    fn min_interval(&self) -> Duration {
+        MIN_INTERVAL
+    }
+
+    async fn apply(&self, state: &LightState) -> Result<(), LightError> {
+        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+        socket.set_broadcast(true)?;
+        let sequence = self
+            .sequence
+            .fetch_add(3, std::sync::atomic::Ordering::Relaxed);
+        for message in messages(state, sequence) {
+            socket.send_to(&message, self.target).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(bytes: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes([bytes[at], bytes[at + 1]])
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_header_layout() {
+        let msg = set_power(true, Duration::from_millis(250), 7);
+        assert_eq!(msg.len(), HEADER_LEN + 6);
+        assert_eq!(u16_at(&msg, 0), 42);
+        // protocol 1024, addressable, tagged
+        assert_eq!(u16_at(&msg, 2), 0x3400);
+        assert_eq!(u32_at(&msg, 4), SOURCE);
+        assert!(msg[8..22].iter().all(|&b| b == 0));
+        assert_eq!(msg[23], 7);
+        assert_eq!(u16_at(&msg, 32), SET_POWER);
+        assert_eq!(u16_at(&msg, 36), u16::MAX);
+        assert_eq!(u32_at(&msg, 38), 250);
+    }
+
+    #[test]
+    fn test_color_and_pulse_messages() {
+        let state = LightState {
+            on: true,
+            hue: 180.0,
+            saturation: 1.0,
+            brightness: 0.5,
+            pulse_ms: 2000,
+        };
+        let msgs = messages(&state, 0);
+        assert_eq!(msgs.len(), 3);
+
+        let color = &msgs[1];
+        assert_eq!(color.len(), HEADER_LEN + 13);
+        assert_eq!(u16_at(color, 32), SET_COLOR);
+        assert_eq!(u16_at(color, 37), 32768);
+        assert_eq!(u16_at(color, 39), 65535);
+        assert_eq!(u16_at(color, 41), 32768);
+        assert_eq!(u16_at(color, 43), KELVIN);
+        assert_eq!(u32_at(color, 45), 250);
+
+        let wave = &msgs[2];
+        assert_eq!(wave.len(), HEADER_LEN + 21);
+        assert_eq!(u16_at(wave, 32), SET_WAVEFORM);
+        assert_eq!(wave[37], 1);
+        assert_eq!(u16_at(wave, 42), 16384);
+        assert_eq!(u32_at(wave, 46), 2000);
+        assert_eq!(wave[56], WAVEFORM_SINE);
+
+        let off = messages(&LightState::OFF, 3);
+        assert_eq!(off.len(), 1);
+        assert_eq!(u16_at(&off[0], 36), 0);
+    }
+
+    #[tokio::test]
+    async fn test_parse_address() {
+        assert_eq!(
+            parse_address("255.255.255.255").await.unwrap(),
+            "255.255.255.255:56700".parse().unwrap()
+        );
+        assert_eq!(
+            parse_address("192.168.1.40:5000").await.unwrap(),
+            "192.168.1.40:5000".parse().unwrap()
+        );
+        assert!(parse_address("not an address").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sends_to_bulb_address() {
+        let bulb = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let lifx = LifxLan::new(&bulb.local_addr().unwrap().to_string())
+            .await
+            .unwrap();
+        lifx.apply(&LightState::OFF).await.unwrap();
+
+        let mut buf = [0u8; 128];
+        let (len, _) = bulb.recv_from(&mut buf).await.unwrap();
+        assert_eq!(len, HEADER_LEN + 6);
+        assert_eq!(u16_at(&buf, 32), SET_POWER);
+    }
+}
@@ -0,0 +1,431 @@
+//! Smart lights: mirror the border color into the room.
+//!
+//! Each `border-state-update` is turned into a `LightState` and sent to a
+//! Philips Hue light group (bridge REST API) or LIFX bulbs (LAN protocol).
+//! Updates are coalesced so a backend never gets more than one every
+//! `min_interval`; only the latest state is sent.
+
+pub mod hue;
+pub mod lifx;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use tauri::{command, AppHandle, Listener, Manager};
+use thiserror::Error;
+use tokio::sync::watch;
+
+use crate::border_state::BorderState;
+use crate::settings::{self, LightsBackend, Settings};
+
+pub const BACKEND_KEY: &str = "lights_backend";
+pub const HUE_BRIDGE_KEY: &str = "hue_bridge_url";
+pub const HUE_GROUP_KEY: &str = "hue_group_id";
+pub const LIFX_ADDRESS_KEY: &str = "lifx_address";
+pub const OFF_PHASES_KEY: &str = "lights_off_phases";
+pub const MAX_BRIGHTNESS_KEY: &str = "lights_max_brightness";
+
+/// Settings that change where or how the lights are driven.
+pub const KEYS: &[&str] = &[
+    BACKEND_KEY,
+    HUE_BRIDGE_KEY,
+    HUE_GROUP_KEY,
+    LIFX_ADDRESS_KEY,
+    OFF_PHASES_KEY,
+    MAX_BRIGHTNESS_KEY,
+];
+
+/// Border opacity that maps to full brightness (the overlay's cap).
+const FULL_OPACITY: f64 = 0.95;
+
+/// Brightness of the faintest phase, as a fraction of the maximum, so dim
+/// phases stay visible on a bulb.
+const MIN_BRIGHTNESS: f64 = 0.1;
+
+#[derive(Debug, Error)]
+pub enum LightError {
+    #[error("the Hue bridge is not paired")]
+    NotPaired,
+
+    #[error("press the link button on the Hue bridge, then try again")]
+    LinkButtonNotPressed,
+
+    #[error("light request failed: {0}")]
+    Request(String),
+
+    #[error("Hue bridge error: {0}")]
+    Bridge(String),
+
+    #[error("invalid light address: {0}")]
+    InvalidAddress(String),
+
+    #[error("LAN error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// What a light should show, in backend-neutral units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightState {
+    pub on: bool,
+    /// Hue angle in degrees, 0-360.
+    pub hue: f64,
+    /// 0-1.
+    pub saturation: f64,
+    /// 0-1.
+    pub brightness: f64,
+    /// Breathing cycle in milliseconds, 0 for a steady light.
+    pub pulse_ms: u32,
+}
+
+impl LightState {
+    pub const OFF: LightState = LightState {
+        on: false,
+        hue: 0.0,
+        saturation: 0.0,
+        brightness: 0.0,
+        pulse_ms: 0,
+    };
+}
+
+/// How border states map onto lights, taken from settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightsConfig {
+    pub backend: LightsBackend,
+    pub hue_bridge_url: String,
+    pub hue_group_id: String,
+    pub lifx_address: String,
+    /// Phases during which the lights are switched off.
+    pub off_phases: Vec<String>,
+    /// 1-100.
+    pub max_brightness: u8,
+}
+
+impl LightsConfig {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            backend: settings.lights_backend,
+            hue_bridge_url: settings.hue_bridge_url.clone(),
+            hue_group_id: settings.hue_group_id.clone(),
+            lifx_address: settings.lifx_address.clone(),
+            off_phases: settings.lights_off_phases.clone(),
+            max_brightness: settings.lights_max_brightness,
+        }
+    }
+}
+
+/// Parse `#RRGGBB` into hue (degrees), saturation and value (0-1).
+pub fn hex_to_hsv(hex: &str) -> Option<(f64, f64, f64)> {
+    let hex = hex.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| {
+        u8::from_str_radix(hex.get(i..i + 2)?, 16)
+            .ok()
+            .map(|c| c as f64 / 255.0)
+    };
+    let (r, g, b) = (channel(0)?, channel(2)?, channel(4)?);
+
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+    Some((hue, saturation, max))
+}
+
+/// Map the border onto a light. Opacity sets brightness between
+/// `MIN_BRIGHTNESS` and `max_brightness`; the color's own value is ignored
+/// so dark palette colors still light the room.
+pub fn light_state(border: &BorderState, config: &LightsConfig) -> LightState {
+    if config.off_phases.contains(&border.phase) {
+        return LightState::OFF;
+    }
+    let Some((hue, saturation, _)) = hex_to_hsv(&border.color) else {
+        return LightState::OFF;
+    };
+    let level = (border.opacity / FULL_OPACITY).clamp(0.0, 1.0);
+    let brightness =
+        (MIN_BRIGHTNESS + (1.0 - MIN_BRIGHTNESS) * level) * config.max_brightness as f64 / 100.0;
+    LightState {
+        on: true,
+        hue,
+        saturation,
+        brightness,
+        pulse_ms: border.pulse_speed,
+    }
+}
+
+/// A way of driving lights.
+#[async_trait]
+pub trait LightBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Shortest gap between two updates.
+    fn min_interval(&self) -> Duration;
+
+    /// How often an unchanged pulsing state must be re-sent to keep
+    /// pulsing, if the backend's pulse runs out.
+    fn pulse_refresh(&self) -> Option<Duration> {
+        None
+    }
+
+    async fn apply(&self, state: &LightState) -> Result<(), LightError>;
+}
+
+/// Build the configured backend. `None` when lights are off or the Hue
+/// bridge is not paired yet.
+async fn build_backend(config: &LightsConfig) -> Result<Option<Box<dyn LightBackend>>, LightError> {
+    match config.backend {
+        LightsBackend::Off => Ok(None),
+        LightsBackend::Hue => {
+            let Some(key) = hue::stored_key() else {
+                return Ok(None);
+            };
+            let bridge = hue::HueBridge::new(&config.hue_bridge_url).with_key(key);
+            Ok(Some(Box::new(hue::HueLights::new(
+                bridge,
+                &config.hue_group_id,
+            ))))
+        }
+        LightsBackend::Lifx => Ok(Some(Box::new(
+            lifx::LifxLan::new(&config.lifx_address).await?,
+        ))),
+    }
+}
+
+// --- Runtime ---
+
+/// Managed state: the latest border state for the sender task.
+pub struct Lights {
+    border: watch::Sender<BorderState>,
+    /// Set when stored credentials change, so the backend is rebuilt even
+    /// though the settings did not.
+    reconnect: AtomicBool,
+}
+
+impl Default for Lights {
+    fn default() -> Self {
+        Self {
+            border: watch::Sender::new(BorderState::default()),
+            reconnect: AtomicBool::new(false),
+        }
+    }
+}
+
+/// Re-send the current state, e.g. after a lights setting changed.
+pub fn refresh(app: &AppHandle) {
+    app.state::<Lights>().border.send_modify(|_| {});
+}
+
+/// Rebuild the backend with fresh credentials, then re-send.
+fn reconnect(app: &AppHandle) {
+    app.state::<Lights>()
+        .reconnect
+        .store(true, Ordering::SeqCst);
+    refresh(app);
+}
+
+/// Send border states to the lights until the app exits.
+async fn run(app: AppHandle, mut border: watch::Receiver<BorderState>) {
+    // The config the backend was built from; no backend when lights are off
+    // or the bridge is unpaired.
+    let mut current: Option<(LightsConfig, Option<Box<dyn LightBackend>>)> = None;
+    let mut last_sent: Option<LightState> = None;
+
+    loop {
+        // Wait for a new border state, or for a pulsing Hue alert to run out.
+        let refresh = current
+            .as_ref()
+            .and_then(|(_, b)| b.as_ref()?.pulse_refresh())
+            .filter(|_| last_sent.is_some_and(|s| s.on && s.pulse_ms > 0));
+        match refresh {
+            Some(after) => match tokio::time::timeout(after, border.changed()).await {
+                Ok(Ok(())) => {}
+                Ok(Err(_)) => return,
+                // The pulse ran out; send the same state again.
+                Err(_) => last_sent = None,
+            },
+            None => {
+                if border.changed().await.is_err() {
+                    return;
+                }
+            }
+        }
+        let state = border.borrow_and_update().clone();
+
+        let config = LightsConfig::from_settings(&settings::current_settings(&app).await);
+        let reconnect = app
+            .state::<Lights>()
+            .reconnect
+            .swap(false, Ordering::SeqCst);
+        if reconnect || current.as_ref().map(|(c, _)| c) != Some(&config) {
+            // Leave the old lights off rather than stuck on a stale color.
+            if let Some((_, Some(old))) = current.take() {
+                if let Err(e) = old.apply(&LightState::OFF).await {
+                    eprintln!("[lights] Failed to switch off {}: {e}", old.name());
+                }
+            }
+            last_sent = None;
+            let backend = build_backend(&config).await.unwrap_or_else(|e| {
+                eprintln!("[lights] {e}");
+                None
+            });
+            current = Some((config, backend));
+        }
+        let Some((config, Some(backend))) = current.as_ref() else {
+            continue;
+        };
+
+        let target = light_state(&state, config);
+        if last_sent == Some(target) {
+            continue;
+        }
+        match backend.apply(&target).await {
+            Ok(()) => last_sent = Some(target),
+            Err(e) => eprintln!("[lights] Failed to update {}: {e}", backend.name()),
+        }
+        tokio::time::sleep(backend.min_interval()).await;
+    }
+}
+
+/// Forward border states to the lights task and start it.
+pub fn start(app: &tauri::App) {
+    let lights = app.state::<Lights>();
+    let receiver = lights.border.subscribe();
+    let sender = lights.border.clone();
+    app.listen("border-state-update", move |event| {
+        if let Ok(state) = serde_json::from_str::<BorderState>(event.payload()) {
+            sender.send_replace(state);
+        }
+    });
+    tauri::async_runtime::spawn(run(app.handle().clone(), receiver));
+}
+
+// --- Tauri commands ---
+
+/// Pair with the Hue bridge in settings. The bridge's link button must have
+/// been pressed within the last 30 seconds.
+#[command]
+pub async fn pair_hue_bridge(app: AppHandle) -> Result<(), String> {
+    let settings = settings::current_settings(&app).await;
+    let bridge = hue::HueBridge::new(&settings.hue_bridge_url);
+    let key = bridge.pair().await.map_err(|e| e.to_string())?;
+    hue::store_key(&key).map_err(|e| e.to_string())?;
+    reconnect(&app);
+    Ok(())
+}
+
+/// Forget the Hue bridge key.
+#[command]
+pub fn unpair_hue_bridge(app: AppHandle) -> Result<(), String> {
+    hue::delete_key();
+    reconnect(&app);
+    Ok(())
+}
+
+/// A Hue room or zone the lights can follow.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HueGroupInfo {
+    pub id: String,
+    pub name: String,
+}
+
+/// List the light groups on the paired bridge.
+#[command]
+pub async fn list_hue_groups(app: AppHandle) -> Result<Vec<HueGroupInfo>, String> {
+    let settings = settings::current_settings(&app).await;
+    let key = hue::stored_key().ok_or_else(|| LightError::NotPaired.to_string())?;
+    hue::HueBridge::new(&settings.hue_bridge_url)
+        .with_key(key)
+        .groups()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> LightsConfig {
+        LightsConfig {
+            backend: LightsBackend::Hue,
+            hue_bridge_url: "http://127.0.0.1".to_string(),
+            hue_group_id: "0".to_string(),
+            lifx_address: "255.255.255.255".to_string(),
+            off_phases: vec!["no-events".to_string()],
+            max_brightness: 100,
+        }
+    }
+
+    fn border(phase: &str, color: &str, opacity: f64, pulse_speed: u32) -> BorderState {
+        BorderState {
+            phase: phase.to_string(),
+            color: color.to_string(),
+            opacity,
+            pulse_speed,
+        }
+    }
+
+    #[test]
+    fn test_hex_to_hsv() {
+        assert_eq!(hex_to_hsv("#FF0000"), Some((0.0, 1.0, 1.0)));
+        assert_eq!(hex_to_hsv("#00ff00"), Some((120.0, 1.0, 1.0)));
+        assert_eq!(hex_to_hsv("#0000FF"), Some((240.0, 1.0, 1.0)));
+        assert_eq!(
+            hex_to_hsv("#808080").map(|(h, s, _)| (h, s)),
+            Some((0.0, 0.0))
+        );
+
+        let (h, s, v) = hex_to_hsv("#8B6AAE").unwrap();
+        assert!((h - 269.1).abs() < 0.1, "hue was {h}");
+        assert!((s - 0.391).abs() < 0.01, "saturation was {s}");
+        assert!((v - 0.682).abs() < 0.01, "value was {v}");
+
+        assert_eq!(hex_to_hsv("8B6AAE"), None);
+        assert_eq!(hex_to_hsv("#8B6AA"), None);
+        assert_eq!(hex_to_hsv("#GGGGGG"), None);
+    }
+
+    #[test]
+    fn test_brightness_follows_opacity() {
+        let faint = light_state(&border("free-deep", "#4A9B6E", 0.0, 0), &config());
+        let full = light_state(&border("overtime", "#7B5A9E", 0.95, 2000), &config());
+        assert!(faint.on && full.on);
+        assert!((faint.brightness - MIN_BRIGHTNESS).abs() < 1e-9);
+        assert!((full.brightness - 1.0).abs() < 1e-9);
+        assert_eq!(full.pulse_ms, 2000);
+
+        let mid = light_state(&border("warning-mid", "#A3B84C", 0.55, 3000), &config());
+        assert!(faint.brightness < mid.brightness && mid.brightness < full.brightness);
+
+        let capped = LightsConfig {
+            max_brightness: 50,
+            ..config()
+        };
+        let half = light_state(&border("overtime", "#7B5A9E", 0.95, 0), &capped);
+        assert!((half.brightness - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_off_phases_and_bad_colors_switch_off() {
+        assert_eq!(
+            light_state(&border("no-events", "#8A9BA8", 0.15, 0), &config()),
+            LightState::OFF
+        );
+        assert_eq!(
+            light_state(&border("free-deep", "green", 0.25, 0), &config()),
+            LightState::OFF
+        );
+    }
+}
@@ -0,0 +1,420 @@
+//! Versioned database schema.
+//!
+//! This module owns every schema change to `morph.db`. Migrations are applied
+//! in order by [`migrate`], which records each applied version in the
+//! `schema_version` table, runs each migration in its own transaction, and
+//! backs up the database file before any migration marked `destructive`.
+//!
+//! Setting defaults are not part of the schema: they live in
+//! [`crate::settings::SCHEMA`] and are seeded after migrating.
+//!
+//! To add a migration: append a `NNN_description.sql` file under
+//! `src-tauri/migrations/`, add it to [`MIGRATIONS`], and add a snapshot of a
+//! database at the new version under `src-tauri/migrations/fixtures/` so
+//! future upgrades from it stay tested.
+
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+use thiserror::Error;
+
+/// A single schema migration.
+#[derive(Debug, Clone, Copy)]
+pub struct SchemaMigration {
+    pub version: i64,
+    pub description: &'static str,
+    pub sql: &'static str,
+    /// Drops or rewrites existing data. The database file is backed up
+    /// before a destructive migration is applied.
+    pub destructive: bool,
+}
+
+/// Every migration, in version order. Append only.
+pub const MIGRATIONS: &[SchemaMigration] = &[
+    SchemaMigration {
+        version: 1,
+        description: "create initial schema",
+        sql: include_str!("../migrations/001_initial_schema.sql"),
+        destructive: false,
+    },
+    SchemaMigration {
+        version: 2,
+        description: "add settings profiles",
+        sql: include_str!("../migrations/002_settings_profiles.sql"),
+        destructive: false,
+    },
+    SchemaMigration {
+        version: 3,
+        description: "add session history",
+        sql: include_str!("../migrations/003_history.sql"),
+        destructive: false,
+    },
+    SchemaMigration {
+        version: 4,
+        description: "add meeting series and overruns",
+        sql: include_str!("../migrations/004_meeting_overruns.sql"),
+        destructive: false,
+    },
+    SchemaMigration {
+        version: 5,
+        description: "add webhooks",
+        sql: include_str!("../migrations/005_webhooks.sql"),
+        destructive: false,
+    },
+    SchemaMigration {
+        version: 6,
+        description: "add meeting links",
+        sql: include_str!("../migrations/006_meeting_links.sql"),
+        destructive: false,
+    },
+    SchemaMigration {
+        version: 7,
+        description: "add focus mode blocks",
+        sql: include_str!("../migrations/007_focus_blocks.sql"),
+        destructive: false,
+    },
+    SchemaMigration {
+        version: 8,
+        description: "persist border pauses",
+        sql: include_str!("../migrations/008_border_pause.sql"),
+        destructive: false,
+    },
+    SchemaMigration {
+        version: 9,
+        description: "add event locations",
+        sql: include_str!("../migrations/009_event_location.sql"),
+        destructive: false,
+    },
+    SchemaMigration {
+        version: 10,
+        description: "add custom palettes",
+        sql: include_str!("../migrations/010_palettes.sql"),
+        destructive: false,
+    },
+];
+
+/// The schema version a fully migrated database is at.
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("failed to back up database before migration {version}: {reason}")]
+    Backup { version: i64, reason: String },
+
+    #[error("migration {version} ({description}) failed: {source}")]
+    Failed {
+        version: i64,
+        description: &'static str,
+        source: sqlx::Error,
+    },
+
+    #[error("database is at version {found}, newer than this build supports ({latest})")]
+    TooNew { found: i64, latest: i64 },
+}
+
+/// Outcome of a [`migrate`] run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+    pub from_version: i64,
+    pub applied: Vec<i64>,
+    /// Path of the backup taken before a destructive migration, if any.
+    pub backup: Option<String>,
+}
+
+/// Read the current schema version (0 for a new database).
+pub async fn current_version(pool: &SqlitePool) -> Result<i64, MigrationError> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+          version INTEGER PRIMARY KEY,
+          applied_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+    )
+    .execute(pool)
+    .await?;
+    let version: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM schema_version")
+        .fetch_one(pool)
+        .await?;
+    Ok(version.unwrap_or(0))
+}
+
+/// Path of the main database file, or `None` for in-memory databases.
+async fn database_file(pool: &SqlitePool) -> Result<Option<String>, MigrationError> {
+    let rows = sqlx::query("PRAGMA database_list").fetch_all(pool).await?;
+    Ok(rows
+        .iter()
+        .find(|r| r.get::<String, _>("name") == "main")
+        .map(|r| r.get::<String, _>("file"))
+        .filter(|file| !file.is_empty()))
+}
+
+/// Write a consistent copy of the database next to the original, named after
+/// the version it was taken at. Returns `None` for in-memory databases.
+pub async fn backup_database(
+    pool: &SqlitePool,
+    version: i64,
+) -> Result<Option<String>, MigrationError> {
+    let Some(file) = database_file(pool).await? else {
+        return Ok(None);
+    };
+    let stamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
+    let target = format!("{file}.v{version}-{stamp}.bak");
+    sqlx::query("VACUUM INTO ?1")
+        .bind(&target)
+        .execute(pool)
+        .await
+        .map_err(|e| MigrationError::Backup {
+            version,
+            reason: e.to_string(),
+        })?;
+    Ok(Some(target))
+}
+
+/// Apply every pending migration from [`MIGRATIONS`].
+pub async fn migrate(pool: &SqlitePool) -> Result<MigrationReport, MigrationError> {
+    migrate_with(pool, MIGRATIONS).await
+}
+
+async fn migrate_with(
+    pool: &SqlitePool,
+    migrations: &[SchemaMigration],
+) -> Result<MigrationReport, MigrationError> {
+    let from_version = current_version(pool).await?;
+    let latest = migrations.last().map_or(0, |m| m.version);
+    if from_version > latest {
+        return Err(MigrationError::TooNew {
+            found: from_version,
+            latest,
+        });
+    }
+
+    let pending: Vec<&SchemaMigration> = migrations
+        .iter()
+        .filter(|m| m.version > from_version)
+        .collect();
+
+    // One backup covers every destructive step in this run.
+    let backup = match pending.iter().find(|m| m.destructive) {
+        Some(m) => backup_database(pool, from_version)
+            .await
+            .map_err(|e| match e {
+                MigrationError::Backup { reason, .. } => MigrationError::Backup {
+                    version: m.version,
+                    reason,
+                },
+                other => other,
+            })?,
+        None => None,
+    };
+
+    let mut applied = Vec::new();
+    for migration in pending {
+        let failed = |source| MigrationError::Failed {
+            version: migration.version,
+            description: migration.description,
+            source,
+        };
+        let mut tx = pool.begin().await?;
+        sqlx::raw_sql(migration.sql)
+            .execute(&mut *tx)
+            .await
+            .map_err(failed)?;
+        sqlx::query("INSERT OR IGNORE INTO schema_version (version) VALUES (?1)")
+            .bind(migration.version)
+            .execute(&mut *tx)
+            .await
+            .map_err(failed)?;
+        tx.commit().await.map_err(failed)?;
+        eprintln!(
+            "[migrations] Applied {} ({})",
+            migration.version, migration.description
+        );
+        applied.push(migration.version);
+    }
+
+    Ok(MigrationReport {
+        from_version,
+        applied,
+        backup,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Databases as they existed at each released schema version, including
+    /// sample user data. Built from SQL snapshots rather than by replaying
+    /// migrations so upgrades are tested against what users actually have.
+    const FIXTURES: &[(i64, &str)] = &[
+        (1, include_str!("../migrations/fixtures/v1.sql")),
+        (2, include_str!("../migrations/fixtures/v2.sql")),
+        (3, include_str!("../migrations/fixtures/v3.sql")),
+        (4, include_str!("../migrations/fixtures/v4.sql")),
+        (5, include_str!("../migrations/fixtures/v5.sql")),
+        (6, include_str!("../migrations/fixtures/v6.sql")),
+        (7, include_str!("../migrations/fixtures/v7.sql")),
+        (8, include_str!("../migrations/fixtures/v8.sql")),
+        (9, include_str!("../migrations/fixtures/v9.sql")),
+        (10, include_str!("../migrations/fixtures/v10.sql")),
+    ];
+
+    async fn memory_pool() -> SqlitePool {
+        SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory pool")
+    }
+
+    async fn fixture_pool(sql: &str) -> SqlitePool {
+        let pool = memory_pool().await;
+        sqlx::raw_sql(sql)
+            .execute(&pool)
+            .await
+            .expect("bad fixture");
+        pool
+    }
+
+    async fn table_exists(pool: &SqlitePool, name: &str) -> bool {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        )
+        .bind(name)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+            == 1
+    }
+
+    #[test]
+    fn test_versions_are_sequential() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, i as i64 + 1);
+        }
+    }
+
+    #[test]
+    fn test_every_prior_version_has_a_fixture() {
+        let fixtures: Vec<i64> = FIXTURES.iter().map(|(v, _)| *v).collect();
+        let expected: Vec<i64> = (1..=latest_version()).collect();
+        assert_eq!(fixtures, expected);
+    }
+
+    #[tokio::test]
+    async fn test_fresh_database_migrates_to_latest() {
+        let pool = memory_pool().await;
+        let report = migrate(&pool).await.unwrap();
+
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.applied.len(), MIGRATIONS.len());
+        assert_eq!(current_version(&pool).await.unwrap(), latest_version());
+        assert!(table_exists(&pool, "settings").await);
+        assert!(table_exists(&pool, "settings_profiles").await);
+        assert!(table_exists(&pool, "history_phase_transitions").await);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_is_idempotent() {
+        let pool = memory_pool().await;
+        migrate(&pool).await.unwrap();
+        let report = migrate(&pool).await.unwrap();
+        assert!(report.applied.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_upgrades_from_every_fixture_preserve_data() {
+        for (version, sql) in FIXTURES {
+            let pool = fixture_pool(sql).await;
+            assert_eq!(current_version(&pool).await.unwrap(), *version);
+
+            let report = migrate(&pool).await.unwrap();
+            let expected: Vec<i64> = (version + 1..=latest_version()).collect();
+            assert_eq!(report.applied, expected, "from v{version}");
+            assert_eq!(current_version(&pool).await.unwrap(), latest_version());
+
+            // User data from the fixture survives the upgrade.
+            let thickness: String =
+                sqlx::query_scalar("SELECT value FROM settings WHERE key = 'border_thickness'")
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+            assert_eq!(thickness, "thick", "from v{version}");
+            let events: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM calendar_events")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            assert_eq!(events, 1, "from v{version}");
+            assert!(table_exists(&pool, "settings_profiles").await);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_database_from_newer_build() {
+        let pool = memory_pool().await;
+        migrate(&pool).await.unwrap();
+        sqlx::query("INSERT INTO schema_version (version) VALUES (?1)")
+            .bind(latest_version() + 1)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(matches!(
+            migrate(&pool).await,
+            Err(MigrationError::TooNew { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_failed_migration_rolls_back() {
+        let pool = memory_pool().await;
+        let broken = [
+            MIGRATIONS[0],
+            SchemaMigration {
+                version: 2,
+                description: "broken",
+                sql: "CREATE TABLE half_done (id INTEGER); INSERT INTO missing VALUES (1);",
+                destructive: false,
+            },
+        ];
+        let err = migrate_with(&pool, &broken).await.unwrap_err();
+        assert!(matches!(err, MigrationError::Failed { version: 2, .. }));
+        assert_eq!(current_version(&pool).await.unwrap(), 1);
+        assert!(!table_exists(&pool, "half_done").await);
+    }
+
+    #[tokio::test]
+    async fn test_destructive_migration_backs_up_file_database() {
+        let dir = std::env::temp_dir().join(format!(
+            "morph-migrations-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = dir.join("morph.db");
+        let pool = SqlitePool::connect(&format!("sqlite://{}?mode=rwc", db.display()))
+            .await
+            .unwrap();
+        migrate(&pool).await.unwrap();
+
+        let mut with_destructive = MIGRATIONS.to_vec();
+        with_destructive.push(SchemaMigration {
+            version: latest_version() + 1,
+            description: "drop cached events",
+            sql: "DROP TABLE calendar_events;",
+            destructive: true,
+        });
+        let report = migrate_with(&pool, &with_destructive).await.unwrap();
+        pool.close().await;
+
+        let backup = report.backup.expect("backup should be taken");
+        let copy = SqlitePool::connect(&format!("sqlite://{backup}"))
+            .await
+            .unwrap();
+        assert_eq!(current_version(&copy).await.unwrap(), latest_version());
+        assert!(table_exists(&copy, "calendar_events").await);
+        copy.close().await;
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,864 @@
+//! Optional MQTT client for home automation.
+//!
+//! Publishes retained state topics under the topic prefix (default `morph`):
+//! - `morph/status`: `online`, or `offline` via the last will
+//! - `morph/phase`, `morph/color`: the border
+//! - `morph/next_meeting`, `morph/next_meeting_title`: the next event
+//! - `morph/timer`, `morph/timer_ends_at`: the session timer
+//!
+//! Empty payloads mean "none". Home Assistant discovery configs are
+//! published under the discovery prefix so the sensors and buttons appear
+//! on their own.
+//!
+//! Commands are read from `morph/command/...`:
+//! - `timer/start`: a duration (`25m`, `90s`, bare minutes), a preset ID,
+//!   or a JSON body as for `POST /v1/timer/start`
+//! - `timer/pause`, `timer/resume`, `timer/stop`
+//! - `border/pause`: a duration, default 30 minutes
+//! - `border/resume`
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::Utc;
+use rand::Rng;
+use rumqttc::{AsyncClient, Event, EventLoop, LastWill, MqttOptions, Packet, QoS, Transport};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::async_runtime::JoinHandle;
+use tauri::{command, AppHandle, Listener, Manager};
+
+use crate::border_state::BorderState;
+use crate::calendar::types::CalendarEvent;
+use crate::http_api::{self, Route};
+use crate::settings;
+use crate::timer::{self, TimerState};
+
+pub const ENABLED_KEY: &str = "mqtt_enabled";
+pub const BROKER_URL_KEY: &str = "mqtt_broker_url";
+pub const TOPIC_PREFIX_KEY: &str = "mqtt_topic_prefix";
+pub const DISCOVERY_PREFIX_KEY: &str = "mqtt_discovery_prefix";
+
+/// Settings that change the connection.
+pub const KEYS: &[&str] = &[
+    ENABLED_KEY,
+    BROKER_URL_KEY,
+    TOPIC_PREFIX_KEY,
+    DISCOVERY_PREFIX_KEY,
+];
+
+const KEYRING_SERVICE: &str = "com.morph.mqtt";
+
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+
+/// Wait after the first failed connection attempt; doubled per failure.
+const RETRY_BASE: Duration = Duration::from_secs(1);
+const RETRY_MAX: Duration = Duration::from_secs(60);
+
+/// How often state is re-checked, so the next meeting moves on as meetings
+/// start.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Border pause length when `border/pause` has no payload.
+const DEFAULT_PAUSE_MINUTES: i32 = 30;
+
+/// Where and how to connect, from settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MqttConfig {
+    pub broker_url: String,
+    pub topic_prefix: String,
+    pub discovery_prefix: String,
+}
+
+impl MqttConfig {
+    fn topic(&self, name: &str) -> String {
+        format!("{}/{name}", self.topic_prefix)
+    }
+
+    fn availability_topic(&self) -> String {
+        self.topic("status")
+    }
+
+    fn command_filter(&self) -> String {
+        self.topic("command/#")
+    }
+}
+
+/// Build client options for the broker URL (`mqtt://host:port` or
+/// `mqtts://host:port`).
+pub fn options(
+    config: &MqttConfig,
+    credentials: Option<(String, String)>,
+    client_id: &str,
+) -> Result<MqttOptions, String> {
+    let url = url::Url::parse(&config.broker_url)
+        .map_err(|e| format!("invalid broker URL {}: {e}", config.broker_url))?;
+    let (transport, default_port) = match url.scheme() {
+        "mqtt" => (Transport::Tcp, 1883),
+        "mqtts" => (Transport::tls_with_default_config(), 8883),
+        other => return Err(format!("unsupported broker scheme: {other}")),
+    };
+    let host = url
+        .host_str()
+        .ok_or_else(|| format!("broker URL has no host: {}", config.broker_url))?;
+
+    let mut options = MqttOptions::new(client_id, host, url.port().unwrap_or(default_port));
+    options
+        .set_transport(transport)
+        .set_keep_alive(KEEP_ALIVE)
+        .set_last_will(LastWill::new(
+            config.availability_topic(),
+            "offline",
+            QoS::AtLeastOnce,
+            true,
+        ));
+    if let Some((username, password)) = credentials {
+        options.set_credentials(username, password);
+    }
+    Ok(options)
+}
+
+/// Delay before reconnect attempt `failures` (1-based).
+pub fn retry_delay(failures: u32) -> Duration {
+    RETRY_BASE
+        .saturating_mul(1 << failures.saturating_sub(1).min(16))
+        .min(RETRY_MAX)
+}
+
+// --- State topics ---
+
+/// (topic name, payload) pairs for the current state, relative to the
+/// topic prefix.
+pub fn state_messages(
+    border: &BorderState,
+    timer: &TimerState,
+    next: Option<&CalendarEvent>,
+) -> Vec<(&'static str, String)> {
+    vec![
+        ("phase", border.phase.clone()),
+        ("color", border.color.clone()),
+        (
+            "next_meeting",
+            next.map(|e| e.start_time.to_rfc3339()).unwrap_or_default(),
+        ),
+        (
+            "next_meeting_title",
+            next.map(|e| e.title.clone()).unwrap_or_default(),
+        ),
+        ("timer", timer.status.clone()),
+        (
+            "timer_ends_at",
+            timer.ends_at().map(|t| t.to_rfc3339()).unwrap_or_default(),
+        ),
+    ]
+}
+
+// --- Home Assistant discovery ---
+
+/// A discovered entity: (component, object id, name, extra config).
+type Entity = (&'static str, &'static str, &'static str, Value);
+
+fn entities(config: &MqttConfig) -> Vec<Entity> {
+    let state = |name: &str| json!({ "state_topic": config.topic(name) });
+    // HA shows an empty timestamp as unknown only through a template.
+    let timestamp = |name: &str| {
+        json!({
+            "state_topic": config.topic(name),
+            "device_class": "timestamp",
+            "value_template": "{{ value if value else None }}",
+        })
+    };
+    let press = |name: &str, payload: &str| {
+        json!({
+            "command_topic": config.topic(&format!("command/{name}")),
+            "payload_press": payload,
+        })
+    };
+    vec![
+        ("sensor", "phase", "Phase", state("phase")),
+        ("sensor", "color", "Color", state("color")),
+        (
+            "sensor",
+            "next_meeting",
+            "Next meeting",
+            timestamp("next_meeting"),
+        ),
+        (
+            "sensor",
+            "next_meeting_title",
+            "Next meeting title",
+            state("next_meeting_title"),
+        ),
+        ("sensor", "timer", "Timer", state("timer")),
+        (
+            "sensor",
+            "timer_ends_at",
+            "Timer ends",
+            timestamp("timer_ends_at"),
+        ),
+        (
+            "button",
+            "timer_start",
+            "Start focus timer",
+            press("timer/start", "25m"),
+        ),
+        (
+            "button",
+            "timer_pause",
+            "Pause timer",
+            press("timer/pause", ""),
+        ),
+        (
+            "button",
+            "timer_resume",
+            "Resume timer",
+            press("timer/resume", ""),
+        ),
+        (
+            "button",
+            "timer_stop",
+            "Stop timer",
+            press("timer/stop", ""),
+        ),
+        (
+            "button",
+            "border_pause",
+            "Pause border",
+            press("border/pause", "30m"),
+        ),
+        (
+            "button",
+            "border_resume",
+            "Resume border",
+            press("border/resume", ""),
+        ),
+    ]
+}
+
+/// Retained (topic, payload) discovery configs for Home Assistant.
+pub fn discovery_messages(config: &MqttConfig) -> Vec<(String, String)> {
+    let device = json!({
+        "identifiers": [format!("morph_{}", config.topic_prefix)],
+        "name": "Morph",
+        "manufacturer": "Morph",
+    });
+    entities(config)
+        .into_iter()
+        .map(|(component, id, name, extra)| {
+            let unique_id = format!("{}_{id}", config.topic_prefix.replace('/', "_"));
+            let mut payload = json!({
+                "name": name,
+                "unique_id": unique_id,
+                "availability_topic": config.availability_topic(),
+                "device": device,
+            });
+            if let (Some(payload), Value::Object(extra)) = (payload.as_object_mut(), extra) {
+                payload.extend(extra);
+            }
+            (
+                format!("{}/{component}/{unique_id}/config", config.discovery_prefix),
+                payload.to_string(),
+            )
+        })
+        .collect()
+}
+
+// --- Commands ---
+
+/// Map a message on a command topic to an API route and body.
+pub fn parse_command(
+    config: &MqttConfig,
+    topic: &str,
+    payload: &str,
+) -> Result<(Route, Value), String> {
+    let name = topic
+        .strip_prefix(&config.topic("command/"))
+        .ok_or_else(|| format!("not a command topic: {topic}"))?;
+    let payload = payload.trim();
+    match name {
+        "timer/start" => {
+            let body = if payload.starts_with('{') {
+                serde_json::from_str(payload).map_err(|e| format!("invalid JSON: {e}"))?
+            } else if let Ok(seconds) = crate::ipc::parse_duration(payload) {
+                json!({ "durationSeconds": seconds })
+            } else if !payload.is_empty() {
+                json!({ "presetId": payload })
+            } else {
+                return Err("timer/start needs a duration or preset ID".to_string());
+            };
+            Ok((Route::StartTimer, body))
+        }
+        "timer/pause" => Ok((Route::PauseTimer, Value::Null)),
+        "timer/resume" => Ok((Route::ResumeTimer, Value::Null)),
+        "timer/stop" => Ok((Route::StopTimer, Value::Null)),
+        "border/pause" => {
+            let minutes = if payload.is_empty() {
+                DEFAULT_PAUSE_MINUTES
+            } else {
+                crate::ipc::parse_duration(payload)?.div_ceil(60) as i32
+            };
+            Ok((Route::PauseBorder, json!({ "minutes": minutes })))
+        }
+        "border/resume" => Ok((Route::ResumeBorder, Value::Null)),
+        other => Err(format!("unknown command: {other}")),
+    }
+}
+
+/// Run a command through the HTTP API's dispatcher off the async workers.
+async fn run_command(app: &AppHandle, route: Route, body: Value) -> Result<(), String> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        http_api::dispatch(&app, route, &body.to_string())
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// --- Runtime ---
+
+/// Publish discovery configs, mark Morph online and listen for commands.
+async fn announce(client: &AsyncClient, config: &MqttConfig) -> Result<(), rumqttc::ClientError> {
+    for (topic, payload) in discovery_messages(config) {
+        client
+            .publish(topic, QoS::AtLeastOnce, true, payload)
+            .await?;
+    }
+    client
+        .publish(
+            config.availability_topic(),
+            QoS::AtLeastOnce,
+            true,
+            "online",
+        )
+        .await?;
+    client
+        .subscribe(config.command_filter(), QoS::AtLeastOnce)
+        .await
+}
+
+struct Running {
+    config: MqttConfig,
+    client: AsyncClient,
+    task: JoinHandle<()>,
+}
+
+/// Managed state for the MQTT client.
+#[derive(Default)]
+pub struct Mqtt {
+    running: tokio::sync::Mutex<Option<Running>>,
+    connected: Arc<AtomicBool>,
+    /// Last payload sent per state topic, cleared on every (re)connect.
+    published: Arc<Mutex<HashMap<String, String>>>,
+}
+
+/// Drive the connection: re-announce after every (re)connect, run commands,
+/// and back off between failed attempts.
+async fn run(app: AppHandle, client: AsyncClient, mut eventloop: EventLoop, config: MqttConfig) {
+    let mut failures = 0;
+    loop {
+        match eventloop.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                failures = 0;
+                eprintln!("[mqtt] Connected to {}", config.broker_url);
+                let mqtt = app.state::<Mqtt>();
+                mqtt.connected.store(true, Ordering::SeqCst);
+                if let Ok(mut published) = mqtt.published.lock() {
+                    published.clear();
+                }
+                let (client, config, app) = (client.clone(), config.clone(), app.clone());
+                // Publishing waits on this loop, so it runs alongside it.
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = announce(&client, &config).await {
+                        eprintln!("[mqtt] Failed to announce: {e}");
+                    }
+                    publish_state(&app).await;
+                });
+            }
+            Ok(Event::Incoming(Packet::Publish(message))) => {
+                let payload = String::from_utf8_lossy(&message.payload).into_owned();
+                match parse_command(&config, &message.topic, &payload) {
+                    Ok((route, body)) => {
+                        let app = app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            if let Err(e) = run_command(&app, route, body).await {
+                                eprintln!("[mqtt] Command failed: {e}");
+                            }
+                        });
+                    }
+                    Err(e) => eprintln!("[mqtt] Ignoring {}: {e}", message.topic),
+                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                app.state::<Mqtt>().connected.store(false, Ordering::SeqCst);
+                failures += 1;
+                let delay = retry_delay(failures);
+                eprintln!(
+                    "[mqtt] Connection to {} failed ({e}); retrying in {}s",
+                    config.broker_url,
+                    delay.as_secs()
+                );
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+/// Publish state topics whose payload changed since the last publish.
+pub async fn publish_state(app: &AppHandle) {
+    let mqtt = app.state::<Mqtt>();
+    let (client, config) = {
+        let running = mqtt.running.lock().await;
+        match running.as_ref() {
+            Some(r) => (r.client.clone(), r.config.clone()),
+            None => return,
+        }
+    };
+    if !mqtt.connected.load(Ordering::SeqCst) {
+        return;
+    }
+
+    let border = app
+        .state::<Mutex<BorderState>>()
+        .lock()
+        .map(|b| b.clone())
+        .unwrap_or_default();
+    let timer = timer::get_timer_state(app.clone()).unwrap_or_default();
+    let next = match settings::app_pool(app).await {
+        Ok(pool) => {
+            let events = crate::calendar::poller::load_cached_events(&pool)
+                .await
+                .unwrap_or_default();
+            let ignored = settings::current_settings(app).await.ignored_calendar_ids;
+            http_api::next_event(&events, Utc::now(), &ignored)
+        }
+        Err(_) => None,
+    };
+
+    let changed: Vec<(String, String)> = {
+        let Ok(mut published) = mqtt.published.lock() else {
+            return;
+        };
+        state_messages(&border, &timer, next.as_ref())
+            .into_iter()
+            .map(|(name, payload)| (config.topic(name), payload))
+            .filter(|(topic, payload)| {
+                published.insert(topic.clone(), payload.clone()).as_ref() != Some(payload)
+            })
+            .collect()
+    };
+    for (topic, payload) in changed {
+        if let Err(e) = client.publish(topic, QoS::AtLeastOnce, true, payload).await {
+            eprintln!("[mqtt] Failed to publish: {e}");
+        }
+    }
+}
+
+fn stored_credentials() -> Option<(String, String)> {
+    let get = |key: &str| {
+        keyring::Entry::new(KEYRING_SERVICE, key)
+            .ok()?
+            .get_password()
+            .ok()
+    };
+    Some((get("username")?, get("password").unwrap_or_default()))
+}
+
+/// Connect, disconnect or reconnect to match the current settings. With
+/// `force`, reconnect even if the settings did not change.
+pub async fn apply(app: &AppHandle, force: bool) {
+    let saved = settings::current_settings(app).await;
+    let wanted = saved.mqtt_enabled.then(|| MqttConfig {
+        broker_url: saved.mqtt_broker_url.clone(),
+        topic_prefix: saved.mqtt_topic_prefix.trim_matches('/').to_string(),
+        discovery_prefix: saved.mqtt_discovery_prefix.trim_matches('/').to_string(),
+    });
+
+    let mqtt = app.state::<Mqtt>();
+    let mut running = mqtt.running.lock().await;
+    if !force && running.as_ref().map(|r| &r.config) == wanted.as_ref() {
+        return;
+    }
+    if let Some(old) = running.take() {
+        // Dropping the connection lets the broker publish the last will.
+        old.task.abort();
+        mqtt.connected.store(false, Ordering::SeqCst);
+        eprintln!("[mqtt] Disconnected from {}", old.config.broker_url);
+    }
+    let Some(config) = wanted else {
+        return;
+    };
+
+    let client_id = format!("morph-{:08x}", rand::rng().random::<u32>());
+    let options = match options(&config, stored_credentials(), &client_id) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("[mqtt] {e}");
+            return;
+        }
+    };
+    let (client, eventloop) = AsyncClient::new(options, 32);
+    let task =
+        tauri::async_runtime::spawn(run(app.clone(), client.clone(), eventloop, config.clone()));
+    *running = Some(Running {
+        config,
+        client,
+        task,
+    });
+}
+
+/// Publish state changes, refresh periodically, and connect if enabled.
+pub fn start(app: &tauri::App) {
+    for name in [
+        "border-state-update",
+        "timer-state-update",
+        "calendar-events-update",
+    ] {
+        let handle = app.handle().clone();
+        app.listen(name, move |_| {
+            let handle = handle.clone();
+            tauri::async_runtime::spawn(async move {
+                publish_state(&handle).await;
+            });
+        });
+    }
+
+    let handle = app.handle().clone();
+    tauri::async_runtime::spawn(async move {
+        apply(&handle, false).await;
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        loop {
+            interval.tick().await;
+            publish_state(&handle).await;
+        }
+    });
+}
+
+// --- Tauri commands ---
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MqttStatus {
+    pub connected: bool,
+    pub has_credentials: bool,
+}
+
+#[command]
+pub fn get_mqtt_status(app: AppHandle) -> MqttStatus {
+    MqttStatus {
+        connected: app.state::<Mqtt>().connected.load(Ordering::SeqCst),
+        has_credentials: stored_credentials().is_some(),
+    }
+}
+
+/// Store broker credentials in the keyring and reconnect with them.
+#[command]
+pub async fn set_mqtt_credentials(
+    app: AppHandle,
+    username: String,
+    password: String,
+) -> Result<(), String> {
+    for (key, value) in [("username", &username), ("password", &password)] {
+        keyring::Entry::new(KEYRING_SERVICE, key)
+            .and_then(|entry| entry.set_password(value))
+            .map_err(|e| format!("keyring store error: {e}"))?;
+    }
+    apply(&app, true).await;
+    Ok(())
+}
+
+/// Forget broker credentials and reconnect anonymously.
+#[command]
+pub async fn clear_mqtt_credentials(app: AppHandle) -> Result<(), String> {
+    for key in ["username", "password"] {
+        if let Ok(entry) = keyring::Entry::new(KEYRING_SERVICE, key) {
+            let _ = entry.delete_credential();
+        }
+    }
+    apply(&app, true).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use chrono::{Duration as ChronoDuration, TimeZone};
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+
+    fn config() -> MqttConfig {
+        MqttConfig {
+            broker_url: "mqtt://127.0.0.1".to_string(),
+            topic_prefix: "morph".to_string(),
+            discovery_prefix: "homeassistant".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_options_from_broker_url() {
+        let opts = options(&config(), None, "morph-test").unwrap();
+        assert_eq!(opts.broker_address(), ("127.0.0.1".to_string(), 1883));
+        assert_eq!(opts.keep_alive(), KEEP_ALIVE);
+        let will = opts.last_will().unwrap();
+        assert_eq!(will.topic, "morph/status");
+        assert_eq!(&will.message[..], b"offline");
+        assert!(will.retain);
+
+        let custom = MqttConfig {
+            broker_url: "mqtts://broker.lan:9883".to_string(),
+            ..config()
+        };
+        let opts = options(&custom, Some(("ha".into(), "pw".into())), "id").unwrap();
+        assert_eq!(opts.broker_address(), ("broker.lan".to_string(), 9883));
+        assert_eq!(
+            opts.credentials(),
+            Some(("ha".to_string(), "pw".to_string()))
+        );
+
+        for url in ["http://broker", "mqtt://", "broker:1883"] {
+            let bad = MqttConfig {
+                broker_url: url.to_string(),
+                ..config()
+            };
+            assert!(options(&bad, None, "id").is_err(), "{url} should fail");
+        }
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_max() {
+        assert_eq!(retry_delay(1), Duration::from_secs(1));
+        assert_eq!(retry_delay(2), Duration::from_secs(2));
+        assert_eq!(retry_delay(4), Duration::from_secs(8));
+        assert_eq!(retry_delay(7), RETRY_MAX);
+        assert_eq!(retry_delay(500), RETRY_MAX);
+    }
+
+    #[test]
+    fn test_state_messages() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+        let border = BorderState {
+            phase: "warning-mid".to_string(),
+            color: "#A3B84C".to_string(),
+            opacity: 0.55,
+            pulse_speed: 3000,
+        };
+        let next = CalendarEvent {
+            title: "Standup".to_string(),
+            ..test_support::event(
+                "evt-1",
+                now + ChronoDuration::minutes(15),
+                now + ChronoDuration::minutes(30),
+            )
+        };
+        let timer = TimerState::running(1500, now);
+
+        let messages: HashMap<_, _> = state_messages(&border, &timer, Some(&next))
+            .into_iter()
+            .collect();
+        assert_eq!(messages["phase"], "warning-mid");
+        assert_eq!(messages["color"], "#A3B84C");
+        assert_eq!(messages["next_meeting"], "2026-03-02T09:15:00+00:00");
+        assert_eq!(messages["next_meeting_title"], "Standup");
+        assert_eq!(messages["timer"], "running");
+        assert_eq!(messages["timer_ends_at"], "2026-03-02T09:25:00+00:00");
+
+        let idle: HashMap<_, _> = state_messages(&border, &TimerState::default(), None)
+            .into_iter()
+            .collect();
+        assert_eq!(idle["next_meeting"], "");
+        assert_eq!(idle["timer"], "idle");
+        assert_eq!(idle["timer_ends_at"], "");
+    }
+
+    #[test]
+    fn test_discovery_messages() {
+        let messages = discovery_messages(&config());
+        let (topic, payload) = messages
+            .iter()
+            .find(|(t, _)| t.contains("next_meeting/"))
+            .unwrap();
+        assert_eq!(topic, "homeassistant/sensor/morph_next_meeting/config");
+        let payload: Value = serde_json::from_str(payload).unwrap();
+        assert_eq!(payload["state_topic"], "morph/next_meeting");
+        assert_eq!(payload["device_class"], "timestamp");
+        assert_eq!(payload["availability_topic"], "morph/status");
+        assert_eq!(payload["unique_id"], "morph_next_meeting");
+        assert_eq!(payload["device"]["name"], "Morph");
+
+        let (_, button) = messages
+            .iter()
+            .find(|(t, _)| t == "homeassistant/button/morph_timer_pause/config")
+            .unwrap();
+        let button: Value = serde_json::from_str(button).unwrap();
+        assert_eq!(button["command_topic"], "morph/command/timer/pause");
+
+        // Every command a button sends must parse.
+        for (_, payload) in &messages {
+            let payload: Value = serde_json::from_str(payload).unwrap();
+            if let Some(topic) = payload["command_topic"].as_str() {
+                let press = payload["payload_press"].as_str().unwrap();
+                parse_command(&config(), topic, press).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_command() {
+        let parse = |name: &str, payload: &str| {
+            parse_command(&config(), &format!("morph/command/{name}"), payload)
+        };
+        assert_eq!(
+            parse("timer/start", "25m").unwrap(),
+            (Route::StartTimer, json!({ "durationSeconds": 1500 }))
+        );
+        assert_eq!(
+            parse("timer/start", "10").unwrap(),
+            (Route::StartTimer, json!({ "durationSeconds": 600 }))
+        );
+        assert_eq!(
+            parse("timer/start", "pomodoro").unwrap(),
+            (Route::StartTimer, json!({ "presetId": "pomodoro" }))
+        );
+        assert_eq!(
+            parse("timer/start", r#"{"sequenceId":"pomodoro-cycle"}"#).unwrap(),
+            (Route::StartTimer, json!({ "sequenceId": "pomodoro-cycle" }))
+        );
+        assert_eq!(
+            parse("timer/pause", "").unwrap(),
+            (Route::PauseTimer, Value::Null)
+        );
+        assert_eq!(
+            parse("border/pause", "").unwrap(),
+            (Route::PauseBorder, json!({ "minutes": 30 }))
+        );
+        assert_eq!(
+            parse("border/pause", "1h").unwrap(),
+            (Route::PauseBorder, json!({ "minutes": 60 }))
+        );
+        assert!(parse("timer/start", "").is_err());
+        assert!(parse("border/pause", "soon").is_err());
+        assert!(parse("launch", "").is_err());
+        assert!(parse_command(&config(), "other/command/timer/stop", "").is_err());
+    }
+
+    // --- Minimal local broker ---
+
+    /// A PUBLISH seen by the stand-in broker.
+    #[derive(Debug)]
+    struct Published {
+        topic: String,
+        payload: String,
+        retain: bool,
+    }
+
+    fn read_packet(stream: &mut TcpStream) -> Option<(u8, Vec<u8>)> {
+        let mut byte = [0u8; 1];
+        stream.read_exact(&mut byte).ok()?;
+        let header = byte[0];
+        let (mut len, mut shift) = (0usize, 0);
+        loop {
+            stream.read_exact(&mut byte).ok()?;
+            len |= ((byte[0] & 0x7f) as usize) << shift;
+            if byte[0] & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+        }
+        let mut body = vec![0u8; len];
+        stream.read_exact(&mut body).ok()?;
+        Some((header, body))
+    }
+
+    /// Accept one client, acknowledge what it sends, record its publishes,
+    /// and send `command` on `command_topic` once it subscribes.
+    fn stand_in_broker(
+        command_topic: &'static str,
+        command: &'static str,
+    ) -> (u16, Arc<Mutex<Vec<Published>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let published = Arc::new(Mutex::new(Vec::new()));
+        let log = published.clone();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            while let Some((header, body)) = read_packet(&mut stream) {
+                match header >> 4 {
+                    1 => stream.write_all(&[0x20, 2, 0, 0]).unwrap(),
+                    3 => {
+                        let qos = (header >> 1) & 3;
+                        let topic_len = u16::from_be_bytes([body[0], body[1]]) as usize;
+                        let topic = String::from_utf8_lossy(&body[2..2 + topic_len]).into_owned();
+                        let mut rest = &body[2 + topic_len..];
+                        if qos > 0 {
+                            stream.write_all(&[0x40, 2, rest[0], rest[1]]).unwrap();
+                            rest = &rest[2..];
+                        }
+                        log.lock().unwrap().push(Published {
+                            topic,
+                            payload: String::from_utf8_lossy(rest).into_owned(),
+                            retain: header & 1 == 1,
+                        });
+                    }
+                    8 => {
+                        stream.write_all(&[0x90, 3, body[0], body[1], 1]).unwrap();
+                        let mut packet =
+                            vec![0x30, (2 + command_topic.len() + command.len()) as u8];
+                        packet.extend_from_slice(&(command_topic.len() as u16).to_be_bytes());
+                        packet.extend_from_slice(command_topic.as_bytes());
+                        packet.extend_from_slice(command.as_bytes());
+                        stream.write_all(&packet).unwrap();
+                    }
+                    12 => stream.write_all(&[0xd0, 0]).unwrap(),
+                    _ => {}
+                }
+            }
+        });
+        (port, published)
+    }
+
+    #[tokio::test]
+    async fn test_announces_and_receives_commands_from_broker() {
+        let (port, published) = stand_in_broker("morph/command/timer/start", "pomodoro");
+        let config = MqttConfig {
+            broker_url: format!("mqtt://127.0.0.1:{port}"),
+            ..config()
+        };
+        let (client, mut eventloop) =
+            AsyncClient::new(options(&config, None, "morph-test").unwrap(), 32);
+
+        let command = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                match eventloop.poll().await.unwrap() {
+                    Event::Incoming(Packet::ConnAck(_)) => {
+                        let (client, config) = (client.clone(), config.clone());
+                        tokio::spawn(async move { announce(&client, &config).await.unwrap() });
+                    }
+                    Event::Incoming(Packet::Publish(p)) => {
+                        return (p.topic, String::from_utf8_lossy(&p.payload).into_owned());
+                    }
+                    _ => {}
+                }
+            }
+        })
+        .await
+        .expect("no command received");
+
+        assert_eq!(
+            parse_command(&config, &command.0, &command.1).unwrap(),
+            (Route::StartTimer, json!({ "presetId": "pomodoro" }))
+        );
+        let published = published.lock().unwrap();
+        assert_eq!(published.len(), discovery_messages(&config).len() + 1);
+        assert!(published.iter().all(|p| p.retain));
+        let online = published.last().unwrap();
+        assert_eq!(
+            (online.topic.as_str(), online.payload.as_str()),
+            ("morph/status", "online")
+        );
+    }
+}
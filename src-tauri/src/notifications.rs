@@ -0,0 +1,526 @@
+//! Native OS notifications as an optional backup to the ambient border.
+//!
+//! The backend watches the event cache itself, so notices arrive even while
+//! the overlay windows are hidden. Each kind has its own toggle:
+//! - `notify_warning_5min` / `notify_warning_2min`: before a meeting, if the
+//!   matching `warning_5min` / `warning_2min` border warning is also on
+//! - `notify_meeting_end`: when a meeting reaches its scheduled end
+//! - `notify_timer_complete`: when a focus timer finishes
+//!
+//! Nothing is shown during quiet hours when `notify_quiet_hours` is on.
+//! Warnings for events with a meeting link offer a "Join" action. Events
+//! with a travel or prep buffer are warned about before the buffer instead.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveTime, Utc};
+use tauri::{command, AppHandle, Manager};
+
+use crate::calendar::buffers;
+use crate::calendar::types::CalendarEvent;
+use crate::settings::{self, Settings};
+use crate::timer::TimerCompleted;
+
+pub const WARNING_5MIN_KEY: &str = "notify_warning_5min";
+pub const WARNING_2MIN_KEY: &str = "notify_warning_2min";
+pub const MEETING_END_KEY: &str = "notify_meeting_end";
+pub const TIMER_COMPLETE_KEY: &str = "notify_timer_complete";
+pub const QUIET_HOURS_KEY: &str = "notify_quiet_hours";
+pub const QUIET_START_KEY: &str = "notify_quiet_start";
+pub const QUIET_END_KEY: &str = "notify_quiet_end";
+
+/// How often the event cache is checked for thresholds.
+const TICK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// A threshold missed by more than this (sleep, a late poll) is skipped
+/// rather than announced late.
+const GRACE_SECONDS: i64 = 120;
+
+/// Action ID for the "Join" button.
+const JOIN_ACTION: &str = "join";
+
+/// A moment worth a notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Trigger {
+    /// This many minutes before the start.
+    Warning(u32),
+    MeetingEnd,
+}
+
+/// A notification to show.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notice {
+    pub title: String,
+    pub body: String,
+    pub join_url: Option<String>,
+}
+
+/// Notification inputs derived from settings.
+#[derive(Debug, Clone, Default)]
+pub struct NotifyConfig {
+    /// Minutes before a meeting to warn at.
+    pub warnings: Vec<u32>,
+    pub meeting_end: bool,
+    pub timer_complete: bool,
+    /// Local (start, end) of quiet hours, if enabled.
+    pub quiet_hours: Option<(NaiveTime, NaiveTime)>,
+    pub ignored_calendar_ids: Vec<String>,
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M").ok()
+}
+
+impl NotifyConfig {
+    pub fn from_settings(settings: &Settings) -> Self {
+        let warnings = [
+            (settings.warning_5min && settings.notify_warning_5min, 5),
+            (settings.warning_2min && settings.notify_warning_2min, 2),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, minutes)| enabled.then_some(minutes))
+        .collect();
+        let quiet_hours = settings
+            .notify_quiet_hours
+            .then(|| {
+                Some((
+                    parse_time(&settings.notify_quiet_start)?,
+                    parse_time(&settings.notify_quiet_end)?,
+                ))
+            })
+            .flatten();
+        Self {
+            warnings,
+            meeting_end: settings.notify_meeting_end,
+            timer_complete: settings.notify_timer_complete,
+            quiet_hours,
+            ignored_calendar_ids: settings.ignored_calendar_ids.clone(),
+        }
+    }
+
+    /// Whether `time` (local) falls in quiet hours. An end earlier than the
+    /// start runs overnight; equal times cover the whole day.
+    pub fn is_quiet(&self, time: NaiveTime) -> bool {
+        match self.quiet_hours {
+            None => false,
+            Some((start, end)) if start < end => start <= time && time < end,
+            Some((start, end)) => time >= start || time < end,
+        }
+    }
+
+    /// Whether an event counts as a meeting (same filter as the overlay).
+    fn tracks(&self, e: &CalendarEvent) -> bool {
+        e.counts_as_meeting(&self.ignored_calendar_ids)
+    }
+}
+
+fn warning_notice(event: &CalendarEvent, minutes: u32) -> Notice {
+    let at = event.start_time.with_timezone(&Local).format("%H:%M");
+    Notice {
+        title: event.title.clone(),
+        body: format!("Starts in {minutes} minutes, at {at}"),
+        join_url: event.meeting_url.clone(),
+    }
+}
+
+fn meeting_end_notice(event: &CalendarEvent) -> Notice {
+    Notice {
+        title: event.title.clone(),
+        body: "Scheduled to end now".to_string(),
+        join_url: None,
+    }
+}
+
+pub fn timer_notice(completed: &TimerCompleted) -> Notice {
+    let body = match (completed.step, completed.total_steps) {
+        (Some(step), Some(total)) if completed.next_preset_id.is_some() => {
+            format!("Step {step} of {total} done; next step started")
+        }
+        _ => "Timer finished".to_string(),
+    };
+    Notice {
+        title: completed.name.clone(),
+        body,
+        join_url: None,
+    }
+}
+
+/// Remembers which thresholds were already announced.
+#[derive(Debug, Default)]
+pub struct NoticeTracker {
+    /// (event ID, trigger) → when the threshold was.
+    sent: HashMap<(String, Trigger), DateTime<Utc>>,
+}
+
+impl NoticeTracker {
+    /// Notices for thresholds reached since the last tick.
+    pub fn tick(
+        &mut self,
+        events: &[CalendarEvent],
+        now: DateTime<Utc>,
+        config: &NotifyConfig,
+    ) -> Vec<Notice> {
+        let grace = chrono::Duration::seconds(GRACE_SECONDS);
+        self.sent.retain(|_, at| *at + grace > now);
+
+        // Warnings for these come from their buffers.
+        let buffered = buffers::buffered_ids(events);
+        let mut notices = Vec::new();
+        for event in events.iter().filter(|e| config.tracks(e)) {
+            let mut due = |trigger: Trigger, at: DateTime<Utc>| {
+                at <= now
+                    && now - at < grace
+                    && self.sent.insert((event.id.clone(), trigger), at) != Some(at)
+            };
+            let warnings = if buffered.contains(event.id.as_str()) {
+                &[][..]
+            } else {
+                &config.warnings[..]
+            };
+            for &minutes in warnings {
+                let at = event.start_time - chrono::Duration::minutes(i64::from(minutes));
+                if now < event.start_time && due(Trigger::Warning(minutes), at) {
+                    notices.push(warning_notice(event, minutes));
+                }
+            }
+            let meeting_end = config.meeting_end && !event.is_buffer;
+            if meeting_end && due(Trigger::MeetingEnd, event.end_time) {
+                notices.push(meeting_end_notice(event));
+            }
+        }
+        notices
+    }
+}
+
+// --- Runtime ---
+
+/// Show an error that stops Morph from starting. Waits until the
+/// notification is up, since the app exits right after.
+pub(crate) fn show_startup_error(body: &str) {
+    let result = notify_rust::Notification::new()
+        .summary("Morph couldn't start")
+        .body(body)
+        .appname("Morph")
+        .show();
+    if let Err(e) = result {
+        eprintln!("[notifications] Failed to show notification: {e}");
+    }
+}
+
+/// Show a notification on a separate thread, which waits for "Join" if the
+/// notice has a link.
+pub(crate) fn show(app: &AppHandle, notice: Notice) {
+    #[cfg(target_os = "windows")]
+    let app_id = app.config().identifier.clone();
+    #[cfg(not(target_os = "windows"))]
+    let _ = app;
+    std::thread::spawn(move || {
+        let mut notification = notify_rust::Notification::new();
+        notification
+            .summary(&notice.title)
+            .body(&notice.body)
+            .appname("Morph");
+        #[cfg(target_os = "windows")]
+        notification.app_id(&app_id);
+        if notice.join_url.is_some() {
+            notification.action(JOIN_ACTION, "Join");
+        }
+        let handle = match notification.show() {
+            Ok(handle) => handle,
+            Err(e) => {
+                eprintln!("[notifications] Failed to show notification: {e}");
+                return;
+            }
+        };
+        if let Some(url) = notice.join_url {
+            handle.wait_for_action(|action| {
+                // "default" is a click on the notification itself.
+                if action == JOIN_ACTION || action == "default" {
+                    if let Err(e) = open::that(&url) {
+                        eprintln!("[notifications] Failed to open meeting link: {e}");
+                    }
+                }
+            });
+        }
+    });
+}
+
+/// Show `notices` unless it is quiet hours.
+fn deliver(app: &AppHandle, config: &NotifyConfig, notices: Vec<Notice>) {
+    if notices.is_empty() || config.is_quiet(Local::now().time()) {
+        return;
+    }
+    for notice in notices {
+        show(app, notice);
+    }
+}
+
+async fn tick(app: &AppHandle) {
+    let settings = settings::current_settings(app).await;
+    let config = NotifyConfig::from_settings(&settings);
+    if config.warnings.is_empty() && !config.meeting_end {
+        return;
+    }
+    let Ok(pool) = settings::app_pool(app).await else {
+        return;
+    };
+    let events = match crate::calendar::poller::load_cached_events(&pool).await {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("[notifications] Failed to load events: {e}");
+            return;
+        }
+    };
+    let events = buffers::with_buffers(
+        &events,
+        &settings.buffer_rules,
+        &settings.ignored_calendar_ids,
+    );
+    let notices = {
+        let managed = app.state::<Mutex<NoticeTracker>>();
+        let Ok(mut tracker) = managed.lock() else {
+            return;
+        };
+        tracker.tick(&events, Utc::now(), &config)
+    };
+    deliver(app, &config, notices);
+}
+
+/// Announce a finished timer, if enabled. Called by the timer service.
+pub fn timer_completed(app: &AppHandle, completed: &TimerCompleted) {
+    let app = app.clone();
+    let notice = timer_notice(completed);
+    tauri::async_runtime::spawn(async move {
+        let config = NotifyConfig::from_settings(&settings::current_settings(&app).await);
+        if config.timer_complete {
+            deliver(&app, &config, vec![notice]);
+        }
+    });
+}
+
+/// Start watching for meeting thresholds in the background.
+pub fn start(app: AppHandle) {
+    #[cfg(target_os = "macos")]
+    if let Err(e) = notify_rust::set_application(&app.config().identifier) {
+        eprintln!("[notifications] Failed to set application: {e}");
+    }
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(TICK_INTERVAL);
+        loop {
+            interval.tick().await;
+            tick(&app).await;
+        }
+    });
+}
+
+// --- Tauri commands ---
+
+/// Show a sample notification so the user can check (and grant) permission.
+/// Ignores quiet hours.
+#[command]
+pub fn send_test_notification(app: AppHandle) {
+    show(
+        &app,
+        Notice {
+            title: "Morph".to_string(),
+            body: "Notifications are working".to_string(),
+            join_url: None,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use chrono::TimeZone;
+
+    fn event(id: &str, start: DateTime<Utc>, minutes: i64) -> CalendarEvent {
+        CalendarEvent {
+            meeting_url: Some(format!("https://meet.example.com/{id}")),
+            ..test_support::event(id, start, start + chrono::Duration::minutes(minutes))
+        }
+    }
+
+    fn config() -> NotifyConfig {
+        NotifyConfig {
+            warnings: vec![5, 2],
+            meeting_end: true,
+            timer_complete: true,
+            quiet_hours: None,
+            ignored_calendar_ids: vec!["holidays".to_string()],
+        }
+    }
+
+    fn at(h: u32, m: u32, s: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, h, m, s).unwrap()
+    }
+
+    #[test]
+    fn test_config_requires_border_warning_too() {
+        let mut settings = Settings {
+            notify_warning_5min: true,
+            notify_warning_2min: true,
+            warning_2min: false,
+            ..Settings::default()
+        };
+        let config = NotifyConfig::from_settings(&settings);
+        assert_eq!(config.warnings, vec![5]);
+        assert!(config.quiet_hours.is_none());
+
+        settings.notify_quiet_hours = true;
+        let config = NotifyConfig::from_settings(&settings);
+        assert_eq!(
+            config.quiet_hours,
+            Some((parse_time("22:00").unwrap(), parse_time("08:00").unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_fires_each_threshold_once() {
+        let events = vec![event("a", at(10, 0, 0), 30)];
+        let mut tracker = NoticeTracker::default();
+        let config = config();
+
+        assert!(tracker.tick(&events, at(9, 54, 50), &config).is_empty());
+        let notices = tracker.tick(&events, at(9, 55, 5), &config);
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].title, "Meeting a");
+        assert!(notices[0].body.starts_with("Starts in 5 minutes"));
+        assert_eq!(
+            notices[0].join_url.as_deref(),
+            Some("https://meet.example.com/a")
+        );
+        assert!(tracker.tick(&events, at(9, 55, 20), &config).is_empty());
+
+        let notices = tracker.tick(&events, at(9, 58, 0), &config);
+        assert!(notices[0].body.starts_with("Starts in 2 minutes"));
+
+        let notices = tracker.tick(&events, at(10, 30, 10), &config);
+        assert_eq!(
+            notices,
+            vec![Notice {
+                title: "Meeting a".to_string(),
+                body: "Scheduled to end now".to_string(),
+                join_url: None,
+            }]
+        );
+        assert!(tracker.tick(&events, at(10, 30, 25), &config).is_empty());
+    }
+
+    #[test]
+    fn test_skips_thresholds_missed_by_more_than_grace() {
+        let events = vec![event("a", at(10, 0, 0), 30)];
+        let mut tracker = NoticeTracker::default();
+        // Woke from sleep a minute before the meeting: the 5-minute warning
+        // is stale, the 2-minute one is still useful.
+        let notices = tracker.tick(&events, at(9, 59, 0), &config());
+        assert_eq!(notices.len(), 1);
+        assert!(notices[0].body.starts_with("Starts in 2 minutes"));
+        // No warnings once the meeting has started.
+        let late = vec![event("b", at(10, 0, 0), 30)];
+        assert!(tracker.tick(&late, at(10, 0, 30), &config()).is_empty());
+    }
+
+    #[test]
+    fn test_rescheduled_meeting_is_announced_again() {
+        let mut tracker = NoticeTracker::default();
+        let config = NotifyConfig {
+            warnings: vec![5],
+            ..config()
+        };
+        let original = vec![event("a", at(10, 0, 0), 30)];
+        assert_eq!(tracker.tick(&original, at(9, 55, 0), &config).len(), 1);
+
+        let moved = vec![event("a", at(10, 15, 0), 30)];
+        assert!(tracker.tick(&moved, at(9, 56, 0), &config).is_empty());
+        assert_eq!(tracker.tick(&moved, at(10, 10, 0), &config).len(), 1);
+    }
+
+    #[test]
+    fn test_ignores_filtered_events() {
+        let mut ignored = event("a", at(10, 0, 0), 30);
+        ignored.ignored = true;
+        let mut all_day = event("b", at(10, 0, 0), 30);
+        all_day.is_all_day = true;
+        let mut holiday = event("c", at(10, 0, 0), 30);
+        holiday.calendar_id = Some("holidays".to_string());
+
+        let mut tracker = NoticeTracker::default();
+        let events = vec![ignored, all_day, holiday];
+        assert!(tracker.tick(&events, at(9, 55, 0), &config()).is_empty());
+    }
+
+    #[test]
+    fn test_buffer_takes_over_warnings() {
+        let rules = buffers::parse_rules(r#"[{"minutes": 15, "titleContains": "a"}]"#).unwrap();
+        let events = buffers::with_buffers(&[event("a", at(10, 0, 0), 30)], &rules, &[]);
+        let mut tracker = NoticeTracker::default();
+        let config = config();
+
+        let notices = tracker.tick(&events, at(9, 40, 0), &config);
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].title, "Prep: Meeting a");
+        assert!(notices[0].body.starts_with("Starts in 5 minutes, at"));
+        assert_eq!(tracker.tick(&events, at(9, 43, 0), &config).len(), 1);
+        // Nothing for the meeting itself until it ends, and no end notice
+        // for the buffer.
+        assert!(tracker.tick(&events, at(9, 55, 0), &config).is_empty());
+        assert!(tracker.tick(&events, at(10, 0, 0), &config).is_empty());
+        let notices = tracker.tick(&events, at(10, 30, 0), &config);
+        assert_eq!(notices[0].title, "Meeting a");
+    }
+
+    #[test]
+    fn test_quiet_hours() {
+        let t = |s: &str| parse_time(s).unwrap();
+        let overnight = NotifyConfig {
+            quiet_hours: Some((t("22:00"), t("08:00"))),
+            ..config()
+        };
+        assert!(overnight.is_quiet(t("23:30")));
+        assert!(overnight.is_quiet(t("07:59")));
+        assert!(!overnight.is_quiet(t("08:00")));
+        assert!(!overnight.is_quiet(t("12:00")));
+
+        let lunch = NotifyConfig {
+            quiet_hours: Some((t("12:00"), t("13:00"))),
+            ..config()
+        };
+        assert!(lunch.is_quiet(t("12:30")));
+        assert!(!lunch.is_quiet(t("13:00")));
+
+        let all_day = NotifyConfig {
+            quiet_hours: Some((t("09:00"), t("09:00"))),
+            ..config()
+        };
+        assert!(all_day.is_quiet(t("03:00")));
+        assert!(!config().is_quiet(t("03:00")));
+    }
+
+    #[test]
+    fn test_timer_notice() {
+        let mut completed = TimerCompleted {
+            preset_id: "pomodoro".to_string(),
+            name: "Pomodoro".to_string(),
+            duration_seconds: 1500,
+            sequence_id: None,
+            step: None,
+            total_steps: None,
+            next_preset_id: None,
+        };
+        assert_eq!(timer_notice(&completed).body, "Timer finished");
+        assert_eq!(timer_notice(&completed).title, "Pomodoro");
+
+        completed.sequence_id = Some("cycle".to_string());
+        completed.step = Some(1);
+        completed.total_steps = Some(4);
+        completed.next_preset_id = Some("short-break".to_string());
+        assert_eq!(
+            timer_notice(&completed).body,
+            "Step 1 of 4 done; next step started"
+        );
+    }
+}
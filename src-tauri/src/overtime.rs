@@ -0,0 +1,706 @@
+//! Overtime tracking for meetings that run past their scheduled end.
+//!
+//! When a meeting ends the backend assumes the user is still in it and enters
+//! overtime. Overtime ends when the user says they have left (tray item "I've
+//! left the meeting"), when the next meeting takes over (it starts, or its
+//! last warning window begins), or after `overtime_max_minutes` with no
+//! confirmation. Each ended overtime is stored in `meeting_overruns` so
+//! recurring series that habitually run long can be reported.
+//!
+//! The overlay shows the `overtime` phase only while this tracker says so
+//! (`overtime-state-update`).
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+use tauri::{command, AppHandle, Emitter, Manager};
+
+use crate::calendar::types::CalendarEvent;
+use crate::history::{parse_timestamp, timestamp};
+use crate::settings::{self, Settings};
+use crate::webhooks::{self, WebhookEvent};
+
+/// Setting key for how long overtime lasts without confirmation.
+pub const MAX_MINUTES_KEY: &str = "overtime_max_minutes";
+
+/// How often the tracker re-checks the event cache.
+const TICK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Overruns shorter than this count as finishing on time.
+const OVERRUN_THRESHOLD_SECONDS: i64 = 60;
+
+/// The meeting currently (or most recently) tracked. Emitted to the overlay
+/// as the `overtime-state-update` payload while in overtime, `null` otherwise.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OvertimeMeeting {
+    pub event_id: String,
+    pub series_id: Option<String>,
+    pub title: String,
+    /// When the meeting was scheduled to end, i.e. when overtime began.
+    pub scheduled_end: DateTime<Utc>,
+}
+
+impl From<&CalendarEvent> for OvertimeMeeting {
+    fn from(e: &CalendarEvent) -> Self {
+        Self {
+            event_id: e.id.clone(),
+            series_id: e.series_id.clone(),
+            title: e.title.clone(),
+            scheduled_end: e.end_time,
+        }
+    }
+}
+
+/// How a meeting's overtime ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OvertimeEnd {
+    /// The user confirmed they left.
+    Left,
+    /// The next meeting started or was about to.
+    Yielded,
+    /// Nobody confirmed within the overtime limit.
+    Expired,
+}
+
+impl OvertimeEnd {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OvertimeEnd::Left => "left",
+            OvertimeEnd::Yielded => "yielded",
+            OvertimeEnd::Expired => "expired",
+        }
+    }
+}
+
+/// A finished overtime, as stored in `meeting_overruns`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverrunRecord {
+    pub meeting: OvertimeMeeting,
+    pub ended_at: DateTime<Utc>,
+    pub outcome: OvertimeEnd,
+}
+
+impl OverrunRecord {
+    /// The overrun, if known. Only a confirmed departure tells us when the
+    /// meeting actually ended.
+    pub fn overrun_seconds(&self) -> Option<i64> {
+        (self.outcome == OvertimeEnd::Left).then(|| {
+            (self.ended_at - self.meeting.scheduled_end)
+                .num_seconds()
+                .max(0)
+        })
+    }
+}
+
+/// Tracker inputs derived from settings.
+#[derive(Debug, Clone)]
+pub struct OvertimeConfig {
+    /// Overtime ends unconfirmed after this long.
+    pub max: chrono::Duration,
+    /// Overtime yields once the next meeting is this close (its last warning
+    /// window, `warning-imminent` with the default windows).
+    pub yield_before: chrono::Duration,
+    pub ignored_calendar_ids: Vec<String>,
+}
+
+impl OvertimeConfig {
+    pub fn from_settings(settings: &Settings) -> Self {
+        let windows = [
+            (settings.warning_30min, 30),
+            (settings.warning_15min, 15),
+            (settings.warning_5min, 5),
+            (settings.warning_2min, 2),
+        ];
+        let last_warning = windows
+            .iter()
+            .filter(|(enabled, _)| *enabled)
+            .map(|(_, minutes)| *minutes)
+            .min()
+            .unwrap_or(0);
+        Self {
+            max: chrono::Duration::minutes(i64::from(settings.overtime_max_minutes)),
+            yield_before: chrono::Duration::minutes(last_warning),
+            ignored_calendar_ids: settings.ignored_calendar_ids.clone(),
+        }
+    }
+
+    /// Whether an event counts as a meeting (same filter as the overlay).
+    fn tracks(&self, e: &CalendarEvent) -> bool {
+        e.counts_as_meeting(&self.ignored_calendar_ids)
+    }
+}
+
+/// Result of a [`OvertimeTracker::tick`].
+#[derive(Debug, Default, PartialEq)]
+pub struct Tick {
+    /// The overtime state changed and should be re-emitted.
+    pub changed: bool,
+    pub finished: Vec<OverrunRecord>,
+    /// A meeting came into session.
+    pub started: Option<OvertimeMeeting>,
+    /// The meeting in session reached its scheduled end.
+    pub ended: Option<OvertimeMeeting>,
+}
+
+/// Follows the meeting in session and the overtime after it ends.
+#[derive(Debug, Default)]
+pub struct OvertimeTracker {
+    in_session: Option<OvertimeMeeting>,
+    overtime: Option<OvertimeMeeting>,
+}
+
+impl OvertimeTracker {
+    /// The meeting currently in overtime, if any.
+    pub fn current(&self) -> Option<&OvertimeMeeting> {
+        self.overtime.as_ref()
+    }
+
+    /// Advance to `now` given the latest known events.
+    pub fn tick(
+        &mut self,
+        events: &[CalendarEvent],
+        now: DateTime<Utc>,
+        config: &OvertimeConfig,
+    ) -> Tick {
+        let before = self.overtime.clone();
+        let mut finished = Vec::new();
+
+        let tracked = || events.iter().filter(|e| config.tracks(e));
+        let current: Option<OvertimeMeeting> = tracked()
+            .filter(|e| e.start_time <= now && now < e.end_time)
+            .max_by_key(|e| e.end_time)
+            .map(OvertimeMeeting::from);
+        let next_start = tracked()
+            .filter(|e| e.start_time > now)
+            .map(|e| e.start_time)
+            .min();
+
+        // A meeting extended past its old end is back in session.
+        let mut extended = false;
+        if let (Some(over), Some(cur)) = (&self.overtime, &current) {
+            if over.event_id == cur.event_id {
+                self.overtime = None;
+                extended = true;
+            }
+        }
+
+        let started = current
+            .as_ref()
+            .filter(|c| {
+                !extended
+                    && self
+                        .in_session
+                        .as_ref()
+                        .is_none_or(|p| p.event_id != c.event_id)
+            })
+            .cloned();
+        let mut ended = None;
+
+        // The meeting we were following has ended: go into overtime. A
+        // meeting that vanished before its end was cancelled; forget it.
+        if let Some(previous) = self.in_session.take() {
+            let still_running = current
+                .as_ref()
+                .is_some_and(|c| c.event_id == previous.event_id);
+            if !still_running && previous.scheduled_end <= now {
+                ended = Some(previous.clone());
+                if let Some(older) = self.overtime.replace(previous) {
+                    finished.push(OverrunRecord {
+                        meeting: older,
+                        ended_at: now,
+                        outcome: OvertimeEnd::Yielded,
+                    });
+                }
+            }
+        }
+        self.in_session = current;
+
+        if let Some(over) = &self.overtime {
+            let expires = over.scheduled_end + config.max;
+            let next_is_close = next_start.is_some_and(|start| start - now <= config.yield_before);
+            let outcome = if now >= expires {
+                Some((OvertimeEnd::Expired, expires))
+            } else if self.in_session.is_some() || next_is_close {
+                Some((OvertimeEnd::Yielded, now))
+            } else {
+                None
+            };
+            if let Some((outcome, ended_at)) = outcome {
+                finished.push(OverrunRecord {
+                    meeting: self.overtime.take().expect("checked above"),
+                    ended_at,
+                    outcome,
+                });
+            }
+        }
+
+        Tick {
+            changed: self.overtime != before,
+            finished,
+            started,
+            ended,
+        }
+    }
+
+    /// The user left the meeting: end overtime now.
+    pub fn leave(&mut self, now: DateTime<Utc>) -> Option<OverrunRecord> {
+        self.overtime.take().map(|meeting| OverrunRecord {
+            meeting,
+            ended_at: now,
+            outcome: OvertimeEnd::Left,
+        })
+    }
+}
+
+/// Overrun statistics for one recurring series.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesOverrun {
+    pub series_id: String,
+    /// Title of the most recent occurrence.
+    pub title: String,
+    /// Occurrences tracked in the range.
+    pub meetings: u32,
+    /// Occurrences where the user confirmed when they left.
+    pub confirmed: u32,
+    /// Confirmed occurrences that ran at least a minute over.
+    pub overran: u32,
+    pub total_overrun_minutes: f64,
+    /// Mean overrun over confirmed occurrences.
+    pub average_overrun_minutes: Option<f64>,
+    pub last_meeting_at: DateTime<Utc>,
+}
+
+/// One `meeting_overruns` row, as used for reporting.
+#[derive(Debug, Clone)]
+struct OverrunRow {
+    series_id: String,
+    title: String,
+    scheduled_end: DateTime<Utc>,
+    overrun_seconds: Option<i64>,
+}
+
+/// Group overruns by series, worst (most total overrun) first.
+fn summarize(rows: &[OverrunRow]) -> Vec<SeriesOverrun> {
+    let mut by_series: BTreeMap<&str, SeriesOverrun> = BTreeMap::new();
+    for row in rows {
+        let entry = by_series
+            .entry(&row.series_id)
+            .or_insert_with(|| SeriesOverrun {
+                series_id: row.series_id.clone(),
+                title: row.title.clone(),
+                meetings: 0,
+                confirmed: 0,
+                overran: 0,
+                total_overrun_minutes: 0.0,
+                average_overrun_minutes: None,
+                last_meeting_at: row.scheduled_end,
+            });
+        entry.meetings += 1;
+        if row.scheduled_end >= entry.last_meeting_at {
+            entry.last_meeting_at = row.scheduled_end;
+            entry.title = row.title.clone();
+        }
+        if let Some(seconds) = row.overrun_seconds {
+            entry.confirmed += 1;
+            if seconds >= OVERRUN_THRESHOLD_SECONDS {
+                entry.overran += 1;
+            }
+            entry.total_overrun_minutes += seconds as f64 / 60.0;
+        }
+    }
+
+    let mut series: Vec<SeriesOverrun> = by_series
+        .into_values()
+        .map(|mut s| {
+            s.average_overrun_minutes =
+                (s.confirmed > 0).then(|| s.total_overrun_minutes / f64::from(s.confirmed));
+            s
+        })
+        .collect();
+    series.sort_by(|a, b| {
+        b.total_overrun_minutes
+            .total_cmp(&a.total_overrun_minutes)
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    series
+}
+
+// --- Pool-based inner functions (testable without Tauri runtime) ---
+
+async fn insert_overrun(pool: &SqlitePool, record: &OverrunRecord) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO meeting_overruns
+           (event_id, series_id, title, scheduled_end, ended_at, outcome, overrun_seconds)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )
+    .bind(&record.meeting.event_id)
+    .bind(&record.meeting.series_id)
+    .bind(&record.meeting.title)
+    .bind(timestamp(record.meeting.scheduled_end))
+    .bind(timestamp(record.ended_at))
+    .bind(record.outcome.as_str())
+    .bind(record.overrun_seconds())
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Per-series overrun report for meetings scheduled to end at or after `from`.
+async fn overrun_report_inner(
+    pool: &SqlitePool,
+    from: DateTime<Utc>,
+) -> Result<Vec<SeriesOverrun>, String> {
+    let rows: Vec<OverrunRow> = sqlx::query(
+        "SELECT series_id, title, scheduled_end, overrun_seconds FROM meeting_overruns
+         WHERE series_id IS NOT NULL AND scheduled_end >= ?1",
+    )
+    .bind(timestamp(from))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .iter()
+    .filter_map(|r| {
+        Some(OverrunRow {
+            series_id: r.get("series_id"),
+            title: r.get("title"),
+            scheduled_end: parse_timestamp(&r.get::<String, _>("scheduled_end"))?,
+            overrun_seconds: r.get("overrun_seconds"),
+        })
+    })
+    .collect();
+    Ok(summarize(&rows))
+}
+
+// --- Runtime ---
+
+/// The meeting currently in overtime, if any.
+pub fn current(app: &AppHandle) -> Option<OvertimeMeeting> {
+    app.try_state::<Mutex<OvertimeTracker>>()
+        .and_then(|t| t.lock().ok().and_then(|t| t.current().cloned()))
+}
+
+/// Store finished overtimes and, if the state changed, tell the overlay and
+/// rebuild the tray menu.
+async fn publish(app: &AppHandle, changed: bool, finished: Vec<OverrunRecord>) {
+    if !finished.is_empty() {
+        if let Ok(pool) = settings::app_pool(app).await {
+            for record in &finished {
+                if let Err(e) = insert_overrun(&pool, record).await {
+                    eprintln!("[overtime] Failed to record overrun: {e}");
+                }
+            }
+        }
+    }
+    if changed {
+        let _ = app.emit("overtime-state-update", current(app));
+        if let Err(e) = crate::tray::refresh_tray_menu(app) {
+            eprintln!("[overtime] Failed to refresh tray menu: {e}");
+        }
+    }
+}
+
+async fn tick(app: &AppHandle) {
+    let Ok(pool) = settings::app_pool(app).await else {
+        return;
+    };
+    let events = match crate::calendar::poller::load_cached_events(&pool).await {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("[overtime] Failed to load events: {e}");
+            return;
+        }
+    };
+    let config = OvertimeConfig::from_settings(&settings::current_settings(app).await);
+
+    let result = {
+        let managed = app.state::<Mutex<OvertimeTracker>>();
+        let Ok(mut tracker) = managed.lock() else {
+            return;
+        };
+        tracker.tick(&events, Utc::now(), &config)
+    };
+    if let Some(meeting) = &result.started {
+        webhooks::notify(app, WebhookEvent::MeetingStarted, meeting);
+    }
+    if let Some(meeting) = &result.ended {
+        webhooks::notify(app, WebhookEvent::MeetingEnded, meeting);
+    }
+    publish(app, result.changed, result.finished).await;
+}
+
+/// End overtime because the user left the meeting.
+pub async fn leave(app: &AppHandle) -> Result<(), String> {
+    let record = {
+        let managed = app.state::<Mutex<OvertimeTracker>>();
+        let mut tracker = managed.lock().map_err(|e| e.to_string())?;
+        tracker.leave(Utc::now())
+    };
+    if let Some(record) = record {
+        publish(app, true, vec![record]).await;
+    }
+    Ok(())
+}
+
+/// Start tracking overtime in the background.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(TICK_INTERVAL);
+        loop {
+            interval.tick().await;
+            tick(&app).await;
+        }
+    });
+}
+
+// --- Tauri commands ---
+
+/// Return the meeting currently in overtime, or `null`.
+#[command]
+pub fn get_overtime_state(app: AppHandle) -> Option<OvertimeMeeting> {
+    current(&app)
+}
+
+/// End the current overtime ("I've left the meeting").
+#[command]
+pub async fn end_overtime(app: AppHandle) -> Result<(), String> {
+    leave(&app).await
+}
+
+/// Overrun statistics per recurring series over the last `days` days.
+#[command]
+pub async fn get_overrun_report(app: AppHandle, days: u32) -> Result<Vec<SeriesOverrun>, String> {
+    if days == 0 {
+        return Err("days must be at least 1".to_string());
+    }
+    let pool = settings::app_pool(&app).await?;
+    let from = Utc::now() - chrono::Duration::days(i64::from(days));
+    overrun_report_inner(&pool, from).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, hour, minute, 0).unwrap()
+    }
+
+    fn event(id: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> CalendarEvent {
+        CalendarEvent {
+            series_id: Some(format!("series-{id}")),
+            ..test_support::event(id, start, end)
+        }
+    }
+
+    fn config() -> OvertimeConfig {
+        OvertimeConfig::from_settings(&Settings::default())
+    }
+
+    #[test]
+    fn test_config_yields_at_last_enabled_warning() {
+        let mut settings = Settings::default();
+        assert_eq!(config().yield_before, chrono::Duration::minutes(2));
+        assert_eq!(config().max, chrono::Duration::minutes(15));
+        settings.warning_2min = false;
+        assert_eq!(
+            OvertimeConfig::from_settings(&settings).yield_before,
+            chrono::Duration::minutes(5)
+        );
+    }
+
+    #[test]
+    fn test_meeting_end_enters_overtime_until_user_leaves() {
+        let mut tracker = OvertimeTracker::default();
+        let events = [event("a", at(9, 0), at(10, 0))];
+
+        assert!(!tracker.tick(&events, at(9, 30), &config()).changed);
+        let tick = tracker.tick(&events, at(10, 0), &config());
+        assert!(tick.changed);
+        assert_eq!(tracker.current().unwrap().event_id, "a");
+
+        let record = tracker.leave(at(10, 7)).unwrap();
+        assert_eq!(record.outcome, OvertimeEnd::Left);
+        assert_eq!(record.overrun_seconds(), Some(7 * 60));
+        assert!(tracker.current().is_none());
+        assert!(tracker.leave(at(10, 8)).is_none());
+    }
+
+    #[test]
+    fn test_reports_meeting_start_and_end_once() {
+        let mut tracker = OvertimeTracker::default();
+        let events = [
+            event("a", at(9, 0), at(10, 0)),
+            event("b", at(10, 0), at(10, 30)),
+        ];
+
+        assert!(tracker
+            .tick(&events, at(8, 59), &config())
+            .started
+            .is_none());
+        let tick = tracker.tick(&events, at(9, 0), &config());
+        assert_eq!(tick.started.unwrap().event_id, "a");
+        assert!(tick.ended.is_none());
+        assert!(tracker
+            .tick(&events, at(9, 30), &config())
+            .started
+            .is_none());
+
+        // Back to back: "a" ends as "b" starts.
+        let tick = tracker.tick(&events, at(10, 0), &config());
+        assert_eq!(tick.ended.unwrap().event_id, "a");
+        assert_eq!(tick.started.unwrap().event_id, "b");
+
+        let tick = tracker.tick(&events, at(10, 30), &config());
+        assert_eq!(tick.ended.unwrap().event_id, "b");
+        assert!(tick.started.is_none());
+    }
+
+    #[test]
+    fn test_overtime_expires_without_confirmation() {
+        let mut tracker = OvertimeTracker::default();
+        let events = [event("a", at(9, 0), at(10, 0))];
+        tracker.tick(&events, at(9, 59), &config());
+        tracker.tick(&events, at(10, 1), &config());
+
+        let tick = tracker.tick(&events, at(10, 20), &config());
+        assert!(tick.changed);
+        assert_eq!(tick.finished.len(), 1);
+        assert_eq!(tick.finished[0].outcome, OvertimeEnd::Expired);
+        assert_eq!(tick.finished[0].ended_at, at(10, 15));
+        assert_eq!(tick.finished[0].overrun_seconds(), None);
+    }
+
+    #[test]
+    fn test_overtime_yields_to_imminent_next_meeting() {
+        let mut tracker = OvertimeTracker::default();
+        let events = [
+            event("a", at(9, 0), at(10, 0)),
+            event("b", at(10, 10), at(11, 0)),
+        ];
+        tracker.tick(&events, at(9, 50), &config());
+        tracker.tick(&events, at(10, 0), &config());
+        assert!(tracker.current().is_some());
+
+        // Still in overtime during the gap, until b's last warning.
+        assert!(!tracker.tick(&events, at(10, 7), &config()).changed);
+        let tick = tracker.tick(&events, at(10, 8), &config());
+        assert!(tick.changed);
+        assert_eq!(tick.finished[0].outcome, OvertimeEnd::Yielded);
+        assert_eq!(tick.finished[0].meeting.event_id, "a");
+    }
+
+    #[test]
+    fn test_back_to_back_meeting_yields_immediately() {
+        let mut tracker = OvertimeTracker::default();
+        let events = [
+            event("a", at(9, 0), at(10, 0)),
+            event("b", at(10, 0), at(11, 0)),
+        ];
+        tracker.tick(&events, at(9, 59), &config());
+
+        let tick = tracker.tick(&events, at(10, 0), &config());
+        assert!(!tick.changed);
+        assert_eq!(tick.finished.len(), 1);
+        assert_eq!(tick.finished[0].outcome, OvertimeEnd::Yielded);
+        assert!(tracker.current().is_none());
+
+        // b is followed in turn.
+        tracker.tick(&events, at(11, 0), &config());
+        assert_eq!(tracker.current().unwrap().event_id, "b");
+    }
+
+    #[test]
+    fn test_extended_meeting_leaves_overtime() {
+        let mut tracker = OvertimeTracker::default();
+        tracker.tick(&[event("a", at(9, 0), at(10, 0))], at(9, 59), &config());
+        tracker.tick(&[event("a", at(9, 0), at(10, 0))], at(10, 1), &config());
+        assert!(tracker.current().is_some());
+
+        let extended = [event("a", at(9, 0), at(10, 30))];
+        let tick = tracker.tick(&extended, at(10, 2), &config());
+        assert!(tick.changed);
+        assert!(tick.finished.is_empty());
+        assert!(tick.started.is_none());
+        assert!(tracker.current().is_none());
+        tracker.tick(&extended, at(10, 30), &config());
+        assert_eq!(tracker.current().unwrap().scheduled_end, at(10, 30));
+    }
+
+    #[test]
+    fn test_cancelled_and_ignored_meetings_are_not_tracked() {
+        let mut tracker = OvertimeTracker::default();
+        tracker.tick(&[event("a", at(9, 0), at(10, 0))], at(9, 30), &config());
+        // Cancelled mid-meeting: it disappears before its end.
+        assert!(!tracker.tick(&[], at(9, 40), &config()).changed);
+        assert!(!tracker.tick(&[], at(10, 5), &config()).changed);
+
+        let mut ignored = event("b", at(11, 0), at(12, 0));
+        ignored.calendar_id = Some("holidays".to_string());
+        let mut config = config();
+        config.ignored_calendar_ids = vec!["holidays".to_string()];
+        tracker.tick(std::slice::from_ref(&ignored), at(11, 30), &config);
+        assert!(!tracker.tick(&[ignored], at(12, 0), &config).changed);
+    }
+
+    #[tokio::test]
+    async fn test_report_groups_confirmed_overruns_by_series() {
+        let pool = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory pool");
+        crate::migrations::migrate(&pool).await.unwrap();
+
+        let weekly = |day: u32| OvertimeMeeting {
+            event_id: format!("weekly-{day}"),
+            series_id: Some("weekly".to_string()),
+            title: "Weekly sync".to_string(),
+            scheduled_end: Utc.with_ymd_and_hms(2026, 3, day, 10, 0, 0).unwrap(),
+        };
+        let records = [
+            (weekly(2), 12, OvertimeEnd::Left),
+            (weekly(3), 0, OvertimeEnd::Left),
+            (weekly(4), 0, OvertimeEnd::Expired),
+            (weekly(5), 8, OvertimeEnd::Left),
+        ];
+        for (meeting, minutes, outcome) in records {
+            let ended_at = meeting.scheduled_end + chrono::Duration::minutes(minutes);
+            let record = OverrunRecord {
+                meeting,
+                ended_at,
+                outcome,
+            };
+            insert_overrun(&pool, &record).await.unwrap();
+        }
+        // One-off meetings are not part of any series.
+        let mut one_off = weekly(5);
+        one_off.series_id = None;
+        let record = OverrunRecord {
+            ended_at: one_off.scheduled_end + chrono::Duration::minutes(30),
+            meeting: one_off,
+            outcome: OvertimeEnd::Left,
+        };
+        insert_overrun(&pool, &record).await.unwrap();
+
+        let report = overrun_report_inner(&pool, at(0, 0)).await.unwrap();
+        assert_eq!(report.len(), 1);
+        let series = &report[0];
+        assert_eq!(series.meetings, 4);
+        assert_eq!(series.confirmed, 3);
+        assert_eq!(series.overran, 2);
+        assert_eq!(series.total_overrun_minutes, 20.0);
+        assert_eq!(series.average_overrun_minutes, Some(20.0 / 3.0));
+        assert_eq!(
+            series.last_meeting_at,
+            Utc.with_ymd_and_hms(2026, 3, 5, 10, 0, 0).unwrap()
+        );
+    }
+}
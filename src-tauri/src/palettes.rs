@@ -0,0 +1,1001 @@
+//! Border color palettes: built-in presets plus user-defined palettes
+//! stored in SQLite.
+//!
+//! A palette gives every border phase a hex color, an opacity and a pulse
+//! speed. The `color_palette` setting holds the active palette's ID; the
+//! overlay loads its entries with `get_active_palette` and again on each
+//! `palette-changed` event, so `BorderState` colors come from it.
+//!
+//! Saving rejects malformed palettes (a missing phase, a bad hex color,
+//! opacity outside 0-1). Accessibility is checked separately and reported
+//! as [`PaletteIssue`]s rather than refused, since no palette suits every
+//! kind of vision: consecutive phases should be told apart by color or
+//! pulse, and alert phases should stand out on both light and dark
+//! desktops, with normal vision and under simulated protanopia,
+//! deuteranopia and tritanopia.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+use tauri::{command, AppHandle, Emitter};
+
+use crate::settings;
+use crate::tray::icon;
+
+pub const ACTIVE_KEY: &str = "color_palette";
+pub const DEFAULT_PALETTE: &str = "ambient";
+
+/// IDs of user-defined palettes start with this, so they never clash with
+/// built-in presets.
+const CUSTOM_PREFIX: &str = "custom-";
+
+/// Current version of the palette export format.
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// Every phase a palette must define, as named by the color engine.
+pub const PHASES: [&str; 14] = [
+    "free-deep",
+    "warning-far",
+    "warning-mid",
+    "warning-near",
+    "warning-imminent",
+    "in-session-early",
+    "in-session-mid",
+    "in-session-late",
+    "in-session-end",
+    "overtime",
+    "gap-short",
+    "gap-long",
+    "no-events",
+    "off-hours",
+];
+
+/// Phases shown one after another, in order. Each should be told apart
+/// from the next.
+const SEQUENCES: [&[&str]; 3] = [
+    &[
+        "free-deep",
+        "warning-far",
+        "warning-mid",
+        "warning-near",
+        "warning-imminent",
+    ],
+    &[
+        "in-session-early",
+        "in-session-mid",
+        "in-session-late",
+        "in-session-end",
+        "overtime",
+    ],
+    &["gap-long", "gap-short"],
+];
+
+/// Phases that ask for attention and so must be visible on any desktop.
+const ALERT_PHASES: [&str; 5] = [
+    "warning-near",
+    "warning-imminent",
+    "in-session-end",
+    "overtime",
+    "gap-short",
+];
+
+/// Smallest CIE76 color difference for two phases to count as distinct.
+const MIN_DELTA_E: f64 = 12.0;
+
+/// Pulse speeds (ms) this far apart tell phases apart on their own.
+const MIN_PULSE_DIFFERENCE: u32 = 500;
+
+/// Smallest contrast ratio of an alert phase against white and black
+/// desktops, after blending at its opacity.
+const MIN_CONTRAST: f64 = 1.4;
+
+/// How one phase looks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteEntry {
+    pub hex: String,
+    pub opacity: f64,
+    /// Pulse period in milliseconds; 0 for none.
+    pub pulse_speed: u32,
+}
+
+/// Phase name → entry.
+pub type PaletteEntries = BTreeMap<String, PaletteEntry>;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Palette {
+    pub id: String,
+    pub name: String,
+    pub builtin: bool,
+    pub entries: PaletteEntries,
+}
+
+/// A palette as exported to, and imported from, a JSON file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteExport {
+    pub format_version: u32,
+    pub name: String,
+    pub entries: PaletteEntries,
+}
+
+/// A saved palette with what the accessibility check found.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedPalette {
+    pub palette: Palette,
+    pub issues: Vec<PaletteIssue>,
+}
+
+/// Kind of color vision a palette is checked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Vision {
+    Normal,
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl Vision {
+    pub const ALL: [Vision; 4] = [
+        Vision::Normal,
+        Vision::Protanopia,
+        Vision::Deuteranopia,
+        Vision::Tritanopia,
+    ];
+
+    /// Machado et al. (2009) simulation matrix at full severity, applied to
+    /// linear RGB.
+    fn matrix(self) -> [[f64; 3]; 3] {
+        match self {
+            Vision::Normal => [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            Vision::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            Vision::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            Vision::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueKind {
+    /// Two consecutive phases look the same.
+    Indistinct,
+    /// An alert phase barely shows on a light or dark desktop.
+    LowContrast,
+}
+
+/// An accessibility problem found in a palette.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteIssue {
+    pub vision: Vision,
+    pub kind: IssueKind,
+    /// The phases involved: two for `Indistinct`, one for `LowContrast`.
+    pub phases: Vec<String>,
+    pub message: String,
+}
+
+// --- Color math ---
+
+/// sRGB channels in 0-1.
+type Rgb = [f64; 3];
+
+/// Parse `#RRGGBB` into 0-1 channels.
+fn parse_rgb(hex: &str) -> Option<Rgb> {
+    icon::parse_hex(hex).map(|rgb| rgb.map(|c| f64::from(c) / 255.0))
+}
+
+fn to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn from_linear(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// How `color` looks with `vision`.
+fn simulate(color: Rgb, vision: Vision) -> Rgb {
+    let linear = color.map(to_linear);
+    let m = vision.matrix();
+    let mut out = [0.0; 3];
+    for (i, row) in m.iter().enumerate() {
+        let value: f64 = row.iter().zip(linear).map(|(k, c)| k * c).sum();
+        out[i] = from_linear(value.clamp(0.0, 1.0));
+    }
+    out
+}
+
+/// WCAG relative luminance.
+fn luminance(color: Rgb) -> f64 {
+    let [r, g, b] = color.map(to_linear);
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+fn contrast_ratio(a: Rgb, b: Rgb) -> f64 {
+    let (la, lb) = (luminance(a), luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+/// `color` at `opacity` over `background`, blended the way the overlay
+/// window is composited.
+fn blend(color: Rgb, opacity: f64, background: Rgb) -> Rgb {
+    [0, 1, 2].map(|i| color[i] * opacity + background[i] * (1.0 - opacity))
+}
+
+/// CIE L*a*b* (D65).
+fn lab(color: Rgb) -> [f64; 3] {
+    let [r, g, b] = color.map(to_linear);
+    let x = (0.4124564 * r + 0.3575761 * g + 0.1804375 * b) / 0.95047;
+    let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+    let z = (0.0193339 * r + 0.1191920 * g + 0.9503041 * b) / 1.08883;
+    let f = |t: f64| {
+        if t > 0.008856 {
+            t.cbrt()
+        } else {
+            7.787 * t + 16.0 / 116.0
+        }
+    };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+/// CIE76 color difference.
+fn delta_e(a: Rgb, b: Rgb) -> f64 {
+    let (a, b) = (lab(a), lab(b));
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+// --- Validation ---
+
+/// Reject palettes the overlay can't draw: every phase must be present
+/// with a `#RRGGBB` color, an opacity in 0-1 and a pulse of 0 or 0.5-10 s.
+pub fn validate(entries: &PaletteEntries) -> Result<(), String> {
+    if let Some(phase) = entries.keys().find(|p| !PHASES.contains(&p.as_str())) {
+        return Err(format!("unknown phase '{phase}'"));
+    }
+    for phase in PHASES {
+        let entry = entries
+            .get(phase)
+            .ok_or_else(|| format!("missing phase '{phase}'"))?;
+        if parse_rgb(&entry.hex).is_none() {
+            return Err(format!(
+                "{phase}: invalid color '{}' (expected #RRGGBB)",
+                entry.hex
+            ));
+        }
+        if !(0.0..=1.0).contains(&entry.opacity) {
+            return Err(format!(
+                "{phase}: opacity {} must be between 0 and 1",
+                entry.opacity
+            ));
+        }
+        if entry.pulse_speed != 0 && !(500..=10_000).contains(&entry.pulse_speed) {
+            return Err(format!(
+                "{phase}: pulse speed {} must be 0 or 500-10000 ms",
+                entry.pulse_speed
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Accessibility problems in a valid palette, for each kind of vision.
+pub fn check_accessibility(entries: &PaletteEntries) -> Vec<PaletteIssue> {
+    let color = |phase: &str, vision| {
+        let entry = &entries[phase];
+        (
+            simulate(parse_rgb(&entry.hex).unwrap_or_default(), vision),
+            entry,
+        )
+    };
+    let mut issues = Vec::new();
+    for vision in Vision::ALL {
+        for sequence in SEQUENCES {
+            for pair in sequence.windows(2) {
+                let (a, entry_a) = color(pair[0], vision);
+                let (b, entry_b) = color(pair[1], vision);
+                let pulses_differ =
+                    entry_a.pulse_speed.abs_diff(entry_b.pulse_speed) >= MIN_PULSE_DIFFERENCE;
+                if !pulses_differ && delta_e(a, b) < MIN_DELTA_E {
+                    issues.push(PaletteIssue {
+                        vision,
+                        kind: IssueKind::Indistinct,
+                        phases: vec![pair[0].to_string(), pair[1].to_string()],
+                        message: format!(
+                            "{} and {} look alike{}",
+                            pair[0],
+                            pair[1],
+                            vision_suffix(vision)
+                        ),
+                    });
+                }
+            }
+        }
+        for phase in ALERT_PHASES {
+            let (c, entry) = color(phase, vision);
+            let weakest = [[1.0; 3], [0.0; 3]]
+                .into_iter()
+                .map(|bg| contrast_ratio(blend(c, entry.opacity, bg), bg))
+                .fold(f64::INFINITY, f64::min);
+            if weakest < MIN_CONTRAST {
+                issues.push(PaletteIssue {
+                    vision,
+                    kind: IssueKind::LowContrast,
+                    phases: vec![phase.to_string()],
+                    message: format!(
+                        "{phase} is hard to see on some desktops{} (contrast {weakest:.2}:1)",
+                        vision_suffix(vision)
+                    ),
+                });
+            }
+        }
+    }
+    issues
+}
+
+fn vision_suffix(vision: Vision) -> &'static str {
+    match vision {
+        Vision::Normal => "",
+        Vision::Protanopia => " with protanopia",
+        Vision::Deuteranopia => " with deuteranopia",
+        Vision::Tritanopia => " with tritanopia",
+    }
+}
+
+// --- Built-in presets ---
+
+/// (phase, hex, opacity, pulse speed) for each phase, in [`PHASES`] order.
+type PresetEntries = [(&'static str, &'static str, f64, u32); 14];
+
+struct Preset {
+    id: &'static str,
+    name: &'static str,
+    entries: PresetEntries,
+}
+
+/// Green → yellow → orange → purple. Matches docs/color-palette.md.
+const AMBIENT: PresetEntries = [
+    ("free-deep", "#4A9B6E", 0.25, 0),
+    ("warning-far", "#5BAE7A", 0.4, 4000),
+    ("warning-mid", "#A3B84C", 0.55, 3000),
+    ("warning-near", "#D4A843", 0.7, 2000),
+    ("warning-imminent", "#D4864A", 0.8, 1500),
+    ("in-session-early", "#4A9B6E", 0.35, 0),
+    ("in-session-mid", "#B8AD42", 0.5, 0),
+    ("in-session-late", "#D4864A", 0.65, 0),
+    ("in-session-end", "#8B6AAE", 0.75, 2500),
+    ("overtime", "#7B5A9E", 0.8, 2000),
+    ("gap-short", "#D4864A", 0.6, 2500),
+    ("gap-long", "#5BAE7A", 0.3, 0),
+    ("no-events", "#8A9BA8", 0.15, 0),
+    ("off-hours", "#6E7C8C", 0.08, 0),
+];
+
+/// Blue → sand → orange, for protanopia and deuteranopia.
+const OCEAN: PresetEntries = [
+    ("free-deep", "#4A7FB5", 0.25, 0),
+    ("warning-far", "#5B92C4", 0.4, 4000),
+    ("warning-mid", "#8CADD4", 0.55, 3000),
+    ("warning-near", "#D4C078", 0.7, 2000),
+    ("warning-imminent", "#D49458", 0.8, 1500),
+    ("in-session-early", "#4A7FB5", 0.35, 0),
+    ("in-session-mid", "#8CADD4", 0.5, 0),
+    ("in-session-late", "#D49458", 0.65, 0),
+    ("in-session-end", "#C47A5A", 0.75, 2500),
+    ("overtime", "#B5684A", 0.8, 2000),
+    ("gap-short", "#D49458", 0.6, 2500),
+    ("gap-long", "#5B92C4", 0.3, 0),
+    ("no-events", "#8A9BA8", 0.15, 0),
+    ("off-hours", "#6E7C8C", 0.08, 0),
+];
+
+/// Okabe-Ito colors with steps apart in lightness, for all three kinds of
+/// color blindness, and stronger opacities.
+const HIGH_CONTRAST: PresetEntries = [
+    ("free-deep", "#0072B2", 0.35, 0),
+    ("warning-far", "#56B4E9", 0.5, 4000),
+    ("warning-mid", "#F0E442", 0.65, 3000),
+    ("warning-near", "#E69F00", 0.8, 2000),
+    ("warning-imminent", "#D55E00", 0.9, 1500),
+    ("in-session-early", "#0072B2", 0.45, 0),
+    ("in-session-mid", "#56B4E9", 0.6, 0),
+    ("in-session-late", "#E69F00", 0.75, 0),
+    ("in-session-end", "#D55E00", 0.85, 2500),
+    ("overtime", "#CC79A7", 0.9, 2000),
+    ("gap-short", "#E69F00", 0.7, 2500),
+    ("gap-long", "#56B4E9", 0.4, 0),
+    ("no-events", "#999999", 0.2, 0),
+    ("off-hours", "#777777", 0.1, 0),
+];
+
+/// Teal → pink → crimson, along the red-cyan axis tritanopia keeps.
+const TRITAN: PresetEntries = [
+    ("free-deep", "#2A9D8F", 0.25, 0),
+    ("warning-far", "#48B5A8", 0.4, 4000),
+    ("warning-mid", "#E8A0A0", 0.55, 3000),
+    ("warning-near", "#E06666", 0.7, 2000),
+    ("warning-imminent", "#C0392B", 0.8, 1500),
+    ("in-session-early", "#2A9D8F", 0.35, 0),
+    ("in-session-mid", "#8FD3CB", 0.5, 0),
+    ("in-session-late", "#E06666", 0.65, 0),
+    ("in-session-end", "#A61E4D", 0.75, 2500),
+    ("overtime", "#7A1538", 0.8, 2000),
+    ("gap-short", "#E06666", 0.6, 2500),
+    ("gap-long", "#48B5A8", 0.3, 0),
+    ("no-events", "#8A9BA8", 0.15, 0),
+    ("off-hours", "#6E7C8C", 0.08, 0),
+];
+
+const PRESETS: [Preset; 4] = [
+    Preset {
+        id: "ambient",
+        name: "Ambient",
+        entries: AMBIENT,
+    },
+    Preset {
+        id: "ocean",
+        name: "Ocean",
+        entries: OCEAN,
+    },
+    Preset {
+        id: "high-contrast",
+        name: "High contrast",
+        entries: HIGH_CONTRAST,
+    },
+    Preset {
+        id: "tritan",
+        name: "Tritan",
+        entries: TRITAN,
+    },
+];
+
+impl Preset {
+    fn palette(&self) -> Palette {
+        Palette {
+            id: self.id.to_string(),
+            name: self.name.to_string(),
+            builtin: true,
+            entries: self
+                .entries
+                .iter()
+                .map(|&(phase, hex, opacity, pulse_speed)| {
+                    let entry = PaletteEntry {
+                        hex: hex.to_string(),
+                        opacity,
+                        pulse_speed,
+                    };
+                    (phase.to_string(), entry)
+                })
+                .collect(),
+        }
+    }
+}
+
+pub fn builtin_palettes() -> Vec<Palette> {
+    PRESETS.iter().map(Preset::palette).collect()
+}
+
+fn builtin(id: &str) -> Option<Palette> {
+    PRESETS.iter().find(|p| p.id == id).map(Preset::palette)
+}
+
+/// Whether `id` could name a palette: a built-in one or `custom-` plus
+/// lowercase letters, digits and dashes. Used to validate `color_palette`.
+pub fn is_valid_id(id: &str) -> bool {
+    builtin(id).is_some()
+        || id.strip_prefix(CUSTOM_PREFIX).is_some_and(|slug| {
+            !slug.is_empty()
+                && slug.len() <= 48
+                && slug
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        })
+}
+
+/// `custom-` plus the name in lowercase with runs of other characters as
+/// single dashes ("My Theme!" → "custom-my-theme").
+fn custom_id(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug.trim_end_matches('-').chars().take(40).collect();
+    let slug = slug.trim_end_matches('-');
+    format!(
+        "{CUSTOM_PREFIX}{}",
+        if slug.is_empty() { "palette" } else { slug }
+    )
+}
+
+fn validate_name(name: &str) -> Result<&str, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Palette name must not be empty".to_string());
+    }
+    if name.chars().count() > 64 {
+        return Err("Palette name must be at most 64 characters".to_string());
+    }
+    Ok(name)
+}
+
+// --- Store ---
+
+async fn load_custom(pool: &SqlitePool, id: &str) -> Result<Option<Palette>, String> {
+    let row = sqlx::query("SELECT id, name, entries FROM palettes WHERE id = ?1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    row.map(|row| palette_from_row(&row)).transpose()
+}
+
+fn palette_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<Palette, String> {
+    let entries: PaletteEntries =
+        serde_json::from_str(&row.get::<String, _>("entries")).map_err(|e| e.to_string())?;
+    Ok(Palette {
+        id: row.get("id"),
+        name: row.get("name"),
+        builtin: false,
+        entries,
+    })
+}
+
+/// Built-in presets, then custom palettes by name.
+async fn list_palettes_inner(pool: &SqlitePool) -> Result<Vec<Palette>, String> {
+    let rows = sqlx::query("SELECT id, name, entries FROM palettes ORDER BY name COLLATE NOCASE")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut palettes = builtin_palettes();
+    for row in &rows {
+        match palette_from_row(row) {
+            Ok(palette) => palettes.push(palette),
+            Err(e) => eprintln!("[palettes] Skipping unreadable palette: {e}"),
+        }
+    }
+    Ok(palettes)
+}
+
+/// The palette with `id`, falling back to Ambient if it was deleted or
+/// no longer validates.
+async fn resolve(pool: &SqlitePool, id: &str) -> Palette {
+    let palette = match builtin(id) {
+        Some(palette) => Some(palette),
+        None => load_custom(pool, id).await.unwrap_or_else(|e| {
+            eprintln!("[palettes] Failed to load palette '{id}': {e}");
+            None
+        }),
+    };
+    palette
+        .filter(|p| validate(&p.entries).is_ok())
+        .unwrap_or_else(|| builtin(DEFAULT_PALETTE).expect("default palette is built in"))
+}
+
+/// Create a custom palette, or replace one when `id` is given.
+async fn save_palette_inner(
+    pool: &SqlitePool,
+    id: Option<&str>,
+    name: &str,
+    entries: PaletteEntries,
+) -> Result<SavedPalette, String> {
+    let name = validate_name(name)?;
+    validate(&entries)?;
+    let id = match id {
+        Some(id) if builtin(id).is_some() => {
+            return Err(format!("'{id}' is a built-in palette and can't be changed"));
+        }
+        Some(id) if load_custom(pool, id).await?.is_some() => id.to_string(),
+        Some(id) => return Err(format!("No palette with ID '{id}'")),
+        None => unused_id(pool, &custom_id(name)).await?,
+    };
+
+    let json = serde_json::to_string(&entries).map_err(|e| e.to_string())?;
+    sqlx::query(
+        "INSERT INTO palettes (id, name, entries, updated_at) VALUES (?1, ?2, ?3, datetime('now'))
+         ON CONFLICT(id) DO UPDATE SET name = excluded.name, entries = excluded.entries,
+                                       updated_at = datetime('now')",
+    )
+    .bind(&id)
+    .bind(name)
+    .bind(json)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let issues = check_accessibility(&entries);
+    Ok(SavedPalette {
+        palette: Palette {
+            id,
+            name: name.to_string(),
+            builtin: false,
+            entries,
+        },
+        issues,
+    })
+}
+
+/// `base`, or `base-2`, `base-3`, ... if taken.
+async fn unused_id(pool: &SqlitePool, base: &str) -> Result<String, String> {
+    let mut id = base.to_string();
+    let mut n = 1;
+    while load_custom(pool, &id).await?.is_some() {
+        n += 1;
+        id = format!("{base}-{n}");
+    }
+    Ok(id)
+}
+
+async fn delete_palette_inner(pool: &SqlitePool, id: &str) -> Result<(), String> {
+    if builtin(id).is_some() {
+        return Err(format!("'{id}' is a built-in palette and can't be deleted"));
+    }
+    sqlx::query("DELETE FROM palettes WHERE id = ?1")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn build_export(palette: &Palette) -> PaletteExport {
+    PaletteExport {
+        format_version: EXPORT_FORMAT_VERSION,
+        name: palette.name.clone(),
+        entries: palette.entries.clone(),
+    }
+}
+
+/// Parse and validate an exported palette.
+pub fn parse_export(contents: &str) -> Result<PaletteExport, String> {
+    let export: PaletteExport =
+        serde_json::from_str(contents).map_err(|e| format!("Not a Morph palette file: {e}"))?;
+    if export.format_version == 0 || export.format_version > EXPORT_FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported palette file version {} (this version of Morph reads up to {})",
+            export.format_version, EXPORT_FORMAT_VERSION
+        ));
+    }
+    validate(&export.entries)?;
+    Ok(export)
+}
+
+// --- Runtime glue ---
+
+/// Send the active palette to the overlay.
+pub async fn emit_active(app: &AppHandle) {
+    match settings::app_pool(app).await {
+        Ok(pool) => {
+            let settings = settings::current_settings(app).await;
+            let palette = resolve(&pool, &settings.color_palette).await;
+            let _ = app.emit("palette-changed", &palette);
+        }
+        Err(e) => eprintln!("[palettes] {e}"),
+    }
+}
+
+// --- Tauri commands ---
+
+#[command]
+pub async fn list_palettes(app: AppHandle) -> Result<Vec<Palette>, String> {
+    let pool = settings::app_pool(&app).await?;
+    list_palettes_inner(&pool).await
+}
+
+/// The palette named by `color_palette`, or Ambient if it is gone.
+#[command]
+pub async fn get_active_palette(app: AppHandle) -> Result<Palette, String> {
+    let pool = settings::app_pool(&app).await?;
+    let settings = settings::current_settings(&app).await;
+    Ok(resolve(&pool, &settings.color_palette).await)
+}
+
+/// Validate entries and report accessibility issues, without saving.
+#[command]
+pub fn check_palette(entries: PaletteEntries) -> Result<Vec<PaletteIssue>, String> {
+    validate(&entries)?;
+    Ok(check_accessibility(&entries))
+}
+
+/// Save a custom palette (a new one unless `id` is given).
+#[command]
+pub async fn save_palette(
+    app: AppHandle,
+    id: Option<String>,
+    name: String,
+    entries: PaletteEntries,
+) -> Result<SavedPalette, String> {
+    let pool = settings::app_pool(&app).await?;
+    let saved = save_palette_inner(&pool, id.as_deref(), &name, entries).await?;
+    if settings::current_settings(&app).await.color_palette == saved.palette.id {
+        emit_active(&app).await;
+    }
+    Ok(saved)
+}
+
+/// Delete a custom palette, switching back to Ambient if it was active.
+#[command]
+pub async fn delete_palette(app: AppHandle, id: String) -> Result<(), String> {
+    let pool = settings::app_pool(&app).await?;
+    delete_palette_inner(&pool, &id).await?;
+    if settings::current_settings(&app).await.color_palette == id {
+        settings::set_setting_inner(&pool, ACTIVE_KEY, DEFAULT_PALETTE).await?;
+        settings::emit_setting_changed(&app, ACTIVE_KEY, DEFAULT_PALETTE);
+    }
+    Ok(())
+}
+
+/// Export a palette (built-in or custom) as a JSON document.
+#[command]
+pub async fn export_palette(app: AppHandle, id: String) -> Result<String, String> {
+    let pool = settings::app_pool(&app).await?;
+    let palette = match builtin(&id) {
+        Some(palette) => palette,
+        None => load_custom(&pool, &id)
+            .await?
+            .ok_or_else(|| format!("No palette with ID '{id}'"))?,
+    };
+    serde_json::to_string_pretty(&build_export(&palette)).map_err(|e| e.to_string())
+}
+
+/// Import an exported palette as a new custom palette.
+#[command]
+pub async fn import_palette(app: AppHandle, contents: String) -> Result<SavedPalette, String> {
+    let pool = settings::app_pool(&app).await?;
+    let export = parse_export(&contents)?;
+    save_palette_inner(&pool, None, &export.name, export.entries).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn test_pool() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory pool");
+        crate::migrations::migrate(&pool).await.unwrap();
+        pool
+    }
+
+    fn entries(id: &str) -> PaletteEntries {
+        builtin(id).unwrap().entries
+    }
+
+    fn issues_for(entries: &PaletteEntries, vision: Vision) -> Vec<String> {
+        check_accessibility(entries)
+            .into_iter()
+            .filter(|i| i.vision == vision)
+            .map(|i| i.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_color_math() {
+        let white = parse_rgb("#FFFFFF").unwrap();
+        let black = parse_rgb("#000000").unwrap();
+        assert!((contrast_ratio(white, black) - 21.0).abs() < 0.01);
+        assert!((delta_e(white, black) - 100.0).abs() < 0.01);
+        assert_eq!(
+            simulate(white, Vision::Deuteranopia).map(|c| c.round()),
+            white
+        );
+        assert_eq!(parse_rgb("#fF8000").unwrap()[0], 1.0);
+        for bad in ["FF8000", "#FF800", "#GG8000", "#FF80000"] {
+            assert!(parse_rgb(bad).is_none(), "{bad}");
+        }
+        // Red and green, far apart normally, converge for red-green
+        // color blindness but not for tritanopia.
+        let (red, green) = (parse_rgb("#D4864A").unwrap(), parse_rgb("#5BAE7A").unwrap());
+        let normal = delta_e(red, green);
+        let deutan = delta_e(
+            simulate(red, Vision::Deuteranopia),
+            simulate(green, Vision::Deuteranopia),
+        );
+        let tritan = delta_e(
+            simulate(red, Vision::Tritanopia),
+            simulate(green, Vision::Tritanopia),
+        );
+        assert!(deutan < normal / 2.0, "{deutan} vs {normal}");
+        assert!(tritan > deutan);
+    }
+
+    #[test]
+    fn test_builtin_presets_are_valid() {
+        for palette in builtin_palettes() {
+            validate(&palette.entries).unwrap();
+            assert!(is_valid_id(&palette.id));
+            assert!(
+                issues_for(&palette.entries, Vision::Normal).is_empty(),
+                "{}: {:?}",
+                palette.id,
+                check_accessibility(&palette.entries)
+            );
+        }
+    }
+
+    #[test]
+    fn test_accessible_presets_pass_their_checks() {
+        let cases = [
+            ("ocean", &[Vision::Protanopia, Vision::Deuteranopia][..]),
+            (
+                "high-contrast",
+                &[Vision::Protanopia, Vision::Deuteranopia, Vision::Tritanopia],
+            ),
+            ("tritan", &[Vision::Tritanopia]),
+        ];
+        for (id, visions) in cases {
+            for &vision in visions {
+                assert_eq!(
+                    issues_for(&entries(id), vision),
+                    Vec::<String>::new(),
+                    "{id}"
+                );
+            }
+        }
+        // Ambient's green → orange steps are the classic red-green trap.
+        assert!(!issues_for(&entries("ambient"), Vision::Deuteranopia).is_empty());
+    }
+
+    #[test]
+    fn test_check_flags_indistinct_and_faint_phases() {
+        let mut palette = entries("ambient");
+        palette.get_mut("in-session-mid").unwrap().hex = "#4C9D70".to_string();
+        palette.get_mut("overtime").unwrap().opacity = 0.05;
+
+        let issues = check_accessibility(&palette);
+        let normal: Vec<_> = issues
+            .iter()
+            .filter(|i| i.vision == Vision::Normal)
+            .collect();
+        assert_eq!(normal.len(), 2, "{normal:?}");
+        assert_eq!(normal[0].kind, IssueKind::Indistinct);
+        assert_eq!(normal[0].phases, ["in-session-early", "in-session-mid"]);
+        assert_eq!(normal[1].kind, IssueKind::LowContrast);
+        assert_eq!(normal[1].phases, ["overtime"]);
+
+        // A different pulse is enough to tell phases apart.
+        palette.get_mut("in-session-mid").unwrap().pulse_speed = 3000;
+        assert!(!check_accessibility(&palette)
+            .iter()
+            .any(|i| i.kind == IssueKind::Indistinct && i.vision == Vision::Normal));
+    }
+
+    #[test]
+    fn test_validate_rejects_malformed_palettes() {
+        let edit = |f: &dyn Fn(&mut PaletteEntries)| {
+            let mut palette = entries("ambient");
+            f(&mut palette);
+            validate(&palette)
+        };
+        assert!(edit(&|_| {}).is_ok());
+        assert!(edit(&|p| {
+            p.remove("off-hours");
+        })
+        .unwrap_err()
+        .contains("missing phase 'off-hours'"));
+        assert!(edit(&|p| {
+            let entry = p["overtime"].clone();
+            p.insert("lunch".to_string(), entry);
+        })
+        .is_err());
+        assert!(edit(&|p| p.get_mut("overtime").unwrap().hex = "purple".into()).is_err());
+        assert!(edit(&|p| p.get_mut("overtime").unwrap().opacity = 1.5).is_err());
+        assert!(edit(&|p| p.get_mut("overtime").unwrap().opacity = f64::NAN).is_err());
+        assert!(edit(&|p| p.get_mut("overtime").unwrap().pulse_speed = 100).is_err());
+    }
+
+    #[test]
+    fn test_ids() {
+        assert_eq!(custom_id("My Theme!"), "custom-my-theme");
+        assert_eq!(custom_id("  Über -- dark  "), "custom-ber-dark");
+        assert_eq!(custom_id("!!!"), "custom-palette");
+        assert!(is_valid_id("ocean"));
+        assert!(is_valid_id("custom-my-theme"));
+        for bad in ["neon", "custom-", "custom-My", "custom-a b", ""] {
+            assert!(!is_valid_id(bad), "{bad}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_store_round_trip() {
+        let pool = test_pool().await;
+        let saved = save_palette_inner(&pool, None, "Night", entries("tritan"))
+            .await
+            .unwrap();
+        assert_eq!(saved.palette.id, "custom-night");
+        assert!(saved.issues.iter().all(|i| i.vision != Vision::Tritanopia));
+
+        // Same name again: a new ID. Editing keeps the ID.
+        let second = save_palette_inner(&pool, None, "Night", entries("ocean"))
+            .await
+            .unwrap();
+        assert_eq!(second.palette.id, "custom-night-2");
+        save_palette_inner(&pool, Some("custom-night"), "Night shift", entries("ocean"))
+            .await
+            .unwrap();
+
+        let list = list_palettes_inner(&pool).await.unwrap();
+        let names: Vec<_> = list.iter().map(|p| (p.id.as_str(), p.builtin)).collect();
+        assert_eq!(
+            names,
+            [
+                ("ambient", true),
+                ("ocean", true),
+                ("high-contrast", true),
+                ("tritan", true),
+                ("custom-night-2", false),
+                ("custom-night", false),
+            ]
+        );
+        assert_eq!(
+            resolve(&pool, "custom-night").await.entries,
+            entries("ocean")
+        );
+
+        assert!(
+            save_palette_inner(&pool, Some("ocean"), "Mine", entries("ocean"))
+                .await
+                .is_err()
+        );
+        assert!(
+            save_palette_inner(&pool, Some("custom-gone"), "Gone", entries("ocean"))
+                .await
+                .is_err()
+        );
+        assert!(delete_palette_inner(&pool, "ambient").await.is_err());
+
+        delete_palette_inner(&pool, "custom-night").await.unwrap();
+        assert_eq!(resolve(&pool, "custom-night").await.id, "ambient");
+    }
+
+    #[tokio::test]
+    async fn test_export_import() {
+        let pool = test_pool().await;
+        let export = build_export(&builtin("high-contrast").unwrap());
+        let json = serde_json::to_string(&export).unwrap();
+        assert!(json.contains("\"formatVersion\":1"));
+        assert!(json.contains("\"pulseSpeed\""));
+
+        let parsed = parse_export(&json).unwrap();
+        let saved = save_palette_inner(&pool, None, &parsed.name, parsed.entries)
+            .await
+            .unwrap();
+        assert_eq!(saved.palette.id, "custom-high-contrast");
+        assert_eq!(saved.palette.entries, entries("high-contrast"));
+
+        let future = json.replace("\"formatVersion\":1", "\"formatVersion\":99");
+        assert!(parse_export(&future).unwrap_err().contains("Unsupported"));
+        let broken = json.replace("#0072B2", "blue");
+        assert!(parse_export(&broken).is_err());
+        assert!(parse_export("{}").is_err());
+    }
+}
@@ -0,0 +1,632 @@
+//! Border pauses.
+//!
+//! A pause runs for a number of minutes, until the next event starts, until
+//! the current event ends, until tomorrow, or for a quiet period from a
+//! schedule rule. The active pause is kept in SQLite so it survives a
+//! restart, and a background task resumes the border when it runs out.
+//! Saves go through one writer task, so the stored pause always ends up as
+//! the latest one.
+//!
+//! Pausing emits `border-paused` with the end time and a label such as
+//! "until Standup ends"; resuming emits `border-resumed` with what resumed
+//! it ("expired" when the pause ran out).
+
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+use tauri::{command, AppHandle, Emitter, Listener, Manager};
+use tokio::sync::watch;
+
+use crate::calendar::types::CalendarEvent;
+use crate::history::{self, parse_timestamp, timestamp};
+use crate::settings;
+use crate::webhooks::{self, WebhookEvent};
+
+/// What a pause lasts until.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PauseKind {
+    Minutes,
+    NextEvent,
+    EventEnd,
+    Tomorrow,
+    Quiet,
+}
+
+impl PauseKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            PauseKind::Minutes => "minutes",
+            PauseKind::NextEvent => "next-event",
+            PauseKind::EventEnd => "event-end",
+            PauseKind::Tomorrow => "tomorrow",
+            PauseKind::Quiet => "quiet",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        [
+            PauseKind::Minutes,
+            PauseKind::NextEvent,
+            PauseKind::EventEnd,
+            PauseKind::Tomorrow,
+            PauseKind::Quiet,
+        ]
+        .into_iter()
+        .find(|kind| kind.as_str() == value)
+    }
+}
+
+/// A request to pause, as sent by the settings UI:
+/// `{"kind": "minutes", "minutes": 15}`, `{"kind": "next-event"}`, ...
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum PauseRequest {
+    Minutes {
+        minutes: u32,
+    },
+    NextEvent,
+    EventEnd,
+    Tomorrow,
+    /// A recurring quiet period from a schedule rule, until the rule ends.
+    #[serde(skip)]
+    Quiet {
+        name: String,
+        until: DateTime<Utc>,
+    },
+}
+
+/// The active pause.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pause {
+    pub kind: PauseKind,
+    pub started_at: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    /// What paused the border: "manual", "tray", "hotkey", "api",
+    /// "schedule: Evening", ...
+    pub source: String,
+    /// The event a next-event or event-end pause follows.
+    pub event_id: Option<String>,
+    /// That event's title, or the schedule rule's name.
+    pub title: Option<String>,
+}
+
+impl Pause {
+    /// How long the pause lasts, for menus and notifications: "until 14:30",
+    /// "until Standup ends", "for Evening until 22:00".
+    pub fn label(&self) -> String {
+        let at = self.until.with_timezone(&Local).format("%H:%M");
+        match (self.kind, self.title.as_deref()) {
+            (PauseKind::NextEvent, Some(title)) => format!("until {title} starts"),
+            (PauseKind::EventEnd, Some(title)) => format!("until {title} ends"),
+            (PauseKind::Tomorrow, _) => "until tomorrow".to_string(),
+            (PauseKind::Quiet, Some(name)) => format!("for {name} until {at}"),
+            _ => format!("until {at}"),
+        }
+    }
+
+    /// Whole minutes left, rounded up.
+    fn minutes_left(&self, now: DateTime<Utc>) -> i64 {
+        ((self.until - now).num_seconds().max(0) + 59) / 60
+    }
+}
+
+/// A pause as the frontend shows it: payload of `border-paused` and
+/// `get_pause_state`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PauseInfo {
+    pub until: DateTime<Utc>,
+    pub kind: PauseKind,
+    pub label: String,
+    pub source: String,
+}
+
+impl From<&Pause> for PauseInfo {
+    fn from(pause: &Pause) -> Self {
+        PauseInfo {
+            until: pause.until,
+            kind: pause.kind,
+            label: pause.label(),
+            source: pause.source.clone(),
+        }
+    }
+}
+
+/// Tracks when the border overlay is paused until.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PauseState {
+    /// If `Some`, the border is paused until this time. If `None`, the border is active.
+    pub paused_until: Option<DateTime<Utc>>,
+    pub pause: Option<Pause>,
+}
+
+impl PauseState {
+    fn set(&mut self, pause: Option<Pause>) {
+        self.paused_until = pause.as_ref().map(|p| p.until);
+        self.pause = pause;
+    }
+}
+
+/// Events that pauses can follow: timed, not ignored.
+fn timed(events: &[CalendarEvent]) -> impl Iterator<Item = &CalendarEvent> {
+    events.iter().filter(|e| !e.ignored && !e.is_all_day)
+}
+
+/// The next local midnight.
+fn tomorrow(now: DateTime<Utc>) -> DateTime<Utc> {
+    let midnight = now.with_timezone(&Local).date_naive() + Duration::days(1);
+    Local
+        .from_local_datetime(&midnight.and_time(chrono::NaiveTime::MIN))
+        .earliest()
+        .map_or(now + Duration::days(1), |t| t.to_utc())
+}
+
+/// Work out when a pause requested at `now` ends.
+pub fn resolve(
+    request: &PauseRequest,
+    events: &[CalendarEvent],
+    now: DateTime<Utc>,
+    source: &str,
+) -> Result<Pause, String> {
+    let pause = |kind, until, event: Option<&CalendarEvent>| Pause {
+        kind,
+        started_at: now,
+        until,
+        source: source.to_string(),
+        event_id: event.map(|e| e.id.clone()),
+        title: event.map(|e| e.title.clone()),
+    };
+    match request {
+        PauseRequest::Minutes { minutes: 0 } => Err("minutes must be positive".to_string()),
+        PauseRequest::Minutes { minutes } => Ok(pause(
+            PauseKind::Minutes,
+            now + Duration::minutes(i64::from(*minutes)),
+            None,
+        )),
+        PauseRequest::NextEvent => timed(events)
+            .filter(|e| e.start_time > now)
+            .min_by_key(|e| e.start_time)
+            .map(|e| pause(PauseKind::NextEvent, e.start_time, Some(e)))
+            .ok_or_else(|| "no upcoming event".to_string()),
+        PauseRequest::EventEnd => timed(events)
+            .filter(|e| e.start_time <= now && now < e.end_time)
+            .min_by_key(|e| e.end_time)
+            .map(|e| pause(PauseKind::EventEnd, e.end_time, Some(e)))
+            .ok_or_else(|| "no event in progress".to_string()),
+        PauseRequest::Tomorrow => Ok(pause(PauseKind::Tomorrow, tomorrow(now), None)),
+        PauseRequest::Quiet { name, until } if *until > now => Ok(Pause {
+            title: Some(name.clone()),
+            ..pause(PauseKind::Quiet, *until, None)
+        }),
+        PauseRequest::Quiet { .. } => Err("quiet period already ended".to_string()),
+    }
+}
+
+/// A pause that follows an event, moved to the event's new time after a
+/// sync. `None` if nothing changed or the pause doesn't follow an event.
+pub fn retarget(pause: &Pause, events: &[CalendarEvent]) -> Option<Pause> {
+    let id = pause.event_id.as_deref()?;
+    let event = timed(events).find(|e| e.id == id)?;
+    let until = match pause.kind {
+        PauseKind::NextEvent => event.start_time,
+        PauseKind::EventEnd => event.end_time,
+        _ => return None,
+    };
+    (until != pause.until || Some(&event.title) != pause.title.as_ref()).then(|| Pause {
+        until,
+        title: Some(event.title.clone()),
+        ..pause.clone()
+    })
+}
+
+// --- Persistence ---
+
+async fn load(pool: &SqlitePool) -> Result<Option<Pause>, String> {
+    let row = sqlx::query(
+        "SELECT kind, started_at, until, source, event_id, title FROM border_pause WHERE id = 1",
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(row.and_then(|r| {
+        Some(Pause {
+            kind: PauseKind::parse(r.get("kind"))?,
+            started_at: parse_timestamp(r.get("started_at"))?,
+            until: parse_timestamp(r.get("until"))?,
+            source: r.get("source"),
+            event_id: r.get("event_id"),
+            title: r.get("title"),
+        })
+    }))
+}
+
+async fn save(pool: &SqlitePool, pause: Option<&Pause>) -> Result<(), String> {
+    let Some(pause) = pause else {
+        sqlx::query("DELETE FROM border_pause")
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        return Ok(());
+    };
+    sqlx::query(
+        "INSERT OR REPLACE INTO border_pause (id, kind, started_at, until, source, event_id, title)
+         VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6)",
+    )
+    .bind(pause.kind.as_str())
+    .bind(timestamp(pause.started_at))
+    .bind(timestamp(pause.until))
+    .bind(&pause.source)
+    .bind(&pause.event_id)
+    .bind(&pause.title)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Hands pauses to the writer task. Only the latest one matters, so
+/// changes that arrive while a save is running collapse into one.
+struct PauseWriter(watch::Sender<Option<Pause>>);
+
+/// Queue `pause` to be saved. Called with the [`PauseState`] lock held, so
+/// saves follow the order the state changed in.
+fn persist(app: &AppHandle, pause: Option<Pause>) {
+    if let Some(writer) = app.try_state::<PauseWriter>() {
+        writer.0.send_replace(pause);
+    }
+}
+
+/// Save pauses one at a time as [`persist`] queues them.
+fn spawn_writer(app: &tauri::App) {
+    let (tx, mut rx) = watch::channel(None);
+    app.manage(PauseWriter(tx));
+    let handle = app.handle().clone();
+    tauri::async_runtime::spawn(async move {
+        while rx.changed().await.is_ok() {
+            let pause = rx.borrow_and_update().clone();
+            let Ok(pool) = settings::app_pool(&handle).await else {
+                continue;
+            };
+            if let Err(e) = save(&pool, pause.as_ref()).await {
+                eprintln!("[pause] Failed to save pause: {e}");
+            }
+        }
+    });
+}
+
+// --- Runtime ---
+
+/// Cached events, leaving out ignored calendars.
+async fn load_events(app: &AppHandle) -> Result<Vec<CalendarEvent>, String> {
+    let ignored = settings::current_settings(app).await.ignored_calendar_ids;
+    let pool = settings::app_pool(app).await?;
+    let mut events = crate::calendar::poller::load_cached_events(&pool).await?;
+    events.retain(|e| {
+        e.calendar_id
+            .as_ref()
+            .is_none_or(|id| !ignored.contains(id))
+    });
+    Ok(events)
+}
+
+fn emit_paused(app: &AppHandle, pause: &Pause) {
+    let _ = app.emit("border-paused", PauseInfo::from(pause));
+}
+
+/// Resume the border once `pause` runs out, unless it was replaced first.
+fn schedule_resume(app: &AppHandle, pause: &Pause) {
+    let app = app.clone();
+    let pause = pause.clone();
+    tauri::async_runtime::spawn(async move {
+        let wait = (pause.until - Utc::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+        let current = app
+            .state::<Mutex<PauseState>>()
+            .lock()
+            .is_ok_and(|state| state.pause.as_ref() == Some(&pause));
+        if current {
+            if let Err(e) = resume(&app, "expired") {
+                eprintln!("[pause] Failed to resume border: {e}");
+            }
+        }
+    });
+}
+
+/// Make `pause` the active pause: save it, record it, tell the overlay and
+/// webhooks, and schedule the resume.
+fn apply(app: &AppHandle, pause: Pause) -> Result<Pause, String> {
+    {
+        let managed = app.state::<Mutex<PauseState>>();
+        let mut state = managed.lock().map_err(|e| e.to_string())?;
+        state.set(Some(pause.clone()));
+        persist(app, Some(pause.clone()));
+    }
+    history::record_pause(app, Some(pause.until), pause.source.clone());
+    webhooks::notify(
+        app,
+        WebhookEvent::BorderPaused,
+        serde_json::json!({
+            "minutes": pause.minutes_left(Utc::now()),
+            "pausedUntil": pause.until,
+            "kind": pause.kind,
+            "source": pause.source,
+        }),
+    );
+    emit_paused(app, &pause);
+    schedule_resume(app, &pause);
+    Ok(pause)
+}
+
+/// Pause the border as requested. `source` says what asked for it.
+pub async fn pause(app: &AppHandle, request: PauseRequest, source: &str) -> Result<Pause, String> {
+    let events = match request {
+        PauseRequest::NextEvent | PauseRequest::EventEnd => load_events(app).await?,
+        _ => Vec::new(),
+    };
+    let pause = resolve(&request, &events, Utc::now(), source)?;
+    apply(app, pause)
+}
+
+/// Pause the border for `minutes`.
+pub fn pause_for(app: &AppHandle, minutes: u32, source: &str) -> Result<Pause, String> {
+    let pause = resolve(&PauseRequest::Minutes { minutes }, &[], Utc::now(), source)?;
+    apply(app, pause)
+}
+
+/// Resume the border. Does nothing if it isn't paused.
+pub fn resume(app: &AppHandle, source: &str) -> Result<(), String> {
+    {
+        let managed = app.state::<Mutex<PauseState>>();
+        let mut state = managed.lock().map_err(|e| e.to_string())?;
+        if state.pause.is_none() {
+            return Ok(());
+        }
+        state.set(None);
+        persist(app, None);
+    }
+    history::record_pause(app, None, source.to_string());
+    webhooks::notify(
+        app,
+        WebhookEvent::BorderResumed,
+        serde_json::json!({ "source": source }),
+    );
+    app.emit("border-resumed", serde_json::json!({ "source": source }))
+        .map_err(|e| e.to_string())
+}
+
+/// Follow event-tied pauses to their event's new time after a sync.
+async fn retarget_after_sync(app: &AppHandle) {
+    let Some(current) = app
+        .state::<Mutex<PauseState>>()
+        .lock()
+        .ok()
+        .and_then(|state| state.pause.clone())
+        .filter(|p| p.event_id.is_some())
+    else {
+        return;
+    };
+    let events = match load_events(app).await {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("[pause] Failed to load events: {e}");
+            return;
+        }
+    };
+    let Some(moved) = retarget(&current, &events) else {
+        return;
+    };
+    if moved.until <= Utc::now() {
+        if let Err(e) = resume(app, "expired") {
+            eprintln!("[pause] Failed to resume border: {e}");
+        }
+        return;
+    }
+    if let Ok(mut state) = app.state::<Mutex<PauseState>>().lock() {
+        if state.pause.as_ref() != Some(&current) {
+            return;
+        }
+        state.set(Some(moved.clone()));
+        persist(app, Some(moved.clone()));
+    }
+    emit_paused(app, &moved);
+    schedule_resume(app, &moved);
+}
+
+/// Restore a pause saved before the last exit, and follow event-tied
+/// pauses as calendars sync.
+pub fn start(app: &tauri::App) {
+    spawn_writer(app);
+    let handle = app.handle().clone();
+    tauri::async_runtime::spawn(async move {
+        let Ok(pool) = settings::app_pool(&handle).await else {
+            return;
+        };
+        let saved = match load(&pool).await {
+            Ok(saved) => saved,
+            Err(e) => {
+                eprintln!("[pause] Failed to load pause: {e}");
+                return;
+            }
+        };
+        match saved {
+            Some(pause) if pause.until > Utc::now() => {
+                if let Ok(mut state) = handle.state::<Mutex<PauseState>>().lock() {
+                    state.set(Some(pause.clone()));
+                }
+                eprintln!("[pause] Restored pause {}", pause.label());
+                emit_paused(&handle, &pause);
+                schedule_resume(&handle, &pause);
+            }
+            Some(_) => {
+                if let Ok(state) = handle.state::<Mutex<PauseState>>().lock() {
+                    if state.pause.is_none() {
+                        persist(&handle, None);
+                    }
+                }
+                history::record_pause(&handle, None, "expired".to_string());
+            }
+            None => {}
+        }
+    });
+
+    let handle = app.handle().clone();
+    app.listen("calendar-events-update", move |_event| {
+        let h = handle.clone();
+        tauri::async_runtime::spawn(async move {
+            retarget_after_sync(&h).await;
+        });
+    });
+}
+
+// --- Tauri commands ---
+
+/// Pause the border overlay for a given number of minutes.
+/// - `duration_minutes > 0`: pause for that many minutes
+/// - `duration_minutes == 0`: resume immediately (unpause)
+#[command]
+pub fn pause_border(app: AppHandle, duration_minutes: i32) -> Result<(), String> {
+    match u32::try_from(duration_minutes) {
+        Ok(minutes) if minutes > 0 => pause_for(&app, minutes, "manual").map(|_| ()),
+        _ => resume(&app, "manual"),
+    }
+}
+
+/// Pause the border until a time, an event, or tomorrow.
+#[command]
+pub async fn pause_border_until(
+    app: AppHandle,
+    request: PauseRequest,
+) -> Result<PauseInfo, String> {
+    pause(&app, request, "manual")
+        .await
+        .map(|pause| PauseInfo::from(&pause))
+}
+
+/// The active pause, if any.
+#[command]
+pub fn get_pause_state(app: AppHandle) -> Result<Option<PauseInfo>, String> {
+    let managed = app.state::<Mutex<PauseState>>();
+    let state = managed.lock().map_err(|e| e.to_string())?;
+    Ok(state.pause.as_ref().map(PauseInfo::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    fn t0() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, 13, 45, 0).unwrap()
+    }
+
+    fn event(id: &str, start_min: i64, end_min: i64) -> CalendarEvent {
+        let at = |min| t0() + Duration::minutes(min);
+        test_support::event(id, at(start_min), at(end_min))
+    }
+
+    #[test]
+    fn test_request_deserializes_from_tagged_json() {
+        let parse = |json| serde_json::from_str::<PauseRequest>(json);
+        assert_eq!(
+            parse(r#"{"kind":"minutes","minutes":15}"#).unwrap(),
+            PauseRequest::Minutes { minutes: 15 }
+        );
+        assert_eq!(
+            parse(r#"{"kind":"event-end"}"#).unwrap(),
+            PauseRequest::EventEnd
+        );
+        assert!(parse(r#"{"kind":"quiet"}"#).is_err());
+    }
+
+    #[test]
+    fn test_resolve_minutes_and_tomorrow() {
+        let pause = resolve(&PauseRequest::Minutes { minutes: 15 }, &[], t0(), "hotkey").unwrap();
+        assert_eq!(pause.until, t0() + Duration::minutes(15));
+        assert_eq!(pause.source, "hotkey");
+        assert!(resolve(&PauseRequest::Minutes { minutes: 0 }, &[], t0(), "api").is_err());
+
+        let pause = resolve(&PauseRequest::Tomorrow, &[], t0(), "manual").unwrap();
+        let local = pause.until.with_timezone(&Local);
+        assert_eq!(local.time(), chrono::NaiveTime::MIN);
+        assert!(pause.until > t0() && pause.until <= t0() + Duration::days(1));
+        assert_eq!(pause.label(), "until tomorrow");
+    }
+
+    #[test]
+    fn test_resolve_follows_events() {
+        let mut all_day = event("all-day", -600, 600);
+        all_day.is_all_day = true;
+        let events = vec![
+            event("current", -15, 30),
+            event("later", 90, 120),
+            event("next", 45, 60),
+            all_day,
+        ];
+
+        let pause = resolve(&PauseRequest::NextEvent, &events, t0(), "manual").unwrap();
+        assert_eq!(pause.until, t0() + Duration::minutes(45));
+        assert_eq!(pause.event_id.as_deref(), Some("next"));
+        assert_eq!(pause.label(), "until Meeting next starts");
+
+        let pause = resolve(&PauseRequest::EventEnd, &events, t0(), "manual").unwrap();
+        assert_eq!(pause.until, t0() + Duration::minutes(30));
+        assert_eq!(pause.label(), "until Meeting current ends");
+
+        let late = t0() + Duration::minutes(200);
+        assert!(resolve(&PauseRequest::NextEvent, &events, late, "manual").is_err());
+        assert!(resolve(&PauseRequest::EventEnd, &events, late, "manual").is_err());
+    }
+
+    #[test]
+    fn test_resolve_quiet_period() {
+        let request = PauseRequest::Quiet {
+            name: "Evening".to_string(),
+            until: t0() + Duration::hours(2),
+        };
+        let pause = resolve(&request, &[], t0(), "schedule: Evening").unwrap();
+        assert_eq!(pause.kind, PauseKind::Quiet);
+        assert!(pause.label().starts_with("for Evening until "));
+        assert!(resolve(&request, &[], t0() + Duration::hours(3), "schedule").is_err());
+    }
+
+    #[test]
+    fn test_retarget_follows_moved_event() {
+        let events = vec![event("standup", -15, 30)];
+        let pause = resolve(&PauseRequest::EventEnd, &events, t0(), "manual").unwrap();
+        assert_eq!(retarget(&pause, &events), None);
+
+        let moved = vec![event("standup", -15, 40)];
+        let retargeted = retarget(&pause, &moved).unwrap();
+        assert_eq!(retargeted.until, t0() + Duration::minutes(40));
+        assert_eq!(retargeted.started_at, pause.started_at);
+
+        // A pause with no event, or whose event is gone, stays as it is.
+        assert_eq!(retarget(&pause, &[]), None);
+        let timed = resolve(&PauseRequest::Minutes { minutes: 5 }, &[], t0(), "api").unwrap();
+        assert_eq!(retarget(&timed, &moved), None);
+    }
+
+    #[tokio::test]
+    async fn test_pause_round_trips_through_database() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        crate::migrations::migrate(&pool).await.unwrap();
+        assert_eq!(load(&pool).await.unwrap(), None);
+
+        let events = vec![event("standup", -15, 30)];
+        let pause = resolve(&PauseRequest::EventEnd, &events, t0(), "tray").unwrap();
+        save(&pool, Some(&pause)).await.unwrap();
+        assert_eq!(load(&pool).await.unwrap(), Some(pause.clone()));
+
+        let replaced = resolve(&PauseRequest::Tomorrow, &[], t0(), "api").unwrap();
+        save(&pool, Some(&replaced)).await.unwrap();
+        assert_eq!(load(&pool).await.unwrap(), Some(replaced));
+
+        save(&pool, None).await.unwrap();
+        assert_eq!(load(&pool).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,503 @@
+//! Settings export/import and named settings profiles.
+//!
+//! Only portable settings (see [`SettingSpec::portable`]) are exported or
+//! stored in profiles, so secrets and machine-specific values such as the
+//! selected display never leave this machine. Applying an export or a profile
+//! writes only the keys whose value actually differs and emits one
+//! `settings-changed` event per written key.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+use tauri::{command, AppHandle, Manager};
+
+use crate::settings::{self, SettingSpec, SCHEMA};
+
+/// Current version of the export file format. Bump when the layout changes
+/// and teach [`parse_export`] to read older versions.
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// Versioned settings export file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsExport {
+    pub format_version: u32,
+    pub app_version: String,
+    pub exported_at: String,
+    pub settings: BTreeMap<String, String>,
+}
+
+/// A single key whose value would change (or did change).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingChange {
+    pub key: String,
+    pub from: Option<String>,
+    pub to: String,
+}
+
+/// What importing a file would do, without applying it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    pub format_version: u32,
+    pub exported_at: String,
+    pub changes: Vec<SettingChange>,
+    /// Keys in the file that this version does not import (unknown or local).
+    pub ignored_keys: Vec<String>,
+}
+
+/// A saved profile as listed in the settings UI and tray menu.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSummary {
+    pub name: String,
+    pub active: bool,
+    pub updated_at: String,
+}
+
+/// Cached profile list so the tray menu can be rebuilt synchronously.
+#[derive(Debug, Default)]
+pub struct ProfileList(pub Vec<ProfileSummary>);
+
+fn is_portable(key: &str) -> bool {
+    settings::setting_spec(key).is_some_and(|spec| spec.portable)
+}
+
+/// Keep only the portable keys from stored (key, value) pairs.
+fn portable_settings(pairs: &[(String, String)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .filter(|(key, _)| is_portable(key))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// Build an export document from stored (key, value) pairs.
+pub fn build_export(pairs: &[(String, String)], exported_at: String) -> SettingsExport {
+    SettingsExport {
+        format_version: EXPORT_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at,
+        settings: portable_settings(pairs),
+    }
+}
+
+/// Parse and validate an export document. Fails on unsupported versions or
+/// if any portable value is invalid; unknown and local keys are reported in
+/// the second element and otherwise ignored.
+pub fn parse_export(contents: &str) -> Result<(SettingsExport, Vec<String>), String> {
+    let mut export: SettingsExport =
+        serde_json::from_str(contents).map_err(|e| format!("Not a Morph settings file: {e}"))?;
+
+    if export.format_version == 0 || export.format_version > EXPORT_FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported settings file version {} (this version of Morph reads up to {})",
+            export.format_version, EXPORT_FORMAT_VERSION
+        ));
+    }
+
+    let mut ignored = Vec::new();
+    let mut errors = Vec::new();
+    export.settings.retain(|key, value| {
+        if !is_portable(key) {
+            ignored.push(key.clone());
+            return false;
+        }
+        if let Err(e) = settings::validate_setting(key, value) {
+            errors.push(e.to_string());
+        }
+        true
+    });
+
+    if !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+    Ok((export, ignored))
+}
+
+/// Compute the changes needed to move `current` to `incoming`. Keys missing
+/// from `incoming` are left untouched.
+pub fn diff_settings(
+    current: &[(String, String)],
+    incoming: &BTreeMap<String, String>,
+) -> Vec<SettingChange> {
+    let current: BTreeMap<&str, &str> = current
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+
+    // Iterate in schema order so events fire in a stable, meaningful order.
+    SCHEMA
+        .iter()
+        .filter_map(|SettingSpec { key, .. }| {
+            let to = incoming.get(*key)?;
+            let from = current.get(key).copied();
+            (from != Some(to.as_str())).then(|| SettingChange {
+                key: key.to_string(),
+                from: from.map(str::to_string),
+                to: to.clone(),
+            })
+        })
+        .collect()
+}
+
+// --- Pool-based inner functions (testable without Tauri runtime) ---
+
+async fn export_inner(pool: &SqlitePool, exported_at: String) -> Result<String, String> {
+    let pairs = settings::get_all_settings_inner(pool).await?;
+    serde_json::to_string_pretty(&build_export(&pairs, exported_at)).map_err(|e| e.to_string())
+}
+
+async fn preview_import_inner(pool: &SqlitePool, contents: &str) -> Result<ImportPreview, String> {
+    let (export, ignored_keys) = parse_export(contents)?;
+    let current = settings::get_all_settings_inner(pool).await?;
+    Ok(ImportPreview {
+        format_version: export.format_version,
+        exported_at: export.exported_at,
+        changes: diff_settings(&current, &export.settings),
+        ignored_keys,
+    })
+}
+
+/// Write every differing key in one transaction and return what changed.
+async fn apply_settings_inner(
+    pool: &SqlitePool,
+    incoming: &BTreeMap<String, String>,
+) -> Result<Vec<SettingChange>, String> {
+    for (key, value) in incoming {
+        settings::validate_setting(key, value).map_err(|e| e.to_string())?;
+    }
+
+    let current = settings::get_all_settings_inner(pool).await?;
+    let changes = diff_settings(&current, incoming);
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for change in &changes {
+        sqlx::query(
+            "INSERT INTO settings (key, value, updated_at) VALUES (?1, ?2, datetime('now'))
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = datetime('now')",
+        )
+        .bind(&change.key)
+        .bind(&change.to)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(changes)
+}
+
+async fn import_inner(pool: &SqlitePool, contents: &str) -> Result<Vec<SettingChange>, String> {
+    let (export, _) = parse_export(contents)?;
+    apply_settings_inner(pool, &export.settings).await
+}
+
+fn validate_profile_name(name: &str) -> Result<&str, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name must not be empty".to_string());
+    }
+    if name.chars().count() > 64 {
+        return Err("Profile name must be at most 64 characters".to_string());
+    }
+    Ok(name)
+}
+
+async fn list_profiles_inner(pool: &SqlitePool) -> Result<Vec<ProfileSummary>, String> {
+    let rows = sqlx::query(
+        "SELECT name, is_active, updated_at FROM settings_profiles ORDER BY name COLLATE NOCASE",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows
+        .iter()
+        .map(|r| ProfileSummary {
+            name: r.get("name"),
+            active: r.get::<i64, _>("is_active") != 0,
+            updated_at: r.get("updated_at"),
+        })
+        .collect())
+}
+
+async fn mark_active(pool: &SqlitePool, name: &str) -> Result<(), String> {
+    sqlx::query("UPDATE settings_profiles SET is_active = (name = ?1)")
+        .bind(name)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Snapshot the current portable settings under `name` and make it active.
+async fn save_profile_inner(pool: &SqlitePool, name: &str) -> Result<(), String> {
+    let name = validate_profile_name(name)?;
+    let pairs = settings::get_all_settings_inner(pool).await?;
+    let snapshot = serde_json::to_string(&portable_settings(&pairs)).map_err(|e| e.to_string())?;
+
+    sqlx::query(
+        "INSERT INTO settings_profiles (name, settings, updated_at) VALUES (?1, ?2, datetime('now'))
+         ON CONFLICT(name) DO UPDATE SET settings = excluded.settings, updated_at = datetime('now')",
+    )
+    .bind(name)
+    .bind(snapshot)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    mark_active(pool, name).await
+}
+
+async fn apply_profile_inner(pool: &SqlitePool, name: &str) -> Result<Vec<SettingChange>, String> {
+    let row = sqlx::query("SELECT settings FROM settings_profiles WHERE name = ?1")
+        .bind(name)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("No profile named '{name}'"))?;
+
+    let stored: BTreeMap<String, String> =
+        serde_json::from_str(&row.get::<String, _>("settings")).map_err(|e| e.to_string())?;
+
+    // Drop keys that are no longer portable or no longer valid instead of
+    // refusing to switch; the rest of the profile still applies.
+    let incoming: BTreeMap<String, String> = stored
+        .into_iter()
+        .filter(|(key, value)| {
+            let ok = is_portable(key) && settings::validate_setting(key, value).is_ok();
+            if !ok {
+                eprintln!("[profiles] Skipping stale '{key}' in profile '{name}'");
+            }
+            ok
+        })
+        .collect();
+
+    let changes = apply_settings_inner(pool, &incoming).await?;
+    mark_active(pool, name).await?;
+    Ok(changes)
+}
+
+async fn delete_profile_inner(pool: &SqlitePool, name: &str) -> Result<(), String> {
+    sqlx::query("DELETE FROM settings_profiles WHERE name = ?1")
+        .bind(name)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// --- Runtime glue ---
+
+fn emit_changes(app: &AppHandle, changes: &[SettingChange]) {
+    for change in changes {
+        settings::emit_setting_changed(app, &change.key, &change.to);
+    }
+}
+
+/// Reload the cached profile list and rebuild the tray menu.
+pub async fn refresh(app: &AppHandle) {
+    let profiles = match settings::app_pool(app).await {
+        Ok(pool) => list_profiles_inner(&pool).await,
+        Err(e) => Err(e),
+    };
+    match profiles {
+        Ok(profiles) => {
+            if let Ok(mut list) = app.state::<Mutex<ProfileList>>().lock() {
+                list.0 = profiles;
+            }
+            if let Err(e) = crate::tray::refresh_tray_menu(app) {
+                eprintln!("[profiles] Failed to refresh tray menu: {e}");
+            }
+        }
+        Err(e) => eprintln!("[profiles] Failed to load profiles: {e}"),
+    }
+}
+
+/// Switch to a saved profile (used by the tray menu).
+pub async fn switch_profile(app: &AppHandle, name: &str) -> Result<Vec<SettingChange>, String> {
+    let pool = settings::app_pool(app).await?;
+    let changes = apply_profile_inner(&pool, name).await?;
+    emit_changes(app, &changes);
+    refresh(app).await;
+    Ok(changes)
+}
+
+// --- Tauri commands ---
+
+/// Export all portable settings as a versioned JSON document.
+#[command]
+pub async fn export_settings(app: AppHandle) -> Result<String, String> {
+    let pool = settings::app_pool(&app).await?;
+    export_inner(&pool, chrono::Utc::now().to_rfc3339()).await
+}
+
+/// Validate an export document and report what importing it would change.
+#[command]
+pub async fn preview_settings_import(
+    app: AppHandle,
+    contents: String,
+) -> Result<ImportPreview, String> {
+    let pool = settings::app_pool(&app).await?;
+    preview_import_inner(&pool, &contents).await
+}
+
+/// Import an export document, returning the keys that changed.
+#[command]
+pub async fn import_settings(
+    app: AppHandle,
+    contents: String,
+) -> Result<Vec<SettingChange>, String> {
+    let pool = settings::app_pool(&app).await?;
+    let changes = import_inner(&pool, &contents).await?;
+    emit_changes(&app, &changes);
+    Ok(changes)
+}
+
+#[command]
+pub async fn list_profiles(app: AppHandle) -> Result<Vec<ProfileSummary>, String> {
+    let pool = settings::app_pool(&app).await?;
+    list_profiles_inner(&pool).await
+}
+
+/// Save the current settings as a named profile (overwriting any existing
+/// profile with that name).
+#[command]
+pub async fn save_profile(app: AppHandle, name: String) -> Result<(), String> {
+    let pool = settings::app_pool(&app).await?;
+    save_profile_inner(&pool, &name).await?;
+    refresh(&app).await;
+    Ok(())
+}
+
+#[command]
+pub async fn apply_profile(app: AppHandle, name: String) -> Result<Vec<SettingChange>, String> {
+    switch_profile(&app, &name).await
+}
+
+#[command]
+pub async fn delete_profile(app: AppHandle, name: String) -> Result<(), String> {
+    let pool = settings::app_pool(&app).await?;
+    delete_profile_inner(&pool, &name).await?;
+    refresh(&app).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn test_pool() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory pool");
+        crate::migrations::migrate(&pool).await.unwrap();
+        settings::seed_defaults_from_pool(&pool).await.unwrap();
+        pool
+    }
+
+    async fn set(pool: &SqlitePool, key: &str, value: &str) {
+        settings::set_setting_inner(pool, key, value).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_export_excludes_local_settings() {
+        let pool = test_pool().await;
+        set(&pool, "selected_display", "monitor-2").await;
+
+        let json = export_inner(&pool, "2026-01-01T00:00:00Z".into())
+            .await
+            .unwrap();
+        let export: SettingsExport = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(export.format_version, EXPORT_FORMAT_VERSION);
+        assert!(export.settings.contains_key("border_thickness"));
+        assert!(!export.settings.contains_key("selected_display"));
+        assert!(!export.settings.contains_key("onboarding_complete"));
+    }
+
+    #[tokio::test]
+    async fn test_export_import_round_trip_reports_only_differences() {
+        let source = test_pool().await;
+        set(&source, "border_thickness", "thick").await;
+        set(&source, "color_palette", "ocean").await;
+        let json = export_inner(&source, "now".into()).await.unwrap();
+
+        let target = test_pool().await;
+        let preview = preview_import_inner(&target, &json).await.unwrap();
+        let keys: Vec<&str> = preview.changes.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(keys, vec!["border_thickness", "color_palette"]);
+        assert_eq!(preview.changes[0].from.as_deref(), Some("medium"));
+        assert_eq!(preview.changes[0].to, "thick");
+
+        // Preview must not write anything.
+        let settings = settings::get_all_settings_inner(&target).await.unwrap();
+        assert!(settings.contains(&("border_thickness".into(), "medium".into())));
+
+        let applied = import_inner(&target, &json).await.unwrap();
+        assert_eq!(applied, preview.changes);
+        assert!(import_inner(&target, &json).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_export_rejects_future_version() {
+        let json = r#"{"formatVersion":99,"appVersion":"9.0.0","exportedAt":"x","settings":{}}"#;
+        let err = parse_export(json).unwrap_err();
+        assert!(err.contains("version 99"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn test_parse_export_rejects_invalid_values() {
+        let json = r#"{"formatVersion":1,"appVersion":"0.1.0","exportedAt":"x",
+            "settings":{"border_thickness":"huge","warning_5min":"maybe"}}"#;
+        let err = parse_export(json).unwrap_err();
+        assert!(err.contains("border_thickness") && err.contains("warning_5min"));
+    }
+
+    #[test]
+    fn test_parse_export_ignores_unknown_and_local_keys() {
+        let json = r#"{"formatVersion":1,"appVersion":"0.1.0","exportedAt":"x",
+            "settings":{"border_position":"top","selected_display":"m1","future_key":"1"}}"#;
+        let (export, ignored) = parse_export(json).unwrap();
+        assert_eq!(export.settings.len(), 1);
+        assert_eq!(
+            ignored,
+            vec!["future_key".to_string(), "selected_display".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_profiles_save_switch_and_activate() {
+        let pool = test_pool().await;
+        set(&pool, "color_palette", "ocean").await;
+        save_profile_inner(&pool, "Home").await.unwrap();
+
+        set(&pool, "color_palette", "ambient").await;
+        set(&pool, "border_thickness", "thin").await;
+        save_profile_inner(&pool, "Work").await.unwrap();
+
+        let changes = apply_profile_inner(&pool, "Home").await.unwrap();
+        let keys: Vec<&str> = changes.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(keys, vec!["border_thickness", "color_palette"]);
+
+        let profiles = list_profiles_inner(&pool).await.unwrap();
+        let active: Vec<&str> = profiles
+            .iter()
+            .filter(|p| p.active)
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(active, vec!["Home"]);
+
+        assert!(apply_profile_inner(&pool, "Home").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_apply_missing_profile_fails() {
+        let pool = test_pool().await;
+        assert!(apply_profile_inner(&pool, "Nope").await.is_err());
+        assert!(save_profile_inner(&pool, "   ").await.is_err());
+    }
+}
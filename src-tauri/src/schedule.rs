@@ -0,0 +1,467 @@
+//! Scheduled setting overrides.
+//!
+//! Rules are stored as JSON in the `schedule_rules` setting. Every
+//! [`EVALUATION_INTERVAL`] the engine picks the first rule that matches the
+//! local time and day of week and layers its overrides on top of the stored
+//! settings. Overrides are never written to the settings table: they only
+//! change the effective values returned by [`crate::settings::current_settings`]
+//! and `get_effective_settings`, and a `settings-changed` event is emitted
+//! for each key whose effective value changes when the active rule changes.
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Emitter, Manager};
+
+use crate::pause::{self, PauseRequest};
+use crate::settings;
+
+/// Setting key holding the JSON rule list.
+pub const RULES_KEY: &str = "schedule_rules";
+
+/// How often rules are re-evaluated against the clock.
+const EVALUATION_INTERVAL: Duration = Duration::from_secs(30);
+
+const DAY_NAMES: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// A time-of-day / day-of-week rule.
+///
+/// `start` and `end` are local `HH:MM` times. A rule whose end is earlier
+/// than its start runs overnight and belongs to the day it starts on; equal
+/// times cover the whole day. An empty `days` list matches every day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleRule {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub days: Vec<String>,
+    pub start: String,
+    pub end: String,
+    /// Setting key → value applied while the rule is active.
+    #[serde(default)]
+    pub overrides: BTreeMap<String, String>,
+    /// Pause the border for as long as the rule is active.
+    #[serde(default)]
+    pub pause_border: bool,
+}
+
+/// A rule resolved against the clock.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveRule {
+    pub id: String,
+    pub name: String,
+    pub overrides: BTreeMap<String, String>,
+    pub pause_border: bool,
+    pub ends_at: NaiveDateTime,
+}
+
+/// Managed engine state.
+#[derive(Debug, Default)]
+pub struct ScheduleState {
+    pub active: Option<ActiveRule>,
+    /// Whether the current border pause was started by a rule (and should be
+    /// lifted when that rule stops applying early).
+    paused_by_rule: bool,
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M").ok()
+}
+
+fn day_index(name: &str) -> Option<usize> {
+    DAY_NAMES.iter().position(|d| *d == name)
+}
+
+impl ScheduleRule {
+    fn applies_on(&self, weekday: chrono::Weekday) -> bool {
+        self.days.is_empty()
+            || self
+                .days
+                .iter()
+                .any(|d| day_index(d) == Some(weekday.num_days_from_monday() as usize))
+    }
+
+    /// If the rule is active at `now`, return when it stops applying.
+    pub fn active_until(&self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = parse_time(&self.start)?;
+        let end = parse_time(&self.end)?;
+        let today = now.date();
+        let time = now.time();
+
+        if start == end {
+            return self
+                .applies_on(today.weekday())
+                .then(|| today.succ_opt().map(|d| d.and_time(NaiveTime::MIN)))
+                .flatten();
+        }
+
+        if start < end {
+            return (self.applies_on(today.weekday()) && start <= time && time < end)
+                .then(|| today.and_time(end));
+        }
+
+        // Overnight: the evening part of today, or the morning tail of a
+        // rule that started yesterday.
+        if self.applies_on(today.weekday()) && time >= start {
+            return today.succ_opt().map(|d| d.and_time(end));
+        }
+        let yesterday = today.pred_opt()?;
+        (self.applies_on(yesterday.weekday()) && time < end).then(|| today.and_time(end))
+    }
+}
+
+/// Parse and validate the `schedule_rules` setting.
+pub fn parse_rules(json: &str) -> Result<Vec<ScheduleRule>, String> {
+    let rules: Vec<ScheduleRule> =
+        serde_json::from_str(json).map_err(|e| format!("invalid schedule rules: {e}"))?;
+
+    let mut ids = HashSet::new();
+    for rule in &rules {
+        if rule.id.trim().is_empty() || rule.name.trim().is_empty() {
+            return Err("schedule rule id and name must not be empty".to_string());
+        }
+        if !ids.insert(rule.id.as_str()) {
+            return Err(format!("duplicate schedule rule id '{}'", rule.id));
+        }
+        for time in [&rule.start, &rule.end] {
+            if parse_time(time).is_none() {
+                return Err(format!("rule '{}': invalid time '{time}'", rule.name));
+            }
+        }
+        if let Some(day) = rule.days.iter().find(|d| day_index(d).is_none()) {
+            return Err(format!(
+                "rule '{}': invalid day '{day}' (expected one of {})",
+                rule.name,
+                DAY_NAMES.join(", ")
+            ));
+        }
+        for (key, value) in &rule.overrides {
+            let portable = settings::setting_spec(key).is_some_and(|spec| spec.portable);
+            if !portable || key == RULES_KEY {
+                return Err(format!("rule '{}': cannot override '{key}'", rule.name));
+            }
+            settings::validate_setting(key, value)
+                .map_err(|e| format!("rule '{}': {e}", rule.name))?;
+        }
+    }
+    Ok(rules)
+}
+
+/// Resolve the first matching rule at `now`. Rule order is priority order.
+pub fn evaluate(rules: &[ScheduleRule], now: NaiveDateTime) -> Option<ActiveRule> {
+    rules.iter().find_map(|rule| {
+        rule.active_until(now).map(|ends_at| ActiveRule {
+            id: rule.id.clone(),
+            name: rule.name.clone(),
+            overrides: rule.overrides.clone(),
+            pause_border: rule.pause_border,
+            ends_at,
+        })
+    })
+}
+
+/// Keys whose effective value differs between two override layers on top of
+/// the same stored settings, with their new effective value.
+pub fn effective_changes(
+    stored: &[(String, String)],
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+) -> Vec<(String, String)> {
+    let base = |key: &str| -> Option<String> {
+        stored
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+            .or_else(|| settings::setting_spec(key).map(|s| s.default.to_string()))
+    };
+
+    let keys: std::collections::BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let before = old.get(key).cloned().or_else(|| base(key));
+            let after = new.get(key).cloned().or_else(|| base(key))?;
+            (before.as_deref() != Some(after.as_str())).then(|| (key.clone(), after))
+        })
+        .collect()
+}
+
+/// Layer overrides onto stored (key, value) pairs.
+pub fn apply_overrides(pairs: &mut Vec<(String, String)>, overrides: &BTreeMap<String, String>) {
+    for (key, value) in overrides {
+        match pairs.iter_mut().find(|(k, _)| k == key) {
+            Some(pair) => pair.1 = value.clone(),
+            None => pairs.push((key.clone(), value.clone())),
+        }
+    }
+}
+
+/// A rule's local end time as an instant. An end that falls in a DST gap
+/// doesn't exist, so there's nothing to pause until.
+fn local_to_utc(at: NaiveDateTime) -> Option<DateTime<Utc>> {
+    Local
+        .from_local_datetime(&at)
+        .earliest()
+        .map(|t| t.to_utc())
+}
+
+// --- Runtime ---
+
+/// Overrides from the currently active rule, if any.
+pub fn active_overrides(app: &AppHandle) -> BTreeMap<String, String> {
+    app.try_state::<Mutex<ScheduleState>>()
+        .and_then(|state| {
+            state
+                .lock()
+                .ok()
+                .and_then(|s| s.active.as_ref().map(|a| a.overrides.clone()))
+        })
+        .unwrap_or_default()
+}
+
+/// Whether `key` is currently overridden by a schedule rule.
+pub fn is_overridden(app: &AppHandle, key: &str) -> bool {
+    app.try_state::<Mutex<ScheduleState>>()
+        .and_then(|state| {
+            state.lock().ok().map(|s| {
+                s.active
+                    .as_ref()
+                    .is_some_and(|a| a.overrides.contains_key(key))
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// Name of the active rule, for the tray status line.
+pub fn active_rule_name(app: &AppHandle) -> Option<String> {
+    app.try_state::<Mutex<ScheduleState>>().and_then(|state| {
+        state
+            .lock()
+            .ok()
+            .and_then(|s| s.active.as_ref().map(|a| a.name.clone()))
+    })
+}
+
+/// Re-evaluate the rules now and apply any change in the active rule.
+pub async fn reevaluate(app: &AppHandle) {
+    let Ok(pool) = settings::app_pool(app).await else {
+        return;
+    };
+    let stored = match settings::get_all_settings_inner(&pool).await {
+        Ok(pairs) => pairs,
+        Err(e) => {
+            eprintln!("[schedule] Failed to read settings: {e}");
+            return;
+        }
+    };
+    let rules = stored
+        .iter()
+        .find(|(k, _)| k == RULES_KEY)
+        .map(|(_, v)| {
+            parse_rules(v).unwrap_or_else(|e| {
+                eprintln!("[schedule] Ignoring invalid rules: {e}");
+                Vec::new()
+            })
+        })
+        .unwrap_or_default();
+
+    let now = Local::now().naive_local();
+    let next = evaluate(&rules, now);
+
+    let (previous, paused_by_rule) = {
+        let managed = app.state::<Mutex<ScheduleState>>();
+        let Ok(mut state) = managed.lock() else {
+            return;
+        };
+        if state.active == next {
+            return;
+        }
+        let previous = std::mem::replace(&mut state.active, next.clone());
+        let paused_by_rule = state.paused_by_rule;
+        state.paused_by_rule = next.as_ref().is_some_and(|r| r.pause_border);
+        (previous, paused_by_rule)
+    };
+
+    let empty = BTreeMap::new();
+    let old = previous.as_ref().map_or(&empty, |r| &r.overrides);
+    let new = next.as_ref().map_or(&empty, |r| &r.overrides);
+    for (key, value) in effective_changes(&stored, old, new) {
+        let _ = app.emit(
+            "settings-changed",
+            serde_json::json!({ "key": key, "value": value }),
+        );
+    }
+
+    match &next {
+        Some(rule) if rule.pause_border => {
+            let reason = format!("schedule: {}", rule.name);
+            let request = local_to_utc(rule.ends_at).map(|until| PauseRequest::Quiet {
+                name: rule.name.clone(),
+                until,
+            });
+            let result = match request {
+                Some(request) => pause::pause(app, request, &reason).await.map(|_| ()),
+                None => Err(format!("rule end {} doesn't exist locally", rule.ends_at)),
+            };
+            if let Err(e) = result {
+                eprintln!("[schedule] Failed to pause border: {e}");
+            }
+        }
+        _ if paused_by_rule => {
+            if let Err(e) = pause::resume(app, "schedule") {
+                eprintln!("[schedule] Failed to resume border: {e}");
+            }
+        }
+        _ => {}
+    }
+
+    match &next {
+        Some(rule) => eprintln!("[schedule] Rule '{}' active", rule.name),
+        None => eprintln!("[schedule] No rule active"),
+    }
+    if let Err(e) = crate::tray::refresh_tray_menu(app) {
+        eprintln!("[schedule] Failed to refresh tray menu: {e}");
+    }
+}
+
+/// Start the background evaluation loop.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(EVALUATION_INTERVAL);
+        loop {
+            interval.tick().await;
+            reevaluate(&app).await;
+        }
+    });
+}
+
+/// Name of the active schedule rule, if any.
+#[command]
+pub fn get_active_schedule_rule(app: AppHandle) -> Option<String> {
+    active_rule_name(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    /// 2026-03-02 is a Monday.
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn rule(id: &str, days: &[&str], start: &str, end: &str) -> ScheduleRule {
+        ScheduleRule {
+            id: id.to_string(),
+            name: id.to_string(),
+            days: days.iter().map(|d| d.to_string()).collect(),
+            start: start.to_string(),
+            end: end.to_string(),
+            overrides: BTreeMap::new(),
+            pause_border: false,
+        }
+    }
+
+    #[test]
+    fn test_daytime_rule_window() {
+        let r = rule(
+            "work",
+            &["mon", "tue", "wed", "thu", "fri"],
+            "09:00",
+            "18:00",
+        );
+        assert_eq!(r.active_until(at(2, 9, 0)), Some(at(2, 18, 0)));
+        assert_eq!(r.active_until(at(2, 17, 59)), Some(at(2, 18, 0)));
+        assert_eq!(r.active_until(at(2, 18, 0)), None);
+        assert_eq!(r.active_until(at(2, 8, 59)), None);
+        // Saturday
+        assert_eq!(r.active_until(at(7, 10, 0)), None);
+    }
+
+    #[test]
+    fn test_overnight_rule_belongs_to_start_day() {
+        let r = rule("evening", &["fri"], "18:00", "09:00");
+        assert_eq!(r.active_until(at(6, 20, 0)), Some(at(7, 9, 0)));
+        assert_eq!(r.active_until(at(7, 8, 0)), Some(at(7, 9, 0)));
+        // Friday morning is the tail of Thursday, which is not in the rule.
+        assert_eq!(r.active_until(at(6, 8, 0)), None);
+    }
+
+    #[test]
+    fn test_all_day_rule() {
+        let r = rule("weekend", &["sat", "sun"], "00:00", "00:00");
+        assert_eq!(r.active_until(at(7, 0, 0)), Some(at(8, 0, 0)));
+        assert_eq!(r.active_until(at(8, 23, 59)), Some(at(9, 0, 0)));
+        assert_eq!(r.active_until(at(9, 12, 0)), None);
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let rules = vec![
+            rule("weekend", &["sat", "sun"], "00:00", "00:00"),
+            rule("off-hours", &[], "18:00", "09:00"),
+        ];
+        assert_eq!(evaluate(&rules, at(7, 20, 0)).unwrap().id, "weekend");
+        assert_eq!(evaluate(&rules, at(3, 20, 0)).unwrap().id, "off-hours");
+        assert!(evaluate(&rules, at(3, 12, 0)).is_none());
+    }
+
+    #[test]
+    fn test_parse_rules_validates_overrides() {
+        let ok = r#"[{"id":"evening","name":"Evening","start":"18:00","end":"22:00",
+            "overrides":{"border_thickness":"thin","color_intensity":"subtle"}}]"#;
+        assert_eq!(parse_rules(ok).unwrap()[0].overrides.len(), 2);
+
+        let cases = [
+            r#"[{"id":"a","name":"A","start":"25:00","end":"09:00"}]"#,
+            r#"[{"id":"a","name":"A","days":["funday"],"start":"09:00","end":"10:00"}]"#,
+            r#"[{"id":"a","name":"A","start":"09:00","end":"10:00","overrides":{"border_thickness":"huge"}}]"#,
+            r#"[{"id":"a","name":"A","start":"09:00","end":"10:00","overrides":{"selected_display":"m2"}}]"#,
+            r#"[{"id":"a","name":"A","start":"09:00","end":"10:00"},{"id":"a","name":"B","start":"09:00","end":"10:00"}]"#,
+        ];
+        for json in cases {
+            assert!(parse_rules(json).is_err(), "should reject {json}");
+        }
+    }
+
+    #[test]
+    fn test_effective_changes_only_reports_differing_overridden_keys() {
+        let stored = vec![
+            ("border_thickness".to_string(), "thin".to_string()),
+            ("color_intensity".to_string(), "normal".to_string()),
+            ("warning_5min".to_string(), "true".to_string()),
+        ];
+        let evening: BTreeMap<String, String> = [
+            ("border_thickness".to_string(), "thin".to_string()),
+            ("color_intensity".to_string(), "subtle".to_string()),
+        ]
+        .into();
+
+        // Entering: thickness already matches, so only intensity changes.
+        let entering = effective_changes(&stored, &BTreeMap::new(), &evening);
+        assert_eq!(
+            entering,
+            vec![("color_intensity".to_string(), "subtle".to_string())]
+        );
+
+        // Leaving: intensity reverts to the stored value.
+        let leaving = effective_changes(&stored, &evening, &BTreeMap::new());
+        assert_eq!(
+            leaving,
+            vec![("color_intensity".to_string(), "normal".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_local_to_utc_round_trips() {
+        let until = local_to_utc(at(2, 18, 0)).unwrap();
+        assert_eq!(until.with_timezone(&Local).naive_local(), at(2, 18, 0));
+    }
+}
@@ -0,0 +1,581 @@
+//! Screen-share detection: keep the border out of shared screens and
+//! presentations.
+//!
+//! With `screen_share_detection` on, running apps and visible windows are
+//! checked against `screen_share_rules` every few seconds. The defaults
+//! match the sharing toolbars of Zoom, Teams and Chromium browsers (Meet
+//! and other web apps) and full-screen slide shows; users can edit them or
+//! add app names. None of the supported platforms tells other apps when the
+//! screen is being captured, so the sharing apps' own windows stand in for
+//! the OS capture indicators.
+//!
+//! While a rule matches, `screen_share_action` either hides the border
+//! windows, or ("minimal") has the overlay draw a single still edge. Hiding
+//! goes through `window_manager::BorderVisibility`, shared with the "hide
+//! border" shortcut, so the border only returns once neither hides it. It
+//! comes back a couple of checks after the last match, so a toolbar that
+//! briefly disappears doesn't flash it. Changes are emitted as
+//! `screen-share-changed`.
+
+pub mod window_list;
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Emitter, Manager};
+
+use crate::focus::apps::{self, Blocklist, ProcessInfo};
+use crate::settings::{self, ScreenShareAction, Settings};
+use crate::window_manager;
+
+use window_list::{Screen, WindowInfo};
+
+pub const ENABLED_KEY: &str = "screen_share_detection";
+pub const ACTION_KEY: &str = "screen_share_action";
+pub const RULES_KEY: &str = "screen_share_rules";
+
+/// Sharing toolbars of common meeting apps, and full-screen slide shows.
+pub const DEFAULT_RULES: &str = concat!(
+    "[",
+    r#"{"name":"Zoom","windowTitle":"zoom share"},"#,
+    r#"{"name":"Microsoft Teams","windowTitle":"Sharing control bar"},"#,
+    r#"{"name":"Browser screen share","windowTitle":"is sharing your screen"},"#,
+    r#"{"name":"Browser window share","windowTitle":"is sharing a window"},"#,
+    r#"{"name":"PowerPoint","windowTitle":"PowerPoint Slide Show","fullscreen":true},"#,
+    r#"{"name":"Keynote","app":"Keynote","fullscreen":true},"#,
+    r#"{"name":"LibreOffice Impress","app":"soffice.bin","fullscreen":true},"#,
+    r#"{"name":"Google Slides","windowTitle":"Google Slides","fullscreen":true}"#,
+    "]"
+);
+
+/// How often apps and windows are checked.
+const CHECK_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Checks in a row without a match before the border is restored.
+const RESTORE_AFTER: u32 = 2;
+
+/// What counts as sharing or presenting. Every condition given must match.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareRule {
+    /// Shown in logs and the settings UI.
+    pub name: String,
+    /// App name, matched like the focus blocklist: case-insensitive, with
+    /// or without `.exe` / `.app`. On its own, the app running is enough.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    /// Case-insensitive text in a window title.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_title: Option<String>,
+    /// Only match full-screen windows.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fullscreen: bool,
+}
+
+impl ShareRule {
+    fn needs_windows(&self) -> bool {
+        self.window_title.is_some() || self.fullscreen
+    }
+
+    /// Whether the rule matches the running apps and windows.
+    pub fn matches(&self, snapshot: &Snapshot, own_pid: u32) -> bool {
+        let app = self
+            .app
+            .as_ref()
+            .map(|app| Blocklist::new(std::slice::from_ref(app)));
+        if !self.needs_windows() {
+            return app.is_some_and(|app| {
+                snapshot
+                    .processes
+                    .iter()
+                    .any(|p| p.pid != own_pid && app.matches(p).is_some())
+            });
+        }
+        let title = self.window_title.as_ref().map(|t| t.trim().to_lowercase());
+        snapshot.windows.iter().any(|w| {
+            w.pid != own_pid
+                && (!self.fullscreen || w.fullscreen)
+                && title
+                    .as_ref()
+                    .is_none_or(|t| w.title.to_lowercase().contains(t))
+                && app
+                    .as_ref()
+                    .is_none_or(|app| snapshot.owners(w).any(|p| app.matches(&p).is_some()))
+        })
+    }
+}
+
+/// Parse and validate the `screen_share_rules` setting.
+pub fn parse_rules(json: &str) -> Result<Vec<ShareRule>, String> {
+    let rules: Vec<ShareRule> =
+        serde_json::from_str(json).map_err(|e| format!("invalid screen share rules: {e}"))?;
+    for rule in &rules {
+        if rule.name.trim().is_empty() {
+            return Err("screen share rules need a name".to_string());
+        }
+        let blank = |value: &Option<String>| value.as_ref().is_some_and(|v| v.trim().is_empty());
+        if blank(&rule.app) || blank(&rule.window_title) {
+            return Err(format!(
+                "screen share rule '{}': conditions must not be empty",
+                rule.name
+            ));
+        }
+        if rule.app.is_none() && !rule.needs_windows() {
+            return Err(format!(
+                "screen share rule '{}': needs app, windowTitle or fullscreen",
+                rule.name
+            ));
+        }
+    }
+    Ok(rules)
+}
+
+/// Running apps and visible windows at one moment.
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub processes: Vec<ProcessInfo>,
+    pub windows: Vec<WindowInfo>,
+}
+
+impl Snapshot {
+    /// The process owning `window`, as listed and as the window names it.
+    fn owners<'a>(&'a self, window: &'a WindowInfo) -> impl Iterator<Item = ProcessInfo> + 'a {
+        let listed = self
+            .processes
+            .iter()
+            .filter(move |p| window.pid != 0 && p.pid == window.pid)
+            .cloned();
+        let named = window.app.iter().map(move |name| ProcessInfo {
+            pid: window.pid,
+            name: name.clone(),
+            path: None,
+        });
+        listed.chain(named)
+    }
+}
+
+/// The first rule that matches, never counting Morph's own windows.
+pub fn detect<'a>(
+    rules: &'a [ShareRule],
+    snapshot: &Snapshot,
+    own_pid: u32,
+) -> Option<&'a ShareRule> {
+    rules.iter().find(|rule| rule.matches(snapshot, own_pid))
+}
+
+/// Detection inputs derived from settings.
+#[derive(Debug, Clone)]
+pub struct ShareConfig {
+    pub enabled: bool,
+    pub action: ScreenShareAction,
+    pub rules: Vec<ShareRule>,
+}
+
+impl ShareConfig {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            enabled: settings.screen_share_detection,
+            action: settings.screen_share_action,
+            rules: settings.screen_share_rules.clone(),
+        }
+    }
+
+    /// List only what the rules look at: `ps`, System Events and friends
+    /// aren't free.
+    fn snapshot(&self, screens: &[Screen]) -> Result<Snapshot, String> {
+        let needs_windows = self.rules.iter().any(ShareRule::needs_windows);
+        let needs_processes = self.rules.iter().any(|r| r.app.is_some());
+        Ok(Snapshot {
+            processes: if needs_processes {
+                apps::running()?
+            } else {
+                Vec::new()
+            },
+            windows: if needs_windows {
+                window_list::visible(screens)?
+            } else {
+                Vec::new()
+            },
+        })
+    }
+}
+
+/// Detection state, and what was done to the overlay about it.
+#[derive(Debug, Default)]
+pub struct ShareGuard {
+    /// Name of the matching rule while sharing is detected.
+    active: Option<String>,
+    /// Checks without a match since the last one.
+    misses: u32,
+    /// What is currently applied to the overlay.
+    applied: Option<ScreenShareAction>,
+    /// Last listing error, so each is logged once.
+    error: Option<String>,
+}
+
+impl ShareGuard {
+    /// Record one check. Detection starts on the first match and ends after
+    /// `RESTORE_AFTER` checks without one. Returns whether it started or
+    /// ended.
+    pub fn observe(&mut self, found: Option<&str>) -> bool {
+        match found {
+            Some(rule) => {
+                self.misses = 0;
+                let started = self.active.is_none();
+                self.active = Some(rule.to_string());
+                started
+            }
+            None if self.active.is_some() => {
+                self.misses += 1;
+                if self.misses < RESTORE_AFTER {
+                    return false;
+                }
+                self.active = None;
+                self.misses = 0;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stop detecting right away, e.g. when detection is turned off.
+    pub fn clear(&mut self) {
+        self.active = None;
+        self.misses = 0;
+    }
+
+    /// What should be applied to the overlay given `action`.
+    pub fn wanted(&self, action: ScreenShareAction) -> Option<ScreenShareAction> {
+        self.active.as_ref().map(|_| action)
+    }
+
+    pub fn state(&self) -> ScreenShareState {
+        ScreenShareState {
+            active: self.active.is_some(),
+            rule: self.active.clone(),
+            action: self.applied,
+        }
+    }
+}
+
+// --- Runtime ---
+
+/// Bring the overlay in line with the guard and tell the frontend if
+/// anything changed.
+fn apply(app: &AppHandle, guard: &mut ShareGuard, action: ScreenShareAction) {
+    let wanted = guard.wanted(action);
+    if wanted == guard.applied {
+        return;
+    }
+    let hidden = |a: Option<ScreenShareAction>| a == Some(ScreenShareAction::Hide);
+    if hidden(guard.applied) != hidden(wanted) {
+        let result = window_manager::update_visibility(app, |v| v.screen_share = hidden(wanted));
+        if let Err(e) = result {
+            eprintln!("[screen-share] Failed to update the border windows: {e}");
+        }
+    }
+    guard.applied = wanted;
+    match &guard.active {
+        Some(rule) => eprintln!(
+            "[screen-share] Detected {rule}; border set to {}",
+            action.as_str()
+        ),
+        None => eprintln!("[screen-share] Sharing ended; border restored"),
+    }
+    let _ = app.emit("screen-share-changed", guard.state());
+}
+
+/// Screens in physical pixels, for telling full-screen windows apart.
+fn screens(app: &AppHandle) -> Vec<Screen> {
+    app.available_monitors()
+        .map(|monitors| {
+            monitors
+                .iter()
+                .map(|m| Screen {
+                    x: m.position().x,
+                    y: m.position().y,
+                    width: m.size().width,
+                    height: m.size().height,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Apply one check. Lists processes and windows, so it belongs on a
+/// blocking thread.
+fn enforce(app: &AppHandle, config: &ShareConfig, screens: &[Screen]) {
+    let snapshot = (config.enabled && !config.rules.is_empty()).then(|| config.snapshot(screens));
+
+    let managed = app.state::<Mutex<ShareGuard>>();
+    let Ok(mut guard) = managed.lock() else {
+        return;
+    };
+    match snapshot {
+        None => guard.clear(),
+        Some(Ok(snapshot)) => {
+            guard.error = None;
+            let found = detect(&config.rules, &snapshot, std::process::id());
+            guard.observe(found.map(|r| r.name.as_str()));
+        }
+        Some(Err(e)) => {
+            // Keep the current state rather than flash the border
+            if guard.error.as_ref() != Some(&e) {
+                eprintln!("[screen-share] Failed to list apps and windows: {e}");
+            }
+            guard.error = Some(e);
+        }
+    }
+    apply(app, &mut guard, config.action);
+}
+
+/// Check running apps and windows against the rules.
+pub async fn check(app: &AppHandle) {
+    let config = ShareConfig::from_settings(&settings::current_settings(app).await);
+    let screens = screens(app);
+    let app = app.clone();
+    let _ = tauri::async_runtime::spawn_blocking(move || enforce(&app, &config, &screens)).await;
+}
+
+/// Check periodically.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            check(&app).await;
+        }
+    });
+}
+
+// --- Tauri commands ---
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenShareState {
+    /// Whether sharing or presenting is detected.
+    pub active: bool,
+    /// Name of the rule that matched.
+    pub rule: Option<String>,
+    /// What is applied to the overlay: `hide`, `minimal`, or none.
+    pub action: Option<ScreenShareAction>,
+}
+
+#[command]
+pub fn get_screen_share_state(app: AppHandle) -> Result<ScreenShareState, String> {
+    let managed = app.state::<Mutex<ShareGuard>>();
+    let guard = managed.lock().map_err(|e| e.to_string())?;
+    Ok(guard.state())
+}
+
+/// Names of the rules that match right now, to try rules out before
+/// saving them. Uses the saved rules when `rules` is omitted.
+#[command]
+pub async fn detect_screen_share(
+    app: AppHandle,
+    rules: Option<Vec<ShareRule>>,
+) -> Result<Vec<String>, String> {
+    let mut config = ShareConfig::from_settings(&settings::current_settings(&app).await);
+    if let Some(rules) = rules {
+        parse_rules(&serde_json::to_string(&rules).map_err(|e| e.to_string())?)?;
+        config.rules = rules;
+    }
+    let screens = screens(&app);
+    tauri::async_runtime::spawn_blocking(move || {
+        let snapshot = config.snapshot(&screens)?;
+        let own_pid = std::process::id();
+        Ok(config
+            .rules
+            .iter()
+            .filter(|rule| rule.matches(&snapshot, own_pid))
+            .map(|rule| rule.name.clone())
+            .collect())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWN_PID: u32 = 99;
+
+    fn process(pid: u32, name: &str, path: Option<&str>) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            name: name.to_string(),
+            path: path.map(str::to_string),
+        }
+    }
+
+    fn window(pid: u32, app: Option<&str>, title: &str, fullscreen: bool) -> WindowInfo {
+        WindowInfo {
+            pid,
+            app: app.map(str::to_string),
+            title: title.to_string(),
+            fullscreen,
+        }
+    }
+
+    fn detected(rules: &[ShareRule], snapshot: &Snapshot) -> Option<String> {
+        detect(rules, snapshot, OWN_PID).map(|r| r.name.clone())
+    }
+
+    #[test]
+    fn test_default_rules_are_valid() {
+        let rules = parse_rules(DEFAULT_RULES).unwrap();
+        assert_eq!(rules.len(), 8);
+        assert!(rules.iter().all(|r| r.app.is_some() || r.needs_windows()));
+    }
+
+    #[test]
+    fn test_default_rules_detect_sharing_and_presenting() {
+        let rules = parse_rules(DEFAULT_RULES).unwrap();
+        let idle = Snapshot {
+            processes: vec![
+                process(
+                    10,
+                    "zoom.us",
+                    Some("/Applications/zoom.us.app/Contents/MacOS/zoom.us"),
+                ),
+                process(11, "Keynote", None),
+            ],
+            windows: vec![
+                window(10, Some("zoom.us"), "Zoom Meeting", false),
+                window(11, Some("Keynote"), "Quarterly review", false),
+                // Full-screen video isn't a presentation.
+                window(12, Some("VLC"), "holiday.mp4", true),
+            ],
+        };
+        assert_eq!(detected(&rules, &idle), None);
+
+        let cases = [
+            (
+                window(10, Some("zoom.us"), "zoom share toolbar window", false),
+                "Zoom",
+            ),
+            (
+                window(13, None, "Sharing control bar | Microsoft Teams", false),
+                "Microsoft Teams",
+            ),
+            (
+                window(14, None, "meet.google.com is sharing your screen.", false),
+                "Browser screen share",
+            ),
+            (
+                window(11, Some("Keynote"), "Quarterly review", true),
+                "Keynote",
+            ),
+            (
+                window(15, None, "PowerPoint Slide Show - [Deck.pptx]", true),
+                "PowerPoint",
+            ),
+            (
+                window(16, None, "Roadmap - Google Slides - Google Chrome", true),
+                "Google Slides",
+            ),
+        ];
+        for (extra, expected) in cases {
+            let mut snapshot = idle.clone();
+            snapshot.windows.push(extra);
+            assert_eq!(detected(&rules, &snapshot).as_deref(), Some(expected));
+        }
+
+        // Slides that aren't full-screen are just being edited.
+        let mut editing = idle.clone();
+        editing.windows.push(window(
+            16,
+            None,
+            "Roadmap - Google Slides - Google Chrome",
+            false,
+        ));
+        assert_eq!(detected(&rules, &editing), None);
+    }
+
+    #[test]
+    fn test_app_rules_match_processes_and_window_owners() {
+        let rules = parse_rules(
+            r#"[
+                {"name": "OBS", "app": "obs64.exe"},
+                {"name": "Impress", "app": "soffice.bin", "fullscreen": true}
+            ]"#,
+        )
+        .unwrap();
+        let mut snapshot = Snapshot {
+            processes: vec![
+                process(
+                    20,
+                    "soffice.bin",
+                    Some("/usr/lib/libreoffice/program/soffice.bin"),
+                ),
+                process(OWN_PID, "obs64", None),
+            ],
+            // wmctrl reports no app name; the owner comes from the pid.
+            windows: vec![window(20, None, "Deck.odp - LibreOffice Impress", false)],
+        };
+        // Morph itself never counts.
+        assert_eq!(detected(&rules, &snapshot), None);
+
+        snapshot.windows[0].fullscreen = true;
+        assert_eq!(detected(&rules, &snapshot).as_deref(), Some("Impress"));
+
+        snapshot.processes.push(process(21, "OBS64.EXE", None));
+        assert_eq!(detected(&rules, &snapshot).as_deref(), Some("OBS"));
+    }
+
+    #[test]
+    fn test_parse_rules_rejects_bad_rules() {
+        assert!(parse_rules("[]").unwrap().is_empty());
+        assert!(parse_rules(r#"[{"name": "Anything full-screen", "fullscreen": true}]"#).is_ok());
+        for bad in [
+            r#"[{"name": "", "app": "obs"}]"#,
+            r#"[{"name": "Nothing"}]"#,
+            r#"[{"name": "Blank", "windowTitle": "  "}]"#,
+            r#"{"name": "Not a list"}"#,
+        ] {
+            assert!(parse_rules(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_guard_restores_after_consecutive_misses() {
+        let mut guard = ShareGuard::default();
+        assert!(!guard.observe(None));
+        assert_eq!(guard.wanted(ScreenShareAction::Hide), None);
+
+        assert!(guard.observe(Some("Zoom")));
+        assert!(!guard.observe(Some("Zoom")));
+        assert_eq!(
+            guard.wanted(ScreenShareAction::Minimal),
+            Some(ScreenShareAction::Minimal)
+        );
+
+        // A toolbar that blinks out for one check doesn't restore the border.
+        assert!(!guard.observe(None));
+        assert!(!guard.observe(Some("Zoom")));
+        assert!(!guard.observe(None));
+        assert!(guard.observe(None));
+        assert_eq!(guard.wanted(ScreenShareAction::Hide), None);
+        assert!(!guard.state().active);
+
+        guard.observe(Some("Keynote"));
+        assert_eq!(guard.state().rule.as_deref(), Some("Keynote"));
+        guard.clear();
+        assert!(!guard.state().active);
+    }
+
+    #[test]
+    fn test_state_serializes_for_the_overlay() {
+        let state = ScreenShareState {
+            active: true,
+            rule: Some("Zoom".to_string()),
+            action: Some(ScreenShareAction::Minimal),
+        };
+        assert_eq!(
+            serde_json::to_value(&state).unwrap(),
+            serde_json::json!({ "active": true, "rule": "Zoom", "action": "minimal" })
+        );
+    }
+}
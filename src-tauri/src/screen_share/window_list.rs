@@ -0,0 +1,257 @@
+//! Listing top-level windows with their titles and whether they fill a
+//! screen.
+//!
+//! Windows come from `wmctrl -lpG` on Linux (X11 only; Wayland doesn't let
+//! clients see each other's windows), System Events on macOS (needs the
+//! Accessibility permission) and `EnumWindows` on Windows. The parsers take
+//! plain text so they can be tested anywhere.
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use crate::focus::apps::run;
+
+/// A visible top-level window, as far as matching needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowInfo {
+    /// Owning process; 0 when the platform doesn't say.
+    pub pid: u32,
+    /// Owning app's name when the platform reports it. Otherwise it is
+    /// looked up from the pid.
+    pub app: Option<String>,
+    pub title: String,
+    pub fullscreen: bool,
+}
+
+/// A screen's bounds in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Screen {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Parse `wmctrl -lpG`: `0x03a00003  0 4242  0 0 1920 1080 host Title`.
+/// Runs of spaces in titles collapse to one. Windows exactly covering one of
+/// `screens` count as full-screen.
+#[cfg(any(target_os = "linux", test))]
+pub fn parse_wmctrl(output: &str, screens: &[Screen]) -> Vec<WindowInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _id = fields.next()?;
+            let _desktop = fields.next()?;
+            let pid = fields.next()?.parse().ok()?;
+            let x = fields.next()?.parse().ok()?;
+            let y = fields.next()?.parse().ok()?;
+            let width = fields.next()?.parse().ok()?;
+            let height = fields.next()?.parse().ok()?;
+            let _host = fields.next()?;
+            let bounds = Screen {
+                x,
+                y,
+                width,
+                height,
+            };
+            Some(WindowInfo {
+                pid,
+                app: None,
+                title: fields.collect::<Vec<_>>().join(" "),
+                fullscreen: screens.contains(&bounds),
+            })
+        })
+        .collect()
+}
+
+/// JXA listing every window of every foreground app as
+/// `pid<TAB>app<TAB>fullscreen<TAB>title` lines.
+#[cfg(target_os = "macos")]
+const SYSTEM_EVENTS_SCRIPT: &str = r#"
+const lines = [];
+for (const p of Application("System Events").applicationProcesses.whose({ backgroundOnly: false })()) {
+  let windows;
+  try { windows = p.windows(); } catch (e) { continue; }
+  for (const w of windows) {
+    let full = false;
+    try { full = w.attributes.byName("AXFullScreen").value() === true; } catch (e) {}
+    let title = "";
+    try { title = w.name() || ""; } catch (e) {}
+    lines.push([p.unixId(), p.name(), full, title.replace(/[\t\n]/g, " ")].join("\t"));
+  }
+}
+lines.join("\n");
+"#;
+
+/// Parse the System Events script's output.
+#[cfg(any(target_os = "macos", test))]
+pub fn parse_system_events(output: &str) -> Vec<WindowInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\t');
+            let pid = fields.next()?.trim().parse().ok()?;
+            let app = fields.next()?.to_string();
+            let fullscreen = fields.next()? == "true";
+            Some(WindowInfo {
+                pid,
+                app: Some(app),
+                title: fields.next().unwrap_or_default().to_string(),
+                fullscreen,
+            })
+        })
+        .collect()
+}
+
+/// Every visible top-level window. `screens` is needed where the platform
+/// doesn't say which windows are full-screen.
+#[allow(unused_variables)]
+pub fn visible(screens: &[Screen]) -> Result<Vec<WindowInfo>, String> {
+    #[cfg(target_os = "linux")]
+    let windows = run("wmctrl", &["-lpG"]).map(|out| parse_wmctrl(&out, screens));
+    #[cfg(target_os = "macos")]
+    let windows = run(
+        "osascript",
+        &["-l", "JavaScript", "-e", SYSTEM_EVENTS_SCRIPT],
+    )
+    .map(|out| parse_system_events(&out));
+    #[cfg(target_os = "windows")]
+    let windows = win32::visible_windows();
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    let windows = Err("listing windows is not supported on this platform".to_string());
+    windows
+}
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use windows::Win32::Foundation::{BOOL, HWND, LPARAM, RECT, TRUE};
+    use windows::Win32::Graphics::Gdi::{
+        GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONEAREST,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetWindowRect, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId,
+        IsWindowVisible,
+    };
+
+    use super::WindowInfo;
+
+    /// Whether the window covers its monitor exactly. Maximized windows
+    /// overhang the monitor by their frame, so they don't count.
+    unsafe fn is_fullscreen(hwnd: HWND) -> bool {
+        let mut rect = RECT::default();
+        if GetWindowRect(hwnd, &mut rect).is_err() {
+            return false;
+        }
+        let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        if !GetMonitorInfoW(monitor, &mut info).as_bool() {
+            return false;
+        }
+        let m = info.rcMonitor;
+        (rect.left, rect.top, rect.right, rect.bottom) == (m.left, m.top, m.right, m.bottom)
+    }
+
+    /// Callback for `EnumWindows` that collects visible, titled windows.
+    unsafe extern "system" fn collect(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        // SAFETY: `lparam` is the `Vec` passed by `visible_windows`.
+        let windows = &mut *(lparam.0 as *mut Vec<WindowInfo>);
+        let len = GetWindowTextLengthW(hwnd);
+        if !IsWindowVisible(hwnd).as_bool() || len == 0 {
+            return TRUE;
+        }
+        let mut title = vec![0u16; len as usize + 1];
+        let copied = GetWindowTextW(hwnd, &mut title).max(0) as usize;
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid as *mut u32));
+        windows.push(WindowInfo {
+            pid,
+            app: None,
+            title: String::from_utf16_lossy(&title[..copied]),
+            fullscreen: is_fullscreen(hwnd),
+        });
+        TRUE
+    }
+
+    pub fn visible_windows() -> Result<Vec<WindowInfo>, String> {
+        let mut windows: Vec<WindowInfo> = Vec::new();
+        // SAFETY: the callback only writes to `windows`, which outlives the call.
+        unsafe {
+            EnumWindows(
+                Some(collect),
+                LPARAM(&mut windows as *mut Vec<WindowInfo> as isize),
+            )
+        }
+        .map_err(|e| e.to_string())?;
+        Ok(windows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wmctrl() {
+        let screens = [
+            Screen {
+                x: 0,
+                y: 0,
+                width: 1920,
+                height: 1080,
+            },
+            Screen {
+                x: 1920,
+                y: 0,
+                width: 2560,
+                height: 1440,
+            },
+        ];
+        let output =
+            "0x03a00003  0 4242   1920 0    2560 1440 desk Deck.odp - LibreOffice  Impress\n\
+                      0x04000007 -1 5150   760  40   400  48   desk zoom share toolbar window\n\
+                      0x04200001  0 0      0    0    1920 1050 desk Terminal\n\
+                      garbage\n";
+        let windows = parse_wmctrl(output, &screens);
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows[0].pid, 4242);
+        assert_eq!(windows[0].title, "Deck.odp - LibreOffice Impress");
+        assert!(windows[0].fullscreen);
+        assert_eq!(windows[1].title, "zoom share toolbar window");
+        assert!(!windows[1].fullscreen);
+        // Maximized under a panel: not full-screen.
+        assert!(!windows[2].fullscreen);
+    }
+
+    #[test]
+    fn test_parse_system_events() {
+        let output = "412\tKeynote\ttrue\tQuarterly review\n\
+                      980\tzoom.us\tfalse\tzoom share toolbar window\n\
+                      981\tFinder\tfalse\t\n\
+                      x\tBroken\tfalse\tline";
+        assert_eq!(
+            parse_system_events(output),
+            vec![
+                WindowInfo {
+                    pid: 412,
+                    app: Some("Keynote".to_string()),
+                    title: "Quarterly review".to_string(),
+                    fullscreen: true,
+                },
+                WindowInfo {
+                    pid: 980,
+                    app: Some("zoom.us".to_string()),
+                    title: "zoom share toolbar window".to_string(),
+                    fullscreen: false,
+                },
+                WindowInfo {
+                    pid: 981,
+                    app: Some("Finder".to_string()),
+                    title: String::new(),
+                    fullscreen: false,
+                },
+            ]
+        );
+    }
+}
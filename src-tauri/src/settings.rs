@@ -1,27 +1,700 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
 use sqlx::sqlite::SqlitePool;
 use sqlx::Row;
 use tauri::command;
 use tauri_plugin_sql::DbInstances;
+use thiserror::Error;
+
+use crate::calendar::buffers;
+use crate::{
+    dnd, focus, history, hotkeys, http_api, join, lights, mqtt, notifications, overtime, palettes,
+    schedule, screen_share, status_sync, timer, tray, working_hours,
+};
 
 const DB_URL: &str = "sqlite:morph.db";
 
-/// Default settings applied on first run.
-const DEFAULT_SETTINGS: &[(&str, &str)] = &[
-    ("border_thickness", "medium"),
-    ("border_position", "all"),
-    ("color_palette", "ambient"),
-    ("color_intensity", "normal"),
-    ("warning_30min", "true"),
-    ("warning_15min", "true"),
-    ("warning_5min", "true"),
-    ("warning_2min", "true"),
-    ("poll_interval_seconds", "60"),
-    ("launch_at_login", "false"),
-    ("selected_display", "primary"),
-    ("onboarding_complete", "false"),
-    ("ignored_calendar_ids", "[]"),
+/// Declare a string-backed settings enum. Variants serialize to (and parse
+/// from) the exact spelling stored in the settings table.
+macro_rules! setting_enum {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident => $value:literal),+ $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+        pub enum $name {
+            $(#[serde(rename = $value)] $variant),+
+        }
+
+        impl $name {
+            /// Every accepted value, in declaration order.
+            pub const VALUES: &'static [&'static str] = &[$($value),+];
+
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $($name::$variant => $value),+
+                }
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $($value => Ok($name::$variant),)+
+                    _ => Err(format!("expected one of {}", Self::VALUES.join(", "))),
+                }
+            }
+        }
+    };
+}
+
+setting_enum!(
+    /// Border thickness preset.
+    BorderThickness {
+        Thin => "thin",
+        Medium => "medium",
+        Thick => "thick",
+    }
+);
+
+setting_enum!(
+    /// Which screen edges display the border.
+    BorderPosition {
+        All => "all",
+        Top => "top",
+        Sides => "sides",
+        Bottom => "bottom",
+        TopSides => "top-sides",
+        BottomSides => "bottom-sides",
+    }
+);
+
+setting_enum!(
+    /// Opacity scaling applied to the palette.
+    ColorIntensity {
+        Subtle => "subtle",
+        Normal => "normal",
+        Vivid => "vivid",
+    }
+);
+
+setting_enum!(
+    /// Settings window theme.
+    ThemePreference {
+        System => "system",
+        Light => "light",
+        Dark => "dark",
+    }
+);
+
+setting_enum!(
+    /// Which smart lights mirror the border.
+    LightsBackend {
+        Off => "off",
+        Hue => "hue",
+        Lifx => "lifx",
+    }
+);
+
+setting_enum!(
+    /// What focus mode does with a blocked app.
+    FocusAppAction {
+        Warn => "warn",
+        Hide => "hide",
+        Quit => "quit",
+    }
+);
+
+setting_enum!(
+    /// What the border does while the screen is shared or presented.
+    ScreenShareAction {
+        Hide => "hide",
+        Minimal => "minimal",
+    }
+);
+
+setting_enum!(
+    /// What the border does outside working hours: show the dim
+    /// `off-hours` phase, or hide.
+    OffHoursMode {
+        Phase => "phase",
+        Hide => "hide",
+    }
+);
+
+impl BorderThickness {
+    /// Thickness in logical pixels.
+    pub fn px(self) -> f64 {
+        match self {
+            BorderThickness::Thin => 8.0,
+            BorderThickness::Medium => 16.0,
+            BorderThickness::Thick => 28.0,
+        }
+    }
+}
+
+/// Errors returned when reading or writing settings.
+#[derive(Debug, Error)]
+pub enum SettingsError {
+    #[error("unknown setting: {0}")]
+    UnknownKey(String),
+
+    #[error("invalid value '{value}' for {key}: {reason}")]
+    InvalidValue {
+        key: String,
+        value: String,
+        reason: String,
+    },
+
+    #[error("database error: {0}")]
+    Database(String),
+}
+
+const HTTP: &[&str] = &["http", "https"];
+
+/// The shape of the values accepted for a setting key.
+#[derive(Debug, Clone, Copy)]
+enum SettingKind {
+    Enum(&'static [&'static str]),
+    Bool,
+    Integer {
+        min: i64,
+        max: i64,
+    },
+    Text,
+    /// A local `HH:MM` time of day.
+    Time,
+    /// An absolute URL with one of the given schemes.
+    Url(&'static [&'static str]),
+    /// JSON array of strings.
+    StringList,
+    /// JSON array of domain names (or URLs to take the host from).
+    Domains,
+    /// A global shortcut like `CommandOrControl+Alt+T`, or empty for none.
+    Hotkey,
+    /// JSON array of `timer::TimerPreset`.
+    TimerPresets,
+    /// JSON array of `timer::TimerSequence`.
+    TimerSequences,
+    /// JSON array of `schedule::ScheduleRule`.
+    ScheduleRules,
+    /// JSON array of `calendar::buffers::BufferRule`.
+    BufferRules,
+    /// JSON array of `screen_share::ShareRule`.
+    ShareRules,
+    /// JSON object of `working_hours::WorkingHours`.
+    WorkingHours,
+    /// A built-in palette ID or `custom-<slug>`; see `palettes::is_valid_id`.
+    PaletteId,
+}
+
+impl SettingKind {
+    fn check(self, value: &str) -> Result<(), String> {
+        match self {
+            SettingKind::Enum(values) => {
+                if values.contains(&value) {
+                    Ok(())
+                } else {
+                    Err(format!("expected one of {}", values.join(", ")))
+                }
+            }
+            SettingKind::Bool => match value {
+                "true" | "false" => Ok(()),
+                _ => Err("expected true or false".to_string()),
+            },
+            SettingKind::Integer { min, max } => match value.parse::<i64>() {
+                Ok(n) if (min..=max).contains(&n) => Ok(()),
+                Ok(_) => Err(format!("expected an integer between {min} and {max}")),
+                Err(_) => Err("expected an integer".to_string()),
+            },
+            SettingKind::Text => {
+                if value.trim().is_empty() {
+                    Err("must not be empty".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+            SettingKind::Time => chrono::NaiveTime::parse_from_str(value, "%H:%M")
+                .map(|_| ())
+                .map_err(|_| "expected a time as HH:MM".to_string()),
+            SettingKind::Url(schemes) => match url::Url::parse(value) {
+                Ok(url) if schemes.contains(&url.scheme()) && url.has_host() => Ok(()),
+                _ => Err(format!("expected a {} URL", schemes.join(" or "))),
+            },
+            SettingKind::StringList => serde_json::from_str::<Vec<String>>(value)
+                .map(|_| ())
+                .map_err(|e| format!("expected a JSON array of strings ({e})")),
+            SettingKind::Domains => {
+                let entries: Vec<String> = serde_json::from_str(value)
+                    .map_err(|e| format!("expected a JSON array of domains ({e})"))?;
+                match entries
+                    .iter()
+                    .find(|d| focus::hosts::normalize_domain(d).is_none())
+                {
+                    Some(bad) => Err(format!("'{bad}' is not a domain name")),
+                    None => Ok(()),
+                }
+            }
+            SettingKind::Hotkey => hotkeys::validate(value),
+            SettingKind::TimerPresets => timer::parse_custom_presets(value).map(|_| ()),
+            SettingKind::TimerSequences => timer::parse_sequences(value).map(|_| ()),
+            SettingKind::ScheduleRules => schedule::parse_rules(value).map(|_| ()),
+            SettingKind::WorkingHours => working_hours::parse(value).map(|_| ()),
+            SettingKind::BufferRules => buffers::parse_rules(value).map(|_| ()),
+            SettingKind::ShareRules => screen_share::parse_rules(value).map(|_| ()),
+            SettingKind::PaletteId => {
+                if palettes::is_valid_id(value) {
+                    Ok(())
+                } else {
+                    Err("expected a built-in palette or custom-<name>".to_string())
+                }
+            }
+        }
+    }
+}
+
+/// A known setting key with its default value and accepted values.
+#[derive(Debug, Clone, Copy)]
+pub struct SettingSpec {
+    pub key: &'static str,
+    pub default: &'static str,
+    /// Whether the value travels with exports and profiles. Secrets and
+    /// machine-specific values stay local.
+    pub portable: bool,
+    kind: SettingKind,
+}
+
+const fn spec(key: &'static str, default: &'static str, kind: SettingKind) -> SettingSpec {
+    SettingSpec {
+        key,
+        default,
+        portable: true,
+        kind,
+    }
+}
+
+/// A setting that is never exported or stored in a profile.
+const fn local(key: &'static str, default: &'static str, kind: SettingKind) -> SettingSpec {
+    SettingSpec {
+        key,
+        default,
+        portable: false,
+        kind,
+    }
+}
+
+/// Every setting the backend accepts, with its default (applied on first run).
+pub const SCHEMA: &[SettingSpec] = &[
+    spec(
+        "border_thickness",
+        "medium",
+        SettingKind::Enum(BorderThickness::VALUES),
+    ),
+    spec(
+        "border_position",
+        "all",
+        SettingKind::Enum(BorderPosition::VALUES),
+    ),
+    spec(
+        palettes::ACTIVE_KEY,
+        palettes::DEFAULT_PALETTE,
+        SettingKind::PaletteId,
+    ),
+    spec(
+        "color_intensity",
+        "normal",
+        SettingKind::Enum(ColorIntensity::VALUES),
+    ),
+    spec("warning_30min", "true", SettingKind::Bool),
+    spec("warning_15min", "true", SettingKind::Bool),
+    spec("warning_5min", "true", SettingKind::Bool),
+    spec("warning_2min", "true", SettingKind::Bool),
+    spec(
+        "poll_interval_seconds",
+        "60",
+        SettingKind::Integer { min: 15, max: 3600 },
+    ),
+    local("launch_at_login", "false", SettingKind::Bool),
+    local("selected_display", "primary", SettingKind::Text),
+    local("onboarding_complete", "false", SettingKind::Bool),
+    spec("ignored_calendar_ids", "[]", SettingKind::StringList),
+    spec(
+        "theme_preference",
+        "system",
+        SettingKind::Enum(ThemePreference::VALUES),
+    ),
+    spec("custom_timer_presets", "[]", SettingKind::TimerPresets),
+    spec("timer_sequences", "[]", SettingKind::TimerSequences),
+    spec(schedule::RULES_KEY, "[]", SettingKind::ScheduleRules),
+    spec(working_hours::ENABLED_KEY, "false", SettingKind::Bool),
+    spec(
+        working_hours::HOURS_KEY,
+        working_hours::DEFAULT_HOURS,
+        SettingKind::WorkingHours,
+    ),
+    spec(
+        working_hours::MODE_KEY,
+        "phase",
+        SettingKind::Enum(OffHoursMode::VALUES),
+    ),
+    spec(buffers::RULES_KEY, "[]", SettingKind::BufferRules),
+    spec(
+        history::RETENTION_KEY,
+        "90",
+        SettingKind::Integer { min: 1, max: 3650 },
+    ),
+    spec(
+        overtime::MAX_MINUTES_KEY,
+        "15",
+        SettingKind::Integer { min: 1, max: 120 },
+    ),
+    local(http_api::ENABLED_KEY, "false", SettingKind::Bool),
+    local(
+        http_api::PORT_KEY,
+        "7317",
+        SettingKind::Integer {
+            min: 1024,
+            max: 65535,
+        },
+    ),
+    local(status_sync::SLACK_ENABLED_KEY, "false", SettingKind::Bool),
+    local(status_sync::TEAMS_ENABLED_KEY, "false", SettingKind::Bool),
+    spec(
+        status_sync::MEETING_EMOJI_KEY,
+        ":calendar:",
+        SettingKind::Text,
+    ),
+    spec(
+        status_sync::FOCUS_EMOJI_KEY,
+        ":headphones:",
+        SettingKind::Text,
+    ),
+    local(
+        status_sync::SLACK_API_BASE_KEY,
+        "https://slack.com/api",
+        SettingKind::Url(HTTP),
+    ),
+    local(
+        status_sync::GRAPH_API_BASE_KEY,
+        "https://graph.microsoft.com/v1.0",
+        SettingKind::Url(HTTP),
+    ),
+    local(
+        lights::BACKEND_KEY,
+        "off",
+        SettingKind::Enum(LightsBackend::VALUES),
+    ),
+    local(
+        lights::HUE_BRIDGE_KEY,
+        "http://philips-hue.local",
+        SettingKind::Url(HTTP),
+    ),
+    local(lights::HUE_GROUP_KEY, "0", SettingKind::Text),
+    local(
+        lights::LIFX_ADDRESS_KEY,
+        "255.255.255.255",
+        SettingKind::Text,
+    ),
+    spec(lights::OFF_PHASES_KEY, "[]", SettingKind::StringList),
+    spec(
+        lights::MAX_BRIGHTNESS_KEY,
+        "100",
+        SettingKind::Integer { min: 1, max: 100 },
+    ),
+    local(mqtt::ENABLED_KEY, "false", SettingKind::Bool),
+    local(
+        mqtt::BROKER_URL_KEY,
+        "mqtt://localhost:1883",
+        SettingKind::Url(&["mqtt", "mqtts"]),
+    ),
+    local(mqtt::TOPIC_PREFIX_KEY, "morph", SettingKind::Text),
+    local(
+        mqtt::DISCOVERY_PREFIX_KEY,
+        "homeassistant",
+        SettingKind::Text,
+    ),
+    spec(notifications::WARNING_5MIN_KEY, "false", SettingKind::Bool),
+    spec(notifications::WARNING_2MIN_KEY, "false", SettingKind::Bool),
+    spec(notifications::MEETING_END_KEY, "false", SettingKind::Bool),
+    spec(
+        notifications::TIMER_COMPLETE_KEY,
+        "false",
+        SettingKind::Bool,
+    ),
+    spec(notifications::QUIET_HOURS_KEY, "false", SettingKind::Bool),
+    spec(notifications::QUIET_START_KEY, "22:00", SettingKind::Time),
+    spec(notifications::QUIET_END_KEY, "08:00", SettingKind::Time),
+    spec(join::AUTO_JOIN_KEY, "false", SettingKind::Bool),
+    spec(dnd::ENABLED_KEY, "false", SettingKind::Bool),
+    spec(focus::ENABLED_KEY, "false", SettingKind::Bool),
+    spec(focus::BLOCKED_APPS_KEY, "[]", SettingKind::StringList),
+    spec(
+        focus::APP_ACTION_KEY,
+        "warn",
+        SettingKind::Enum(FocusAppAction::VALUES),
+    ),
+    spec(focus::BLOCKED_SITES_KEY, "[]", SettingKind::Domains),
+    local(focus::BLOCK_SITES_KEY, "false", SettingKind::Bool),
+    spec(focus::CALENDAR_KEYWORDS_KEY, "[]", SettingKind::StringList),
+    spec(screen_share::ENABLED_KEY, "false", SettingKind::Bool),
+    spec(
+        screen_share::ACTION_KEY,
+        "hide",
+        SettingKind::Enum(ScreenShareAction::VALUES),
+    ),
+    spec(
+        screen_share::RULES_KEY,
+        screen_share::DEFAULT_RULES,
+        SettingKind::ShareRules,
+    ),
+    spec(tray::COUNTDOWN_TITLE_KEY, "false", SettingKind::Bool),
+    local(hotkeys::ENABLED_KEY, "false", SettingKind::Bool),
+    local(
+        hotkeys::START_TIMER_KEY,
+        "CommandOrControl+Alt+Shift+T",
+        SettingKind::Hotkey,
+    ),
+    local(
+        hotkeys::TOGGLE_TIMER_KEY,
+        "CommandOrControl+Alt+Shift+P",
+        SettingKind::Hotkey,
+    ),
+    local(
+        hotkeys::PAUSE_BORDER_KEY,
+        "CommandOrControl+Alt+Shift+B",
+        SettingKind::Hotkey,
+    ),
+    local(
+        hotkeys::HIDE_BORDER_KEY,
+        "CommandOrControl+Alt+Shift+H",
+        SettingKind::Hotkey,
+    ),
+    local(
+        hotkeys::SYNC_KEY,
+        "CommandOrControl+Alt+Shift+S",
+        SettingKind::Hotkey,
+    ),
 ];
 
+/// Look up the schema entry for a key.
+pub fn setting_spec(key: &str) -> Option<&'static SettingSpec> {
+    SCHEMA.iter().find(|s| s.key == key)
+}
+
+/// Check that `value` is acceptable for `key`.
+pub fn validate_setting(key: &str, value: &str) -> Result<(), SettingsError> {
+    let spec = setting_spec(key).ok_or_else(|| SettingsError::UnknownKey(key.to_string()))?;
+    spec.kind
+        .check(value)
+        .map_err(|reason| SettingsError::InvalidValue {
+            key: key.to_string(),
+            value: value.to_string(),
+            reason,
+        })
+}
+
+/// All settings, parsed into their typed values.
+///
+/// Field names match the setting keys so the frontend can map each field
+/// back to the key it writes with `set_setting`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Settings {
+    pub border_thickness: BorderThickness,
+    pub border_position: BorderPosition,
+    /// ID of the active palette (built-in or custom).
+    pub color_palette: String,
+    pub color_intensity: ColorIntensity,
+    pub warning_30min: bool,
+    pub warning_15min: bool,
+    pub warning_5min: bool,
+    pub warning_2min: bool,
+    pub poll_interval_seconds: u32,
+    pub launch_at_login: bool,
+    pub selected_display: String,
+    pub onboarding_complete: bool,
+    pub ignored_calendar_ids: Vec<String>,
+    pub theme_preference: ThemePreference,
+    pub custom_timer_presets: Vec<timer::TimerPreset>,
+    pub timer_sequences: Vec<timer::TimerSequence>,
+    pub schedule_rules: Vec<schedule::ScheduleRule>,
+    pub working_hours_enabled: bool,
+    pub working_hours: working_hours::WorkingHours,
+    pub off_hours_mode: OffHoursMode,
+    pub buffer_rules: Vec<buffers::BufferRule>,
+    pub history_retention_days: u32,
+    pub overtime_max_minutes: u32,
+    pub http_api_enabled: bool,
+    pub http_api_port: u16,
+    pub status_sync_slack: bool,
+    pub status_sync_teams: bool,
+    pub status_meeting_emoji: String,
+    pub status_focus_emoji: String,
+    pub slack_api_base: String,
+    pub graph_api_base: String,
+    pub lights_backend: LightsBackend,
+    pub hue_bridge_url: String,
+    pub hue_group_id: String,
+    pub lifx_address: String,
+    pub lights_off_phases: Vec<String>,
+    pub lights_max_brightness: u8,
+    pub mqtt_enabled: bool,
+    pub mqtt_broker_url: String,
+    pub mqtt_topic_prefix: String,
+    pub mqtt_discovery_prefix: String,
+    pub notify_warning_5min: bool,
+    pub notify_warning_2min: bool,
+    pub notify_meeting_end: bool,
+    pub notify_timer_complete: bool,
+    pub notify_quiet_hours: bool,
+    pub notify_quiet_start: String,
+    pub notify_quiet_end: String,
+    pub auto_join_meetings: bool,
+    pub dnd_sync: bool,
+    pub focus_mode: bool,
+    pub focus_blocked_apps: Vec<String>,
+    pub focus_app_action: FocusAppAction,
+    pub focus_blocked_sites: Vec<String>,
+    pub focus_block_sites: bool,
+    pub focus_calendar_keywords: Vec<String>,
+    pub screen_share_detection: bool,
+    pub screen_share_action: ScreenShareAction,
+    pub screen_share_rules: Vec<screen_share::ShareRule>,
+    pub tray_countdown_title: bool,
+    pub hotkeys_enabled: bool,
+    pub hotkey_start_timer: String,
+    pub hotkey_toggle_timer: String,
+    pub hotkey_pause_border: String,
+    pub hotkey_hide_border: String,
+    pub hotkey_sync: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::from_pairs(&[])
+    }
+}
+
+impl Settings {
+    /// Build typed settings from stored (key, value) pairs. Missing keys and
+    /// values that fail validation fall back to the schema default; unknown
+    /// keys are ignored.
+    pub fn from_pairs(pairs: &[(String, String)]) -> Self {
+        let stored: HashMap<&str, &str> = pairs
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+
+        let value = |key: &'static str| -> &str {
+            let spec = setting_spec(key).expect("key is in SCHEMA");
+            match stored.get(key) {
+                Some(v) => match spec.kind.check(v) {
+                    Ok(()) => v,
+                    Err(reason) => {
+                        eprintln!("[settings] Ignoring invalid stored {key}='{v}': {reason}");
+                        spec.default
+                    }
+                },
+                None => spec.default,
+            }
+        };
+        let flag = |key: &'static str| value(key) == "true";
+
+        Self {
+            border_thickness: value("border_thickness")
+                .parse()
+                .unwrap_or(BorderThickness::Medium),
+            border_position: value("border_position")
+                .parse()
+                .unwrap_or(BorderPosition::All),
+            color_palette: value(palettes::ACTIVE_KEY).to_string(),
+            color_intensity: value("color_intensity")
+                .parse()
+                .unwrap_or(ColorIntensity::Normal),
+            warning_30min: flag("warning_30min"),
+            warning_15min: flag("warning_15min"),
+            warning_5min: flag("warning_5min"),
+            warning_2min: flag("warning_2min"),
+            poll_interval_seconds: value("poll_interval_seconds").parse().unwrap_or(60),
+            launch_at_login: flag("launch_at_login"),
+            selected_display: value("selected_display").to_string(),
+            onboarding_complete: flag("onboarding_complete"),
+            ignored_calendar_ids: serde_json::from_str(value("ignored_calendar_ids"))
+                .unwrap_or_default(),
+            theme_preference: value("theme_preference")
+                .parse()
+                .unwrap_or(ThemePreference::System),
+            custom_timer_presets: timer::parse_custom_presets(value("custom_timer_presets"))
+                .unwrap_or_default(),
+            timer_sequences: timer::parse_sequences(value("timer_sequences")).unwrap_or_default(),
+            schedule_rules: schedule::parse_rules(value(schedule::RULES_KEY)).unwrap_or_default(),
+            working_hours_enabled: flag(working_hours::ENABLED_KEY),
+            working_hours: working_hours::parse(value(working_hours::HOURS_KEY))
+                .unwrap_or_default(),
+            off_hours_mode: value(working_hours::MODE_KEY)
+                .parse()
+                .unwrap_or(OffHoursMode::Phase),
+            buffer_rules: buffers::parse_rules(value(buffers::RULES_KEY)).unwrap_or_default(),
+            history_retention_days: value(history::RETENTION_KEY).parse().unwrap_or(90),
+            overtime_max_minutes: value(overtime::MAX_MINUTES_KEY).parse().unwrap_or(15),
+            http_api_enabled: flag(http_api::ENABLED_KEY),
+            http_api_port: value(http_api::PORT_KEY).parse().unwrap_or(7317),
+            status_sync_slack: flag(status_sync::SLACK_ENABLED_KEY),
+            status_sync_teams: flag(status_sync::TEAMS_ENABLED_KEY),
+            status_meeting_emoji: value(status_sync::MEETING_EMOJI_KEY).to_string(),
+            status_focus_emoji: value(status_sync::FOCUS_EMOJI_KEY).to_string(),
+            slack_api_base: value(status_sync::SLACK_API_BASE_KEY).to_string(),
+            graph_api_base: value(status_sync::GRAPH_API_BASE_KEY).to_string(),
+            lights_backend: value(lights::BACKEND_KEY)
+                .parse()
+                .unwrap_or(LightsBackend::Off),
+            hue_bridge_url: value(lights::HUE_BRIDGE_KEY).to_string(),
+            hue_group_id: value(lights::HUE_GROUP_KEY).to_string(),
+            lifx_address: value(lights::LIFX_ADDRESS_KEY).to_string(),
+            lights_off_phases: serde_json::from_str(value(lights::OFF_PHASES_KEY))
+                .unwrap_or_default(),
+            lights_max_brightness: value(lights::MAX_BRIGHTNESS_KEY).parse().unwrap_or(100),
+            mqtt_enabled: flag(mqtt::ENABLED_KEY),
+            mqtt_broker_url: value(mqtt::BROKER_URL_KEY).to_string(),
+            mqtt_topic_prefix: value(mqtt::TOPIC_PREFIX_KEY).to_string(),
+            mqtt_discovery_prefix: value(mqtt::DISCOVERY_PREFIX_KEY).to_string(),
+            notify_warning_5min: flag(notifications::WARNING_5MIN_KEY),
+            notify_warning_2min: flag(notifications::WARNING_2MIN_KEY),
+            notify_meeting_end: flag(notifications::MEETING_END_KEY),
+            notify_timer_complete: flag(notifications::TIMER_COMPLETE_KEY),
+            notify_quiet_hours: flag(notifications::QUIET_HOURS_KEY),
+            notify_quiet_start: value(notifications::QUIET_START_KEY).to_string(),
+            notify_quiet_end: value(notifications::QUIET_END_KEY).to_string(),
+            auto_join_meetings: flag(join::AUTO_JOIN_KEY),
+            dnd_sync: flag(dnd::ENABLED_KEY),
+            focus_mode: flag(focus::ENABLED_KEY),
+            focus_blocked_apps: serde_json::from_str(value(focus::BLOCKED_APPS_KEY))
+                .unwrap_or_default(),
+            focus_app_action: value(focus::APP_ACTION_KEY)
+                .parse()
+                .unwrap_or(FocusAppAction::Warn),
+            focus_blocked_sites: serde_json::from_str(value(focus::BLOCKED_SITES_KEY))
+                .unwrap_or_default(),
+            focus_block_sites: flag(focus::BLOCK_SITES_KEY),
+            focus_calendar_keywords: serde_json::from_str(value(focus::CALENDAR_KEYWORDS_KEY))
+                .unwrap_or_default(),
+            screen_share_detection: flag(screen_share::ENABLED_KEY),
+            screen_share_action: value(screen_share::ACTION_KEY)
+                .parse()
+                .unwrap_or(ScreenShareAction::Hide),
+            screen_share_rules: screen_share::parse_rules(value(screen_share::RULES_KEY))
+                .unwrap_or_default(),
+            tray_countdown_title: flag(tray::COUNTDOWN_TITLE_KEY),
+            hotkeys_enabled: flag(hotkeys::ENABLED_KEY),
+            hotkey_start_timer: value(hotkeys::START_TIMER_KEY).to_string(),
+            hotkey_toggle_timer: value(hotkeys::TOGGLE_TIMER_KEY).to_string(),
+            hotkey_pause_border: value(hotkeys::PAUSE_BORDER_KEY).to_string(),
+            hotkey_hide_border: value(hotkeys::HIDE_BORDER_KEY).to_string(),
+            hotkey_sync: value(hotkeys::SYNC_KEY).to_string(),
+        }
+    }
+}
+
 /// Helper to get the SQLite pool from the plugin's managed state.
 async fn get_pool(
     db_instances: &tauri::State<'_, DbInstances>,
@@ -37,6 +710,12 @@ async fn get_pool(
     }
 }
 
+/// Get the SQLite pool from an app handle (background services, tray handlers).
+pub(crate) async fn app_pool(app: &tauri::AppHandle) -> Result<SqlitePool, String> {
+    use tauri::Manager;
+    get_pool(&app.state::<DbInstances>()).await
+}
+
 // --- Pool-based inner functions (testable without Tauri runtime) ---
 
 async fn get_setting_inner(pool: &SqlitePool, key: &str) -> Result<Option<String>, String> {
@@ -48,7 +727,16 @@ async fn get_setting_inner(pool: &SqlitePool, key: &str) -> Result<Option<String
     Ok(row.map(|r| r.get("value")))
 }
 
-async fn set_setting_inner(pool: &SqlitePool, key: &str, value: &str) -> Result<(), String> {
+pub(crate) async fn set_setting_inner(
+    pool: &SqlitePool,
+    key: &str,
+    value: &str,
+) -> Result<(), String> {
+    validate_setting(key, value).map_err(|e| e.to_string())?;
+    if hotkeys::KEYS.contains(&key) {
+        let current = load_settings_inner(pool).await?;
+        hotkeys::check_conflict(&current, key, value)?;
+    }
     sqlx::query(
         "INSERT INTO settings (key, value, updated_at) VALUES (?1, ?2, datetime('now'))
          ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = datetime('now')",
@@ -61,7 +749,9 @@ async fn set_setting_inner(pool: &SqlitePool, key: &str, value: &str) -> Result<
     Ok(())
 }
 
-async fn get_all_settings_inner(pool: &SqlitePool) -> Result<Vec<(String, String)>, String> {
+pub(crate) async fn get_all_settings_inner(
+    pool: &SqlitePool,
+) -> Result<Vec<(String, String)>, String> {
     let rows = sqlx::query("SELECT key, value FROM settings ORDER BY key")
         .fetch_all(pool)
         .await
@@ -72,8 +762,18 @@ async fn get_all_settings_inner(pool: &SqlitePool) -> Result<Vec<(String, String
         .collect())
 }
 
+async fn load_settings_inner(pool: &SqlitePool) -> Result<Settings, String> {
+    let pairs = get_all_settings_inner(pool).await?;
+    Ok(Settings::from_pairs(&pairs))
+}
+
 async fn seed_defaults_inner(pool: &SqlitePool) -> Result<(), String> {
-    for (key, value) in DEFAULT_SETTINGS {
+    for SettingSpec {
+        key,
+        default: value,
+        ..
+    } in SCHEMA
+    {
         sqlx::query(
             "INSERT OR IGNORE INTO settings (key, value, updated_at) VALUES (?1, ?2, datetime('now'))",
         )
@@ -86,6 +786,52 @@ async fn seed_defaults_inner(pool: &SqlitePool) -> Result<(), String> {
     Ok(())
 }
 
+/// Read a single setting from outside a Tauri command (background services,
+/// tray handlers). Returns `None` if the database or key is unavailable.
+pub async fn read_setting(app: &tauri::AppHandle, key: &str) -> Option<String> {
+    let pool = app_pool(app).await.ok()?;
+    get_setting_inner(&pool, key).await.ok().flatten()
+}
+
+/// Stored key-value pairs with any active schedule overrides layered on top.
+pub(crate) async fn effective_pairs(
+    app: &tauri::AppHandle,
+) -> Result<Vec<(String, String)>, String> {
+    let pool = app_pool(app).await?;
+    let mut pairs = get_all_settings_inner(&pool).await?;
+    schedule::apply_overrides(&mut pairs, &schedule::active_overrides(app));
+    Ok(pairs)
+}
+
+/// Load stored settings with any active schedule overrides layered on top.
+async fn effective_settings(app: &tauri::AppHandle) -> Result<Settings, String> {
+    Ok(Settings::from_pairs(&effective_pairs(app).await?))
+}
+
+/// Load effective typed settings (stored values plus schedule overrides) from
+/// outside a Tauri command. Falls back to defaults if the database is
+/// unavailable.
+pub async fn current_settings(app: &tauri::AppHandle) -> Settings {
+    effective_settings(app).await.unwrap_or_else(|e| {
+        eprintln!("[settings] Failed to load settings: {e}");
+        Settings::default()
+    })
+}
+
+/// Notify all windows (overlay + settings) and backend listeners that a
+/// setting changed. Keys overridden by the active schedule rule are skipped
+/// because their effective value did not change.
+pub(crate) fn emit_setting_changed(app: &tauri::AppHandle, key: &str, value: &str) {
+    use tauri::Emitter;
+    if schedule::is_overridden(app, key) {
+        return;
+    }
+    let _ = app.emit(
+        "settings-changed",
+        serde_json::json!({ "key": key, "value": value }),
+    );
+}
+
 // --- Tauri commands (delegate to inner functions) ---
 
 /// Read a single setting by key. Returns `None` if the key does not exist.
@@ -98,7 +844,8 @@ pub async fn get_setting(
     get_setting_inner(&pool, &key).await
 }
 
-/// Insert or update a setting. Emits a `settings-changed` event so the
+/// Insert or update a setting. Rejects unknown keys and values that fail the
+/// schema with a descriptive error. Emits a `settings-changed` event so the
 /// overlay can react to changes in real time.
 #[command]
 pub async fn set_setting(
@@ -110,23 +857,25 @@ pub async fn set_setting(
     let pool = get_pool(&db_instances).await?;
     set_setting_inner(&pool, &key, &value).await?;
 
-    // Notify all windows (overlay + settings) about the change
-    use tauri::Emitter;
-    let _ = app.emit(
-        "settings-changed",
-        serde_json::json!({ "key": key, "value": value }),
-    );
-
+    emit_setting_changed(&app, &key, &value);
     Ok(())
 }
 
-/// Return all settings as a list of (key, value) pairs.
+/// Return all settings as a typed object, with stored values only. The
+/// settings UI edits these.
 #[command]
 pub async fn get_all_settings(
     db_instances: tauri::State<'_, DbInstances>,
-) -> Result<Vec<(String, String)>, String> {
+) -> Result<Settings, String> {
     let pool = get_pool(&db_instances).await?;
-    get_all_settings_inner(&pool).await
+    load_settings_inner(&pool).await
+}
+
+/// Return the settings currently in effect, including overrides from the
+/// active schedule rule. The overlay renders these.
+#[command]
+pub async fn get_effective_settings(app: tauri::AppHandle) -> Result<Settings, String> {
+    effective_settings(&app).await
 }
 
 /// Seed default settings. Only inserts rows that do not already exist.
@@ -172,15 +921,17 @@ mod tests {
         let all = get_all_settings_inner(&pool).await.expect("get_all failed");
         let keys: Vec<&str> = all.iter().map(|(k, _)| k.as_str()).collect();
 
-        for (expected_key, expected_value) in DEFAULT_SETTINGS {
+        for spec in SCHEMA {
             assert!(
-                keys.contains(expected_key),
-                "Missing default key: {expected_key}"
+                keys.contains(&spec.key),
+                "Missing default key: {}",
+                spec.key
             );
-            let actual = all.iter().find(|(k, _)| k == expected_key).unwrap();
+            let actual = all.iter().find(|(k, _)| k == spec.key).unwrap();
             assert_eq!(
-                actual.1, *expected_value,
-                "Wrong default value for {expected_key}"
+                actual.1, spec.default,
+                "Wrong default value for {}",
+                spec.key
             );
         }
     }
@@ -188,9 +939,11 @@ mod tests {
     #[tokio::test]
     async fn test_get_set_round_trip() {
         let pool = test_pool().await;
-        set_setting_inner(&pool, "theme", "dark").await.unwrap();
+        set_setting_inner(&pool, "theme_preference", "dark")
+            .await
+            .unwrap();
 
-        let val = get_setting_inner(&pool, "theme").await.unwrap();
+        let val = get_setting_inner(&pool, "theme_preference").await.unwrap();
         assert_eq!(val, Some("dark".to_string()));
     }
 
@@ -207,16 +960,143 @@ mod tests {
         seed_defaults_inner(&pool).await.unwrap();
 
         let all = get_all_settings_inner(&pool).await.unwrap();
-        assert_eq!(all.len(), DEFAULT_SETTINGS.len());
+        assert_eq!(all.len(), SCHEMA.len());
     }
 
     #[tokio::test]
     async fn test_set_upserts() {
         let pool = test_pool().await;
-        set_setting_inner(&pool, "color", "red").await.unwrap();
-        set_setting_inner(&pool, "color", "blue").await.unwrap();
+        set_setting_inner(&pool, "color_palette", "ambient")
+            .await
+            .unwrap();
+        set_setting_inner(&pool, "color_palette", "ocean")
+            .await
+            .unwrap();
+
+        let val = get_setting_inner(&pool, "color_palette").await.unwrap();
+        assert_eq!(val, Some("ocean".to_string()));
+    }
+
+    #[test]
+    fn test_schema_defaults_are_valid() {
+        for spec in SCHEMA {
+            validate_setting(spec.key, spec.default)
+                .unwrap_or_else(|e| panic!("default for {} is invalid: {e}", spec.key));
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_values() {
+        let cases = [
+            ("border_thickness", "huge"),
+            ("warning_5min", "yes"),
+            ("poll_interval_seconds", "5"),
+            ("poll_interval_seconds", "soon"),
+            ("selected_display", "  "),
+            ("ignored_calendar_ids", "cal-1"),
+            ("custom_timer_presets", "[{\"id\":\"pomodoro\"}]"),
+            ("slack_api_base", "slack.com/api"),
+            ("graph_api_base", "ftp://localhost/graph"),
+            ("mqtt_broker_url", "http://localhost:1883"),
+            ("notify_quiet_start", "25:00"),
+            ("focus_app_action", "delete"),
+            ("focus_blocked_sites", "[\"reddit.com\", \"not a domain\"]"),
+            ("hotkey_sync", "Shift+S"),
+            ("hotkey_hide_border", "Ctrl+Alt+Wheel"),
+        ];
+        for (key, value) in cases {
+            assert!(
+                matches!(
+                    validate_setting(key, value),
+                    Err(SettingsError::InvalidValue { .. })
+                ),
+                "{key}={value} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_key() {
+        assert!(matches!(
+            validate_setting("no_such_key", "1"),
+            Err(SettingsError::UnknownKey(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_set_rejects_invalid_value_without_writing() {
+        let pool = test_pool().await;
+        seed_defaults_inner(&pool).await.unwrap();
+
+        let err = set_setting_inner(&pool, "border_position", "diagonal")
+            .await
+            .unwrap_err();
+        assert!(err.contains("border_position"), "unexpected error: {err}");
+
+        let val = get_setting_inner(&pool, "border_position").await.unwrap();
+        assert_eq!(val, Some("all".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_set_rejects_hotkey_bound_to_another_action() {
+        let pool = test_pool().await;
+        seed_defaults_inner(&pool).await.unwrap();
+
+        let taken = Settings::default().hotkey_start_timer;
+        let err = set_setting_inner(&pool, "hotkey_sync", &taken)
+            .await
+            .unwrap_err();
+        assert!(err.contains("already"), "unexpected error: {err}");
+
+        set_setting_inner(&pool, "hotkey_start_timer", "")
+            .await
+            .unwrap();
+        set_setting_inner(&pool, "hotkey_sync", &taken)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_load_settings_typed() {
+        let pool = test_pool().await;
+        seed_defaults_inner(&pool).await.unwrap();
+        set_setting_inner(&pool, "border_thickness", "thick")
+            .await
+            .unwrap();
+        set_setting_inner(&pool, "poll_interval_seconds", "120")
+            .await
+            .unwrap();
+        set_setting_inner(&pool, "ignored_calendar_ids", "[\"work\"]")
+            .await
+            .unwrap();
 
-        let val = get_setting_inner(&pool, "color").await.unwrap();
-        assert_eq!(val, Some("blue".to_string()));
+        let settings = load_settings_inner(&pool).await.unwrap();
+        assert_eq!(settings.border_thickness, BorderThickness::Thick);
+        assert_eq!(settings.border_thickness.px(), 28.0);
+        assert_eq!(settings.poll_interval_seconds, 120);
+        assert_eq!(settings.ignored_calendar_ids, vec!["work".to_string()]);
+        assert!(settings.warning_5min);
+    }
+
+    #[test]
+    fn test_from_pairs_falls_back_on_invalid_stored_value() {
+        let pairs = vec![
+            ("border_position".to_string(), "diagonal".to_string()),
+            ("launch_at_login".to_string(), "true".to_string()),
+            ("legacy_key".to_string(), "x".to_string()),
+        ];
+        let settings = Settings::from_pairs(&pairs);
+        assert_eq!(settings.border_position, BorderPosition::All);
+        assert!(settings.launch_at_login);
+    }
+
+    #[test]
+    fn test_settings_serialize_with_setting_keys() {
+        let json = serde_json::to_value(Settings::default()).unwrap();
+        for spec in SCHEMA {
+            assert!(json.get(spec.key).is_some(), "missing field {}", spec.key);
+        }
+        assert_eq!(json["border_position"], "all");
+        assert_eq!(json["poll_interval_seconds"], 60);
     }
 }
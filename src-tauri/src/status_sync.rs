@@ -0,0 +1,842 @@
+//! Slack and Microsoft Teams status sync.
+//!
+//! While a meeting is in session the status reads "In a meeting until 14:30";
+//! while a focus timer runs it reads "Focusing until 15:05". Meetings win
+//! when both apply. The status is cleared once neither does.
+//!
+//! Every status is sent with an expiry at its end time, so Slack and Teams
+//! clear it themselves if Morph quits or loses the network first.
+//!
+//! - Slack: `users.profile.set` with a user token that has the
+//!   `users.profile:write` scope, stored in the keyring.
+//! - Teams: Graph `setStatusMessage`, plus Do Not Disturb during focus, using
+//!   the Microsoft OAuth flow with the presence scope.
+//!
+//! Both API base URLs are settings so the sync can run against local mock
+//! servers.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{command, AppHandle, Listener, Manager};
+use thiserror::Error;
+
+use crate::calendar::error::CalendarError;
+use crate::calendar::microsoft::{MicrosoftCalendarProvider, PRESENCE_KEYRING_SERVICE};
+use crate::calendar::provider::CalendarProvider;
+use crate::calendar::types::CalendarEvent;
+use crate::settings::{self, Settings};
+use crate::timer::{TimerService, TimerState};
+
+pub const SLACK_ENABLED_KEY: &str = "status_sync_slack";
+pub const TEAMS_ENABLED_KEY: &str = "status_sync_teams";
+pub const MEETING_EMOJI_KEY: &str = "status_meeting_emoji";
+pub const FOCUS_EMOJI_KEY: &str = "status_focus_emoji";
+pub const SLACK_API_BASE_KEY: &str = "slack_api_base";
+pub const GRAPH_API_BASE_KEY: &str = "graph_api_base";
+
+/// Settings that change what gets synced or where it is sent.
+pub const KEYS: &[&str] = &[
+    SLACK_ENABLED_KEY,
+    TEAMS_ENABLED_KEY,
+    MEETING_EMOJI_KEY,
+    FOCUS_EMOJI_KEY,
+    SLACK_API_BASE_KEY,
+    GRAPH_API_BASE_KEY,
+];
+
+const SLACK_KEYRING_SERVICE: &str = "com.morph.slack";
+const SLACK_KEYRING_KEY: &str = "user_token";
+
+/// How often the status is re-checked against the calendar. Timer changes
+/// trigger a check straight away.
+const SYNC_INTERVAL: Duration = Duration::from_secs(30);
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Error)]
+pub enum StatusError {
+    #[error("{service} request failed: {message}")]
+    Request {
+        service: &'static str,
+        message: String,
+    },
+
+    #[error("{service} rejected the request: {message}")]
+    Rejected {
+        service: &'static str,
+        message: String,
+    },
+
+    #[error(transparent)]
+    Auth(#[from] CalendarError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusKind {
+    Meeting,
+    Focus,
+}
+
+/// A status to show on the connected services.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Status {
+    pub kind: StatusKind,
+    pub text: String,
+    /// Slack emoji code such as `:calendar:`. Teams has no status emoji.
+    pub emoji: String,
+    pub until: DateTime<Utc>,
+}
+
+impl Status {
+    fn new(kind: StatusKind, emoji: &str, until: DateTime<Utc>) -> Self {
+        let label = match kind {
+            StatusKind::Meeting => "In a meeting",
+            StatusKind::Focus => "Focusing",
+        };
+        Self {
+            kind,
+            text: format!(
+                "{label} until {}",
+                until.with_timezone(&Local).format("%H:%M")
+            ),
+            emoji: emoji.to_string(),
+            until,
+        }
+    }
+}
+
+/// What decides the status, taken from settings.
+#[derive(Debug, Clone)]
+pub struct StatusConfig {
+    pub meeting_emoji: String,
+    pub focus_emoji: String,
+    pub ignored_calendar_ids: Vec<String>,
+}
+
+impl StatusConfig {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            meeting_emoji: settings.status_meeting_emoji.clone(),
+            focus_emoji: settings.status_focus_emoji.clone(),
+            ignored_calendar_ids: settings.ignored_calendar_ids.clone(),
+        }
+    }
+}
+
+/// The status that should be showing at `now`, if any. Overlapping meetings
+/// report the latest end.
+pub fn desired_status(
+    events: &[CalendarEvent],
+    timer: &TimerState,
+    now: DateTime<Utc>,
+    config: &StatusConfig,
+) -> Option<Status> {
+    let meeting_end = events
+        .iter()
+        .filter(|e| !e.ignored && !e.is_all_day)
+        .filter(|e| {
+            e.calendar_id
+                .as_ref()
+                .is_none_or(|id| !config.ignored_calendar_ids.contains(id))
+        })
+        .filter(|e| e.start_time <= now && now < e.end_time)
+        .map(|e| e.end_time)
+        .max();
+    if let Some(until) = meeting_end {
+        return Some(Status::new(
+            StatusKind::Meeting,
+            &config.meeting_emoji,
+            until,
+        ));
+    }
+    if timer.is_break {
+        return None;
+    }
+    timer
+        .ends_at()
+        .filter(|&until| until > now)
+        .map(|until| Status::new(StatusKind::Focus, &config.focus_emoji, until))
+}
+
+/// A chat service whose status Morph can set.
+#[async_trait]
+pub trait StatusService: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Show `status`, or clear the status when `None`.
+    async fn apply(&mut self, status: Option<&Status>) -> Result<(), StatusError>;
+}
+
+fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+// --- Slack ---
+
+#[derive(Debug, Deserialize)]
+struct SlackReply {
+    ok: bool,
+    error: Option<String>,
+    user: Option<String>,
+    team: Option<String>,
+}
+
+pub struct SlackStatus {
+    client: reqwest::Client,
+    api_base: String,
+    token: String,
+}
+
+impl SlackStatus {
+    pub fn new(api_base: &str, token: &str) -> Self {
+        Self {
+            client: client(),
+            api_base: api_base.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    /// Load the stored user token, if Slack is connected.
+    pub fn from_keyring(api_base: &str) -> Option<Self> {
+        let entry = keyring::Entry::new(SLACK_KEYRING_SERVICE, SLACK_KEYRING_KEY).ok()?;
+        let token = entry.get_password().ok()?;
+        Some(Self::new(api_base, &token))
+    }
+
+    async fn call(&self, method: &str, body: serde_json::Value) -> Result<SlackReply, StatusError> {
+        let request_failed = |e: reqwest::Error| StatusError::Request {
+            service: "Slack",
+            message: e.to_string(),
+        };
+        let resp = self
+            .client
+            .post(format!("{}/{method}", self.api_base))
+            .bearer_auth(&self.token)
+            .json(&body)
+            .send()
+            .await
+            .map_err(request_failed)?;
+        if !resp.status().is_success() {
+            return Err(StatusError::Request {
+                service: "Slack",
+                message: format!("HTTP {}", resp.status()),
+            });
+        }
+        // Slack reports failures as 200 with `"ok": false`.
+        let reply: SlackReply = resp.json().await.map_err(request_failed)?;
+        if !reply.ok {
+            return Err(StatusError::Rejected {
+                service: "Slack",
+                message: reply.error.unwrap_or_else(|| "unknown error".to_string()),
+            });
+        }
+        Ok(reply)
+    }
+
+    /// Check the token and return "user (team)" for display.
+    pub async fn verify(&self) -> Result<String, StatusError> {
+        let reply = self.call("auth.test", json!({})).await?;
+        Ok(format!(
+            "{} ({})",
+            reply.user.unwrap_or_default(),
+            reply.team.unwrap_or_default()
+        ))
+    }
+}
+
+#[async_trait]
+impl StatusService for SlackStatus {
+    fn name(&self) -> &'static str {
+        "Slack"
+    }
+
+    async fn apply(&mut self, status: Option<&Status>) -> Result<(), StatusError> {
+        let profile = match status {
+            Some(s) => json!({
+                "status_text": s.text,
+                "status_emoji": s.emoji,
+                "status_expiration": s.until.timestamp(),
+            }),
+            None => json!({
+                "status_text": "",
+                "status_emoji": "",
+                "status_expiration": 0,
+            }),
+        };
+        self.call("users.profile.set", json!({ "profile": profile }))
+            .await
+            .map(|_| ())
+    }
+}
+
+// --- Teams ---
+
+pub struct TeamsStatus {
+    client: reqwest::Client,
+    graph_base: String,
+    auth: MicrosoftCalendarProvider,
+}
+
+impl TeamsStatus {
+    pub fn new(graph_base: &str, auth: MicrosoftCalendarProvider) -> Self {
+        Self {
+            client: client(),
+            graph_base: graph_base.trim_end_matches('/').to_string(),
+            auth,
+        }
+    }
+
+    /// Load the stored presence grant, if Teams is connected.
+    pub fn from_keyring(graph_base: &str) -> Option<Self> {
+        let mut auth = MicrosoftCalendarProvider::for_presence();
+        auth.load_stored_tokens().ok()?;
+        auth.has_refresh_token()
+            .then(|| Self::new(graph_base, auth))
+    }
+
+    async fn post(
+        &self,
+        token: &str,
+        path: &str,
+        body: serde_json::Value,
+    ) -> Result<(), StatusError> {
+        let resp = self
+            .client
+            .post(format!("{}/me/presence/{path}", self.graph_base))
+            .bearer_auth(token)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| StatusError::Request {
+                service: "Teams",
+                message: e.to_string(),
+            })?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(StatusError::Rejected {
+                service: "Teams",
+                message: format!("{path} returned {status}: {body}"),
+            });
+        }
+        Ok(())
+    }
+
+    /// Send the Graph presence calls for `status` with an access token.
+    async fn send(&self, token: &str, status: Option<&Status>) -> Result<(), StatusError> {
+        let message = match status {
+            Some(s) => json!({
+                "statusMessage": {
+                    "message": { "content": s.text, "contentType": "text" },
+                    "expiryDateTime": {
+                        "dateTime": s.until.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                        "timeZone": "UTC",
+                    },
+                }
+            }),
+            None => json!({
+                "statusMessage": { "message": { "content": "", "contentType": "text" } }
+            }),
+        };
+        self.post(token, "setStatusMessage", message).await?;
+
+        // Teams already shows "In a meeting" from the calendar, so only
+        // focus sessions override availability.
+        match status {
+            Some(s) if s.kind == StatusKind::Focus => {
+                let minutes = (s.until - Utc::now()).num_minutes().max(1);
+                self.post(
+                    token,
+                    "setUserPreferredPresence",
+                    json!({
+                        "availability": "DoNotDisturb",
+                        "activity": "DoNotDisturb",
+                        "expirationDuration": format!("PT{minutes}M"),
+                    }),
+                )
+                .await
+            }
+            _ => {
+                self.post(token, "clearUserPreferredPresence", json!({}))
+                    .await
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl StatusService for TeamsStatus {
+    fn name(&self) -> &'static str {
+        "Teams"
+    }
+
+    async fn apply(&mut self, status: Option<&Status>) -> Result<(), StatusError> {
+        let token = self.auth.access_token().await?;
+        self.send(&token, status).await
+    }
+}
+
+// --- Runtime ---
+
+/// One connected service and the last status it accepted.
+#[derive(Default)]
+struct Slot {
+    service: Option<Box<dyn StatusService>>,
+    applied: Option<Status>,
+}
+
+impl Slot {
+    /// Bring the service in line with `want`. Failures are logged and retried
+    /// on the next sync.
+    async fn sync(&mut self, want: Option<&Status>) {
+        if self.applied.as_ref() == want {
+            return;
+        }
+        let Some(service) = self.service.as_mut() else {
+            return;
+        };
+        match service.apply(want).await {
+            Ok(()) => self.applied = want.cloned(),
+            Err(e) => eprintln!("[status-sync] Failed to update {}: {e}", service.name()),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Slots {
+    slack: Slot,
+    teams: Slot,
+}
+
+/// Managed state for status sync.
+#[derive(Default)]
+pub struct StatusSync {
+    slots: tokio::sync::Mutex<Slots>,
+}
+
+/// Load stored credentials for any enabled service not loaded yet.
+fn load_services(slots: &mut Slots, settings: &Settings) {
+    if settings.status_sync_slack && slots.slack.service.is_none() {
+        slots.slack.service = SlackStatus::from_keyring(&settings.slack_api_base)
+            .map(|s| Box::new(s) as Box<dyn StatusService>);
+    }
+    if settings.status_sync_teams && slots.teams.service.is_none() {
+        slots.teams.service = TeamsStatus::from_keyring(&settings.graph_api_base)
+            .map(|s| Box::new(s) as Box<dyn StatusService>);
+    }
+}
+
+/// Work out the status for now and push it to every enabled service.
+/// Disabled services get their status cleared.
+pub async fn sync(app: &AppHandle) {
+    let settings = settings::current_settings(app).await;
+    let Ok(pool) = settings::app_pool(app).await else {
+        return;
+    };
+    let events = match crate::calendar::poller::load_cached_events(&pool).await {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("[status-sync] Failed to load events: {e}");
+            return;
+        }
+    };
+    let timer = app
+        .state::<std::sync::Mutex<TimerService>>()
+        .lock()
+        .map(|t| t.state().clone())
+        .unwrap_or_default();
+    let want = desired_status(
+        &events,
+        &timer,
+        Utc::now(),
+        &StatusConfig::from_settings(&settings),
+    );
+
+    let state = app.state::<StatusSync>();
+    let mut slots = state.slots.lock().await;
+    load_services(&mut slots, &settings);
+    let slack_want = want.as_ref().filter(|_| settings.status_sync_slack);
+    let teams_want = want.as_ref().filter(|_| settings.status_sync_teams);
+    slots.slack.sync(slack_want).await;
+    slots.teams.sync(teams_want).await;
+}
+
+/// Re-sync after a status setting changes. A new base URL first clears the
+/// status on the old one and drops the loaded services.
+pub async fn setting_changed(app: &AppHandle, key: &str) {
+    if key == SLACK_API_BASE_KEY || key == GRAPH_API_BASE_KEY {
+        let state = app.state::<StatusSync>();
+        let mut guard = state.slots.lock().await;
+        let slots = &mut *guard;
+        for slot in [&mut slots.slack, &mut slots.teams] {
+            slot.sync(None).await;
+            *slot = Slot::default();
+        }
+    }
+    sync(app).await;
+}
+
+/// Sync on timer and calendar changes, and on a fixed interval so meeting
+/// starts and ends are picked up.
+pub fn start(app: &tauri::App) {
+    for name in ["timer-state-update", "calendar-events-update"] {
+        let handle = app.handle().clone();
+        app.listen(name, move |_| {
+            let handle = handle.clone();
+            tauri::async_runtime::spawn(async move {
+                sync(&handle).await;
+            });
+        });
+    }
+
+    let handle = app.handle().clone();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(SYNC_INTERVAL);
+        loop {
+            interval.tick().await;
+            sync(&handle).await;
+        }
+    });
+}
+
+// --- Tauri commands ---
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusSyncState {
+    pub slack_connected: bool,
+    pub teams_connected: bool,
+    /// The status currently shown on at least one service.
+    pub active: Option<Status>,
+}
+
+/// Report which services are connected and what status is showing.
+#[command]
+pub async fn get_status_sync_state(app: AppHandle) -> Result<StatusSyncState, String> {
+    let settings = settings::current_settings(&app).await;
+    let slack_base = settings.slack_api_base.clone();
+    let graph_base = settings.graph_api_base.clone();
+    let state = app.state::<StatusSync>();
+    let slots = state.slots.lock().await;
+    Ok(StatusSyncState {
+        slack_connected: slots.slack.service.is_some()
+            || SlackStatus::from_keyring(&slack_base).is_some(),
+        teams_connected: slots.teams.service.is_some()
+            || TeamsStatus::from_keyring(&graph_base).is_some(),
+        active: slots
+            .slack
+            .applied
+            .clone()
+            .or_else(|| slots.teams.applied.clone()),
+    })
+}
+
+/// Connect Slack with a user token. The token is checked before it is
+/// stored. Returns the account name.
+#[command]
+pub async fn connect_slack_status(app: AppHandle, token: String) -> Result<String, String> {
+    let settings = settings::current_settings(&app).await;
+    let slack = SlackStatus::new(&settings.slack_api_base, token.trim());
+    let account = slack.verify().await.map_err(|e| e.to_string())?;
+
+    keyring::Entry::new(SLACK_KEYRING_SERVICE, SLACK_KEYRING_KEY)
+        .and_then(|entry| entry.set_password(token.trim()))
+        .map_err(|e| format!("keyring store error: {e}"))?;
+
+    {
+        let state = app.state::<StatusSync>();
+        let mut slots = state.slots.lock().await;
+        slots.slack = Slot {
+            service: Some(Box::new(slack)),
+            applied: None,
+        };
+    }
+    sync(&app).await;
+    Ok(account)
+}
+
+/// Connect Teams by running the Microsoft sign-in with the presence scope.
+#[command]
+pub async fn connect_teams_status(app: AppHandle) -> Result<(), String> {
+    let settings = settings::current_settings(&app).await;
+    let mut auth = MicrosoftCalendarProvider::for_presence();
+    auth.authenticate().await.map_err(|e| e.to_string())?;
+
+    {
+        let state = app.state::<StatusSync>();
+        let mut slots = state.slots.lock().await;
+        slots.teams = Slot {
+            service: Some(Box::new(TeamsStatus::new(&settings.graph_api_base, auth))),
+            applied: None,
+        };
+    }
+    sync(&app).await;
+    Ok(())
+}
+
+/// Clear the status on a service ("slack" or "teams") and forget its
+/// credentials.
+#[command]
+pub async fn disconnect_status_service(app: AppHandle, service: String) -> Result<(), String> {
+    let state = app.state::<StatusSync>();
+    let mut slots = state.slots.lock().await;
+    let slot = match service.as_str() {
+        "slack" => &mut slots.slack,
+        "teams" => &mut slots.teams,
+        other => return Err(format!("unknown status service: {other}")),
+    };
+    slot.sync(None).await;
+    *slot = Slot::default();
+    drop(slots);
+
+    match service.as_str() {
+        "slack" => {
+            if let Ok(entry) = keyring::Entry::new(SLACK_KEYRING_SERVICE, SLACK_KEYRING_KEY) {
+                let _ = entry.delete_credential();
+            }
+        }
+        _ => crate::clear_keyring_entries(PRESENCE_KEYRING_SERVICE),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use crate::test_support::{stand_in, Received};
+    use chrono::{Duration as ChronoDuration, TimeZone};
+    use std::sync::{Arc, Mutex};
+
+    fn t0() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, 13, 45, 0).unwrap()
+    }
+
+    fn event(id: &str, start_min: i64, end_min: i64) -> CalendarEvent {
+        let at = |min| t0() + ChronoDuration::minutes(min);
+        test_support::event(id, at(start_min), at(end_min))
+    }
+
+    fn config() -> StatusConfig {
+        StatusConfig {
+            meeting_emoji: ":calendar:".to_string(),
+            focus_emoji: ":headphones:".to_string(),
+            ignored_calendar_ids: vec!["holidays".to_string()],
+        }
+    }
+
+    fn until(at: DateTime<Utc>) -> String {
+        at.with_timezone(&Local).format("%H:%M").to_string()
+    }
+
+    #[test]
+    fn test_meeting_status_until_latest_end() {
+        let events = vec![event("a", -15, 45), event("b", -5, 15), event("c", 60, 90)];
+        let status = desired_status(&events, &TimerState::default(), t0(), &config()).unwrap();
+        assert_eq!(status.kind, StatusKind::Meeting);
+        assert_eq!(status.emoji, ":calendar:");
+        assert_eq!(status.until, t0() + ChronoDuration::minutes(45));
+        assert_eq!(
+            status.text,
+            format!("In a meeting until {}", until(status.until))
+        );
+    }
+
+    #[test]
+    fn test_skips_ignored_all_day_and_ignored_calendars() {
+        let mut ignored = event("a", -15, 45);
+        ignored.ignored = true;
+        let mut all_day = event("b", -60, 600);
+        all_day.is_all_day = true;
+        let mut holiday = event("c", -15, 45);
+        holiday.calendar_id = Some("holidays".to_string());
+        let events = vec![ignored, all_day, holiday];
+        assert_eq!(
+            desired_status(&events, &TimerState::default(), t0(), &config()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_meeting_wins_over_focus_timer() {
+        let timer = TimerState::running(1500, t0() - ChronoDuration::minutes(5));
+        let status = desired_status(&[], &timer, t0(), &config()).unwrap();
+        assert_eq!(status.kind, StatusKind::Focus);
+        assert_eq!(status.emoji, ":headphones:");
+        assert_eq!(status.until, t0() + ChronoDuration::minutes(20));
+        assert!(status.text.starts_with("Focusing until "));
+
+        let events = vec![event("a", 0, 30)];
+        let status = desired_status(&events, &timer, t0(), &config()).unwrap();
+        assert_eq!(status.kind, StatusKind::Meeting);
+
+        let mut paused = timer.clone();
+        paused.pause(t0());
+        assert_eq!(desired_status(&[], &paused, t0(), &config()), None);
+    }
+
+    #[test]
+    fn test_break_timer_is_not_focus() {
+        let timer = TimerState {
+            is_break: true,
+            ..TimerState::running(300, t0() - ChronoDuration::minutes(1))
+        };
+        assert_eq!(desired_status(&[], &timer, t0(), &config()), None);
+
+        let events = vec![event("a", 0, 30)];
+        let status = desired_status(&events, &timer, t0(), &config()).unwrap();
+        assert_eq!(status.kind, StatusKind::Meeting);
+    }
+
+    /// Service that records what it was asked to show.
+    struct Recorder {
+        calls: Arc<Mutex<Vec<Option<Status>>>>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl StatusService for Recorder {
+        fn name(&self) -> &'static str {
+            "Recorder"
+        }
+
+        async fn apply(&mut self, status: Option<&Status>) -> Result<(), StatusError> {
+            self.calls.lock().unwrap().push(status.cloned());
+            if self.fail {
+                return Err(StatusError::Rejected {
+                    service: "Recorder",
+                    message: "nope".to_string(),
+                });
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slot_sends_only_changes_and_retries_failures() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut slot = Slot {
+            service: Some(Box::new(Recorder {
+                calls: calls.clone(),
+                fail: true,
+            })),
+            applied: None,
+        };
+        let status = Status::new(StatusKind::Focus, ":headphones:", t0());
+
+        slot.sync(Some(&status)).await;
+        assert_eq!(slot.applied, None);
+        slot.service = Some(Box::new(Recorder {
+            calls: calls.clone(),
+            fail: false,
+        }));
+        slot.sync(Some(&status)).await;
+        slot.sync(Some(&status)).await;
+        slot.sync(None).await;
+        slot.sync(None).await;
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(
+            *calls,
+            vec![Some(status.clone()), Some(status.clone()), None]
+        );
+        assert_eq!(slot.applied, None);
+    }
+
+    /// Local stand-in for the Slack and Graph APIs, under `/api`.
+    fn mock_api(replies: Vec<(u16, &'static str)>) -> (String, Arc<Mutex<Vec<Received>>>) {
+        let (base, received) = stand_in(replies);
+        (format!("{base}/api"), received)
+    }
+
+    #[tokio::test]
+    async fn test_slack_sets_and_clears_profile_status() {
+        let (base, received) = mock_api(vec![
+            (200, r#"{"ok":true}"#),
+            (200, r#"{"ok":true}"#),
+            (200, r#"{"ok":false,"error":"invalid_auth"}"#),
+        ]);
+        let mut slack = SlackStatus::new(&base, "xoxp-test");
+        let status = Status::new(StatusKind::Meeting, ":calendar:", t0());
+
+        slack.apply(Some(&status)).await.unwrap();
+        slack.apply(None).await.unwrap();
+        let err = slack.apply(None).await.unwrap_err();
+        assert!(err.to_string().contains("invalid_auth"));
+
+        let received = received.lock().unwrap();
+        assert_eq!(received[0].path, "/api/users.profile.set");
+        assert_eq!(received[0].header("Authorization"), "Bearer xoxp-test");
+        let body = received[0].json();
+        let profile = &body["profile"];
+        assert_eq!(profile["status_text"], status.text);
+        assert_eq!(profile["status_emoji"], ":calendar:");
+        assert_eq!(profile["status_expiration"], t0().timestamp());
+        assert_eq!(received[1].json()["profile"]["status_text"], "");
+        assert_eq!(received[1].json()["profile"]["status_expiration"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_slack_verify_reports_account() {
+        let (base, _) = mock_api(vec![(200, r#"{"ok":true,"user":"sam","team":"Acme"}"#)]);
+        let slack = SlackStatus::new(&format!("{base}/"), "xoxp-test");
+        assert_eq!(slack.verify().await.unwrap(), "sam (Acme)");
+    }
+
+    #[tokio::test]
+    async fn test_teams_focus_sets_message_and_do_not_disturb() {
+        let (base, received) = mock_api(vec![(200, ""), (200, ""), (200, ""), (200, "")]);
+        let teams = TeamsStatus::new(&base, MicrosoftCalendarProvider::for_presence());
+        let until = Utc::now() + ChronoDuration::minutes(25);
+        let status = Status::new(StatusKind::Focus, ":headphones:", until);
+
+        teams.send("graph-token", Some(&status)).await.unwrap();
+        teams.send("graph-token", None).await.unwrap();
+
+        let received = received.lock().unwrap();
+        let paths: Vec<&str> = received.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/api/me/presence/setStatusMessage",
+                "/api/me/presence/setUserPreferredPresence",
+                "/api/me/presence/setStatusMessage",
+                "/api/me/presence/clearUserPreferredPresence",
+            ]
+        );
+        assert_eq!(received[0].header("Authorization"), "Bearer graph-token");
+        let body = received[0].json();
+        let message = &body["statusMessage"];
+        assert_eq!(message["message"]["content"], status.text);
+        assert_eq!(message["expiryDateTime"]["timeZone"], "UTC");
+        assert_eq!(received[1].json()["availability"], "DoNotDisturb");
+        assert_eq!(
+            received[2].json()["statusMessage"]["message"]["content"],
+            ""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_teams_reports_graph_errors() {
+        let (base, _) = mock_api(vec![(403, r#"{"error":{"code":"Forbidden"}}"#)]);
+        let teams = TeamsStatus::new(&base, MicrosoftCalendarProvider::for_presence());
+        let err = teams.send("graph-token", None).await.unwrap_err();
+        assert!(matches!(
+            err,
+            StatusError::Rejected {
+                service: "Teams",
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("403"));
+    }
+}
@@ -0,0 +1,82 @@
+//! Helpers shared by unit tests.
+
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::calendar::types::CalendarEvent;
+
+/// A request seen by a [`stand_in`] server.
+pub struct Received {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Received {
+    pub fn header(&self, name: &str) -> &str {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+            .unwrap_or_default()
+    }
+
+    /// The body as JSON, or `null` when it isn't.
+    pub fn json(&self) -> Value {
+        serde_json::from_str(&self.body).unwrap_or_default()
+    }
+}
+
+/// Local HTTP stand-in answering with `replies` (status and body) in order.
+/// Returns its base URL and the requests it has seen.
+pub fn stand_in(replies: Vec<(u16, &'static str)>) -> (String, Arc<Mutex<Vec<Received>>>) {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", server.server_addr().to_ip().unwrap());
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let log = received.clone();
+    std::thread::spawn(move || {
+        for (status, reply) in replies {
+            let Ok(mut request) = server.recv() else {
+                return;
+            };
+            let mut body = String::new();
+            request.as_reader().read_to_string(&mut body).unwrap();
+            let headers = request
+                .headers()
+                .iter()
+                .map(|h| (h.field.to_string(), h.value.to_string()))
+                .collect();
+            log.lock().unwrap().push(Received {
+                method: request.method().to_string(),
+                path: request.url().to_string(),
+                headers,
+                body,
+            });
+            let response = tiny_http::Response::from_string(reply).with_status_code(status);
+            let _ = request.respond(response);
+        }
+    });
+    (base, received)
+}
+
+/// A timed, unignored meeting on the "work" calendar, titled "Meeting {id}".
+/// Tests change the fields they care about with struct update syntax.
+pub fn event(id: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> CalendarEvent {
+    CalendarEvent {
+        id: id.to_string(),
+        title: format!("Meeting {id}"),
+        start_time: start,
+        end_time: end,
+        ignored: false,
+        calendar_id: Some("work".to_string()),
+        provider_id: "google-1".to_string(),
+        is_all_day: false,
+        series_id: None,
+        meeting_url: None,
+        location: None,
+        is_buffer: false,
+    }
+}
@@ -0,0 +1,993 @@
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::history::TimerSessionRecord;
+
+/// Settings key holding user-defined presets (JSON array of `TimerPreset`).
+pub const CUSTOM_PRESETS_KEY: &str = "custom_timer_presets";
+
+/// Settings key holding user-defined sequences (JSON array of `TimerSequence`).
+pub const SEQUENCES_KEY: &str = "timer_sequences";
+
+/// Bounds for a preset duration (matches `validatePreset` in the frontend).
+const MIN_DURATION_SECONDS: u32 = 1;
+const MAX_DURATION_SECONDS: u32 = 24 * 60 * 60;
+
+/// Timer state emitted to the overlay as `timer-state-update`.
+/// Matches the TypeScript `TimerState` interface exactly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerState {
+    pub status: String,
+    pub duration_seconds: u32,
+    pub started_at: Option<String>,
+    pub paused_at: Option<String>,
+    pub elapsed_before_pause: f64,
+    /// Set while a break preset is loaded, so focus integrations (status,
+    /// DND, focus mode) leave breaks alone.
+    #[serde(default)]
+    pub is_break: bool,
+}
+
+impl Default for TimerState {
+    fn default() -> Self {
+        Self {
+            status: "idle".to_string(),
+            duration_seconds: 0,
+            started_at: None,
+            paused_at: None,
+            elapsed_before_pause: 0.0,
+            is_break: false,
+        }
+    }
+}
+
+impl TimerState {
+    /// A freshly started timer of the given length.
+    pub fn running(duration_seconds: u32, now: DateTime<Utc>) -> Self {
+        Self {
+            status: "running".to_string(),
+            duration_seconds,
+            started_at: Some(now.to_rfc3339()),
+            paused_at: None,
+            elapsed_before_pause: 0.0,
+            is_break: false,
+        }
+    }
+
+    /// True while the timer is running or paused.
+    pub fn is_active(&self) -> bool {
+        self.status == "running" || self.status == "paused"
+    }
+
+    /// True while a timer other than a break is running.
+    pub fn is_focusing(&self) -> bool {
+        self.status == "running" && !self.is_break
+    }
+
+    /// Elapsed seconds, accounting for pauses (mirrors `getElapsedSeconds`).
+    pub fn elapsed_seconds(&self, now: DateTime<Utc>) -> f64 {
+        match self.status.as_str() {
+            "running" => {
+                let since_start = self
+                    .started_at
+                    .as_deref()
+                    .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                    .map(|t| (now - t.to_utc()).num_milliseconds() as f64 / 1000.0)
+                    .unwrap_or(0.0);
+                self.elapsed_before_pause + since_start
+            }
+            "paused" => self.elapsed_before_pause,
+            _ => 0.0,
+        }
+    }
+
+    /// Seconds until the timer reaches its duration. Never negative.
+    pub fn remaining_seconds(&self, now: DateTime<Utc>) -> f64 {
+        (self.duration_seconds as f64 - self.elapsed_seconds(now)).max(0.0)
+    }
+
+    /// When a running timer will reach its duration. `None` unless running.
+    pub fn ends_at(&self) -> Option<DateTime<Utc>> {
+        if self.status != "running" {
+            return None;
+        }
+        let started = DateTime::parse_from_rfc3339(self.started_at.as_deref()?).ok()?;
+        let left = self.duration_seconds as f64 - self.elapsed_before_pause;
+        Some(started.to_utc() + chrono::Duration::milliseconds((left * 1000.0) as i64))
+    }
+
+    /// Pause a running timer, banking the time elapsed so far.
+    /// Returns false if the timer was not running.
+    pub fn pause(&mut self, now: DateTime<Utc>) -> bool {
+        if self.status != "running" {
+            return false;
+        }
+        self.elapsed_before_pause = self.elapsed_seconds(now);
+        self.status = "paused".to_string();
+        self.paused_at = Some(now.to_rfc3339());
+        true
+    }
+
+    /// Resume a paused timer. `started_at` moves forward so elapsed
+    /// calculations stay correct. Returns false if the timer was not paused.
+    pub fn resume(&mut self, now: DateTime<Utc>) -> bool {
+        if self.status != "paused" {
+            return false;
+        }
+        self.status = "running".to_string();
+        self.started_at = Some(now.to_rfc3339());
+        self.paused_at = None;
+        true
+    }
+}
+
+/// A reusable timer preset. Serialized to camelCase to match the TypeScript
+/// `TimerPreset` interface stored in `custom_timer_presets`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerPreset {
+    pub id: String,
+    pub name: String,
+    pub duration_seconds: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// A break rather than a focus session.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_break: bool,
+}
+
+impl TimerPreset {
+    fn new(id: &str, name: &str, minutes: u32) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            duration_seconds: minutes * 60,
+            color: None,
+            is_break: false,
+        }
+    }
+
+    fn new_break(id: &str, name: &str, minutes: u32) -> Self {
+        Self {
+            is_break: true,
+            ..Self::new(id, name, minutes)
+        }
+    }
+
+    /// A one-off timer that isn't saved as a preset, named "Timer" unless
+    /// given a name.
+    pub fn custom(name: Option<String>, duration_seconds: u32) -> Self {
+        Self {
+            id: "custom".to_string(),
+            name: name.unwrap_or_else(|| "Timer".to_string()),
+            duration_seconds,
+            color: None,
+            is_break: false,
+        }
+    }
+
+    /// Menu label, e.g. "Pomodoro (25 min)".
+    pub fn label(&self) -> String {
+        format!("{} ({})", self.name, format_duration(self.duration_seconds))
+    }
+}
+
+/// An ordered list of preset IDs that run back to back, e.g. a Pomodoro cycle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerSequence {
+    pub id: String,
+    pub name: String,
+    pub steps: Vec<String>,
+}
+
+/// The built-in presets (matches `getDefaultPresets` in the frontend).
+pub fn builtin_presets() -> Vec<TimerPreset> {
+    vec![
+        TimerPreset::new("pomodoro", "Pomodoro", 25),
+        TimerPreset::new_break("short-break", "Short Break", 5),
+        TimerPreset::new_break("long-break", "Long Break", 15),
+        TimerPreset::new("focus-hour", "Focus Hour", 60),
+    ]
+}
+
+/// The built-in sequences: a classic Pomodoro cycle of 25/5 ×4 followed by a
+/// 15-minute long break.
+pub fn builtin_sequences() -> Vec<TimerSequence> {
+    let mut steps = Vec::new();
+    for i in 0..4 {
+        steps.push("pomodoro".to_string());
+        steps.push(if i < 3 { "short-break" } else { "long-break" }.to_string());
+    }
+    vec![TimerSequence {
+        id: "pomodoro-cycle".to_string(),
+        name: "Pomodoro Cycle".to_string(),
+        steps,
+    }]
+}
+
+/// Validate a single preset, returning a descriptive error.
+pub fn validate_preset(preset: &TimerPreset) -> Result<(), String> {
+    if preset.id.trim().is_empty() {
+        return Err("preset id is required".to_string());
+    }
+    if preset.name.trim().is_empty() {
+        return Err(format!("preset '{}' has no name", preset.id));
+    }
+    if !(MIN_DURATION_SECONDS..=MAX_DURATION_SECONDS).contains(&preset.duration_seconds) {
+        return Err(format!(
+            "preset '{}' duration must be between {MIN_DURATION_SECONDS} and {MAX_DURATION_SECONDS} seconds",
+            preset.id
+        ));
+    }
+    Ok(())
+}
+
+/// Parse and validate the `custom_timer_presets` setting.
+pub fn parse_custom_presets(json: &str) -> Result<Vec<TimerPreset>, String> {
+    let presets: Vec<TimerPreset> =
+        serde_json::from_str(json).map_err(|e| format!("invalid timer presets: {e}"))?;
+    let builtin = builtin_presets();
+    let mut seen = std::collections::HashSet::new();
+    for preset in &presets {
+        validate_preset(preset)?;
+        if builtin.iter().any(|b| b.id == preset.id) || !seen.insert(preset.id.as_str()) {
+            return Err(format!("duplicate preset id '{}'", preset.id));
+        }
+    }
+    Ok(presets)
+}
+
+/// Parse and validate the `timer_sequences` setting. Step IDs are resolved
+/// later against the full preset catalog.
+pub fn parse_sequences(json: &str) -> Result<Vec<TimerSequence>, String> {
+    let sequences: Vec<TimerSequence> =
+        serde_json::from_str(json).map_err(|e| format!("invalid timer sequences: {e}"))?;
+    for seq in &sequences {
+        if seq.id.trim().is_empty() || seq.name.trim().is_empty() {
+            return Err("sequence id and name are required".to_string());
+        }
+        if seq.steps.is_empty() {
+            return Err(format!("sequence '{}' has no steps", seq.id));
+        }
+    }
+    Ok(sequences)
+}
+
+/// Built-in plus user-defined presets and sequences.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerCatalog {
+    pub presets: Vec<TimerPreset>,
+    pub sequences: Vec<TimerSequence>,
+}
+
+impl Default for TimerCatalog {
+    fn default() -> Self {
+        Self {
+            presets: builtin_presets(),
+            sequences: builtin_sequences(),
+        }
+    }
+}
+
+impl TimerCatalog {
+    /// Build the catalog from the raw setting values. Invalid values are
+    /// logged and skipped so a bad custom entry never hides the built-ins.
+    pub fn from_settings(custom_presets: Option<&str>, sequences: Option<&str>) -> Self {
+        let mut catalog = Self::default();
+        if let Some(json) = custom_presets {
+            match parse_custom_presets(json) {
+                Ok(custom) => catalog.presets.extend(custom),
+                Err(e) => eprintln!("[timer] Ignoring {CUSTOM_PRESETS_KEY}: {e}"),
+            }
+        }
+        if let Some(json) = sequences {
+            match parse_sequences(json) {
+                Ok(custom) => catalog.sequences.extend(custom),
+                Err(e) => eprintln!("[timer] Ignoring {SEQUENCES_KEY}: {e}"),
+            }
+        }
+        catalog
+    }
+
+    pub fn preset(&self, id: &str) -> Option<&TimerPreset> {
+        self.presets.iter().find(|p| p.id == id)
+    }
+
+    pub fn sequence(&self, id: &str) -> Option<&TimerSequence> {
+        self.sequences.iter().find(|s| s.id == id)
+    }
+
+    /// Resolve a sequence's step IDs into presets.
+    pub fn expand(&self, sequence: &TimerSequence) -> Result<Vec<TimerPreset>, String> {
+        sequence
+            .steps
+            .iter()
+            .map(|id| {
+                self.preset(id).cloned().ok_or_else(|| {
+                    format!(
+                        "sequence '{}' references unknown preset '{id}'",
+                        sequence.id
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+/// Progress through a running sequence.
+#[derive(Debug, Clone)]
+struct SequenceRun {
+    id: String,
+    steps: Vec<TimerPreset>,
+    index: usize,
+}
+
+/// What the user started most recently, for "start last timer".
+#[derive(Debug, Clone)]
+enum LastStart {
+    Preset(TimerPreset),
+    Sequence(String),
+}
+
+/// Payload emitted as `timer-completed` when a timer reaches its duration.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerCompleted {
+    pub preset_id: String,
+    pub name: String,
+    pub duration_seconds: u32,
+    pub sequence_id: Option<String>,
+    /// 1-based position of the completed step within its sequence.
+    pub step: Option<usize>,
+    pub total_steps: Option<usize>,
+    /// The preset that was started automatically, if the sequence continues.
+    pub next_preset_id: Option<String>,
+}
+
+/// Backend owner of the timer. Holds the state shared with the overlay,
+/// the preset catalog, and the scheduled completion task.
+#[derive(Default)]
+pub struct TimerService {
+    state: TimerState,
+    preset: Option<TimerPreset>,
+    sequence: Option<SequenceRun>,
+    catalog: TimerCatalog,
+    /// Bumped on every state change so a stale completion task can tell it
+    /// has been superseded.
+    generation: u64,
+    completion: Option<tauri::async_runtime::JoinHandle<()>>,
+    /// When the current run started, for history. Cleared once recorded.
+    session_started_at: Option<DateTime<Utc>>,
+    /// Runs that ended since the last `take_finished_sessions`.
+    finished: Vec<TimerSessionRecord>,
+    last_started: Option<LastStart>,
+}
+
+impl TimerService {
+    pub fn state(&self) -> &TimerState {
+        &self.state
+    }
+
+    pub fn catalog(&self) -> &TimerCatalog {
+        &self.catalog
+    }
+
+    pub fn set_catalog(&mut self, catalog: TimerCatalog) {
+        self.catalog = catalog;
+    }
+
+    /// The preset currently loaded into the timer, if any.
+    pub fn active_preset(&self) -> Option<&TimerPreset> {
+        self.preset.as_ref().filter(|_| self.state.is_active())
+    }
+
+    /// Runs that ended since the last call, for recording in history.
+    pub fn take_finished_sessions(&mut self) -> Vec<TimerSessionRecord> {
+        std::mem::take(&mut self.finished)
+    }
+
+    /// Record the current run as finished with `outcome`. No-op if it was
+    /// already recorded or nothing is running.
+    fn end_session(&mut self, now: DateTime<Utc>, outcome: &'static str) {
+        let Some(started_at) = self.session_started_at.take() else {
+            return;
+        };
+        if !self.state.is_active() {
+            return;
+        }
+        let duration_seconds = self.state.duration_seconds;
+        let preset = self
+            .preset
+            .clone()
+            .unwrap_or_else(|| TimerPreset::custom(None, duration_seconds));
+        let (preset_id, name) = (preset.id, preset.name);
+        let active_seconds = if outcome == "completed" {
+            duration_seconds
+        } else {
+            (self.state.elapsed_seconds(now) as u32).min(duration_seconds)
+        };
+        self.finished.push(TimerSessionRecord {
+            preset_id,
+            name,
+            duration_seconds,
+            active_seconds,
+            started_at,
+            ended_at: now,
+            outcome,
+        });
+    }
+
+    fn start_preset(&mut self, preset: TimerPreset, now: DateTime<Utc>) {
+        self.end_session(now, "stopped");
+        self.session_started_at = Some(now);
+        self.state = TimerState {
+            is_break: preset.is_break,
+            ..TimerState::running(preset.duration_seconds, now)
+        };
+        self.preset = Some(preset);
+        self.sequence = None;
+        self.generation += 1;
+    }
+
+    /// Start a preset the user picked (as opposed to a sequence step).
+    fn start_single(&mut self, preset: TimerPreset, now: DateTime<Utc>) {
+        self.last_started = Some(LastStart::Preset(preset.clone()));
+        self.start_preset(preset, now);
+    }
+
+    fn start_steps(&mut self, id: &str, steps: Vec<TimerPreset>, now: DateTime<Utc>) {
+        let first = steps[0].clone();
+        self.start_preset(first, now);
+        self.sequence = Some(SequenceRun {
+            id: id.to_string(),
+            steps,
+            index: 0,
+        });
+        self.last_started = Some(LastStart::Sequence(id.to_string()));
+    }
+
+    /// Start the preset or sequence started most recently, or the first
+    /// catalog preset if nothing has been started yet.
+    fn restart_last(&mut self, now: DateTime<Utc>) -> Result<(), String> {
+        match self.last_started.clone() {
+            Some(LastStart::Preset(preset)) => self.start_single(preset, now),
+            Some(LastStart::Sequence(id)) => {
+                let sequence = self
+                    .catalog
+                    .sequence(&id)
+                    .cloned()
+                    .ok_or_else(|| format!("unknown timer sequence: {id}"))?;
+                let steps = self.catalog.expand(&sequence)?;
+                self.start_steps(&id, steps, now);
+            }
+            None => {
+                let preset = self
+                    .catalog
+                    .presets
+                    .first()
+                    .cloned()
+                    .ok_or_else(|| "no timer presets".to_string())?;
+                self.start_single(preset, now);
+            }
+        }
+        Ok(())
+    }
+
+    fn pause(&mut self, now: DateTime<Utc>) -> bool {
+        let changed = self.state.pause(now);
+        if changed {
+            self.generation += 1;
+        }
+        changed
+    }
+
+    fn resume(&mut self, now: DateTime<Utc>) -> bool {
+        let changed = self.state.resume(now);
+        if changed {
+            self.generation += 1;
+        }
+        changed
+    }
+
+    fn stop(&mut self, now: DateTime<Utc>) {
+        self.end_session(now, "stopped");
+        self.state = TimerState::default();
+        self.preset = None;
+        self.sequence = None;
+        self.generation += 1;
+    }
+
+    /// Mark the current timer as complete and advance a running sequence.
+    /// Returns `None` if no timer was running.
+    fn complete(&mut self, now: DateTime<Utc>) -> Option<TimerCompleted> {
+        if self.state.status != "running" {
+            return None;
+        }
+        self.end_session(now, "completed");
+        let finished = self.preset.take().unwrap_or_else(|| TimerPreset {
+            is_break: self.state.is_break,
+            ..TimerPreset::custom(None, self.state.duration_seconds)
+        });
+
+        let mut event = TimerCompleted {
+            preset_id: finished.id.clone(),
+            name: finished.name.clone(),
+            duration_seconds: finished.duration_seconds,
+            sequence_id: None,
+            step: None,
+            total_steps: None,
+            next_preset_id: None,
+        };
+
+        let next = match self.sequence.take() {
+            Some(mut run) => {
+                event.sequence_id = Some(run.id.clone());
+                event.step = Some(run.index + 1);
+                event.total_steps = Some(run.steps.len());
+                run.index += 1;
+                let next = run.steps.get(run.index).cloned();
+                if next.is_some() {
+                    self.sequence = Some(run);
+                }
+                next
+            }
+            None => None,
+        };
+
+        match next {
+            Some(preset) => {
+                event.next_preset_id = Some(preset.id.clone());
+                let run = self.sequence.take();
+                self.start_preset(preset, now);
+                self.sequence = run;
+            }
+            None => self.stop(now),
+        }
+        Some(event)
+    }
+}
+
+/// Format a duration for menu labels: "25 min", "1 h", "1 h 30 min".
+pub(crate) fn format_duration(seconds: u32) -> String {
+    let minutes = seconds / 60;
+    if minutes >= 60 {
+        let (h, m) = (minutes / 60, minutes % 60);
+        if m > 0 {
+            format!("{h} h {m} min")
+        } else {
+            format!("{h} h")
+        }
+    } else if minutes > 0 {
+        format!("{minutes} min")
+    } else {
+        format!("{seconds} s")
+    }
+}
+
+// --- Runtime wiring (requires a Tauri app) ---
+
+/// Abort any pending completion task and, if the timer is running, schedule a
+/// new one for the remaining time.
+fn schedule_completion(app: &AppHandle, service: &mut TimerService) {
+    if let Some(handle) = service.completion.take() {
+        handle.abort();
+    }
+    if service.state.status != "running" {
+        return;
+    }
+
+    let remaining = service.state.remaining_seconds(Utc::now());
+    let generation = service.generation;
+    let handle = app.clone();
+    service.completion = Some(tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs_f64(remaining)).await;
+        on_timer_elapsed(&handle, generation);
+    }));
+}
+
+/// Called by the completion task. Emits `timer-completed`, then either starts
+/// the next sequence step or returns the timer to idle.
+fn on_timer_elapsed(app: &AppHandle, generation: u64) {
+    let (completed, state, sessions) = {
+        let managed = app.state::<Mutex<TimerService>>();
+        let Ok(mut service) = managed.lock() else {
+            return;
+        };
+        if service.generation != generation {
+            return;
+        }
+        service.completion = None;
+        let Some(completed) = service.complete(Utc::now()) else {
+            return;
+        };
+        schedule_completion(app, &mut service);
+        (
+            completed,
+            service.state.clone(),
+            service.take_finished_sessions(),
+        )
+    };
+    crate::history::record_timer_sessions(app, sessions);
+
+    // Emit outside the lock so listeners can query the timer.
+    let _ = app.emit("timer-completed", &completed);
+    let _ = app.emit("timer-state-update", &state);
+    crate::webhooks::notify(
+        app,
+        crate::webhooks::WebhookEvent::TimerCompleted,
+        &completed,
+    );
+    crate::notifications::timer_completed(app, &completed);
+}
+
+/// Apply a mutation to the timer service, reschedule completion, and emit the
+/// new state. The closure returns false if nothing changed.
+fn update(app: &AppHandle, f: impl FnOnce(&mut TimerService) -> bool) -> Result<(), String> {
+    let (state, sessions) = {
+        let managed = app.state::<Mutex<TimerService>>();
+        let mut service = managed.lock().map_err(|e| e.to_string())?;
+        if !f(&mut service) {
+            return Ok(());
+        }
+        schedule_completion(app, &mut service);
+        (service.state.clone(), service.take_finished_sessions())
+    };
+    crate::history::record_timer_sessions(app, sessions);
+    app.emit("timer-state-update", &state)
+        .map_err(|e| e.to_string())
+}
+
+/// Start a single preset, replacing any active timer or sequence.
+pub fn start(app: &AppHandle, preset: TimerPreset) -> Result<(), String> {
+    validate_preset(&preset)?;
+    update(app, |s| {
+        s.start_single(preset, Utc::now());
+        true
+    })
+}
+
+/// Start the last preset or sequence again (the first preset if none yet).
+pub fn start_last(app: &AppHandle) -> Result<(), String> {
+    update_checked(app, |s| s.restart_last(Utc::now()))
+}
+
+/// Start a catalog preset by ID.
+pub fn start_preset_by_id(app: &AppHandle, preset_id: &str) -> Result<(), String> {
+    let preset = {
+        let managed = app.state::<Mutex<TimerService>>();
+        let service = managed.lock().map_err(|e| e.to_string())?;
+        service.catalog.preset(preset_id).cloned()
+    };
+    match preset {
+        Some(p) => start(app, p),
+        None => Err(format!("unknown timer preset: {preset_id}")),
+    }
+}
+
+/// Start a catalog sequence by ID from its first step.
+pub fn start_sequence_by_id(app: &AppHandle, sequence_id: &str) -> Result<(), String> {
+    update_checked(app, |s| {
+        let sequence = s
+            .catalog
+            .sequence(sequence_id)
+            .cloned()
+            .ok_or_else(|| format!("unknown timer sequence: {sequence_id}"))?;
+        let steps = s.catalog.expand(&sequence)?;
+        s.start_steps(&sequence.id, steps, Utc::now());
+        Ok(())
+    })
+}
+
+/// Like `update`, for mutations that can fail validation.
+fn update_checked(
+    app: &AppHandle,
+    f: impl FnOnce(&mut TimerService) -> Result<(), String>,
+) -> Result<(), String> {
+    let mut result = Ok(());
+    update(app, |s| {
+        result = f(s);
+        result.is_ok()
+    })?;
+    result
+}
+
+pub fn pause(app: &AppHandle) -> Result<(), String> {
+    update(app, |s| s.pause(Utc::now()))
+}
+
+pub fn resume(app: &AppHandle) -> Result<(), String> {
+    update(app, |s| s.resume(Utc::now()))
+}
+
+pub fn stop(app: &AppHandle) -> Result<(), String> {
+    update(app, |s| {
+        s.stop(Utc::now());
+        true
+    })
+}
+
+/// Reload custom presets and sequences from settings, then rebuild the tray
+/// menu so it lists them.
+pub async fn reload_catalog(app: &AppHandle) {
+    let custom = crate::settings::read_setting(app, CUSTOM_PRESETS_KEY).await;
+    let sequences = crate::settings::read_setting(app, SEQUENCES_KEY).await;
+    let catalog = TimerCatalog::from_settings(custom.as_deref(), sequences.as_deref());
+
+    if let Ok(mut service) = app.state::<Mutex<TimerService>>().lock() {
+        service.set_catalog(catalog);
+    }
+    if let Err(e) = crate::tray::refresh_tray_menu(app) {
+        eprintln!("[timer] Failed to refresh tray menu: {e}");
+    }
+}
+
+// --- Tauri commands ---
+
+/// Return the current timer state.
+#[tauri::command]
+pub fn get_timer_state(app: AppHandle) -> Result<TimerState, String> {
+    let managed = app.state::<Mutex<TimerService>>();
+    let service = managed.lock().map_err(|e| e.to_string())?;
+    Ok(service.state.clone())
+}
+
+/// Return all available presets and sequences (built-in and custom).
+#[tauri::command]
+pub fn get_timer_catalog(app: AppHandle) -> Result<TimerCatalog, String> {
+    let managed = app.state::<Mutex<TimerService>>();
+    let service = managed.lock().map_err(|e| e.to_string())?;
+    Ok(service.catalog.clone())
+}
+
+/// Start a preset by ID.
+#[tauri::command]
+pub fn start_timer_preset(app: AppHandle, preset_id: String) -> Result<(), String> {
+    start_preset_by_id(&app, &preset_id)
+}
+
+/// Start a sequence by ID.
+#[tauri::command]
+pub fn start_timer_sequence(app: AppHandle, sequence_id: String) -> Result<(), String> {
+    start_sequence_by_id(&app, &sequence_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn t0() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_timer_state_default_is_idle() {
+        let state = TimerState::default();
+        assert_eq!(state.status, "idle");
+        assert_eq!(state.duration_seconds, 0);
+        assert!(state.started_at.is_none());
+        assert!(state.paused_at.is_none());
+        assert_eq!(state.elapsed_before_pause, 0.0);
+    }
+
+    #[test]
+    fn test_timer_pause_sets_status() {
+        let mut state = TimerState::running(1500, t0());
+        assert!(state.pause(t0() + Duration::seconds(10)));
+        assert_eq!(state.status, "paused");
+        assert!(state.paused_at.is_some());
+    }
+
+    #[test]
+    fn test_timer_resume_clears_paused_at() {
+        let mut state = TimerState::running(1500, t0());
+        state.pause(t0() + Duration::seconds(10));
+        assert!(state.resume(t0() + Duration::seconds(20)));
+        assert_eq!(state.status, "running");
+        assert!(state.paused_at.is_none());
+    }
+
+    #[test]
+    fn test_timer_pause_preserves_duration() {
+        let mut state = TimerState::running(1500, t0());
+        state.pause(t0() + Duration::seconds(10));
+        assert_eq!(state.duration_seconds, 1500);
+    }
+
+    #[test]
+    fn test_paused_time_does_not_count_as_elapsed() {
+        let mut state = TimerState::running(1500, t0());
+        state.pause(t0() + Duration::seconds(100));
+        state.resume(t0() + Duration::seconds(400));
+
+        let now = t0() + Duration::seconds(500);
+        assert_eq!(state.elapsed_seconds(now), 200.0);
+        assert_eq!(state.remaining_seconds(now), 1300.0);
+    }
+
+    #[test]
+    fn test_ends_at_accounts_for_pauses() {
+        let mut state = TimerState::running(1500, t0());
+        assert_eq!(state.ends_at(), Some(t0() + Duration::seconds(1500)));
+        state.pause(t0() + Duration::seconds(100));
+        assert_eq!(state.ends_at(), None);
+        state.resume(t0() + Duration::seconds(400));
+        assert_eq!(state.ends_at(), Some(t0() + Duration::seconds(1800)));
+    }
+
+    #[test]
+    fn test_remaining_never_negative() {
+        let state = TimerState::running(60, t0());
+        assert_eq!(state.remaining_seconds(t0() + Duration::hours(1)), 0.0);
+    }
+
+    #[test]
+    fn test_pause_ignored_when_not_running() {
+        let mut state = TimerState::default();
+        assert!(!state.pause(t0()));
+        assert!(!state.resume(t0()));
+        assert_eq!(state.status, "idle");
+    }
+
+    #[test]
+    fn test_builtin_pomodoro_cycle_shape() {
+        let catalog = TimerCatalog::default();
+        let cycle = catalog.sequence("pomodoro-cycle").unwrap();
+        let steps = catalog.expand(cycle).unwrap();
+        let minutes: Vec<u32> = steps.iter().map(|p| p.duration_seconds / 60).collect();
+        assert_eq!(minutes, vec![25, 5, 25, 5, 25, 5, 25, 15]);
+    }
+
+    #[test]
+    fn test_parse_custom_presets_camel_case() {
+        let json = r#"[{"id":"custom-1","name":"Deep Work","durationSeconds":5400}]"#;
+        let presets = parse_custom_presets(json).unwrap();
+        assert_eq!(presets.len(), 1);
+        assert_eq!(presets[0].duration_seconds, 5400);
+        assert_eq!(presets[0].label(), "Deep Work (1 h 30 min)");
+    }
+
+    #[test]
+    fn test_parse_custom_presets_rejects_invalid() {
+        assert!(parse_custom_presets("not json").is_err());
+        assert!(parse_custom_presets(r#"[{"id":"x","name":"","durationSeconds":60}]"#).is_err());
+        assert!(parse_custom_presets(r#"[{"id":"x","name":"X","durationSeconds":0}]"#).is_err());
+        assert!(
+            parse_custom_presets(r#"[{"id":"pomodoro","name":"X","durationSeconds":60}]"#).is_err()
+        );
+    }
+
+    #[test]
+    fn test_catalog_skips_invalid_settings() {
+        let catalog = TimerCatalog::from_settings(Some("garbage"), Some("[]"));
+        assert_eq!(catalog.presets, builtin_presets());
+        assert_eq!(catalog.sequences, builtin_sequences());
+    }
+
+    #[test]
+    fn test_custom_sequence_with_unknown_step_fails_to_expand() {
+        let catalog = TimerCatalog::from_settings(
+            None,
+            Some(r#"[{"id":"s","name":"S","steps":["pomodoro","nope"]}]"#),
+        );
+        let seq = catalog.sequence("s").unwrap();
+        assert!(catalog.expand(seq).is_err());
+    }
+
+    #[test]
+    fn test_completion_advances_through_sequence() {
+        let mut service = TimerService::default();
+        let cycle = service.catalog.sequence("pomodoro-cycle").cloned().unwrap();
+        let steps = service.catalog.expand(&cycle).unwrap();
+        service.start_steps(&cycle.id, steps, t0());
+        assert!(service.state.is_focusing());
+
+        let first = service.complete(t0() + Duration::minutes(25)).unwrap();
+        assert_eq!(first.preset_id, "pomodoro");
+        assert_eq!(first.step, Some(1));
+        assert_eq!(first.total_steps, Some(8));
+        assert_eq!(first.next_preset_id.as_deref(), Some("short-break"));
+        assert_eq!(service.state.status, "running");
+        assert_eq!(service.state.duration_seconds, 5 * 60);
+        assert!(service.state.is_break);
+        assert!(!service.state.is_focusing());
+
+        let mut last = first;
+        for _ in 1..8 {
+            last = service.complete(t0()).unwrap();
+        }
+        assert_eq!(last.preset_id, "long-break");
+        assert_eq!(last.step, Some(8));
+        assert_eq!(last.next_preset_id, None);
+        assert_eq!(service.state.status, "idle");
+        assert!(service.complete(t0()).is_none());
+    }
+
+    #[test]
+    fn test_single_preset_completion_returns_to_idle() {
+        let mut service = TimerService::default();
+        service.start_preset(builtin_presets()[3].clone(), t0());
+
+        let done = service.complete(t0() + Duration::hours(1)).unwrap();
+        assert_eq!(done.preset_id, "focus-hour");
+        assert_eq!(done.sequence_id, None);
+        assert_eq!(service.state.status, "idle");
+        assert!(service.active_preset().is_none());
+    }
+
+    #[test]
+    fn test_paused_timer_does_not_complete() {
+        let mut service = TimerService::default();
+        service.start_preset(builtin_presets()[0].clone(), t0());
+        service.pause(t0() + Duration::minutes(1));
+        assert!(service.complete(t0() + Duration::minutes(30)).is_none());
+    }
+
+    #[test]
+    fn test_finished_runs_are_recorded_once() {
+        let mut service = TimerService::default();
+        let cycle = service.catalog.sequence("pomodoro-cycle").cloned().unwrap();
+        let steps = service.catalog.expand(&cycle).unwrap();
+        service.start_steps(&cycle.id, steps, t0());
+        service.complete(t0() + Duration::minutes(25));
+
+        // Stop the break two minutes in, with one minute spent paused.
+        let break_start = t0() + Duration::minutes(25);
+        service.pause(break_start + Duration::minutes(1));
+        service.resume(break_start + Duration::minutes(2));
+        service.stop(break_start + Duration::minutes(3));
+        service.stop(break_start + Duration::minutes(4));
+
+        let sessions = service.take_finished_sessions();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].preset_id, "pomodoro");
+        assert_eq!(sessions[0].outcome, "completed");
+        assert_eq!(sessions[0].active_seconds, 25 * 60);
+        assert_eq!(sessions[1].preset_id, "short-break");
+        assert_eq!(sessions[1].outcome, "stopped");
+        assert_eq!(sessions[1].active_seconds, 2 * 60);
+        assert_eq!(sessions[1].started_at, break_start);
+        assert!(service.take_finished_sessions().is_empty());
+    }
+
+    #[test]
+    fn test_restart_last_preset_or_sequence() {
+        let mut service = TimerService::default();
+        service.restart_last(t0()).unwrap();
+        assert_eq!(service.active_preset().unwrap().id, "pomodoro");
+
+        service.start_single(builtin_presets()[3].clone(), t0());
+        service.stop(t0());
+        service.restart_last(t0()).unwrap();
+        assert_eq!(service.active_preset().unwrap().id, "focus-hour");
+
+        let cycle = service.catalog.sequence("pomodoro-cycle").cloned().unwrap();
+        let steps = service.catalog.expand(&cycle).unwrap();
+        service.start_steps(&cycle.id, steps, t0());
+        // Advancing through the sequence doesn't change what was started.
+        service.complete(t0() + Duration::minutes(25));
+        service.stop(t0());
+        service.restart_last(t0()).unwrap();
+        assert_eq!(service.active_preset().unwrap().id, "pomodoro");
+        assert!(service.sequence.is_some());
+    }
+
+    #[test]
+    fn test_state_changes_bump_generation() {
+        let mut service = TimerService::default();
+        let g0 = service.generation;
+        service.start_preset(builtin_presets()[0].clone(), t0());
+        let g1 = service.generation;
+        service.pause(t0());
+        service.resume(t0());
+        service.stop(t0());
+        assert!(g1 > g0);
+        assert!(service.generation > g1 + 2);
+    }
+}
@@ -0,0 +1,268 @@
+//! Tray icon rendered at runtime: a disc in the border color inside a ring
+//! that fills clockwise from 12 o'clock as the current meeting, gap or timer
+//! runs.
+//!
+//! Pixels are straight (not premultiplied) RGBA, as `Image::new_owned`
+//! expects.
+
+use chrono::{DateTime, Local, Utc};
+
+use crate::calendar::types::CalendarEvent;
+use crate::timer::{self, TimerState};
+
+/// Icon width and height in pixels. Tray hosts scale it down as needed.
+pub const SIZE: u32 = 32;
+
+/// Opacity of the unfilled part of the ring.
+const TRACK_ALPHA: f64 = 0.3;
+
+/// Parse `#RRGGBB`.
+pub fn parse_hex(hex: &str) -> Option<[u8; 3]> {
+    let hex = hex.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// How much of a circle's edge lies inside `radius` for a pixel whose
+/// center is `distance` from the circle's center: a one-pixel linear ramp.
+fn coverage(radius: f64, distance: f64) -> f64 {
+    (radius - distance + 0.5).clamp(0.0, 1.0)
+}
+
+/// Render a `size`×`size` icon in `color`. `progress` (0-1) fills the ring;
+/// `None` leaves it as a faint track.
+pub fn render(size: u32, color: [u8; 3], progress: Option<f64>) -> Vec<u8> {
+    let size_f = size as f64;
+    let center = size_f / 2.0;
+    let outer = center - 0.5;
+    let ring_width = (size_f * 0.16).max(1.0);
+    let inner = outer - ring_width;
+    let disc = inner - (size_f * 0.08).max(1.0);
+    let filled = progress.map_or(0.0, |p| p.clamp(0.0, 1.0));
+
+    let mut pixels = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let dx = x as f64 + 0.5 - center;
+            let dy = y as f64 + 0.5 - center;
+            let distance = dx.hypot(dy);
+
+            // Fraction of a turn clockwise from 12 o'clock.
+            let turn = dx.atan2(-dy).rem_euclid(std::f64::consts::TAU) / std::f64::consts::TAU;
+            let ring_alpha = if turn < filled { 1.0 } else { TRACK_ALPHA };
+            let ring = coverage(outer, distance) * (1.0 - coverage(inner, distance)) * ring_alpha;
+            let alpha = coverage(disc, distance).max(ring);
+
+            pixels.extend_from_slice(&color);
+            pixels.push((alpha * 255.0).round() as u8);
+        }
+    }
+    pixels
+}
+
+/// What the icon shows besides the phase color.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IconStatus {
+    /// How far through the timer, meeting or gap we are (0-1).
+    pub progress: Option<f64>,
+    /// "Standup ends in 7 min".
+    pub tooltip: String,
+    /// Short countdown for the macOS menu bar: "7 min".
+    pub countdown: Option<String>,
+}
+
+/// Seconds rounded up to whole minutes, as "7 min" or "1 h 5 min".
+fn minutes(seconds: f64) -> String {
+    let minutes = (seconds / 60.0).ceil().max(1.0) as u32;
+    timer::format_duration(minutes * 60)
+}
+
+fn fraction(now: DateTime<Utc>, start: DateTime<Utc>, end: DateTime<Utc>) -> Option<f64> {
+    let total = (end - start).num_seconds();
+    (total > 0).then(|| ((now - start).num_seconds() as f64 / total as f64).clamp(0.0, 1.0))
+}
+
+/// The icon's progress and text. An active timer wins over the calendar;
+/// then the meeting in progress that ends first; then the gap until today's
+/// next meeting, measured from the end of the last one. With none of these
+/// the tooltip is `idle_label`. Gaps only count between meetings on the
+/// same day.
+pub fn icon_status(
+    events: &[CalendarEvent],
+    timer: Option<(&TimerState, &str)>,
+    idle_label: &str,
+    now: DateTime<Utc>,
+) -> IconStatus {
+    if let Some((state, name)) = timer.filter(|(state, _)| state.is_active()) {
+        let left = state.remaining_seconds(now);
+        let tooltip = if state.status == "paused" {
+            format!("{name} paused, {} left", minutes(left))
+        } else {
+            format!("{name} ends in {}", minutes(left))
+        };
+        return IconStatus {
+            progress: (state.duration_seconds > 0)
+                .then(|| (state.elapsed_seconds(now) / state.duration_seconds as f64).min(1.0)),
+            tooltip,
+            countdown: Some(minutes(left)),
+        };
+    }
+
+    let timed: Vec<&CalendarEvent> = events.iter().filter(|e| e.counts_as_meeting(&[])).collect();
+    if let Some(current) = timed
+        .iter()
+        .filter(|e| e.start_time <= now && now < e.end_time)
+        .min_by_key(|e| e.end_time)
+    {
+        let left = (current.end_time - now).num_seconds() as f64;
+        return IconStatus {
+            progress: fraction(now, current.start_time, current.end_time),
+            tooltip: format!("{} ends in {}", current.title, minutes(left)),
+            countdown: Some(minutes(left)),
+        };
+    }
+
+    let today = now.with_timezone(&Local).date_naive();
+    let next = timed
+        .iter()
+        .filter(|e| e.start_time > now && (e.start_time - now).num_hours() < 24)
+        .min_by_key(|e| e.start_time);
+    if let Some(next) = next {
+        let left = (next.start_time - now).num_seconds() as f64;
+        let gap_start = timed
+            .iter()
+            .filter(|e| e.end_time <= now && e.end_time.with_timezone(&Local).date_naive() == today)
+            .map(|e| e.end_time)
+            .max();
+        return IconStatus {
+            progress: gap_start.and_then(|start| fraction(now, start, next.start_time)),
+            tooltip: format!("{} starts in {}", next.title, minutes(left)),
+            countdown: Some(minutes(left)),
+        };
+    }
+
+    IconStatus {
+        progress: None,
+        tooltip: idle_label.to_string(),
+        countdown: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use chrono::{Duration, TimeZone};
+
+    const GREEN: [u8; 3] = [0x4A, 0x9B, 0x6E];
+
+    fn alpha(pixels: &[u8], x: u32, y: u32) -> u8 {
+        pixels[((y * SIZE + x) * 4 + 3) as usize]
+    }
+
+    /// Local noon, so "today" doesn't depend on the machine's time zone.
+    fn t0() -> DateTime<Utc> {
+        Local
+            .with_ymd_and_hms(2026, 3, 2, 12, 0, 0)
+            .unwrap()
+            .to_utc()
+    }
+
+    fn event(title: &str, start_min: i64, end_min: i64) -> CalendarEvent {
+        let at = |min| t0() + Duration::minutes(min);
+        CalendarEvent {
+            title: title.to_string(),
+            ..test_support::event(&title.to_lowercase(), at(start_min), at(end_min))
+        }
+    }
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!(parse_hex("#4A9B6E"), Some(GREEN));
+        assert_eq!(parse_hex("4A9B6E"), None);
+        assert_eq!(parse_hex("#4A9B6"), None);
+        assert_eq!(parse_hex("#GG9B6E"), None);
+        assert_eq!(parse_hex("#+A9B6E"), None);
+    }
+
+    #[test]
+    fn test_render_disc_and_transparent_corners() {
+        let pixels = render(SIZE, GREEN, None);
+        assert_eq!(pixels.len(), (SIZE * SIZE * 4) as usize);
+        let center = ((SIZE / 2 * SIZE + SIZE / 2) * 4) as usize;
+        assert_eq!(pixels[center..center + 4], [0x4A, 0x9B, 0x6E, 255]);
+        assert_eq!(alpha(&pixels, 0, 0), 0);
+        assert_eq!(alpha(&pixels, SIZE - 1, SIZE - 1), 0);
+        // The gap between disc and ring is clear.
+        assert_eq!(alpha(&pixels, SIZE / 2, 7), 0);
+        // Without progress the ring is a faint track all round.
+        assert_eq!(alpha(&pixels, SIZE / 2, 1), 77);
+        assert_eq!(alpha(&pixels, 1, SIZE / 2), 77);
+    }
+
+    #[test]
+    fn test_render_fills_ring_clockwise() {
+        let half = render(SIZE, GREEN, Some(0.5));
+        // 3 o'clock and 6 o'clock (just left of center) are filled...
+        assert_eq!(alpha(&half, SIZE - 2, SIZE / 2), 255);
+        assert_eq!(alpha(&half, SIZE / 2 - 1, SIZE - 2), 77);
+        assert_eq!(alpha(&half, SIZE / 2, SIZE - 2), 255);
+        // ...9 o'clock is not yet.
+        assert_eq!(alpha(&half, 1, SIZE / 2), 77);
+
+        let most = render(SIZE, GREEN, Some(0.8));
+        assert_eq!(alpha(&most, 1, SIZE / 2), 255);
+        assert_eq!(alpha(&most, SIZE / 2 - 2, 1), 77);
+
+        let full = render(SIZE, GREEN, Some(1.0));
+        assert_eq!(alpha(&full, SIZE / 2 - 2, 1), 255);
+    }
+
+    #[test]
+    fn test_status_for_timer() {
+        let state = TimerState::running(25 * 60, t0());
+        let now = t0() + Duration::minutes(10) + Duration::seconds(30);
+        let status = icon_status(
+            &[event("Standup", 0, 30)],
+            Some((&state, "Pomodoro")),
+            "",
+            now,
+        );
+        assert_eq!(status.tooltip, "Pomodoro ends in 15 min");
+        assert_eq!(status.countdown.as_deref(), Some("15 min"));
+        assert!((status.progress.unwrap() - 0.42).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_status_for_meeting_and_gap() {
+        let mut skipped = event("Skipped", 10, 20);
+        skipped.ignored = true;
+        let events = vec![event("Standup", 0, 30), event("Planning", 60, 120), skipped];
+
+        let status = icon_status(&events, None, "Free time", t0() + Duration::minutes(23));
+        assert_eq!(status.tooltip, "Standup ends in 7 min");
+        assert!((status.progress.unwrap() - 23.0 / 30.0).abs() < 1e-9);
+
+        let status = icon_status(&events, None, "Free time", t0() + Duration::minutes(45));
+        assert_eq!(status.tooltip, "Planning starts in 15 min");
+        assert_eq!(status.progress, Some(0.5));
+
+        // Before the first meeting there is no gap to measure.
+        let status = icon_status(&events, None, "Free time", t0() - Duration::minutes(90));
+        assert_eq!(status.tooltip, "Standup starts in 1 h 30 min");
+        assert_eq!(status.progress, None);
+
+        let status = icon_status(&events, None, "Free time", t0() + Duration::minutes(180));
+        assert_eq!(
+            status,
+            IconStatus {
+                progress: None,
+                tooltip: "Free time".to_string(),
+                countdown: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,966 @@
+pub(crate) mod icon;
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local, Utc};
+use tauri::image::Image;
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::{TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Listener, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::border_state::BorderState;
+use crate::calendar::types::CalendarEvent;
+use crate::focus;
+use crate::overtime;
+use crate::pause::{self, Pause, PauseState};
+use crate::profiles::{self, ProfileList, ProfileSummary};
+use crate::schedule;
+use crate::settings;
+use crate::timer::{self, TimerCatalog, TimerService, TimerState};
+use crate::working_hours;
+
+/// Holds the tray icon handle so other parts of the app can update the menu.
+pub struct TrayState {
+    pub tray: tauri::tray::TrayIcon,
+    /// Cached events the menu lists, reloaded after every sync.
+    events: Mutex<Vec<CalendarEvent>>,
+    /// What the current menu shows, and its countdown item if a timer is
+    /// active.
+    shown: Mutex<(LiveItems, Option<MenuItem<tauri::Wry>>)>,
+    /// Whether the menu bar shows a countdown next to the icon.
+    countdown_title: Mutex<bool>,
+    /// What the icon was last drawn for, and when.
+    drawn: Mutex<Option<(IconKey, Instant)>>,
+}
+
+/// Show a countdown next to the tray icon (the macOS menu-bar title).
+pub const COUNTDOWN_TITLE_KEY: &str = "tray_countdown_title";
+
+/// How often the icon is redrawn while nothing else about it changes.
+const ICON_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Icon color when the border color can't be parsed.
+const FALLBACK_COLOR: [u8; 3] = [0x8A, 0x9B, 0xA8];
+
+/// Changes that redraw the icon right away rather than on the next minute.
+#[derive(Debug, Clone, PartialEq)]
+struct IconKey {
+    color: String,
+    tracking: bool,
+    countdown_title: bool,
+}
+
+/// Map a BorderState phase string to a human-readable status label.
+pub fn phase_to_label(phase: &str) -> &str {
+    match phase {
+        "no-events" | "free-deep" => "Free time",
+        "warning-far" => "Meeting in ~30 min",
+        "warning-mid" => "Meeting in ~15 min",
+        "warning-near" => "Meeting in ~5 min",
+        "warning-imminent" => "Meeting in ~2 min",
+        "overtime" => "Overtime",
+        working_hours::OFF_HOURS_PHASE => "Off hours",
+        p if p.starts_with("in-session") => "In session",
+        p if p.starts_with("gap-") => "Break",
+        _ => "Free time",
+    }
+}
+
+/// Tray status line: the phase label, followed by the active schedule rule.
+fn status_label(phase: &str, rule: Option<&str>) -> String {
+    match rule {
+        Some(rule) => format!("{} · {rule}", phase_to_label(phase)),
+        None => phase_to_label(phase).to_string(),
+    }
+}
+
+/// Menu ID prefixes for the dynamically-built timer entries.
+const PRESET_ID_PREFIX: &str = "timer_preset:";
+const SEQUENCE_ID_PREFIX: &str = "timer_sequence:";
+const PROFILE_ID_PREFIX: &str = "profile:";
+/// Followed by the meeting link to open.
+const JOIN_ID_PREFIX: &str = "join:";
+
+/// How many of today's events the menu lists.
+const MAX_EVENTS: usize = 4;
+
+/// An upcoming event as the menu shows it.
+#[derive(Debug, Clone, PartialEq)]
+struct MenuEvent {
+    label: String,
+    join_url: Option<String>,
+}
+
+/// The parts of the menu that change with the clock rather than through an
+/// explicit refresh. The menu is rebuilt when these change; the timer
+/// countdown alone is updated in place.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct LiveItems {
+    phase: String,
+    events: Vec<MenuEvent>,
+    /// "running" or "paused" while a timer is active.
+    timer_status: Option<String>,
+    pause: Option<Pause>,
+}
+
+/// The rest of today's events, soonest first: ones in progress, then ones
+/// still to start. Ignored and all-day events are left out; ignored
+/// calendars were already dropped by [`reload`].
+fn upcoming_events(events: &[CalendarEvent], now: DateTime<Utc>) -> Vec<&CalendarEvent> {
+    let today = now.with_timezone(&Local).date_naive();
+    let mut upcoming: Vec<&CalendarEvent> = events
+        .iter()
+        .filter(|e| e.counts_as_meeting(&[]) && e.end_time > now)
+        .filter(|e| e.start_time.with_timezone(&Local).date_naive() <= today)
+        .collect();
+    upcoming.sort_by_key(|e| e.start_time);
+    upcoming.truncate(MAX_EVENTS);
+    upcoming
+}
+
+/// "14:00 Standup", or "Now: Standup (until 14:30)" once it has started.
+fn event_label(event: &CalendarEvent, now: DateTime<Utc>) -> String {
+    if event.start_time <= now {
+        let end = event.end_time.with_timezone(&Local).format("%H:%M");
+        format!("Now: {} (until {end})", event.title)
+    } else {
+        let start = event.start_time.with_timezone(&Local).format("%H:%M");
+        format!("{start} {}", event.title)
+    }
+}
+
+/// Seconds as "4:05" or "1:04:05".
+fn format_countdown(seconds: f64) -> String {
+    let total = seconds.ceil() as u64;
+    let (h, m, s) = (total / 3600, total / 60 % 60, total % 60);
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m}:{s:02}")
+    }
+}
+
+/// "Pomodoro: 12:34 left", or "Pomodoro: paused, 12:34 left".
+fn timer_label(state: &TimerState, name: Option<&str>, now: DateTime<Utc>) -> String {
+    let name = name.unwrap_or("Timer");
+    let left = format_countdown(state.remaining_seconds(now));
+    if state.status == "paused" {
+        format!("{name}: paused, {left} left")
+    } else {
+        format!("{name}: {left} left")
+    }
+}
+
+/// "Paused until 14:30 — Resume", "Paused until Standup ends — Resume".
+fn pause_label(pause: &Pause) -> String {
+    format!("Paused {} — Resume", pause.label())
+}
+
+/// Everything the tray menu shows.
+#[derive(Default)]
+struct MenuContent {
+    status_label: String,
+    catalog: TimerCatalog,
+    profiles: Vec<ProfileSummary>,
+    in_overtime: bool,
+    focus_blocking: bool,
+    live: LiveItems,
+    /// Countdown text for the active timer.
+    timer_label: Option<String>,
+}
+
+/// Build the tray context menu. Today's next events come after the status
+/// line, each followed by "Join Meeting" when it has a link. An active timer
+/// gets a countdown with pause/resume and stop; a paused border gets a resume
+/// item. The "Start Timer" submenu lists every preset and sequence in the
+/// timer catalog; the "Profiles" submenu appears once at least one profile
+/// is saved; "I've left the meeting" appears while a meeting is in overtime;
+/// "Stop Blocking" appears while focus mode is blocking apps and sites.
+///
+/// Returns the countdown item so it can be updated without a rebuild.
+#[allow(clippy::type_complexity)]
+fn build_menu(
+    app: &AppHandle,
+    content: &MenuContent,
+) -> Result<(Menu<tauri::Wry>, Option<MenuItem<tauri::Wry>>), Box<dyn std::error::Error>> {
+    let MenuContent {
+        status_label,
+        catalog,
+        profiles,
+        in_overtime,
+        focus_blocking,
+        live,
+        timer_label,
+    } = content;
+    let timer_submenu = Submenu::new(app, "Start Timer", true)?;
+    for preset in &catalog.presets {
+        timer_submenu.append(&MenuItem::with_id(
+            app,
+            format!("{PRESET_ID_PREFIX}{}", preset.id),
+            preset.label(),
+            true,
+            None::<&str>,
+        )?)?;
+    }
+    if !catalog.sequences.is_empty() {
+        timer_submenu.append(&PredefinedMenuItem::separator(app)?)?;
+        for sequence in &catalog.sequences {
+            timer_submenu.append(&MenuItem::with_id(
+                app,
+                format!("{SEQUENCE_ID_PREFIX}{}", sequence.id),
+                &sequence.name,
+                true,
+                None::<&str>,
+            )?)?;
+        }
+    }
+
+    let menu = Menu::new(app)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "status",
+        status_label,
+        false,
+        None::<&str>,
+    )?)?;
+    if *in_overtime {
+        menu.append(&MenuItem::with_id(
+            app,
+            "end_overtime",
+            "I've left the meeting",
+            true,
+            None::<&str>,
+        )?)?;
+    }
+    if *focus_blocking {
+        menu.append(&MenuItem::with_id(
+            app,
+            "focus_override",
+            "Stop Blocking Until Session Ends",
+            true,
+            None::<&str>,
+        )?)?;
+    }
+    if let Some(pause) = &live.pause {
+        menu.append(&MenuItem::with_id(
+            app,
+            "resume_border",
+            pause_label(pause),
+            true,
+            None::<&str>,
+        )?)?;
+    }
+
+    if !live.events.is_empty() {
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+        for (i, event) in live.events.iter().enumerate() {
+            menu.append(&MenuItem::with_id(
+                app,
+                format!("event:{i}"),
+                &event.label,
+                false,
+                None::<&str>,
+            )?)?;
+            if let Some(url) = &event.join_url {
+                menu.append(&MenuItem::with_id(
+                    app,
+                    format!("{JOIN_ID_PREFIX}{url}"),
+                    "Join Meeting",
+                    true,
+                    None::<&str>,
+                )?)?;
+            }
+        }
+    }
+
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    let mut countdown = None;
+    if let (Some(status), Some(label)) = (&live.timer_status, timer_label) {
+        let item = MenuItem::with_id(app, "timer_countdown", label, false, None::<&str>)?;
+        menu.append(&item)?;
+        countdown = Some(item);
+        let (id, text) = if status == "paused" {
+            ("timer_resume", "Resume Timer")
+        } else {
+            ("timer_pause", "Pause Timer")
+        };
+        menu.append(&MenuItem::with_id(app, id, text, true, None::<&str>)?)?;
+        menu.append(&MenuItem::with_id(
+            app,
+            "timer_stop",
+            "Stop Timer",
+            true,
+            None::<&str>,
+        )?)?;
+    }
+    menu.append(&timer_submenu)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+
+    if !profiles.is_empty() {
+        let profile_submenu = Submenu::new(app, "Profiles", true)?;
+        for profile in profiles {
+            profile_submenu.append(&CheckMenuItem::with_id(
+                app,
+                format!("{PROFILE_ID_PREFIX}{}", profile.name),
+                &profile.name,
+                true,
+                profile.active,
+                None::<&str>,
+            )?)?;
+        }
+        menu.append(&profile_submenu)?;
+    }
+
+    menu.append(&MenuItem::with_id(
+        app,
+        "open_settings",
+        "Open Settings",
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "support",
+        "Support Morph ♥",
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "quit",
+        "Quit Morph",
+        true,
+        None::<&str>,
+    )?)?;
+
+    Ok((menu, countdown))
+}
+
+/// Handle menu events (settings, quit, timer starts).
+fn handle_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
+    match event.id.as_ref() {
+        "open_settings" => {
+            let window = match app.get_webview_window("settings") {
+                Some(w) => w,
+                None => match WebviewWindowBuilder::new(
+                    app,
+                    "settings",
+                    WebviewUrl::App("src/settings/index.html".into()),
+                )
+                .title("Morph Settings")
+                .inner_size(680.0, 560.0)
+                .decorations(true)
+                .resizable(true)
+                .build()
+                {
+                    Ok(w) => w,
+                    Err(e) => {
+                        eprintln!("Failed to create settings window: {e}");
+                        return;
+                    }
+                },
+            };
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        "support" => {
+            tauri::async_runtime::spawn(async {
+                let _ = open::that("https://ko-fi.com/morphlight");
+            });
+        }
+        "quit" => {
+            app.exit(0);
+        }
+        "end_overtime" => {
+            let handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = overtime::leave(&handle).await {
+                    eprintln!("[tray] Failed to end overtime: {e}");
+                }
+            });
+        }
+        "focus_override" => {
+            let handle = app.clone();
+            tauri::async_runtime::spawn_blocking(move || focus::override_session(&handle));
+        }
+        "resume_border" => {
+            if let Err(e) = pause::resume(app, "tray") {
+                eprintln!("[tray] Failed to resume border: {e}");
+            }
+        }
+        "timer_pause" | "timer_resume" | "timer_stop" => {
+            let result = match event.id.as_ref() {
+                "timer_pause" => timer::pause(app),
+                "timer_resume" => timer::resume(app),
+                _ => timer::stop(app),
+            };
+            if let Err(e) = result {
+                eprintln!("[tray] Failed to update timer: {e}");
+            }
+        }
+        id if id.starts_with(JOIN_ID_PREFIX) => {
+            let url = id[JOIN_ID_PREFIX.len()..].to_string();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = open::that(&url) {
+                    eprintln!("[tray] Failed to open meeting link: {e}");
+                }
+            });
+        }
+        id if id.starts_with(PROFILE_ID_PREFIX) => {
+            let name = id[PROFILE_ID_PREFIX.len()..].to_string();
+            let handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = profiles::switch_profile(&handle, &name).await {
+                    eprintln!("[tray] Failed to switch profile: {e}");
+                }
+            });
+        }
+        id => {
+            let result = if let Some(preset_id) = id.strip_prefix(PRESET_ID_PREFIX) {
+                timer::start_preset_by_id(app, preset_id)
+            } else if let Some(sequence_id) = id.strip_prefix(SEQUENCE_ID_PREFIX) {
+                timer::start_sequence_by_id(app, sequence_id)
+            } else {
+                Ok(())
+            };
+            if let Err(e) = result {
+                eprintln!("[tray] Failed to start timer: {e}");
+            }
+        }
+    }
+}
+
+/// Toggle the tray popover window. Creates it if it doesn't exist, otherwise
+/// toggles visibility.
+fn toggle_popover(app: &AppHandle, position: tauri::PhysicalPosition<f64>) {
+    if let Some(window) = app.get_webview_window("tray-popover") {
+        // Window exists — toggle visibility
+        match window.is_visible() {
+            Ok(true) => {
+                let _ = window.hide();
+            }
+            Ok(false) => {
+                // Reposition near tray icon before showing
+                let _ = window.set_position(tauri::Position::Physical(
+                    tauri::PhysicalPosition {
+                        x: (position.x as i32).saturating_sub(160),
+                        y: position.y as i32,
+                    },
+                ));
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            Err(e) => {
+                eprintln!("Failed to check popover visibility: {e}");
+            }
+        }
+        return;
+    }
+
+    // Create new popover window
+    let popover = WebviewWindowBuilder::new(
+        app,
+        "tray-popover",
+        WebviewUrl::App("src/tray/index.html".into()),
+    )
+    .title("")
+    .inner_size(320.0, 400.0)
+    .position(
+        (position.x - 160.0).max(0.0),
+        position.y,
+    )
+    .decorations(false)
+    .skip_taskbar(true)
+    .transparent(true)
+    .visible(true)
+    .build();
+
+    match popover {
+        Ok(_window) => {
+            // On macOS: defer NSWindow config so webview JS can load first
+            // (same pattern as overlay windows in lib.rs)
+            #[cfg(target_os = "macos")]
+            {
+                let handle = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    // Wait for webview to load
+                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+                    let handle2 = handle.clone();
+                    let _ = handle.run_on_main_thread(move || {
+                        if let Some(w) = handle2.get_webview_window("tray-popover") {
+                            if let Ok(ns_window_ptr) = w.ns_window() {
+                                use objc2_app_kit::{NSWindow, NSWindowCollectionBehavior};
+
+                                // SAFETY: ns_window() returns a valid NSWindow pointer
+                                // and we are on the main thread via run_on_main_thread.
+                                let ns_window: &NSWindow =
+                                    unsafe { &*(ns_window_ptr as *const NSWindow) };
+
+                                // NSFloatingWindowLevel (3) — above normal windows
+                                ns_window.setLevel(3);
+                                ns_window.setCollectionBehavior(
+                                    NSWindowCollectionBehavior::CanJoinAllSpaces
+                                        | NSWindowCollectionBehavior::IgnoresCycle,
+                                );
+                                ns_window.setHasShadow(false);
+                            }
+                        }
+                    });
+                });
+            }
+        }
+        Err(e) => {
+            eprintln!("Failed to create tray popover: {e}");
+        }
+    }
+}
+
+/// Create the system tray with an initial menu based on current BorderState.
+pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    let phase = {
+        let managed = app.state::<Mutex<crate::border_state::BorderState>>();
+        let guard = managed.lock().map_err(|e| e.to_string())?;
+        guard.phase.clone()
+    };
+
+    let content = MenuContent {
+        status_label: phase_to_label(&phase).to_string(),
+        ..MenuContent::default()
+    };
+    let (menu, _) = build_menu(app.handle(), &content)?;
+
+    let tray = TrayIconBuilder::new()
+        .icon(app.default_window_icon().unwrap().clone())
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_tray_icon_event(|tray_icon, event| {
+            if let TrayIconEvent::Click { position, .. } = event {
+                toggle_popover(tray_icon.app_handle(), position);
+            }
+        })
+        .on_menu_event(|app, event| {
+            handle_menu_event(app, event);
+        })
+        .build(app)?;
+
+    app.manage(TrayState {
+        tray,
+        events: Mutex::new(Vec::new()),
+        shown: Mutex::new((LiveItems::default(), None)),
+        countdown_title: Mutex::new(false),
+        drawn: Mutex::new(None),
+    });
+
+    // Listen for border-state-update events to update the tray icon color
+    // and, when the phase changes, the status line
+    let handle = app.handle().clone();
+    app.listen("border-state-update", move |_event| {
+        refresh_icon(&handle);
+        refresh_live_items(&handle);
+    });
+
+    // Reload the listed events from the cache after every sync
+    let handle = app.handle().clone();
+    app.listen("calendar-events-update", move |_event| {
+        let h = handle.clone();
+        tauri::async_runtime::spawn(async move {
+            reload(&h).await;
+        });
+    });
+
+    // Tick the timer countdown, drop events and pauses as they end, and
+    // redraw the icon's progress ring once a minute
+    let handle = app.handle().clone();
+    tauri::async_runtime::spawn(async move {
+        reload(&handle).await;
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            interval.tick().await;
+            refresh_icon(&handle);
+            refresh_live_items(&handle);
+        }
+    });
+
+    Ok(())
+}
+
+/// Load today's events from the calendar cache, leaving out ignored
+/// calendars, and the countdown title setting. Updates the menu and icon
+/// if what they show changed.
+pub async fn reload(app: &AppHandle) {
+    let settings = settings::current_settings(app).await;
+    let Ok(pool) = settings::app_pool(app).await else {
+        return;
+    };
+    let mut events = match crate::calendar::poller::load_cached_events(&pool).await {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("[tray] Failed to load events: {e}");
+            return;
+        }
+    };
+    events.retain(|e| {
+        e.calendar_id
+            .as_ref()
+            .is_none_or(|id| !settings.ignored_calendar_ids.contains(id))
+    });
+    if let Some(tray_state) = app.try_state::<TrayState>() {
+        if let Ok(mut cached) = tray_state.events.lock() {
+            *cached = events;
+        }
+        if let Ok(mut countdown_title) = tray_state.countdown_title.lock() {
+            *countdown_title = settings.tray_countdown_title;
+        }
+    }
+    refresh_icon(app);
+    refresh_live_items(app);
+}
+
+/// Redraw the tray icon, tooltip and title. Skipped unless the border color,
+/// whether there's progress to show, or the title setting changed, or a
+/// minute has passed since the last redraw.
+fn refresh_icon(app: &AppHandle) {
+    let Some(tray_state) = app.try_state::<TrayState>() else {
+        return;
+    };
+    let now = Utc::now();
+    let border = app
+        .state::<Mutex<BorderState>>()
+        .lock()
+        .map(|state| state.clone())
+        .unwrap_or_default();
+    let status = {
+        let Ok(events) = tray_state.events.lock() else {
+            return;
+        };
+        let managed = app.state::<Mutex<TimerService>>();
+        let Ok(service) = managed.lock() else {
+            return;
+        };
+        let name = service.active_preset().map_or("Timer", |p| p.name.as_str());
+        // Outside working hours, don't count down to tomorrow's first meeting.
+        let off_hours = border.phase == working_hours::OFF_HOURS_PHASE;
+        icon::icon_status(
+            if off_hours { &[] } else { &events[..] },
+            Some((service.state(), name)),
+            phase_to_label(&border.phase),
+            now,
+        )
+    };
+    let countdown_title = tray_state.countdown_title.lock().is_ok_and(|on| *on);
+    let key = IconKey {
+        color: border.color.clone(),
+        tracking: status.progress.is_some(),
+        countdown_title,
+    };
+    {
+        let Ok(mut drawn) = tray_state.drawn.lock() else {
+            return;
+        };
+        if drawn
+            .as_ref()
+            .is_some_and(|(last, at)| *last == key && at.elapsed() < ICON_INTERVAL)
+        {
+            return;
+        }
+        *drawn = Some((key, Instant::now()));
+    }
+
+    let color = icon::parse_hex(&border.color).unwrap_or(FALLBACK_COLOR);
+    let pixels = icon::render(icon::SIZE, color, status.progress);
+    let image = Image::new_owned(pixels, icon::SIZE, icon::SIZE);
+    let tray = &tray_state.tray;
+    if let Err(e) = tray.set_icon(Some(image)) {
+        eprintln!("[tray] Failed to set icon: {e}");
+    }
+    let _ = tray.set_tooltip(Some(&status.tooltip));
+    let _ = tray.set_title(status.countdown.filter(|_| countdown_title));
+}
+
+/// Read the clock-driven parts of the menu, and the countdown text.
+fn live_items(app: &AppHandle, now: DateTime<Utc>) -> (LiveItems, Option<String>) {
+    let phase = app
+        .state::<Mutex<crate::border_state::BorderState>>()
+        .lock()
+        .map(|state| state.phase.clone())
+        .unwrap_or_default();
+    let events = app
+        .try_state::<TrayState>()
+        .and_then(|tray_state| {
+            let events = tray_state.events.lock().ok()?;
+            let listed = upcoming_events(&events, now)
+                .into_iter()
+                .map(|e| MenuEvent {
+                    label: event_label(e, now),
+                    join_url: e.meeting_url.clone(),
+                })
+                .collect();
+            Some(listed)
+        })
+        .unwrap_or_default();
+    let (timer_status, timer_label) = app
+        .state::<Mutex<TimerService>>()
+        .lock()
+        .ok()
+        .filter(|service| service.state().is_active())
+        .map(|service| {
+            let state = service.state();
+            let name = service.active_preset().map(|p| p.name.as_str());
+            (
+                Some(state.status.clone()),
+                Some(timer_label(state, name, now)),
+            )
+        })
+        .unwrap_or_default();
+    let pause = app
+        .state::<Mutex<PauseState>>()
+        .lock()
+        .ok()
+        .and_then(|state| state.pause.clone())
+        .filter(|pause| pause.until > now);
+    let live = LiveItems {
+        phase,
+        events,
+        timer_status,
+        pause,
+    };
+    (live, timer_label)
+}
+
+/// Rebuild the menu if its phase, events, timer or pause changed; otherwise
+/// just update the countdown.
+fn refresh_live_items(app: &AppHandle) {
+    let Some(tray_state) = app.try_state::<TrayState>() else {
+        return;
+    };
+    let (live, timer_label) = live_items(app, Utc::now());
+    let unchanged = {
+        let Ok(shown) = tray_state.shown.lock() else {
+            return;
+        };
+        if let (true, Some(item), Some(label)) = (shown.0 == live, &shown.1, &timer_label) {
+            let _ = item.set_text(label);
+        }
+        shown.0 == live
+    };
+    if !unchanged {
+        if let Err(e) = refresh_tray_menu(app) {
+            eprintln!("[tray] Failed to refresh tray menu: {e}");
+        }
+    }
+}
+
+/// Update the tray menu to reflect a new phase. Call this when the phase changes.
+pub fn update_tray_menu(app: &AppHandle, phase: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let label = status_label(phase, schedule::active_rule_name(app).as_deref());
+    let catalog = {
+        let managed = app.state::<Mutex<TimerService>>();
+        let service = managed.lock().map_err(|e| e.to_string())?;
+        service.catalog().clone()
+    };
+    let profiles = app
+        .try_state::<Mutex<ProfileList>>()
+        .and_then(|list| list.lock().ok().map(|list| list.0.clone()))
+        .unwrap_or_default();
+    let (live, timer_label) = live_items(app, Utc::now());
+    let content = MenuContent {
+        status_label: label,
+        catalog,
+        profiles,
+        in_overtime: overtime::current(app).is_some(),
+        focus_blocking: focus::enforcing(app),
+        live,
+        timer_label,
+    };
+    let (menu, countdown) = build_menu(app, &content)?;
+    if let Some(tray_state) = app.try_state::<TrayState>() {
+        tray_state.tray.set_menu(Some(menu))?;
+        if let Ok(mut shown) = tray_state.shown.lock() {
+            *shown = (content.live, countdown);
+        }
+    }
+    Ok(())
+}
+
+/// Rebuild the tray menu for the current phase (e.g. after the timer catalog
+/// changes).
+pub fn refresh_tray_menu(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let phase = {
+        let managed = app.state::<Mutex<crate::border_state::BorderState>>();
+        let guard = managed.lock().map_err(|e| e.to_string())?;
+        guard.phase.clone()
+    };
+    update_tray_menu(app, &phase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use chrono::{Duration as ChronoDuration, TimeZone};
+
+    /// Local noon, so "today" doesn't depend on the machine's time zone.
+    fn noon() -> DateTime<Utc> {
+        Local
+            .with_ymd_and_hms(2026, 3, 2, 12, 0, 0)
+            .unwrap()
+            .to_utc()
+    }
+
+    fn event(id: &str, start_min: i64, end_min: i64) -> CalendarEvent {
+        let at = |min| noon() + ChronoDuration::minutes(min);
+        test_support::event(id, at(start_min), at(end_min))
+    }
+
+    #[test]
+    fn no_events_maps_to_free_time() {
+        assert_eq!(phase_to_label("no-events"), "Free time");
+    }
+
+    #[test]
+    fn free_deep_maps_to_free_time() {
+        assert_eq!(phase_to_label("free-deep"), "Free time");
+    }
+
+    #[test]
+    fn warning_far_maps_correctly() {
+        assert_eq!(phase_to_label("warning-far"), "Meeting in ~30 min");
+    }
+
+    #[test]
+    fn warning_mid_maps_correctly() {
+        assert_eq!(phase_to_label("warning-mid"), "Meeting in ~15 min");
+    }
+
+    #[test]
+    fn warning_near_maps_correctly() {
+        assert_eq!(phase_to_label("warning-near"), "Meeting in ~5 min");
+    }
+
+    #[test]
+    fn warning_imminent_maps_correctly() {
+        assert_eq!(phase_to_label("warning-imminent"), "Meeting in ~2 min");
+    }
+
+    #[test]
+    fn in_session_early_maps_to_in_session() {
+        assert_eq!(phase_to_label("in-session-early"), "In session");
+    }
+
+    #[test]
+    fn in_session_mid_maps_to_in_session() {
+        assert_eq!(phase_to_label("in-session-mid"), "In session");
+    }
+
+    #[test]
+    fn in_session_late_maps_to_in_session() {
+        assert_eq!(phase_to_label("in-session-late"), "In session");
+    }
+
+    #[test]
+    fn overtime_maps_correctly() {
+        assert_eq!(phase_to_label("overtime"), "Overtime");
+    }
+
+    #[test]
+    fn off_hours_maps_correctly() {
+        assert_eq!(phase_to_label("off-hours"), "Off hours");
+    }
+
+    #[test]
+    fn gap_short_maps_to_break() {
+        assert_eq!(phase_to_label("gap-short"), "Break");
+    }
+
+    #[test]
+    fn gap_long_maps_to_break() {
+        assert_eq!(phase_to_label("gap-long"), "Break");
+    }
+
+    #[test]
+    fn unknown_phase_defaults_to_free_time() {
+        assert_eq!(phase_to_label("something-unknown"), "Free time");
+    }
+
+    #[test]
+    fn upcoming_events_lists_rest_of_today() {
+        let mut ignored = event("ignored", 30, 60);
+        ignored.ignored = true;
+        let mut all_day = event("all-day", -720, 720);
+        all_day.is_all_day = true;
+        let events = vec![
+            event("later", 120, 150),
+            event("done", -90, -30),
+            event("now", -10, 20),
+            event("tomorrow", 24 * 60, 24 * 60 + 30),
+            ignored,
+            all_day,
+            event("soon", 30, 60),
+        ];
+        let ids: Vec<&str> = upcoming_events(&events, noon())
+            .iter()
+            .map(|e| e.id.as_str())
+            .collect();
+        assert_eq!(ids, ["now", "soon", "later"]);
+    }
+
+    #[test]
+    fn upcoming_events_is_capped() {
+        let events: Vec<_> = (0..10)
+            .map(|i| event(&i.to_string(), i * 10, i * 10 + 5))
+            .collect();
+        assert_eq!(upcoming_events(&events, noon()).len(), MAX_EVENTS);
+    }
+
+    #[test]
+    fn event_label_shows_start_or_end() {
+        assert_eq!(event_label(&event("a", 90, 120), noon()), "13:30 Meeting a");
+        assert_eq!(
+            event_label(&event("b", -15, 30), noon()),
+            "Now: Meeting b (until 12:30)"
+        );
+    }
+
+    #[test]
+    fn timer_label_counts_down() {
+        let mut state = TimerState::running(25 * 60, noon());
+        let later = noon() + ChronoDuration::seconds(60 * 12 + 26);
+        assert_eq!(
+            timer_label(&state, Some("Pomodoro"), later),
+            "Pomodoro: 12:34 left"
+        );
+        state.pause(later);
+        assert_eq!(
+            timer_label(&state, None, later),
+            "Timer: paused, 12:34 left"
+        );
+        assert_eq!(format_countdown(3845.2), "1:04:06");
+    }
+
+    #[test]
+    fn pause_label_shows_end_time_or_event() {
+        let minutes = pause::PauseRequest::Minutes { minutes: 150 };
+        let timed = pause::resolve(&minutes, &[], noon(), "tray").unwrap();
+        assert_eq!(pause_label(&timed), "Paused until 14:30 — Resume");
+
+        let events = [event("standup", -5, 10)];
+        let request = pause::PauseRequest::EventEnd;
+        let meeting = pause::resolve(&request, &events, noon(), "tray").unwrap();
+        assert_eq!(
+            pause_label(&meeting),
+            "Paused until Meeting standup ends — Resume"
+        );
+    }
+
+    #[test]
+    fn status_label_appends_active_rule() {
+        assert_eq!(status_label("free-deep", None), "Free time");
+        assert_eq!(
+            status_label("free-deep", Some("Evening")),
+            "Free time · Evening"
+        );
+    }
+}
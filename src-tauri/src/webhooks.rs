@@ -0,0 +1,746 @@
+//! Outgoing webhooks: a signed JSON POST to each configured target when a
+//! subscribed transition happens (phase change, meeting start/end, timer
+//! completion, border pause/resume).
+//!
+//! Every request carries:
+//! - `X-Morph-Event`: the event name
+//! - `X-Morph-Delivery`: an ID shared by all retries of the same delivery
+//! - `X-Morph-Timestamp`: Unix seconds when the attempt was sent
+//! - `X-Morph-Signature`: `sha256=` + hex HMAC-SHA256 of
+//!   `"{timestamp}.{body}"` keyed with the target's secret
+//!
+//! Failed deliveries (network errors, 429 and 5xx) are retried with
+//! exponential backoff. Each attempt is logged in `webhook_deliveries`.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+use tauri::{command, AppHandle};
+
+use crate::history::timestamp;
+use crate::settings;
+
+/// Attempts per delivery, including the first.
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Wait before the first retry; doubled for each one after.
+const RETRY_BASE: Duration = Duration::from_secs(5);
+
+/// Per-attempt request timeout.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delivery log rows kept per target.
+const LOG_LIMIT: i64 = 200;
+
+/// A transition that can trigger a webhook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WebhookEvent {
+    PhaseChanged,
+    MeetingStarted,
+    MeetingEnded,
+    TimerCompleted,
+    BorderPaused,
+    BorderResumed,
+    /// Sent by "Send test" in settings, whatever the target subscribes to.
+    Test,
+}
+
+impl WebhookEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WebhookEvent::PhaseChanged => "phase-changed",
+            WebhookEvent::MeetingStarted => "meeting-started",
+            WebhookEvent::MeetingEnded => "meeting-ended",
+            WebhookEvent::TimerCompleted => "timer-completed",
+            WebhookEvent::BorderPaused => "border-paused",
+            WebhookEvent::BorderResumed => "border-resumed",
+            WebhookEvent::Test => "test",
+        }
+    }
+}
+
+/// A configured webhook target.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookTarget {
+    pub id: i64,
+    pub name: String,
+    pub url: String,
+    /// Shared secret for verifying `X-Morph-Signature`.
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub enabled: bool,
+}
+
+/// A target as edited in settings. `id` is `None` for a new target; the
+/// secret is generated on creation and kept on update.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookTargetInput {
+    #[serde(default)]
+    pub id: Option<i64>,
+    pub name: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub enabled: bool,
+}
+
+impl WebhookTargetInput {
+    fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("name must not be empty".to_string());
+        }
+        let url = url::Url::parse(&self.url).map_err(|e| format!("invalid URL: {e}"))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err("URL must use http or https".to_string());
+        }
+        if self.events.is_empty() {
+            return Err("select at least one event".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// One logged delivery attempt.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryAttempt {
+    pub delivery_id: String,
+    pub event: String,
+    pub attempt: u32,
+    pub attempted_at: String,
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+    pub success: bool,
+}
+
+/// A payload ready to send to one target.
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub id: String,
+    pub event: WebhookEvent,
+    pub body: String,
+}
+
+impl Delivery {
+    /// Wrap `data` in the JSON envelope sent to every target:
+    /// `{id, event, occurredAt, data}`.
+    pub fn new(event: WebhookEvent, data: serde_json::Value) -> Self {
+        let id = random_id(16);
+        let body = serde_json::json!({
+            "id": id,
+            "event": event,
+            "occurredAt": timestamp(Utc::now()),
+            "data": data,
+        })
+        .to_string();
+        Self { id, event, body }
+    }
+}
+
+fn random_id(bytes: usize) -> String {
+    let bytes: Vec<u8> = (0..bytes).map(|_| rand::rng().random::<u8>()).collect();
+    URL_SAFE_NO_PAD.encode(&bytes)
+}
+
+/// Hex HMAC-SHA256 of `message` keyed with `key`.
+fn hmac_hex(key: &str, message: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(message.as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// The `X-Morph-Signature` value for a request body sent at `timestamp`.
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    format!(
+        "sha256={}",
+        hmac_hex(secret, &format!("{timestamp}.{body}"))
+    )
+}
+
+/// Retry schedule for failed deliveries.
+#[derive(Debug, Clone, Copy)]
+pub struct Backoff {
+    pub attempts: u32,
+    pub base: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            attempts: MAX_ATTEMPTS,
+            base: RETRY_BASE,
+        }
+    }
+}
+
+impl Backoff {
+    /// Wait before `attempt` (2-based: the first retry waits `base`).
+    fn delay(&self, attempt: u32) -> Duration {
+        self.base * 2u32.saturating_pow(attempt.saturating_sub(2))
+    }
+}
+
+/// Whether a response status is worth retrying.
+fn retryable(status: u16) -> bool {
+    status == 429 || status >= 500
+}
+
+// --- Database ---
+
+fn target_from_row(row: &sqlx::sqlite::SqliteRow) -> WebhookTarget {
+    WebhookTarget {
+        id: row.get("id"),
+        name: row.get("name"),
+        url: row.get("url"),
+        secret: row.get("secret"),
+        events: serde_json::from_str(&row.get::<String, _>("events")).unwrap_or_default(),
+        enabled: row.get::<i64, _>("enabled") != 0,
+    }
+}
+
+async fn list_targets_inner(pool: &SqlitePool) -> Result<Vec<WebhookTarget>, String> {
+    let rows = sqlx::query(
+        "SELECT id, name, url, secret, events, enabled FROM webhook_targets ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows.iter().map(target_from_row).collect())
+}
+
+async fn get_target_inner(pool: &SqlitePool, id: i64) -> Result<WebhookTarget, String> {
+    sqlx::query("SELECT id, name, url, secret, events, enabled FROM webhook_targets WHERE id = ?1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|row| target_from_row(&row))
+        .ok_or_else(|| format!("unknown webhook: {id}"))
+}
+
+async fn save_target_inner(
+    pool: &SqlitePool,
+    input: &WebhookTargetInput,
+) -> Result<WebhookTarget, String> {
+    input.validate()?;
+    let events = serde_json::to_string(&input.events).map_err(|e| e.to_string())?;
+    let id = match input.id {
+        Some(id) => {
+            let updated = sqlx::query(
+                "UPDATE webhook_targets SET name = ?1, url = ?2, events = ?3, enabled = ?4
+                 WHERE id = ?5",
+            )
+            .bind(input.name.trim())
+            .bind(&input.url)
+            .bind(&events)
+            .bind(input.enabled)
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            if updated.rows_affected() == 0 {
+                return Err(format!("unknown webhook: {id}"));
+            }
+            id
+        }
+        None => sqlx::query(
+            "INSERT INTO webhook_targets (name, url, secret, events, enabled)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .bind(input.name.trim())
+        .bind(&input.url)
+        .bind(random_id(32))
+        .bind(&events)
+        .bind(input.enabled)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .last_insert_rowid(),
+    };
+    get_target_inner(pool, id).await
+}
+
+async fn delete_target_inner(pool: &SqlitePool, id: i64) -> Result<(), String> {
+    sqlx::query("DELETE FROM webhook_deliveries WHERE target_id = ?1")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM webhook_targets WHERE id = ?1")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Enabled targets subscribed to `event`.
+async fn subscribers(pool: &SqlitePool, event: WebhookEvent) -> Result<Vec<WebhookTarget>, String> {
+    Ok(list_targets_inner(pool)
+        .await?
+        .into_iter()
+        .filter(|t| t.enabled && t.events.contains(&event))
+        .collect())
+}
+
+/// Log an attempt and drop the target's oldest rows beyond [`LOG_LIMIT`].
+async fn log_attempt(
+    pool: &SqlitePool,
+    target_id: i64,
+    attempt: &DeliveryAttempt,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO webhook_deliveries
+           (target_id, delivery_id, event, attempt, attempted_at, status_code, error, success)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )
+    .bind(target_id)
+    .bind(&attempt.delivery_id)
+    .bind(&attempt.event)
+    .bind(attempt.attempt)
+    .bind(&attempt.attempted_at)
+    .bind(attempt.status_code)
+    .bind(&attempt.error)
+    .bind(attempt.success)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    sqlx::query(
+        "DELETE FROM webhook_deliveries WHERE target_id = ?1 AND id NOT IN
+           (SELECT id FROM webhook_deliveries WHERE target_id = ?1 ORDER BY id DESC LIMIT ?2)",
+    )
+    .bind(target_id)
+    .bind(LOG_LIMIT)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Most recent attempts for a target, newest first.
+async fn deliveries_inner(
+    pool: &SqlitePool,
+    target_id: i64,
+    limit: u32,
+) -> Result<Vec<DeliveryAttempt>, String> {
+    let rows = sqlx::query(
+        "SELECT delivery_id, event, attempt, attempted_at, status_code, error, success
+         FROM webhook_deliveries WHERE target_id = ?1 ORDER BY id DESC LIMIT ?2",
+    )
+    .bind(target_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows
+        .iter()
+        .map(|r| DeliveryAttempt {
+            delivery_id: r.get("delivery_id"),
+            event: r.get("event"),
+            attempt: r.get::<i64, _>("attempt") as u32,
+            attempted_at: r.get("attempted_at"),
+            status_code: r.get::<Option<i64>, _>("status_code").map(|s| s as u16),
+            error: r.get("error"),
+            success: r.get::<i64, _>("success") != 0,
+        })
+        .collect())
+}
+
+// --- Delivery ---
+
+/// Send one attempt. Returns the response status, or an error if there was
+/// no response.
+async fn send(
+    client: &reqwest::Client,
+    target: &WebhookTarget,
+    delivery: &Delivery,
+) -> Result<u16, String> {
+    let sent_at = Utc::now().timestamp();
+    let response = client
+        .post(&target.url)
+        .timeout(REQUEST_TIMEOUT)
+        .header("Content-Type", "application/json")
+        .header("User-Agent", concat!("Morph/", env!("CARGO_PKG_VERSION")))
+        .header("X-Morph-Event", delivery.event.as_str())
+        .header("X-Morph-Delivery", &delivery.id)
+        .header("X-Morph-Timestamp", sent_at.to_string())
+        .header(
+            "X-Morph-Signature",
+            sign(&target.secret, sent_at, &delivery.body),
+        )
+        .body(delivery.body.clone())
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(response.status().as_u16())
+}
+
+/// Deliver to one target, retrying per `backoff` and logging every attempt.
+/// Returns whether the target accepted the delivery.
+pub async fn deliver(
+    client: &reqwest::Client,
+    pool: &SqlitePool,
+    target: &WebhookTarget,
+    delivery: &Delivery,
+    backoff: Backoff,
+) -> bool {
+    for attempt in 1..=backoff.attempts {
+        if attempt > 1 {
+            tokio::time::sleep(backoff.delay(attempt)).await;
+        }
+        let attempted_at = timestamp(Utc::now());
+        let result = send(client, target, delivery).await;
+        let (status_code, error) = match &result {
+            Ok(status) => (Some(*status), None),
+            Err(e) => (None, Some(e.clone())),
+        };
+        let success = status_code.is_some_and(|s| (200..300).contains(&s));
+        let record = DeliveryAttempt {
+            delivery_id: delivery.id.clone(),
+            event: delivery.event.as_str().to_string(),
+            attempt,
+            attempted_at,
+            status_code,
+            error,
+            success,
+        };
+        if let Err(e) = log_attempt(pool, target.id, &record).await {
+            eprintln!("[webhooks] Failed to log delivery: {e}");
+        }
+
+        if success || status_code.is_some_and(|s| !retryable(s)) {
+            return success;
+        }
+    }
+    eprintln!(
+        "[webhooks] Giving up on {} for '{}' after {} attempts",
+        delivery.event.as_str(),
+        target.name,
+        backoff.attempts
+    );
+    false
+}
+
+fn client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(reqwest::Client::new)
+}
+
+/// Fire `event` to every subscribed target in the background.
+pub fn notify(app: &AppHandle, event: WebhookEvent, data: impl Serialize) {
+    let data = match serde_json::to_value(data) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("[webhooks] Failed to serialize {}: {e}", event.as_str());
+            return;
+        }
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let Ok(pool) = settings::app_pool(&app).await else {
+            return;
+        };
+        let targets = match subscribers(&pool, event).await {
+            Ok(targets) => targets,
+            Err(e) => {
+                eprintln!("[webhooks] Failed to load targets: {e}");
+                return;
+            }
+        };
+        for target in targets {
+            // Each target gets its own delivery ID and retry loop so a slow
+            // target does not hold up the others.
+            let pool = pool.clone();
+            let delivery = Delivery::new(event, data.clone());
+            tauri::async_runtime::spawn(async move {
+                deliver(client(), &pool, &target, &delivery, Backoff::default()).await;
+            });
+        }
+    });
+}
+
+// --- Tauri commands ---
+
+/// Return every webhook target.
+#[command]
+pub async fn list_webhooks(app: AppHandle) -> Result<Vec<WebhookTarget>, String> {
+    let pool = settings::app_pool(&app).await?;
+    list_targets_inner(&pool).await
+}
+
+/// Create or update a webhook target.
+#[command]
+pub async fn save_webhook(
+    app: AppHandle,
+    target: WebhookTargetInput,
+) -> Result<WebhookTarget, String> {
+    let pool = settings::app_pool(&app).await?;
+    save_target_inner(&pool, &target).await
+}
+
+/// Delete a webhook target and its delivery log.
+#[command]
+pub async fn delete_webhook(app: AppHandle, id: i64) -> Result<(), String> {
+    let pool = settings::app_pool(&app).await?;
+    delete_target_inner(&pool, id).await
+}
+
+/// Most recent delivery attempts for a target, newest first.
+#[command]
+pub async fn get_webhook_deliveries(
+    app: AppHandle,
+    id: i64,
+    limit: u32,
+) -> Result<Vec<DeliveryAttempt>, String> {
+    let pool = settings::app_pool(&app).await?;
+    deliveries_inner(&pool, id, limit).await
+}
+
+/// Send a `test` event to one target once, without retries. Returns whether
+/// it was accepted; details are in the delivery log.
+#[command]
+pub async fn test_webhook(app: AppHandle, id: i64) -> Result<bool, String> {
+    let pool = settings::app_pool(&app).await?;
+    let target = get_target_inner(&pool, id).await?;
+    let delivery = Delivery::new(
+        WebhookEvent::Test,
+        serde_json::json!({ "message": "Test delivery from Morph" }),
+    );
+    let once = Backoff {
+        attempts: 1,
+        ..Backoff::default()
+    };
+    Ok(deliver(client(), &pool, &target, &delivery, once).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::stand_in;
+
+    async fn test_pool() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory pool");
+        crate::migrations::migrate(&pool)
+            .await
+            .expect("Failed to migrate");
+        pool
+    }
+
+    fn input(url: &str, events: Vec<WebhookEvent>) -> WebhookTargetInput {
+        WebhookTargetInput {
+            id: None,
+            name: "Busy light".to_string(),
+            url: url.to_string(),
+            events,
+            enabled: true,
+        }
+    }
+
+    fn fast() -> Backoff {
+        Backoff {
+            attempts: 3,
+            base: Duration::from_millis(10),
+        }
+    }
+
+    #[test]
+    fn test_signature_is_hmac_sha256_of_timestamp_and_body() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            hmac_hex("Jefe", "what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            sign("Jefe", 1767225600, "{}"),
+            format!("sha256={}", hmac_hex("Jefe", "1767225600.{}"))
+        );
+        assert_ne!(sign("Jefe", 1, "{}"), sign("Jefe", 2, "{}"));
+        assert_ne!(sign("a", 1, "{}"), sign("b", 1, "{}"));
+    }
+
+    #[test]
+    fn test_backoff_doubles() {
+        let backoff = Backoff {
+            attempts: 5,
+            base: Duration::from_secs(5),
+        };
+        let delays: Vec<u64> = (2..=5).map(|a| backoff.delay(a).as_secs()).collect();
+        assert_eq!(delays, vec![5, 10, 20, 40]);
+    }
+
+    #[tokio::test]
+    async fn test_save_validates_and_keeps_secret() {
+        let pool = test_pool().await;
+        assert!(
+            save_target_inner(&pool, &input("ftp://x", vec![WebhookEvent::Test]))
+                .await
+                .is_err()
+        );
+        assert!(
+            save_target_inner(&pool, &input("http://localhost/x", vec![]))
+                .await
+                .is_err()
+        );
+
+        let created = save_target_inner(
+            &pool,
+            &input("http://localhost/x", vec![WebhookEvent::PhaseChanged]),
+        )
+        .await
+        .unwrap();
+        assert!(!created.secret.is_empty());
+
+        let mut edit = input("http://localhost/y", vec![WebhookEvent::MeetingStarted]);
+        edit.id = Some(created.id);
+        edit.enabled = false;
+        let updated = save_target_inner(&pool, &edit).await.unwrap();
+        assert_eq!(updated.secret, created.secret);
+        assert_eq!(updated.url, "http://localhost/y");
+        assert_eq!(updated.events, vec![WebhookEvent::MeetingStarted]);
+        assert!(!updated.enabled);
+
+        edit.id = Some(999);
+        assert!(save_target_inner(&pool, &edit).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_filter_by_event_and_enabled() {
+        let pool = test_pool().await;
+        save_target_inner(
+            &pool,
+            &input("http://localhost/a", vec![WebhookEvent::PhaseChanged]),
+        )
+        .await
+        .unwrap();
+        let mut disabled = input("http://localhost/b", vec![WebhookEvent::PhaseChanged]);
+        disabled.enabled = false;
+        save_target_inner(&pool, &disabled).await.unwrap();
+        save_target_inner(
+            &pool,
+            &input("http://localhost/c", vec![WebhookEvent::TimerCompleted]),
+        )
+        .await
+        .unwrap();
+
+        let phase = subscribers(&pool, WebhookEvent::PhaseChanged)
+            .await
+            .unwrap();
+        assert_eq!(phase.len(), 1);
+        assert_eq!(phase[0].url, "http://localhost/a");
+        assert!(subscribers(&pool, WebhookEvent::BorderPaused)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delivery_retries_server_errors_and_signs_requests() {
+        let pool = test_pool().await;
+        let (base, received) = stand_in(vec![(503, ""), (200, "")]);
+        let target = save_target_inner(
+            &pool,
+            &input(&format!("{base}/hook"), vec![WebhookEvent::TimerCompleted]),
+        )
+        .await
+        .unwrap();
+        let delivery = Delivery::new(
+            WebhookEvent::TimerCompleted,
+            serde_json::json!({ "presetId": "pomodoro" }),
+        );
+
+        assert!(deliver(&reqwest::Client::new(), &pool, &target, &delivery, fast()).await);
+
+        let received = std::mem::take(&mut *received.lock().unwrap());
+        assert_eq!(received.len(), 2);
+        let last = &received[1];
+        assert_eq!(last.header("X-Morph-Event"), "timer-completed");
+        assert_eq!(last.header("X-Morph-Delivery"), delivery.id);
+        assert_eq!(received[0].header("X-Morph-Delivery"), delivery.id);
+        let sent_at: i64 = last.header("X-Morph-Timestamp").parse().unwrap();
+        assert_eq!(
+            last.header("X-Morph-Signature"),
+            sign(&target.secret, sent_at, &last.body)
+        );
+        let body: serde_json::Value = serde_json::from_str(&last.body).unwrap();
+        assert_eq!(body["event"], "timer-completed");
+        assert_eq!(body["data"]["presetId"], "pomodoro");
+
+        let log = deliveries_inner(&pool, target.id, 10).await.unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(
+            (log[0].attempt, log[0].status_code, log[0].success),
+            (2, Some(200), true)
+        );
+        assert_eq!(
+            (log[1].attempt, log[1].status_code, log[1].success),
+            (1, Some(503), false)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delivery_does_not_retry_client_errors() {
+        let pool = test_pool().await;
+        let (base, received) = stand_in(vec![(404, "")]);
+        let target = save_target_inner(
+            &pool,
+            &input(&format!("{base}/hook"), vec![WebhookEvent::Test]),
+        )
+        .await
+        .unwrap();
+        let delivery = Delivery::new(WebhookEvent::Test, serde_json::Value::Null);
+
+        assert!(!deliver(&reqwest::Client::new(), &pool, &target, &delivery, fast()).await);
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_delivery_gives_up_after_max_attempts() {
+        let pool = test_pool().await;
+        // Nothing listens here, so every attempt fails to connect.
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let target = save_target_inner(
+            &pool,
+            &input(
+                &format!("http://127.0.0.1:{port}/"),
+                vec![WebhookEvent::Test],
+            ),
+        )
+        .await
+        .unwrap();
+        let delivery = Delivery::new(WebhookEvent::Test, serde_json::Value::Null);
+
+        assert!(!deliver(&reqwest::Client::new(), &pool, &target, &delivery, fast()).await);
+        let log = deliveries_inner(&pool, target.id, 10).await.unwrap();
+        assert_eq!(log.len(), 3);
+        assert!(log
+            .iter()
+            .all(|a| a.status_code.is_none() && a.error.is_some()));
+
+        delete_target_inner(&pool, target.id).await.unwrap();
+        assert!(deliveries_inner(&pool, target.id, 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
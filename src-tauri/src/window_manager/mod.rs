@@ -4,6 +4,17 @@ pub mod macos;
 #[cfg(target_os = "windows")]
 pub mod windows;
 
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager};
+
+/// The platform's overlay manager. One is managed as app state, so every
+/// caller works on the same configured windows.
+#[cfg(target_os = "macos")]
+pub type PlatformOverlayManager = macos::MacOSOverlayManager;
+#[cfg(target_os = "windows")]
+pub type PlatformOverlayManager = windows::WindowsOverlayManager;
+
 /// Border window labels used for dynamic window creation.
 pub const BORDER_LABELS: [&str; 4] = ["border-top", "border-bottom", "border-left", "border-right"];
 
@@ -69,6 +80,88 @@ pub trait OverlayManager {
     fn set_target_monitor(&mut self, monitor_id: &str);
 }
 
+/// What keeps the border windows hidden. They are shown only while nothing
+/// does, so ending one hide doesn't undo another.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BorderVisibility {
+    /// Hidden with the "hide border" shortcut.
+    pub hotkey: bool,
+    /// Hidden while the screen is shared or presented.
+    pub screen_share: bool,
+}
+
+impl BorderVisibility {
+    pub fn visible(&self) -> bool {
+        !self.hotkey && !self.screen_share
+    }
+
+    /// Apply `change`. Returns whether the border should now be visible,
+    /// if that changed.
+    pub fn update(&mut self, change: impl FnOnce(&mut Self)) -> Option<bool> {
+        let before = self.visible();
+        change(self);
+        let after = self.visible();
+        (before != after).then_some(after)
+    }
+}
+
+/// Change what hides the border, and show or hide the windows when that
+/// changes whether they should be visible.
+pub fn update_visibility(
+    app: &AppHandle,
+    change: impl FnOnce(&mut BorderVisibility),
+) -> Result<(), String> {
+    let visible = app
+        .state::<Mutex<BorderVisibility>>()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .update(change);
+    if let Some(visible) = visible {
+        set_windows_visible(app, visible);
+    }
+    Ok(())
+}
+
+/// Show or hide the border windows through the managed overlay manager.
+fn set_windows_visible(app: &AppHandle, visible: bool) {
+    let handle = app.clone();
+    // Native window calls must run on the main thread
+    let _ = app.run_on_main_thread(move || {
+        #[cfg(any(target_os = "macos", target_os = "windows"))]
+        {
+            let managed = handle.state::<Mutex<PlatformOverlayManager>>();
+            let Ok(mut manager) = managed.lock() else {
+                return;
+            };
+            // Setup hasn't got to the windows yet
+            if manager.top.is_none() {
+                if let Err(e) = manager.create_overlay_windows(&handle) {
+                    eprintln!("[overlay] Failed to get overlay windows: {e}");
+                    return;
+                }
+            }
+            if visible {
+                manager.show();
+            } else {
+                manager.hide();
+            }
+        }
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        for label in BORDER_LABELS {
+            if let Some(window) = handle.get_webview_window(label) {
+                let result = if visible {
+                    window.show()
+                } else {
+                    window.hide()
+                };
+                if let Err(e) = result {
+                    eprintln!("[overlay] Failed to update '{label}': {e}");
+                }
+            }
+        }
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,4 +186,25 @@ mod tests {
         assert_eq!(json["y"], 0.0);
         assert_eq!(json["is_primary"], true);
     }
+
+    #[test]
+    fn border_shows_only_when_nothing_hides_it() {
+        let mut visibility = BorderVisibility::default();
+        assert!(visibility.visible());
+
+        assert_eq!(visibility.update(|v| v.hotkey = true), Some(false));
+        // Sharing starts while hidden by the shortcut: nothing to do.
+        assert_eq!(visibility.update(|v| v.screen_share = true), None);
+        // Unhiding with the shortcut mid-share keeps the border hidden.
+        assert_eq!(visibility.update(|v| v.hotkey = false), None);
+        assert!(!visibility.visible());
+        assert_eq!(visibility.update(|v| v.screen_share = false), Some(true));
+
+        // Sharing ending doesn't undo the shortcut.
+        visibility.update(|v| v.screen_share = true);
+        visibility.update(|v| v.hotkey = true);
+        assert_eq!(visibility.update(|v| v.screen_share = false), None);
+        assert!(!visibility.visible());
+        assert_eq!(visibility.update(|v| v.hotkey = false), Some(true));
+    }
 }
@@ -0,0 +1,253 @@
+//! Working hours per weekday.
+//!
+//! The color engine in the overlay resolves the border phase: outside
+//! working hours, free time shows as `off-hours` instead of `free-deep`,
+//! `gap-long` or `no-events`, and with `off_hours_mode` = "hide" the border
+//! isn't drawn. Meetings and their warnings still show. This module
+//! validates the setting and imports hours from Outlook mailbox settings,
+//! converted from the mailbox's time zone to the local one. Google
+//! Calendar's API doesn't expose working hours (only working locations), so
+//! there is no Google import.
+
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle};
+
+use crate::calendar::microsoft::{MicrosoftCalendarProvider, MsWorkingHours};
+use crate::calendar::provider::CalendarProvider;
+use crate::settings;
+
+pub const ENABLED_KEY: &str = "working_hours_enabled";
+pub const HOURS_KEY: &str = "working_hours";
+pub const MODE_KEY: &str = "off_hours_mode";
+
+/// Phase reported by the overlay outside working hours.
+pub const OFF_HOURS_PHASE: &str = "off-hours";
+
+/// Default for [`HOURS_KEY`]: 09:00-17:00, Monday to Friday.
+pub const DEFAULT_HOURS: &str = r#"{"mon":{"start":"09:00","end":"17:00"},"tue":{"start":"09:00","end":"17:00"},"wed":{"start":"09:00","end":"17:00"},"thu":{"start":"09:00","end":"17:00"},"fri":{"start":"09:00","end":"17:00"}}"#;
+
+const DAY_NAMES: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// One day's hours, as local `HH:MM` times. `start` is before `end`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayHours {
+    pub start: String,
+    pub end: String,
+}
+
+/// Day name ("mon" ... "sun") → that day's hours. Missing days are off.
+pub type WorkingHours = BTreeMap<String, DayHours>;
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M").ok()
+}
+
+/// Parse and validate the `working_hours` setting.
+pub fn parse(json: &str) -> Result<WorkingHours, String> {
+    let hours: WorkingHours =
+        serde_json::from_str(json).map_err(|e| format!("invalid working hours: {e}"))?;
+    for (day, DayHours { start, end }) in &hours {
+        if !DAY_NAMES.contains(&day.as_str()) {
+            return Err(format!(
+                "invalid day '{day}' (expected one of {})",
+                DAY_NAMES.join(", ")
+            ));
+        }
+        let (Some(from), Some(to)) = (parse_time(start), parse_time(end)) else {
+            return Err(format!("{day}: invalid time in '{start}'-'{end}'"));
+        };
+        if from >= to {
+            return Err(format!("{day}: start {start} must be before end {end}"));
+        }
+    }
+    Ok(hours)
+}
+
+/// Convert Outlook working hours ("monday", "08:00:00.0000000") to local
+/// ones. Graph gives one start and end for all working days, in the mailbox's
+/// time zone; each day is converted on its date in the week of `today`, so
+/// the offset between the zones is the current one.
+pub fn from_graph<Z: TimeZone>(
+    graph: &MsWorkingHours,
+    today: NaiveDate,
+    local: &Z,
+) -> Result<WorkingHours, String> {
+    let zone = graph.time_zone.tz().ok_or_else(|| {
+        format!(
+            "unsupported time zone '{}' in mailbox settings",
+            graph.time_zone.name
+        )
+    })?;
+    let time = |value: &str| {
+        value
+            .get(..5)
+            .and_then(parse_time)
+            .ok_or_else(|| format!("unexpected time '{value}' in mailbox settings"))
+    };
+    let (start, end) = (time(&graph.start_time)?, time(&graph.end_time)?);
+    let monday = today - Duration::days(today.weekday().num_days_from_monday().into());
+
+    let mut hours = WorkingHours::new();
+    for name in &graph.days_of_week {
+        let Some(offset) = DAY_NAMES
+            .iter()
+            .position(|day| name.get(..3).is_some_and(|n| n.eq_ignore_ascii_case(day)))
+        else {
+            continue;
+        };
+        let date = monday + Duration::days(offset as i64);
+        let to_local = |t: NaiveTime| {
+            zone.from_local_datetime(&date.and_time(t))
+                .earliest()
+                .map(|at| at.with_timezone(local).naive_local())
+                .ok_or_else(|| format!("{t} doesn't exist on {date} in {zone}"))
+        };
+        let (from, to) = (to_local(start)?, to_local(end)?);
+        if from.date() != to.date() {
+            return Err(format!(
+                "{name}: {start}-{end} in {zone} crosses midnight in the local time zone"
+            ));
+        }
+        hours.insert(
+            DAY_NAMES[from.weekday().num_days_from_monday() as usize].to_string(),
+            DayHours {
+                start: from.format("%H:%M").to_string(),
+                end: to.format("%H:%M").to_string(),
+            },
+        );
+    }
+    // Validate the result the same way as user input.
+    parse(&serde_json::to_string(&hours).map_err(|e| e.to_string())?)
+}
+
+// --- Tauri commands ---
+
+/// Replace working hours with the ones set in Outlook, and turn them on.
+#[command]
+pub async fn import_working_hours(app: AppHandle) -> Result<WorkingHours, String> {
+    // Mailbox settings have their own grant, asked for on first import.
+    let mut graph = MicrosoftCalendarProvider::for_mailbox();
+    graph.load_stored_tokens().ok();
+    if !graph.has_refresh_token() {
+        graph.authenticate().await.map_err(|e| e.to_string())?;
+    }
+    let working_hours = graph
+        .fetch_working_hours()
+        .await
+        .map_err(|e| e.to_string())?;
+    let hours = from_graph(&working_hours, Local::now().date_naive(), &Local)?;
+    let value = serde_json::to_string(&hours).map_err(|e| e.to_string())?;
+
+    let pool = settings::app_pool(&app).await?;
+    for (key, value) in [(HOURS_KEY, value.as_str()), (ENABLED_KEY, "true")] {
+        settings::set_setting_inner(&pool, key, value).await?;
+        settings::emit_setting_changed(&app, key, value);
+    }
+    Ok(hours)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_default_hours() {
+        let hours = parse(DEFAULT_HOURS).unwrap();
+        assert_eq!(
+            hours.keys().map(String::as_str).collect::<Vec<_>>(),
+            ["fri", "mon", "thu", "tue", "wed"]
+        );
+        assert!(hours
+            .values()
+            .all(|h| h.start == "09:00" && h.end == "17:00"));
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_hours() {
+        for json in [
+            r#"{"monday":{"start":"09:00","end":"17:00"}}"#,
+            r#"{"mon":{"start":"9am","end":"17:00"}}"#,
+            r#"{"mon":{"start":"17:00","end":"09:00"}}"#,
+            r#"{"mon":{"start":"09:00","end":"09:00"}}"#,
+            r#"["mon"]"#,
+        ] {
+            assert!(parse(json).is_err(), "{json}");
+        }
+        assert_eq!(parse("{}").unwrap(), WorkingHours::new());
+    }
+
+    fn outlook(days: &[&str], start: &str, end: &str, zone: &str) -> MsWorkingHours {
+        serde_json::from_value(serde_json::json!({
+            "daysOfWeek": days,
+            "startTime": format!("{start}:00.0000000"),
+            "endTime": format!("{end}:00.0000000"),
+            "timeZone": { "name": zone },
+        }))
+        .unwrap()
+    }
+
+    fn day(start: &str, end: &str) -> DayHours {
+        DayHours {
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_from_graph() {
+        let monday = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let graph: MsWorkingHours = serde_json::from_str(
+            r#"{
+                "daysOfWeek": ["monday", "tuesday", "thursday"],
+                "startTime": "08:30:00.0000000",
+                "endTime": "16:00:00.0000000",
+                "timeZone": { "name": "Pacific Standard Time" }
+            }"#,
+        )
+        .unwrap();
+        let hours = from_graph(&graph, monday, &chrono_tz::America::Los_Angeles).unwrap();
+        assert_eq!(
+            hours.keys().map(String::as_str).collect::<Vec<_>>(),
+            ["mon", "thu", "tue"]
+        );
+        assert_eq!(hours["thu"], day("08:30", "16:00"));
+
+        let overnight = outlook(&["friday"], "22:00", "06:00", "UTC");
+        assert!(from_graph(&overnight, monday, &chrono::Utc).is_err());
+    }
+
+    #[test]
+    fn test_from_graph_converts_time_zone() {
+        let monday = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let pacific = outlook(&["monday"], "08:30", "16:00", "Pacific Standard Time");
+        let hours = from_graph(&pacific, monday, &chrono_tz::America::New_York).unwrap();
+        assert_eq!(hours["mon"], day("11:30", "19:00"));
+
+        // 06:00 Monday in Tokyo is still Sunday in UTC.
+        let tokyo = outlook(&["monday"], "06:00", "08:00", "Asia/Tokyo");
+        let hours = from_graph(&tokyo, monday, &chrono::Utc).unwrap();
+        assert_eq!(hours.keys().collect::<Vec<_>>(), ["sun"]);
+        assert_eq!(hours["sun"], day("21:00", "23:00"));
+
+        // The offset is the one in effect that week: US daylight saving
+        // starts on 2026-03-08, Europe's on 2026-03-29.
+        let week_after = NaiveDate::from_ymd_opt(2026, 3, 11).unwrap();
+        let hours = from_graph(&pacific, week_after, &chrono_tz::Europe::Berlin);
+        assert!(hours.is_err(), "16:00 PDT is 00:00 in Berlin");
+        let early = outlook(&["monday"], "07:00", "14:00", "Pacific Standard Time");
+        let hours = from_graph(&early, week_after, &chrono_tz::Europe::Berlin).unwrap();
+        assert_eq!(hours["mon"], day("15:00", "22:00"));
+    }
+
+    #[test]
+    fn test_from_graph_rejects_unknown_time_zone() {
+        let monday = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let custom = outlook(&["monday"], "09:00", "17:00", "Customized Time Zone");
+        let err = from_graph(&custom, monday, &chrono::Utc).unwrap_err();
+        assert!(err.contains("Customized Time Zone"), "{err}");
+    }
+}
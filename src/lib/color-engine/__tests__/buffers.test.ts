@@ -0,0 +1,54 @@
+import { describe, it, expect } from 'vitest';
+import { getBorderState, DEFAULT_USER_SETTINGS } from '../index.js';
+import type { CalendarEvent } from '../types.js';
+
+const base = new Date('2026-03-02T10:00:00Z');
+
+/** Create a CalendarEvent at a specific time offset (minutes) from `base`. */
+function makeEvent(
+  id: string,
+  startOffsetMin: number,
+  durationMin: number,
+  isBuffer = false,
+): CalendarEvent {
+  const start = new Date(base.getTime() + startOffsetMin * 60_000);
+  const end = new Date(start.getTime() + durationMin * 60_000);
+  return {
+    id,
+    title: isBuffer ? 'Travel: Offsite' : 'Offsite',
+    startTime: start.toISOString(),
+    endTime: end.toISOString(),
+    ignored: false,
+    providerId: isBuffer ? 'buffer' : 'test',
+    isAllDay: false,
+    isBuffer,
+  };
+}
+
+/** A meeting at +60 min with a 15-minute travel buffer before it. */
+const events = [makeEvent('buffer:offsite', 45, 15, true), makeEvent('offsite', 60, 60)];
+const at = (offsetMin: number) => new Date(base.getTime() + offsetMin * 60_000);
+const phaseAt = (offsetMin: number, evts = events) =>
+  getBorderState(evts, at(offsetMin), DEFAULT_USER_SETTINGS).phase;
+
+describe('travel and prep buffers', () => {
+  it('warns ahead of the buffer instead of the event', () => {
+    // 30 min before the buffer starts, 45 before the meeting.
+    expect(phaseAt(15)).toBe('warning-far');
+    expect(phaseAt(15, [makeEvent('offsite', 60, 60)])).not.toBe('warning-far');
+  });
+
+  it('shows the buffer as lead-up time, not a session', () => {
+    expect(phaseAt(45)).toBe('warning-imminent');
+    expect(phaseAt(55)).toBe('warning-imminent');
+  });
+
+  it('starts the session when the event starts', () => {
+    expect(phaseAt(60)).toBe('in-session-early');
+  });
+
+  it('ends with the event', () => {
+    expect(phaseAt(121)).toBe('overtime');
+    expect(phaseAt(125)).toBe('no-events');
+  });
+});
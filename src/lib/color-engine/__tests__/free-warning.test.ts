@@ -168,6 +168,20 @@ describe('ocean palette', () => {
   });
 });
 
+describe('active palette', () => {
+  it('takes colors from the loaded palette over the named one', () => {
+    const activePalette = {
+      ...AMBIENT_PALETTE,
+      'free-deep': { hex: '#0072B2', opacity: 0.35, pulseSpeed: 0 },
+    };
+    const customSettings: UserSettings = { ...settings, palette: 'ocean', activePalette };
+    const events = [makeEvent(now, 90, 30)];
+    const state = getBorderState(events, now, customSettings);
+    expect(state.color).toBe('#0072B2');
+    expect(state.opacity).toBe(0.35);
+  });
+});
+
 describe('smooth interpolation', () => {
   it('produces different colors at different time points in the same zone', () => {
     const events = [makeEvent(now, 20, 30)]; // 20 min out — between warning-far and warning-mid
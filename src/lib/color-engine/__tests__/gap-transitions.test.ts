@@ -149,3 +149,29 @@ describe('gap interpolation smoothness', () => {
     }
   });
 });
+
+describe('backend-driven overtime', () => {
+  function stateWithOvertime(gapMinutes: number, minutesAfterEnd: number, overtime: boolean) {
+    const { base, events } = twoEventsWithGap(gapMinutes);
+    const now = new Date(base.getTime() + (30 + minutesAfterEnd) * 60_000);
+    return getBorderState(events, now, settings, overtime);
+  }
+
+  it('skips the overtime window when the backend says the meeting is over', () => {
+    expect(stateWithOvertime(15, 1, false).phase).toBe('warning-mid');
+    expect(stateWithOvertime(4, 1, false).phase).toBe('gap-short');
+  });
+
+  it('stays in overtime past the fixed window while the backend says so', () => {
+    expect(stateWithOvertime(30, 12, true).phase).toBe('overtime');
+  });
+
+  it('shows overtime even after the ended meeting drops out of the event list', () => {
+    const state = getBorderState([], new Date('2026-03-01T10:00:00Z'), settings, true);
+    expect(state.phase).toBe('overtime');
+  });
+
+  it('never shows overtime over a session in progress', () => {
+    expect(stateWithOvertime(0, 1, true).phase).toMatch(/^in-session/);
+  });
+});
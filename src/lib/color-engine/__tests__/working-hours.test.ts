@@ -0,0 +1,91 @@
+import { describe, it, expect } from 'vitest';
+import {
+  getBorderState,
+  DEFAULT_USER_SETTINGS,
+  isWorkingTime,
+  parseWorkingHours,
+} from '../index.js';
+import type { CalendarEvent, UserSettings, WorkingHours } from '../types.js';
+
+/** Create a CalendarEvent at a specific time offset from `base`. */
+function makeEvent(base: Date, startOffsetMin: number, durationMin: number): CalendarEvent {
+  const start = new Date(base.getTime() + startOffsetMin * 60_000);
+  const end = new Date(start.getTime() + durationMin * 60_000);
+  return {
+    id: `evt-${startOffsetMin}`,
+    title: 'Test Event',
+    startTime: start.toISOString(),
+    endTime: end.toISOString(),
+    ignored: false,
+    providerId: 'test',
+    isAllDay: false,
+  };
+}
+
+const weekday = { start: '09:00', end: '17:00' };
+const hours: WorkingHours = {
+  mon: weekday,
+  tue: weekday,
+  wed: weekday,
+  thu: weekday,
+  fri: weekday,
+};
+const settings: UserSettings = {
+  ...DEFAULT_USER_SETTINGS,
+  workingHoursEnabled: true,
+  workingHours: hours,
+};
+
+// Local times: working hours follow the machine's clock. 2026-03-02 is a Monday.
+const mondayAt = (h: number, m = 0) => new Date(2026, 2, 2, h, m);
+
+describe('isWorkingTime', () => {
+  it('includes the start and excludes the end', () => {
+    expect(isWorkingTime(hours, mondayAt(8, 59))).toBe(false);
+    expect(isWorkingTime(hours, mondayAt(9, 0))).toBe(true);
+    expect(isWorkingTime(hours, mondayAt(16, 59))).toBe(true);
+    expect(isWorkingTime(hours, mondayAt(17, 0))).toBe(false);
+  });
+
+  it('treats missing days as off', () => {
+    const saturday = new Date(2026, 2, 7, 12, 0);
+    expect(isWorkingTime(hours, saturday)).toBe(false);
+    expect(isWorkingTime({}, mondayAt(12))).toBe(false);
+  });
+});
+
+describe('parseWorkingHours', () => {
+  it('falls back to no hours for malformed values', () => {
+    expect(parseWorkingHours('{"mon":{"start":"09:00","end":"17:00"}}')).toEqual({
+      mon: weekday,
+    });
+    expect(parseWorkingHours('not json')).toEqual({});
+    expect(parseWorkingHours('["mon"]')).toEqual({});
+  });
+});
+
+describe('off-hours phase', () => {
+  it('replaces free time outside working hours', () => {
+    const night = mondayAt(23);
+    expect(getBorderState([], night, settings).phase).toBe('off-hours');
+    const tomorrow = makeEvent(night, 600, 30);
+    expect(getBorderState([tomorrow], night, settings).phase).toBe('off-hours');
+  });
+
+  it('keeps free phases during working hours', () => {
+    expect(getBorderState([], mondayAt(10), settings).phase).toBe('no-events');
+  });
+
+  it('still warns before and tracks an evening meeting', () => {
+    const now = mondayAt(19);
+    const soon = makeEvent(now, 5, 30);
+    expect(getBorderState([soon], now, settings).phase).toBe('warning-near');
+    const running = makeEvent(now, -5, 30);
+    expect(getBorderState([running], now, settings).phase).toBe('in-session-early');
+  });
+
+  it('is unchanged when working hours are disabled', () => {
+    const disabled = { ...settings, workingHoursEnabled: false };
+    expect(getBorderState([], mondayAt(23), disabled).phase).toBe('no-events');
+  });
+});
@@ -9,6 +9,10 @@ export type {
   BorderPosition,
   BorderThickness,
   UserSettings,
+  Weekday,
+  DayHours,
+  WorkingHours,
+  OffHoursMode,
 } from './types.js';
 
 export { DEFAULT_USER_SETTINGS, INTENSITY_MULTIPLIERS, MAX_OPACITY } from './types.js';
@@ -21,12 +25,15 @@ export {
   resolvePreSessionPhase,
   resolveInSessionPhase,
   resolveGapPhase,
+  resolveOffHoursPhase,
   buildWarningBoundaries,
   lerp,
 } from './resolve-phase.js';
 
 export type { ParsedEvent, PhaseResult } from './resolve-phase.js';
 
+export { isWorkingTime, parseWorkingHours } from './working-hours.js';
+
 import type { CalendarEvent, BorderState, UserSettings } from './types.js';
 import { INTENSITY_MULTIPLIERS, MAX_OPACITY } from './types.js';
 import { PALETTES } from './palettes.js';
@@ -151,15 +158,19 @@ export function applyIntensity(opacity: number, intensity: UserSettings['intensi
  * Determines which phase the user is in (free time, warning, in-session,
  * gap, overtime, or no-events), then interpolates color/opacity/pulse
  * between adjacent phase palette entries for smooth transitions.
+ *
+ * Pass `overtime` (from the backend's `overtime-state-update`) to let the
+ * backend decide when overtime starts and ends.
  */
 export function getBorderState(
   events: CalendarEvent[],
   now: Date,
   settings: UserSettings,
+  overtime?: boolean,
 ): BorderState {
-  const palette = PALETTES[settings.palette];
+  const palette = settings.activePalette ?? PALETTES[settings.palette];
   const parsed = parseEvents(events, settings);
-  const { fromPhase, toPhase, t } = resolvePhase(parsed, now, settings);
+  const { fromPhase, toPhase, t } = resolvePhase(parsed, now, settings, overtime);
 
   const from = palette[fromPhase];
   const to = palette[toPhase];
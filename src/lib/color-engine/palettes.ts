@@ -19,6 +19,7 @@ export const AMBIENT_PALETTE: Palette = {
   'gap-short': { hex: '#D4864A', opacity: 0.6, pulseSpeed: 2500 },
   'gap-long': { hex: '#5BAE7A', opacity: 0.3, pulseSpeed: 0 },
   'no-events': { hex: '#8A9BA8', opacity: 0.15, pulseSpeed: 0 },
+  'off-hours': { hex: '#6E7C8C', opacity: 0.08, pulseSpeed: 0 },
 };
 
 /**
@@ -42,6 +43,7 @@ export const OCEAN_PALETTE: Palette = {
   'gap-short': { hex: '#D49458', opacity: 0.6, pulseSpeed: 2500 },
   'gap-long': { hex: '#5B92C4', opacity: 0.3, pulseSpeed: 0 },
   'no-events': { hex: '#8A9BA8', opacity: 0.15, pulseSpeed: 0 },
+  'off-hours': { hex: '#6E7C8C', opacity: 0.08, pulseSpeed: 0 },
 };
 
 /** Palette lookup by name. */
@@ -1,10 +1,13 @@
 import type { CalendarEvent, Phase, UserSettings } from './types.js';
+import { isWorkingTime } from './working-hours.js';
 
 /** Parsed event with Date objects for efficient comparison. */
 export interface ParsedEvent {
   id: string;
   startTime: Date;
   endTime: Date;
+  /** Lead-up time before the next event rather than a session of its own. */
+  isBuffer?: boolean;
 }
 
 /** Filter and parse events: remove ignored, all-day, and calendar-ignored events. Sort by start time. */
@@ -21,6 +24,7 @@ export function parseEvents(events: CalendarEvent[], settings?: UserSettings): P
       id: e.id,
       startTime: new Date(e.startTime),
       endTime: new Date(e.endTime),
+      isBuffer: e.isBuffer === true,
     }))
     .sort((a, b) => a.startTime.getTime() - b.startTime.getTime());
 }
@@ -236,22 +240,59 @@ export interface PhaseResult {
 
 /**
  * Determine the current phase given parsed events and current time.
+ *
+ * `overtime` is the backend's overtime state. When given, it replaces the
+ * fixed overtime window after each session: `true` shows overtime unless a
+ * session is in progress, `false` never shows it.
+ *
+ * Travel and prep buffers are lead-up time: warnings count down to the
+ * buffer's start, and the buffer itself holds the most imminent warning.
+ *
+ * With working hours enabled, free time outside them is `off-hours`.
  */
 export function resolvePhase(
   parsed: ParsedEvent[],
   now: Date,
   settings: UserSettings,
+  overtime?: boolean,
 ): PhaseResult {
-  const nowMs = now.getTime();
-
-  if (parsed.length === 0) {
-    return { fromPhase: 'no-events', toPhase: 'no-events', t: 0 };
+  const result = resolveCalendarPhase(parsed, now, settings, overtime);
+  if (!settings.workingHoursEnabled || isWorkingTime(settings.workingHours ?? {}, now)) {
+    return result;
   }
+  return resolveOffHoursPhase(result);
+}
+
+// ---------------------------------------------------------------------------
+// Working hours
+// ---------------------------------------------------------------------------
+
+/** Phases with nothing happening soon; outside working hours these are `off-hours`. */
+const FREE_PHASES: Phase[] = ['free-deep', 'gap-long', 'no-events'];
+
+/**
+ * Swap free-time phases for `off-hours`. Meetings, overtime, short gaps and
+ * warnings are kept, so an evening meeting still fades in from `off-hours`.
+ */
+export function resolveOffHoursPhase(result: PhaseResult): PhaseResult {
+  const swap = (phase: Phase): Phase => (FREE_PHASES.includes(phase) ? 'off-hours' : phase);
+  return { ...result, fromPhase: swap(result.fromPhase), toPhase: swap(result.toPhase) };
+}
+
+/** Phase from the calendar alone, ignoring working hours. */
+function resolveCalendarPhase(
+  parsed: ParsedEvent[],
+  now: Date,
+  settings: UserSettings,
+  overtime?: boolean,
+): PhaseResult {
+  const nowMs = now.getTime();
+  const isCurrent = (e: ParsedEvent) =>
+    nowMs >= e.startTime.getTime() && nowMs < e.endTime.getTime();
+  const sessions = parsed.filter((e) => !e.isBuffer);
 
   // Find current session (now is between start and end)
-  const currentSession = parsed.find(
-    (e) => nowMs >= e.startTime.getTime() && nowMs < e.endTime.getTime(),
-  );
+  const currentSession = sessions.find(isCurrent);
 
   if (currentSession) {
     const duration = currentSession.endTime.getTime() - currentSession.startTime.getTime();
@@ -260,8 +301,23 @@ export function resolvePhase(
     return resolveInSessionPhase(progress);
   }
 
-  // Check if we're past the end of a session (overtime)
-  const pastSessions = parsed.filter((e) => nowMs >= e.endTime.getTime());
+  // In a buffer — the event it leads up to is about to start
+  if (parsed.some((e) => e.isBuffer && isCurrent(e))) {
+    return resolvePreSessionPhase(0, buildWarningBoundaries(settings));
+  }
+
+  if (overtime === true) {
+    return { fromPhase: 'overtime', toPhase: 'overtime', t: 0 };
+  }
+  const overtimeWindow = overtime === undefined ? OVERTIME_DURATION : 0;
+
+  if (parsed.length === 0) {
+    return { fromPhase: 'no-events', toPhase: 'no-events', t: 0 };
+  }
+
+  // Check if we're past the end of a session (overtime). Upcoming buffers
+  // count, so the next warning leads up to the buffer instead of its event.
+  const pastSessions = sessions.filter((e) => nowMs >= e.endTime.getTime());
   const futureSessions = parsed.filter((e) => nowMs < e.startTime.getTime());
 
   if (pastSessions.length > 0 && futureSessions.length > 0) {
@@ -275,14 +331,14 @@ export function resolvePhase(
 
     // Overtime zone: first OVERTIME_DURATION minutes after session end.
     // For very short gaps, overtime fills the whole gap (no separate gap phase).
-    if (timeSinceEnd < OVERTIME_DURATION && gapMinutes <= OVERTIME_DURATION) {
+    if (timeSinceEnd < overtimeWindow && gapMinutes <= overtimeWindow) {
       return { fromPhase: 'overtime', toPhase: 'overtime', t: 0 };
     }
 
     // Transition from overtime → gap/warning over the overtime window
-    if (timeSinceEnd < OVERTIME_DURATION) {
+    if (timeSinceEnd < overtimeWindow) {
       // Interpolate overtime → next phase
-      const t = timeSinceEnd / OVERTIME_DURATION;
+      const t = timeSinceEnd / overtimeWindow;
       if (gapMinutes < SHORT_GAP_THRESHOLD) {
         return { fromPhase: 'overtime', toPhase: 'gap-short', t };
       }
@@ -305,7 +361,7 @@ export function resolvePhase(
     // Past all sessions, check overtime
     const lastEnded = pastSessions[pastSessions.length - 1];
     const timeSinceEnd = (nowMs - lastEnded.endTime.getTime()) / 60_000;
-    if (timeSinceEnd < OVERTIME_DURATION) {
+    if (timeSinceEnd < overtimeWindow) {
       return { fromPhase: 'overtime', toPhase: 'overtime', t: 0 };
     }
     // No more events for the day
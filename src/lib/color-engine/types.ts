@@ -1,5 +1,5 @@
 /**
- * All 13 visual phases of the Morph ambient border.
+ * All 14 visual phases of the Morph ambient border.
  * Each phase maps to a specific color, opacity, and pulse speed.
  */
 export type Phase =
@@ -15,7 +15,8 @@ export type Phase =
   | 'overtime'
   | 'gap-short'
   | 'gap-long'
-  | 'no-events';
+  | 'no-events'
+  | 'off-hours';
 
 /**
  * Calendar event as received from the Rust backend via Tauri events.
@@ -31,6 +32,8 @@ export interface CalendarEvent {
   calendarId?: string;
   providerId: string;
   isAllDay: boolean;
+  seriesId?: string; // recurring series, if any
+  isBuffer?: boolean; // travel or prep time before the next event
 }
 
 /**
@@ -57,7 +60,7 @@ export type Palette = Record<Phase, PaletteEntry>;
 /** User-configurable intensity level that scales opacity values. */
 export type Intensity = 'subtle' | 'normal' | 'vivid';
 
-/** Which named palette to use. */
+/** Which built-in palette to use when no `activePalette` is loaded. */
 export type PaletteName = 'ambient' | 'ocean';
 
 /** Which screen edges display the border. */
@@ -66,16 +69,37 @@ export type BorderPosition = 'all' | 'top' | 'sides' | 'bottom' | 'top-sides' |
 /** Border thickness preset. */
 export type BorderThickness = 'thin' | 'medium' | 'thick';
 
+/** Weekday keys used by the `working_hours` setting. */
+export type Weekday = 'mon' | 'tue' | 'wed' | 'thu' | 'fri' | 'sat' | 'sun';
+
+/** Local `HH:MM` start and end of one working day. */
+export interface DayHours {
+  start: string;
+  end: string;
+}
+
+/** Working hours per weekday. Missing days are off. */
+export type WorkingHours = Partial<Record<Weekday, DayHours>>;
+
+/** What the border does outside working hours. */
+export type OffHoursMode = 'phase' | 'hide';
+
 /** User-configurable settings that affect color engine output. */
 export interface UserSettings {
   /** Warning thresholds in minutes before an event (sorted descending). */
   warningWindows: number[];
   intensity: Intensity;
   palette: PaletteName;
+  /** Entries of the active palette from the backend palette store. Overrides `palette`. */
+  activePalette?: Palette;
   borderThickness: BorderThickness;
   borderPosition: BorderPosition;
   /** Calendar IDs whose events should be excluded from border state calculation. */
   ignoredCalendarIds: string[];
+  /** Whether free time outside `workingHours` resolves to `off-hours`. */
+  workingHoursEnabled?: boolean;
+  workingHours?: WorkingHours;
+  offHoursMode?: OffHoursMode;
 }
 
 /** Default user settings. */
@@ -86,6 +110,9 @@ export const DEFAULT_USER_SETTINGS: UserSettings = {
   borderThickness: 'medium',
   borderPosition: 'all',
   ignoredCalendarIds: [],
+  workingHoursEnabled: false,
+  workingHours: {},
+  offHoursMode: 'phase',
 };
 
 /** Opacity multipliers for each intensity level. */
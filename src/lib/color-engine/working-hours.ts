@@ -0,0 +1,39 @@
+import type { Weekday, WorkingHours } from './types.js';
+
+/** Weekday keys indexed by `Date.getDay()` (0 = Sunday). */
+const WEEKDAYS: Weekday[] = ['sun', 'mon', 'tue', 'wed', 'thu', 'fri', 'sat'];
+
+/** Minutes since midnight for a `HH:MM` string, or null if malformed. */
+function minutesOfDay(time: string): number | null {
+  const match = /^(\d{2}):(\d{2})$/.exec(time);
+  if (!match) return null;
+  const hours = Number(match[1]);
+  const minutes = Number(match[2]);
+  return hours < 24 && minutes < 60 ? hours * 60 + minutes : null;
+}
+
+/**
+ * Whether local time `now` falls within working hours.
+ * Days without hours (or with malformed ones) are off all day.
+ */
+export function isWorkingTime(hours: WorkingHours, now: Date): boolean {
+  const day = hours[WEEKDAYS[now.getDay()]];
+  if (!day) return false;
+  const start = minutesOfDay(day.start);
+  const end = minutesOfDay(day.end);
+  if (start === null || end === null) return false;
+  const minutes = now.getHours() * 60 + now.getMinutes();
+  return start <= minutes && minutes < end;
+}
+
+/** Parse the stored `working_hours` setting, falling back to no hours. */
+export function parseWorkingHours(value: string): WorkingHours {
+  try {
+    const parsed: unknown = JSON.parse(value);
+    return parsed && typeof parsed === 'object' && !Array.isArray(parsed)
+      ? (parsed as WorkingHours)
+      : {};
+  } catch {
+    return {};
+  }
+}
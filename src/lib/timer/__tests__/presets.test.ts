@@ -108,6 +108,12 @@ describe('startTimerFromPreset', () => {
     expect(state.startedAt).toBe(new Date(now).toISOString());
   });
 
+  it('marks break presets', () => {
+    const shortBreak = getDefaultPresets().find((p) => p.id === 'short-break')!;
+    expect(startTimerFromPreset(shortBreak).isBreak).toBe(true);
+    expect(startTimerFromPreset(createPreset('Test', 300)).isBreak).toBe(false);
+  });
+
   it('starts with zero elapsed time', () => {
     const preset = createPreset('Test', 300);
     const state = startTimerFromPreset(preset);
@@ -13,8 +13,8 @@ const MAX_DURATION_SECONDS = 24 * 60 * 60; // 24 hours
 
 const DEFAULT_PRESETS: readonly TimerPreset[] = [
   { id: 'pomodoro', name: 'Pomodoro', durationSeconds: 25 * 60 },
-  { id: 'short-break', name: 'Short Break', durationSeconds: 5 * 60 },
-  { id: 'long-break', name: 'Long Break', durationSeconds: 15 * 60 },
+  { id: 'short-break', name: 'Short Break', durationSeconds: 5 * 60, isBreak: true },
+  { id: 'long-break', name: 'Long Break', durationSeconds: 15 * 60, isBreak: true },
   { id: 'focus-hour', name: 'Focus Hour', durationSeconds: 60 * 60 },
 ];
 
@@ -51,6 +51,7 @@ export function startTimerFromPreset(preset: TimerPreset, now: number = Date.now
     startedAt: new Date(now).toISOString(),
     pausedAt: null,
     elapsedBeforePause: 0,
+    isBreak: preset.isBreak ?? false,
   };
 }
 
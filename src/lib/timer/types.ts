@@ -9,6 +9,8 @@ export interface TimerState {
   pausedAt: string | null;
   /** Seconds of elapsed time accumulated before the current (or most recent) pause. */
   elapsedBeforePause: number;
+  /** True while a break preset is running; set by the backend timer service. */
+  isBreak?: boolean;
 }
 
 /** A reusable timer preset with a pre-configured duration. */
@@ -17,6 +19,8 @@ export interface TimerPreset {
   name: string;
   durationSeconds: number;
   color?: string;
+  /** A break rather than a focus session. Breaks don't count as focus time. */
+  isBreak?: boolean;
 }
 
 /** Actions that can be dispatched to the timer state machine. */
@@ -0,0 +1,25 @@
+/**
+ * Palette store types returned by the `list_palettes`, `save_palette` and
+ * `import_palette` commands.
+ */
+import type { Palette } from '../color-engine/types.js';
+
+export interface StoredPalette {
+  id: string;
+  name: string;
+  builtin: boolean;
+  entries: Palette;
+}
+
+/** An accessibility problem found by `check_palette`. Never blocks saving. */
+export interface PaletteIssue {
+  vision: 'normal' | 'protanopia' | 'deuteranopia' | 'tritanopia';
+  kind: 'indistinct' | 'lowContrast';
+  phases: string[];
+  message: string;
+}
+
+export interface SavedPalette {
+  palette: StoredPalette;
+  issues: PaletteIssue[];
+}
@@ -0,0 +1,122 @@
+/**
+ * Typed settings object returned by the `get_all_settings` and
+ * `get_effective_settings` commands.
+ * Field names match the setting keys used with `set_setting`.
+ */
+import type { TimerPreset } from '@/lib/timer/types';
+
+export interface TimerSequence {
+  id: string;
+  name: string;
+  steps: string[];
+}
+
+export interface ScheduleRule {
+  id: string;
+  name: string;
+  days: string[];
+  start: string;
+  end: string;
+  overrides: Record<string, string>;
+  pauseBorder: boolean;
+}
+
+/** Travel or prep time before matching events. Every condition given must match. */
+export interface BufferRule {
+  minutes: number;
+  label?: string;
+  inPerson?: boolean;
+  titleContains?: string;
+  calendarId?: string;
+  eventId?: string;
+}
+
+/** What counts as screen sharing or presenting. Every condition given must match. */
+export interface ShareRule {
+  name: string;
+  app?: string;
+  windowTitle?: string;
+  fullscreen?: boolean;
+}
+
+export interface Settings {
+  border_thickness: 'thin' | 'medium' | 'thick';
+  border_position: 'all' | 'top' | 'sides' | 'bottom' | 'top-sides' | 'bottom-sides';
+  /** A built-in palette ID or `custom-<name>`; see `list_palettes`. */
+  color_palette: string;
+  color_intensity: 'subtle' | 'normal' | 'vivid';
+  warning_30min: boolean;
+  warning_15min: boolean;
+  warning_5min: boolean;
+  warning_2min: boolean;
+  poll_interval_seconds: number;
+  launch_at_login: boolean;
+  selected_display: string;
+  onboarding_complete: boolean;
+  ignored_calendar_ids: string[];
+  theme_preference: 'system' | 'light' | 'dark';
+  custom_timer_presets: TimerPreset[];
+  timer_sequences: TimerSequence[];
+  schedule_rules: ScheduleRule[];
+  working_hours_enabled: boolean;
+  /** Day ("mon" ... "sun") → local `HH:MM` hours; missing days are off. */
+  working_hours: Record<string, { start: string; end: string }>;
+  off_hours_mode: 'phase' | 'hide';
+  buffer_rules: BufferRule[];
+  history_retention_days: number;
+  overtime_max_minutes: number;
+  http_api_enabled: boolean;
+  http_api_port: number;
+  status_sync_slack: boolean;
+  status_sync_teams: boolean;
+  status_meeting_emoji: string;
+  status_focus_emoji: string;
+  slack_api_base: string;
+  graph_api_base: string;
+  lights_backend: 'off' | 'hue' | 'lifx';
+  hue_bridge_url: string;
+  hue_group_id: string;
+  lifx_address: string;
+  lights_off_phases: string[];
+  lights_max_brightness: number;
+  mqtt_enabled: boolean;
+  mqtt_broker_url: string;
+  mqtt_topic_prefix: string;
+  mqtt_discovery_prefix: string;
+  notify_warning_5min: boolean;
+  notify_warning_2min: boolean;
+  notify_meeting_end: boolean;
+  notify_timer_complete: boolean;
+  notify_quiet_hours: boolean;
+  notify_quiet_start: string;
+  notify_quiet_end: string;
+  auto_join_meetings: boolean;
+  dnd_sync: boolean;
+  focus_mode: boolean;
+  focus_blocked_apps: string[];
+  focus_app_action: 'warn' | 'hide' | 'quit';
+  focus_blocked_sites: string[];
+  focus_block_sites: boolean;
+  focus_calendar_keywords: string[];
+  screen_share_detection: boolean;
+  screen_share_action: 'hide' | 'minimal';
+  screen_share_rules: ShareRule[];
+  tray_countdown_title: boolean;
+  hotkeys_enabled: boolean;
+  hotkey_start_timer: string;
+  hotkey_toggle_timer: string;
+  hotkey_pause_border: string;
+  hotkey_hide_border: string;
+  hotkey_sync: string;
+}
+
+/**
+ * Convert typed settings back to the (key, stored string) pairs that
+ * `set_setting` and `settings-changed` use.
+ */
+export function settingsToEntries(settings: Settings): [string, string][] {
+  return Object.entries(settings).map(([key, value]) => [
+    key,
+    typeof value === 'string' ? value : JSON.stringify(value),
+  ]);
+}
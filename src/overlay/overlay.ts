@@ -1,7 +1,7 @@
 import { listen } from '@tauri-apps/api/event';
 import { invoke } from '@tauri-apps/api/core';
 import { getCurrentWindow } from '@tauri-apps/api/window';
-import { getBorderState } from '@/lib/color-engine/index';
+import { getBorderState, parseWorkingHours } from '@/lib/color-engine/index';
 import type {
   CalendarEvent,
   UserSettings,
@@ -13,6 +13,8 @@ import type {
 import { DEFAULT_USER_SETTINGS } from '@/lib/color-engine/types';
 import { getTimerAsEvent } from '@/lib/timer/index';
 import type { TimerState } from '@/lib/timer/types';
+import { settingsToEntries, type Settings } from '@/lib/types/settings';
+import type { StoredPalette } from '@/lib/types/palette';
 
 /**
  * Payload shape for the border state.
@@ -91,7 +93,18 @@ let timerState: TimerState = {
 };
 let userSettings: UserSettings = { ...DEFAULT_USER_SETTINGS };
 let borderPausedUntil: number | null = null;
+/** Whether the backend says a meeting is in overtime; unset until loaded. */
+let inOvertime: boolean | undefined;
 let lastState: BorderStatePayload | null = null;
+let reportedPhase: string | null = null;
+/** Whether the backend detected screen sharing with the `minimal` action. */
+let screenShareMinimal = false;
+
+/** Only one overlay window reports phase changes to the backend (history). */
+const REPORTING_WINDOW = 'border-top';
+
+/** The one edge drawn, without pulsing, while screen sharing is minimal. */
+const MINIMAL_WINDOW = 'border-bottom';
 
 // ---------------------------------------------------------------------------
 // Setup
@@ -99,8 +112,8 @@ let lastState: BorderStatePayload | null = null;
 
 async function loadInitialSettings(): Promise<void> {
   try {
-    const pairs: [string, string][] = await invoke('get_all_settings');
-    for (const [key, value] of pairs) {
+    const settings: Settings = await invoke('get_effective_settings');
+    for (const [key, value] of settingsToEntries(settings)) {
       applySettingToState(key, value);
     }
   } catch (e) {
@@ -111,8 +124,9 @@ async function loadInitialSettings(): Promise<void> {
 function applySettingToState(key: string, value: string): void {
   switch (key) {
     case 'color_palette':
+      // Custom and other presets arrive with the following `palette-changed`
       if (value === 'ambient' || value === 'ocean') {
-        userSettings = { ...userSettings, palette: value as PaletteName };
+        userSettings = { ...userSettings, palette: value as PaletteName, activePalette: undefined };
       }
       break;
     case 'color_intensity':
@@ -137,6 +151,17 @@ function applySettingToState(key: string, value: string): void {
         userSettings = { ...userSettings, borderPosition: value as BorderPosition };
       }
       break;
+    case 'working_hours_enabled':
+      userSettings = { ...userSettings, workingHoursEnabled: value === 'true' };
+      break;
+    case 'working_hours':
+      userSettings = { ...userSettings, workingHours: parseWorkingHours(value) };
+      break;
+    case 'off_hours_mode':
+      if (value === 'phase' || value === 'hide') {
+        userSettings = { ...userSettings, offHoursMode: value };
+      }
+      break;
   }
 }
 
@@ -181,6 +206,21 @@ function computeAndApply(
   pulse: ReturnType<typeof createPulseController>,
   windowLabel: string,
 ): void {
+  const now = new Date();
+
+  // Merge timer event (if active) into the calendar events
+  const timerEvent = getTimerAsEvent(timerState, now);
+  const allEvents = timerEvent ? [...calendarEvents, timerEvent] : calendarEvents;
+
+  const state = getBorderState(allEvents, now, userSettings, inOvertime);
+
+  // Report the computed phase even while hidden, so history reflects the
+  // calendar rather than what is drawn
+  if (windowLabel === REPORTING_WINDOW && state.phase !== reportedPhase) {
+    reportedPhase = state.phase;
+    invoke('emit_border_state', { state }).catch(console.error);
+  }
+
   // If border is paused, show transparent
   if (borderPausedUntil !== null) {
     if (Date.now() < borderPausedUntil) {
@@ -193,24 +233,24 @@ function computeAndApply(
     borderPausedUntil = null;
   }
 
-  // Hide this window if it's not active for the current position setting
-  if (!isWindowActiveForPosition(windowLabel, userSettings.borderPosition)) {
+  // Hide this window if it's not active for the current position setting,
+  // or outside working hours when set to hide then, or not the minimal edge
+  // while sharing the screen
+  const hideOffHours = state.phase === 'off-hours' && userSettings.offHoursMode === 'hide';
+  const active = screenShareMinimal
+    ? windowLabel === MINIMAL_WINDOW
+    : isWindowActiveForPosition(windowLabel, userSettings.borderPosition);
+  if (hideOffHours || !active) {
     if (stateUnchanged(lastState, HIDDEN_STATE)) return;
     lastState = HIDDEN_STATE;
     pulse.update(HIDDEN_STATE);
     return;
   }
 
-  const now = new Date();
-
-  // Merge timer event (if active) into the calendar events
-  const timerEvent = getTimerAsEvent(timerState, now);
-  const allEvents = timerEvent ? [...calendarEvents, timerEvent] : calendarEvents;
-
-  const state = getBorderState(allEvents, now, userSettings);
-  if (stateUnchanged(lastState, state)) return;
-  lastState = state;
-  pulse.update(state);
+  const shown = screenShareMinimal ? { ...state, pulseSpeed: 0 } : state;
+  if (stateUnchanged(lastState, shown)) return;
+  lastState = shown;
+  pulse.update(shown);
 }
 
 async function setup() {
@@ -244,9 +284,53 @@ async function setup() {
     lastState = null;
   });
 
-  // Listen for border pause events
-  await listen<{ minutes: number }>('border-paused', (event) => {
-    borderPausedUntil = Date.now() + event.payload.minutes * 60 * 1000;
+  // Palettes live in the backend store; it sends the new one on each change
+  try {
+    const palette = await invoke<StoredPalette>('get_active_palette');
+    userSettings = { ...userSettings, activePalette: palette.entries };
+  } catch (e) {
+    console.warn('[overlay] Failed to load active palette:', e);
+  }
+  await listen<StoredPalette>('palette-changed', (event) => {
+    userSettings = { ...userSettings, activePalette: event.payload.entries };
+    lastState = null;
+  });
+
+  // Screen sharing is detected by the backend, which hides the windows
+  // outright or asks for a single still edge
+  try {
+    const share = await invoke<{ action: string | null }>('get_screen_share_state');
+    screenShareMinimal = share.action === 'minimal';
+  } catch (e) {
+    console.warn('[overlay] Failed to load screen share state:', e);
+  }
+  await listen<{ action: string | null }>('screen-share-changed', (event) => {
+    screenShareMinimal = event.payload.action === 'minimal';
+    lastState = null;
+  });
+
+  // Overtime is tracked by the backend (ends when the user leaves the meeting)
+  try {
+    inOvertime = (await invoke<unknown>('get_overtime_state')) !== null;
+  } catch (e) {
+    console.warn('[overlay] Failed to load overtime state:', e);
+  }
+  await listen<unknown>('overtime-state-update', (event) => {
+    inOvertime = event.payload !== null;
+  });
+
+  // Border pauses are owned by the backend, which restores them on launch
+  try {
+    const pause = await invoke<{ until: string } | null>('get_pause_state');
+    borderPausedUntil = pause ? Date.parse(pause.until) : null;
+  } catch (e) {
+    console.warn('[overlay] Failed to load pause state:', e);
+  }
+  await listen<{ until: string }>('border-paused', (event) => {
+    borderPausedUntil = Date.parse(event.payload.until);
+  });
+  await listen('border-resumed', () => {
+    borderPausedUntil = null;
   });
 
   // Drive the color engine at 1 Hz — replaces the Rust tick emitter
@@ -6,6 +6,7 @@ vi.mock('@tauri-apps/api/core', () => ({
 }));
 
 import { invoke } from '@tauri-apps/api/core';
+import { settingsToEntries, type Settings } from '@/lib/types/settings';
 
 const mockInvoke = vi.mocked(invoke);
 
@@ -91,12 +92,28 @@ describe('settings parsing logic', () => {
 
 describe('invoke calls', () => {
   it('get_all_settings calls invoke with correct command', async () => {
-    mockInvoke.mockResolvedValueOnce([['border_thickness', '4']]);
+    mockInvoke.mockResolvedValueOnce({ border_thickness: 'thick' });
 
-    const result = await invoke<[string, string][]>('get_all_settings');
+    const result = await invoke<Partial<Settings>>('get_all_settings');
 
     expect(mockInvoke).toHaveBeenCalledWith('get_all_settings');
-    expect(result).toEqual([['border_thickness', '4']]);
+    expect(result).toEqual({ border_thickness: 'thick' });
+  });
+
+  it('settingsToEntries stringifies non-string values', () => {
+    const entries = new Map(
+      settingsToEntries({
+        border_thickness: 'thin',
+        warning_5min: false,
+        poll_interval_seconds: 120,
+        ignored_calendar_ids: ['work'],
+      } as Settings),
+    );
+
+    expect(entries.get('border_thickness')).toBe('thin');
+    expect(entries.get('warning_5min')).toBe('false');
+    expect(entries.get('poll_interval_seconds')).toBe('120');
+    expect(entries.get('ignored_calendar_ids')).toBe('["work"]');
   });
 
   it('set_setting calls invoke with key and value', async () => {
@@ -59,6 +59,7 @@ function getPhaseLabels(points: SampledPoint[]): Array<{ label: string; x: numbe
     else if (phase.startsWith('in-session')) label = 'In Session';
     else if (phase.startsWith('overtime')) label = 'Overtime';
     else if (phase.startsWith('gap')) label = 'Gap';
+    else if (phase === 'off-hours') label = 'Off Hours';
 
     if (label && !seen.has(label)) {
       seen.add(label);
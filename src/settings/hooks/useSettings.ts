@@ -1,5 +1,6 @@
 import { createContext, useContext, useState, useEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { settingsToEntries, type Settings } from '@/lib/types/settings';
 
 export interface SettingsContextValue {
   settings: Map<string, string>;
@@ -15,9 +16,9 @@ export function useSettingsProvider(): SettingsContextValue {
   const [loading, setLoading] = useState(true);
 
   useEffect(() => {
-    invoke<[string, string][]>('get_all_settings')
-      .then((pairs) => {
-        setSettings(new Map(pairs));
+    invoke<Settings>('get_all_settings')
+      .then((typed) => {
+        setSettings(new Map(settingsToEntries(typed)));
       })
       .catch((err) => {
         console.error('Failed to load settings:', err);
@@ -1,10 +1,12 @@
-import { useState } from 'react';
+import { useEffect, useRef, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import { useSettings } from '../hooks/useSettings';
 import { Slider, Card, SectionHeader, Button } from '@/shared/components';
 import { MiniPreview } from '../components/MiniPreview';
 import { TimelineScrubber } from '../components/TimelineScrubber';
 import type { BorderState, UserSettings } from '@/lib/color-engine/types';
 import { DEFAULT_USER_SETTINGS } from '@/lib/color-engine/types';
+import type { PaletteIssue, SavedPalette, StoredPalette } from '@/lib/types/palette';
 
 const THICKNESS_ENUM = ['thin', 'medium', 'thick'] as const;
 const THICKNESS_LABELS = ['Thin', 'Medium', 'Thick'] as const;
@@ -24,6 +26,9 @@ const PALETTE_OPTIONS = [
   },
 ] as const;
 
+/** Phases shown as swatches for palettes from the backend store. */
+const SWATCH_PHASES = ['free-deep', 'warning-mid', 'warning-imminent', 'in-session-end'] as const;
+
 const INTENSITY_ENUM = ['subtle', 'normal', 'vivid'] as const;
 const INTENSITY_LABELS = ['Subtle (0.6\u00d7)', 'Normal (1.0\u00d7)', 'Vivid (1.4\u00d7)'] as const;
 
@@ -180,6 +185,10 @@ export function BorderTab() {
   const { getSetting, setSetting } = useSettings();
   const [scrubberOpen, setScrubberOpen] = useState(false);
   const [scrubberState, setScrubberState] = useState<BorderState | null>(null);
+  const [storePalettes, setStorePalettes] = useState<StoredPalette[]>([]);
+  const [paletteIssues, setPaletteIssues] = useState<PaletteIssue[]>([]);
+  const [paletteError, setPaletteError] = useState<string | null>(null);
+  const importInput = useRef<HTMLInputElement>(null);
 
   const thickness = getSetting('border_thickness') ?? 'medium';
   const position = getSetting('border_position') ?? 'all';
@@ -189,6 +198,64 @@ export function BorderTab() {
   const thicknessIndex = Math.max(0, THICKNESS_ENUM.indexOf(thickness as (typeof THICKNESS_ENUM)[number]));
   const intensityIndex = Math.max(0, INTENSITY_ENUM.indexOf(intensity as (typeof INTENSITY_ENUM)[number]));
 
+  const extraPalettes = storePalettes.filter(
+    (p) => !PALETTE_OPTIONS.some((opt) => opt.value === p.id),
+  );
+  const activeStored = storePalettes.find((p) => p.id === palette);
+  const activeExtra = extraPalettes.find((p) => p.id === palette);
+
+  useEffect(() => {
+    invoke<StoredPalette[]>('list_palettes')
+      .then(setStorePalettes)
+      .catch((err) => console.error('Failed to list palettes:', err));
+  }, []);
+
+  useEffect(() => {
+    if (!activeStored) {
+      setPaletteIssues([]);
+      return;
+    }
+    invoke<PaletteIssue[]>('check_palette', { entries: activeStored.entries })
+      .then(setPaletteIssues)
+      .catch((err) => console.error('Failed to check palette:', err));
+  }, [activeStored]);
+
+  async function handleImport(file: File) {
+    try {
+      const saved = await invoke<SavedPalette>('import_palette', { contents: await file.text() });
+      setStorePalettes((prev) => [...prev, saved.palette]);
+      setSetting('color_palette', saved.palette.id);
+      setPaletteError(null);
+    } catch (err) {
+      // e.g. "missing phase 'off-hours'"
+      setPaletteError(String(err));
+    }
+  }
+
+  async function handleExport() {
+    try {
+      const json = await invoke<string>('export_palette', { id: palette });
+      const url = URL.createObjectURL(new Blob([json], { type: 'application/json' }));
+      const link = document.createElement('a');
+      link.href = url;
+      link.download = `${palette}.json`;
+      link.click();
+      URL.revokeObjectURL(url);
+    } catch (err) {
+      setPaletteError(String(err));
+    }
+  }
+
+  async function handleDelete(id: string) {
+    try {
+      await invoke('delete_palette', { id });
+      setStorePalettes((prev) => prev.filter((p) => p.id !== id));
+      if (palette === id) setSetting('color_palette', 'ambient');
+    } catch (err) {
+      setPaletteError(String(err));
+    }
+  }
+
   const activeEdges = edgesFromPosition(position);
   const previewPosition = {
     top: activeEdges.has('top'),
@@ -198,7 +265,7 @@ export function BorderTab() {
   };
 
   const defaultPreviewState: BorderState = {
-    color: palette === 'ocean' ? '#3b82f6' : '#22c55e',
+    color: activeExtra?.entries['free-deep'].hex ?? (palette === 'ocean' ? '#3b82f6' : '#22c55e'),
     opacity: 0.25,
     pulseSpeed: 0,
     phase: 'free-deep',
@@ -208,7 +275,8 @@ export function BorderTab() {
 
   const scrubberSettings: UserSettings = {
     ...DEFAULT_USER_SETTINGS,
-    palette: palette as UserSettings['palette'],
+    palette: palette === 'ocean' ? 'ocean' : 'ambient',
+    activePalette: activeExtra?.entries,
     intensity: intensity as UserSettings['intensity'],
     borderThickness: thickness as UserSettings['borderThickness'],
     borderPosition: position as UserSettings['borderPosition'],
@@ -298,7 +366,67 @@ export function BorderTab() {
               </p>
             </Card>
           ))}
+          {extraPalettes.map((p) => (
+            <Card
+              key={p.id}
+              selected={palette === p.id}
+              onClick={() => setSetting('color_palette', p.id)}
+            >
+              <div className="flex gap-1.5 mb-2">
+                {SWATCH_PHASES.map((phase) => (
+                  <div
+                    key={phase}
+                    data-testid="color-swatch"
+                    className="w-6 h-6 rounded-full"
+                    style={{ backgroundColor: p.entries[phase].hex }}
+                  />
+                ))}
+              </div>
+              <span style={{ fontSize: 'var(--text-sm, 0.875rem)', fontWeight: 600, color: 'var(--color-text, #111827)' }}>
+                {p.name}
+              </span>
+              <p style={{ fontSize: 'var(--text-xs, 0.75rem)', color: 'var(--color-text-secondary, #6b7280)', marginTop: '2px' }}>
+                {p.builtin ? 'Built-in preset' : 'Custom'}
+              </p>
+            </Card>
+          ))}
+        </div>
+        {paletteIssues.length > 0 && (
+          <ul className="mt-2" data-testid="palette-issues" style={{ fontSize: 'var(--text-xs, 0.75rem)', color: 'var(--color-text-secondary, #6b7280)' }}>
+            {paletteIssues.map((issue) => (
+              <li key={issue.message}>{issue.message}</li>
+            ))}
+          </ul>
+        )}
+        <div className="flex gap-2 mt-3">
+          <Button variant="ghost" onClick={() => importInput.current?.click()}>
+            Import palette
+          </Button>
+          <Button variant="ghost" onClick={handleExport}>
+            Export palette
+          </Button>
+          {activeStored && !activeStored.builtin && (
+            <Button variant="ghost" onClick={() => handleDelete(activeStored.id)}>
+              Delete palette
+            </Button>
+          )}
+          <input
+            ref={importInput}
+            type="file"
+            accept="application/json,.json"
+            hidden
+            onChange={(e) => {
+              const file = e.target.files?.[0];
+              if (file) handleImport(file);
+              e.target.value = '';
+            }}
+          />
         </div>
+        {paletteError && (
+          <p className="mt-2" style={{ fontSize: 'var(--text-xs, 0.75rem)', color: 'var(--color-text-secondary, #6b7280)' }}>
+            {paletteError}
+          </p>
+        )}
       </section>
 
       {/* Intensity */}
@@ -1,6 +1,6 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { emit } from '@tauri-apps/api/event';
+import { listen } from '@tauri-apps/api/event';
 import { enable, disable, isEnabled } from '@tauri-apps/plugin-autostart';
 import { useSettings } from '../hooks/useSettings';
 import { Toggle, Card, SectionHeader, Chip } from '@/shared/components';
@@ -18,6 +18,27 @@ interface MonitorInfo {
 
 const PAUSE_DURATIONS = [5, 15, 30, 60] as const;
 
+/** A border pause as reported by the backend. */
+interface PauseInfo {
+  until: string;
+  kind: 'minutes' | 'next-event' | 'event-end' | 'tomorrow' | 'quiet';
+  /** "until 14:30", "until Standup ends", ... */
+  label: string;
+  source: string;
+}
+
+type PauseRequest =
+  | { kind: 'minutes'; minutes: number }
+  | { kind: 'next-event' }
+  | { kind: 'event-end' }
+  | { kind: 'tomorrow' };
+
+const PAUSE_UNTIL: { label: string; request: PauseRequest }[] = [
+  { label: 'Until next event', request: { kind: 'next-event' } },
+  { label: 'Until this meeting ends', request: { kind: 'event-end' } },
+  { label: 'Until tomorrow', request: { kind: 'tomorrow' } },
+];
+
 const THEME_OPTIONS: { value: ThemePreference; label: string; swatch: string }[] = [
   { value: 'system', label: 'System', swatch: 'linear-gradient(135deg, #f8f8f8 50%, #1a1a1a 50%)' },
   { value: 'light', label: 'Light', swatch: '#f8f8f8' },
@@ -30,9 +51,8 @@ export function GeneralTab() {
   const launchAtLogin = getSetting('launch_at_login') === 'true';
   const selectedDisplay = getSetting('selected_display') ?? 'primary';
   const [monitors, setMonitors] = useState<MonitorInfo[]>([]);
-  const [pauseState, setPauseState] = useState<{ paused: boolean; minutes?: number }>({
-    paused: false,
-  });
+  const [pause, setPause] = useState<PauseInfo | null>(null);
+  const [pauseError, setPauseError] = useState<string | null>(null);
 
   // Sync theme preference from settings on mount
   const savedTheme = getSetting('theme_preference');
@@ -48,6 +68,20 @@ export function GeneralTab() {
       .catch((err) => console.error('Failed to get monitors:', err));
   }, []);
 
+  useEffect(() => {
+    invoke<PauseInfo | null>('get_pause_state')
+      .then(setPause)
+      .catch((err) => console.error('Failed to get pause state:', err));
+    const unlistenPaused = listen<PauseInfo>('border-paused', (event) => {
+      setPause(event.payload);
+    });
+    const unlistenResumed = listen('border-resumed', () => setPause(null));
+    return () => {
+      unlistenPaused.then((fn) => fn());
+      unlistenResumed.then((fn) => fn());
+    };
+  }, []);
+
   function handleThemeChange(theme: ThemePreference) {
     setPreference(theme);
     setSetting('theme_preference', theme);
@@ -67,14 +101,23 @@ export function GeneralTab() {
     }
   }
 
-  function handlePause(minutes: number) {
-    emit('pause-border', { minutes });
-    setPauseState({ paused: true, minutes });
+  async function handlePause(request: PauseRequest) {
+    try {
+      setPause(await invoke<PauseInfo>('pause_border_until', { request }));
+      setPauseError(null);
+    } catch (err) {
+      // e.g. "no upcoming event today"
+      setPauseError(String(err));
+    }
   }
 
-  function handleResume() {
-    emit('pause-border', { minutes: 0 });
-    setPauseState({ paused: false });
+  async function handleResume() {
+    try {
+      await invoke('pause_border', { durationMinutes: 0 });
+      setPause(null);
+    } catch (err) {
+      console.error('Failed to resume border:', err);
+    }
   }
 
   return (
@@ -151,13 +194,11 @@ export function GeneralTab() {
       {/* Pause / Snooze */}
       <section>
         <SectionHeader title="Pause Border" description="Temporarily hide the border overlay." />
-        {pauseState.paused ? (
+        {pause ? (
           <Card>
             <div className="flex items-center justify-between">
               <span style={{ fontSize: 'var(--text-sm)', color: 'var(--color-text)' }}>
-                {pauseState.minutes
-                  ? `Paused for ${pauseState.minutes} min`
-                  : 'Paused until next event'}
+                Paused {pause.label}
               </span>
               <button
                 onClick={handleResume}
@@ -179,19 +220,27 @@ export function GeneralTab() {
                 key={min}
                 label={`${min}m`}
                 selected={false}
-                onSelect={() => handlePause(min)}
+                onSelect={() => handlePause({ kind: 'minutes', minutes: min })}
+              />
+            ))}
+            {PAUSE_UNTIL.map(({ label, request }) => (
+              <Chip
+                key={request.kind}
+                label={label}
+                selected={false}
+                onSelect={() => handlePause(request)}
               />
             ))}
-            <Chip
-              label="Until next event"
-              selected={false}
-              onSelect={() => {
-                emit('pause-border', { minutes: -1 });
-                setPauseState({ paused: true });
-              }}
-            />
           </div>
         )}
+        {!pause && pauseError && (
+          <p
+            className="mt-2"
+            style={{ fontSize: 'var(--text-xs)', color: 'var(--color-text-secondary)' }}
+          >
+            {pauseError}
+          </p>
+        )}
       </section>
     </div>
   );
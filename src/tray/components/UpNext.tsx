@@ -7,6 +7,7 @@ interface CalendarEvent {
   start: string;
   end: string;
   calendar_color?: string;
+  isBuffer?: boolean;
 }
 
 function formatTime(iso: string): string {
@@ -24,7 +25,7 @@ export function UpNext() {
       todayEnd.setHours(23, 59, 59, 999);
 
       const upcoming = event.payload
-        .filter((e) => new Date(e.end) > now && new Date(e.start) <= todayEnd)
+        .filter((e) => !e.isBuffer && new Date(e.end) > now && new Date(e.start) <= todayEnd)
         .sort((a, b) => new Date(a.start).getTime() - new Date(b.start).getTime())
         .slice(0, 3);
 